@@ -0,0 +1,99 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+/// Implements `bumaga::ViewState` for struct with named fields.
+///
+/// Fields are compared with previous state and only changed ones are converted
+/// to JSON and bound to view. Field attributes:
+/// - `#[view(nested)]` field type is `ViewState` itself, changes are tracked per field
+/// - `#[view(rename = "name")]` binds field to another model key
+/// - `#[view(skip)]` field is not bound
+#[proc_macro_derive(ViewState, attributes(view))]
+pub fn derive_view_state(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                let message = "ViewState can be derived only for struct with named fields";
+                return Err(syn::Error::new_spanned(name, message));
+            }
+        },
+        _ => {
+            let message = "ViewState can be derived only for struct";
+            return Err(syn::Error::new_spanned(name, message));
+        }
+    };
+    let mut statements = vec![];
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let mut key = ident.to_string();
+        let mut nested = false;
+        let mut skip = false;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("view"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("nested") {
+                    nested = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    key = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported view attribute"))
+                }
+            })?;
+        }
+        if skip {
+            continue;
+        }
+        let statement = if nested {
+            quote! {
+                ::bumaga::ViewState::collect_changes(
+                    &self.#ident,
+                    previous.map(|previous| &previous.#ident),
+                    &format!("{}/{}", pointer, #key),
+                    changes,
+                );
+            }
+        } else {
+            quote! {
+                ::bumaga::collect_value_change(
+                    &self.#ident,
+                    previous.map(|previous| &previous.#ident),
+                    || format!("{}/{}", pointer, #key),
+                    changes,
+                );
+            }
+        };
+        statements.push(statement);
+    }
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bumaga::ViewState for #name #type_generics #where_clause {
+            fn collect_changes(
+                &self,
+                previous: Option<&Self>,
+                pointer: &str,
+                changes: &mut ::bumaga::StateChanges,
+            ) {
+                #(#statements)*
+            }
+        }
+    })
+}
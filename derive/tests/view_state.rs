@@ -0,0 +1,101 @@
+use bumaga::{Input, View, ViewState};
+use serde::Serialize;
+use serde_json::json;
+
+#[derive(Clone, PartialEq, Serialize)]
+struct Item {
+    name: String,
+}
+
+#[derive(Clone, ViewState)]
+struct User {
+    name: String,
+    #[view(rename = "level")]
+    experience_level: u32,
+}
+
+#[derive(Clone, ViewState)]
+struct State {
+    #[view(nested)]
+    user: User,
+    items: Vec<Item>,
+    #[view(skip)]
+    #[allow(dead_code)]
+    secret: String,
+    unused: bool,
+}
+
+#[test]
+fn test_collect_changed_fields_only() {
+    let state = State {
+        user: User {
+            name: "Alice".to_string(),
+            experience_level: 1,
+        },
+        items: vec![],
+        secret: "xyz".to_string(),
+        unused: false,
+    };
+    let mut changes = vec![];
+    state.collect_changes(None, "", &mut changes);
+    assert_eq!(
+        changes,
+        vec![
+            ("/user/name".to_string(), json!("Alice")),
+            ("/user/level".to_string(), json!(1)),
+            ("/items".to_string(), json!([])),
+            ("/unused".to_string(), json!(false)),
+        ]
+    );
+
+    let mut next = state.clone();
+    next.user.experience_level = 2;
+    next.secret = "abc".to_string();
+    let mut changes = vec![];
+    next.collect_changes(Some(&state), "", &mut changes);
+    assert_eq!(changes, vec![("/user/level".to_string(), json!(2))]);
+}
+
+#[test]
+fn test_update_view_with_state() {
+    let html = r#"<html>
+    <body>
+        <div @id="{user.name}" @title="{user.level}"></div>
+        <div *item="3 {items}" @id="{item.name}"></div>
+    </body>
+    </html>"#;
+    let mut view = View::compile(html, "", "").expect("view valid");
+    let mut state = State {
+        user: User {
+            name: "Alice".to_string(),
+            experience_level: 1,
+        },
+        items: vec![Item {
+            name: "sword".to_string(),
+        }],
+        secret: String::new(),
+        unused: true,
+    };
+    view.update_state(Input::new(), &state)
+        .expect("valid update");
+    state.items.push(Item {
+        name: "shield".to_string(),
+    });
+    state.user.experience_level = 5;
+    view.update_state(Input::new(), &state)
+        .expect("valid update");
+    assert_eq!(
+        view.model(),
+        &json!({
+            "user": { "name": "Alice", "level": 5 },
+            "items": [{ "name": "sword" }, { "name": "shield" }]
+        })
+    );
+    let body = view.body();
+    let ids: Vec<_> = body
+        .children()
+        .iter()
+        .map(|child| child.attrs.get("id").cloned().unwrap_or_default())
+        .collect();
+    assert_eq!(ids, ["Alice", "sword", "shield"]);
+}
@@ -93,20 +93,16 @@ fn draw_element(element: Fragment, fonts: &FontSystem) {
     draw_rectangle(x, y, w, h, color(&element.element.background.color));
     draw_borders(&element);
     // draw_line()
-    if let Some(text) = element.element.text.as_ref() {
+    // lines are laid out by view, text is drawn at baseline of each line
+    for line in &element.element.lines {
         let text_params = TextParams {
             font_size: element.element.font.size as u16,
             font: Some(&fonts.font),
             color: color(&element.element.color),
             ..Default::default()
         };
-
-        draw_text_ex(
-            &text.to_string(),
-            x,
-            y + fonts.offset_y(&text.to_string(), &element.element.font),
-            text_params,
-        );
+        let [x, y] = line.origin;
+        draw_text_ex(&line.text, x, y + line.baseline, text_params);
     }
 
     for fragment in element.children() {
@@ -141,13 +137,6 @@ struct FontSystem {
     font: Font,
 }
 
-impl FontSystem {
-    pub fn offset_y(&self, text: &str, style: &FontFace) -> f32 {
-        let size = measure_text(text, Some(&self.font), style.size as u16, 1.0);
-        size.offset_y
-    }
-}
-
 impl Fonts for FontSystem {
     fn measure(&mut self, text: &str, style: &FontFace, _max_width: Option<f32>) -> [f32; 2] {
         // NOTE: macroquad does not support width constraint measurement,
@@ -157,14 +157,13 @@ fn draw_element(canvas: &Canvas, element: &Element, fonts: &FontSystem, images:
     );
     canvas.draw_rect(rect, &Paint::new(color(element.background.color), None));
     draw_borders(canvas, element);
-    if let Some(text) = element.text.as_ref() {
+    // lines are laid out by view, text is drawn at baseline of each line
+    for line in &element.lines {
         let paint = Paint::new(color(element.color), None);
+        let [x, y] = line.origin;
         canvas.draw_text_align(
-            text,
-            Point::new(
-                element.layout.location.x,
-                element.layout.location.y + fonts.offset_y(text, &element.text_style),
-            ),
+            &line.text,
+            Point::new(x, y + line.baseline),
             &fonts.get_font(element.text_style.font_size),
             &paint,
             Align::Left,
@@ -259,11 +258,6 @@ impl FontSystem {
     fn get_font(&self, size: f32) -> Font {
         Font::from_typeface(self.typeface.clone(), Some(size))
     }
-
-    pub fn offset_y(&self, text: &str, style: &TextStyle) -> f32 {
-        let (_, rect) = self.get_font(style.font_size).measure_text(text, None);
-        -rect.top
-    }
 }
 
 impl Fonts for FontSystem {
@@ -284,9 +278,7 @@ fn user_input<'f, 't>(events: Vec<WindowEvent>, mouse_position: &mut [f32; 2]) -
             //     keys_down.push(map_keycode(keycode))
             // },
             WindowEvent::CursorMoved { position, .. } => {
-                let px = position.x as f32 / WINDOW_SCALE;
-                let py = position.y as f32 / WINDOW_SCALE;
-                *mouse_position = [px, py];
+                *mouse_position = [position.x as f32, position.y as f32];
             }
             WindowEvent::MouseInput { button, state, .. } => match state {
                 ElementState::Pressed => match button {
@@ -319,7 +311,8 @@ fn user_input<'f, 't>(events: Vec<WindowEvent>, mouse_position: &mut [f32; 2]) -
     }
 
     Input::new()
-        .viewport([WINDOW_WIDTH, WINDOW_HEIGHT])
+        .scale(WINDOW_SCALE)
+        .viewport([WINDOW_WIDTH * WINDOW_SCALE, WINDOW_HEIGHT * WINDOW_SCALE])
         .mouse_position(*mouse_position)
         .mouse_buttons_down(buttons_down)
         // .mouse_buttons_up(buttons_up)
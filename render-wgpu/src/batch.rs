@@ -0,0 +1,723 @@
+use crate::glyphs::Glyphs;
+use bumaga::{BorderStyle, DisplayCommand, FontFace, ObjectFit, Rgba, Shadow};
+use bytemuck::{Pod, Zeroable};
+use std::ops::Range;
+
+pub(crate) const FILL: u32 = 0;
+pub(crate) const BORDER: u32 = 1;
+pub(crate) const IMAGE: u32 = 2;
+pub(crate) const SHADOW: u32 = 3;
+pub(crate) const GRADIENT: u32 = 4;
+pub(crate) const GLYPH: u32 = 5;
+
+/// The rounded box drawn by one instanced quad, all values in physical pixels.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub(crate) struct Instance {
+    /// The box rectangle as x, y, width, height.
+    pub rect: [f32; 4],
+    /// The corner radii in order top-left, top-right, bottom-right, bottom-left.
+    pub radius: [f32; 4],
+    /// The border widths in order top, right, bottom, left, offsets of gradient stops,
+    /// corner radii of shadow shape.
+    pub widths: [f32; 4],
+    /// The fill color, border colors of sides in order top, right, bottom, left or
+    /// colors of gradient stops.
+    pub colors: [[f32; 4]; 4],
+    pub clip_rect: [f32; 4],
+    pub clip_radius: [f32; 4],
+    /// The image placement rectangle, box is filled by image stretched over it,
+    /// shadow shape rectangle or glyph rectangle in atlas.
+    pub image: [f32; 4],
+    /// The blur deviation and inset flag of shadow, angle, stop count and segment
+    /// flags of gradient, last value is distance quad is drawn beyond box.
+    pub params: [f32; 4],
+    /// The kind of instance, sprite-sheet frame index and frame count for images,
+    /// border styles of sides for borders.
+    pub kind: [u32; 4],
+}
+
+/// The texture instances of batch are drawn with.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Texture {
+    Blank,
+    Image(String),
+    Glyphs,
+}
+
+/// The instances drawn with one texture.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Batch {
+    pub texture: Texture,
+    pub instances: Range<u32>,
+}
+
+/// The text run placed by display list, application draws it by its text renderer
+/// after view if renderer has no fonts to draw it, see [`crate::WgpuRenderer::set_font`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextPlacement {
+    pub text: String,
+    /// The position of text box in physical pixels, translation applied.
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    /// The color of text or shadow color if placement is shadow of text.
+    pub color: Rgba,
+    pub font: FontFace,
+    /// The offset of first line baseline from top of text box in physical pixels.
+    pub baseline: f32,
+    /// The clip rectangle in physical pixels as x, y, width, height.
+    pub clip: Option<[f32; 4]>,
+    /// The shadow drawn instead of text, offset and blur in physical pixels.
+    pub shadow: Option<Shadow>,
+}
+
+#[derive(Clone, Copy)]
+struct Clip {
+    rect: [f32; 4],
+    radius: [f32; 4],
+}
+
+/// The batches of display list ready to upload, instances are grouped in
+/// consecutive batches by image to keep paint order.
+#[derive(Default)]
+pub(crate) struct Frame {
+    pub instances: Vec<Instance>,
+    pub batches: Vec<Batch>,
+    pub text: Vec<TextPlacement>,
+}
+
+impl Frame {
+    /// Converts display list to instances, image sizes are needed to fit images,
+    /// text is drawn by glyphs if there are fonts.
+    pub fn build(
+        commands: &[DisplayCommand],
+        scale: f32,
+        linear: bool,
+        image_size: impl Fn(&str) -> Option<[f32; 2]>,
+        glyphs: &mut Glyphs,
+    ) -> Self {
+        let mut frame = Frame::default();
+        let mut clips: Vec<Clip> = vec![];
+        let mut translate = [0.0; 2];
+        let color = |[r, g, b, a]: Rgba| {
+            let channel = |value: u8| {
+                let value = value as f32 / 255.0;
+                if linear {
+                    srgb_to_linear(value)
+                } else {
+                    value
+                }
+            };
+            [channel(r), channel(g), channel(b), a as f32 / 255.0]
+        };
+        let rect = |position: [f32; 2], size: [f32; 2], translate: [f32; 2]| {
+            [
+                (position[0] + translate[0]) * scale,
+                (position[1] + translate[1]) * scale,
+                size[0] * scale,
+                size[1] * scale,
+            ]
+        };
+        for command in commands {
+            let clip = clips.last().copied().unwrap_or(Clip {
+                rect: [-1e6, -1e6, 2e6, 2e6],
+                radius: [0.0; 4],
+            });
+            match command {
+                DisplayCommand::PushClip {
+                    position,
+                    size,
+                    radius,
+                } => {
+                    let rect = intersect(clip.rect, rect(*position, *size, translate));
+                    let radius = radius.map(|radius| radius * scale);
+                    clips.push(Clip { rect, radius });
+                }
+                DisplayCommand::PopClip => {
+                    clips.pop();
+                }
+                DisplayCommand::Transform { translate: value } => translate = *value,
+                // custom materials are bound by engines, drawn by default here
+                DisplayCommand::Material { .. } => {}
+                DisplayCommand::Rect {
+                    position,
+                    size,
+                    color: fill,
+                    radius,
+                } => {
+                    let instance = Instance {
+                        rect: rect(*position, *size, translate),
+                        radius: radius.map(|radius| radius * scale),
+                        widths: [0.0; 4],
+                        colors: [color(*fill); 4],
+                        clip_rect: clip.rect,
+                        clip_radius: clip.radius,
+                        image: [0.0; 4],
+                        params: [0.0; 4],
+                        kind: [FILL, 0, 0, 0],
+                    };
+                    frame.push(Texture::Blank, instance);
+                }
+                DisplayCommand::BoxShadow {
+                    position,
+                    size,
+                    radius,
+                    shadow,
+                } => {
+                    let sigma = shadow.blur / 2.0 * scale;
+                    let spread = match shadow.inset {
+                        true => -shadow.spread * scale,
+                        false => shadow.spread * scale,
+                    };
+                    let [dx, dy] = shadow.offset.map(|offset| offset * scale);
+                    let [x, y, width, height] = rect(*position, *size, translate);
+                    let shape = [
+                        x + dx - spread,
+                        y + dy - spread,
+                        (width + 2.0 * spread).max(0.0),
+                        (height + 2.0 * spread).max(0.0),
+                    ];
+                    let shape_radius = radius.map(|radius| match radius > 0.0 {
+                        true => (radius * scale + spread).max(0.0),
+                        false => 0.0,
+                    });
+                    // outer shadow quad covers blurred shape, inset shadow is inside of box
+                    let outset = match shadow.inset {
+                        true => 0.0,
+                        false => spread.max(0.0) + dx.abs().max(dy.abs()) + 3.0 * sigma + 1.0,
+                    };
+                    let inset = if shadow.inset { 1.0 } else { 0.0 };
+                    let instance = Instance {
+                        rect: [x, y, width, height],
+                        radius: radius.map(|radius| radius * scale),
+                        widths: shape_radius,
+                        colors: [color(shadow.color); 4],
+                        clip_rect: clip.rect,
+                        clip_radius: clip.radius,
+                        image: shape,
+                        params: [sigma, inset, 0.0, outset],
+                        kind: [SHADOW, 0, 0, 0],
+                    };
+                    frame.push(Texture::Blank, instance);
+                }
+                DisplayCommand::Gradient {
+                    position,
+                    size,
+                    radius,
+                    gradient,
+                } => {
+                    // gradient of many stops is drawn by segments of four stops sharing ends
+                    let stops = &gradient.stops;
+                    let last = match stops.len() {
+                        0 => continue,
+                        count => count - 1,
+                    };
+                    let mut start = 0;
+                    loop {
+                        let end = (start + 3).min(last);
+                        let mut colors = [color(stops[end].color); 4];
+                        let mut offsets = [stops[end].offset; 4];
+                        for (index, stop) in stops[start..=end].iter().enumerate() {
+                            colors[index] = color(stop.color);
+                            offsets[index] = stop.offset;
+                        }
+                        let count = (end - start + 1) as f32;
+                        let flags = (start == 0) as u32 | ((end == last) as u32) << 1;
+                        let instance = Instance {
+                            rect: rect(*position, *size, translate),
+                            radius: radius.map(|radius| radius * scale),
+                            widths: offsets,
+                            colors,
+                            clip_rect: clip.rect,
+                            clip_radius: clip.radius,
+                            image: [0.0; 4],
+                            params: [gradient.angle.to_radians(), count, flags as f32, 0.0],
+                            kind: [GRADIENT, 0, 0, 0],
+                        };
+                        frame.push(Texture::Blank, instance);
+                        if end == last {
+                            break;
+                        }
+                        start = end;
+                    }
+                }
+                DisplayCommand::Border {
+                    position,
+                    size,
+                    widths,
+                    colors,
+                    styles,
+                    radius,
+                } => {
+                    let mut widths = widths.map(|width| width * scale);
+                    let mut packed = 0;
+                    for (index, (width, style)) in widths.iter_mut().zip(styles).enumerate() {
+                        if !style.is_visible() {
+                            *width = 0.0;
+                        }
+                        packed |= border_style(*style) << (index * 4);
+                    }
+                    let instance = Instance {
+                        rect: rect(*position, *size, translate),
+                        radius: radius.map(|radius| radius * scale),
+                        widths,
+                        colors: colors.map(color),
+                        clip_rect: clip.rect,
+                        clip_radius: clip.radius,
+                        image: [0.0; 4],
+                        params: [0.0; 4],
+                        kind: [BORDER, packed, 0, 0],
+                    };
+                    frame.push(Texture::Blank, instance);
+                }
+                DisplayCommand::Image {
+                    src,
+                    position,
+                    size,
+                    offset,
+                    fit,
+                    opacity,
+                    radius,
+                    frame: sprite,
+                } => {
+                    let image = match image_size(src) {
+                        Some(image) => image,
+                        None => continue,
+                    };
+                    // fit and offset apply to one frame of sprite-sheet
+                    let [index, count] = [sprite[0], sprite[1].max(1)];
+                    let image = [image[0] / count as f32, image[1]];
+                    let [sx, sy] = fit_scale(*fit, image, *size);
+                    let [x, y] = [
+                        position[0] + (size[0] - image[0] * sx) / 2.0 - offset[0] * sx,
+                        position[1] + (size[1] - image[1] * sy) / 2.0 - offset[1] * sy,
+                    ];
+                    let instance = Instance {
+                        rect: rect(*position, *size, translate),
+                        radius: radius.map(|radius| radius * scale),
+                        widths: [0.0; 4],
+                        colors: [[1.0, 1.0, 1.0, *opacity]; 4],
+                        clip_rect: clip.rect,
+                        clip_radius: clip.radius,
+                        image: rect([x, y], [image[0] * sx, image[1] * sy], translate),
+                        params: [0.0; 4],
+                        kind: [IMAGE, index, count, 0],
+                    };
+                    frame.push(Texture::Image(src.clone()), instance);
+                }
+                DisplayCommand::TextRun {
+                    text,
+                    position,
+                    size,
+                    color: fill,
+                    font,
+                    baseline,
+                } => {
+                    let [x, y, width, height] = rect(*position, *size, translate);
+                    let placement = TextPlacement {
+                        text: text.clone(),
+                        position: [x, y],
+                        size: [width, height],
+                        color: *fill,
+                        font: font.clone(),
+                        baseline: baseline * scale,
+                        clip: (!clips.is_empty()).then_some(clip.rect),
+                        shadow: None,
+                    };
+                    let outline = color(font.stroke.color.unwrap_or(*fill));
+                    let colors = [color(*fill), outline];
+                    if !frame.push_glyphs(&placement, glyphs, scale, clip, colors) {
+                        frame.text.push(placement);
+                    }
+                }
+                DisplayCommand::TextShadow {
+                    text,
+                    position,
+                    size,
+                    font,
+                    baseline,
+                    shadow,
+                } => {
+                    let [x, y, width, height] = rect(*position, *size, translate);
+                    let placement = TextPlacement {
+                        text: text.clone(),
+                        position: [x, y],
+                        size: [width, height],
+                        color: shadow.color,
+                        font: font.clone(),
+                        baseline: baseline * scale,
+                        clip: (!clips.is_empty()).then_some(clip.rect),
+                        shadow: Some(Shadow {
+                            offset: shadow.offset.map(|offset| offset * scale),
+                            blur: shadow.blur * scale,
+                            spread: shadow.spread * scale,
+                            ..*shadow
+                        }),
+                    };
+                    let colors = [color(shadow.color); 2];
+                    if !frame.push_glyphs(&placement, glyphs, scale, clip, colors) {
+                        frame.text.push(placement);
+                    }
+                }
+            }
+        }
+        frame
+    }
+
+    fn push(&mut self, texture: Texture, instance: Instance) {
+        let index = self.instances.len() as u32;
+        self.instances.push(instance);
+        if let Some(batch) = self.batches.last_mut() {
+            if batch.texture == texture {
+                batch.instances.end = index + 1;
+                return;
+            }
+        }
+        self.batches.push(Batch {
+            texture,
+            instances: index..index + 1,
+        });
+    }
+
+    /// Draws text placement by glyphs of renderer fonts, colors of text and its
+    /// outline, returns false if renderer has no fonts.
+    fn push_glyphs(
+        &mut self,
+        placement: &TextPlacement,
+        glyphs: &mut Glyphs,
+        scale: f32,
+        clip: Clip,
+        [fill, outline]: [[f32; 4]; 2],
+    ) -> bool {
+        if glyphs.is_empty() {
+            return false;
+        }
+        let [x, y] = placement.position;
+        let [width, height] = placement.size;
+        let (offset, blur) = match placement.shadow {
+            Some(shadow) => (shadow.offset, shadow.blur / 2.0),
+            None => ([0.0; 2], 0.0),
+        };
+        let rect = [x + offset[0], y + offset[1], width, height];
+        let font = &placement.font;
+        let quads = glyphs.layout(&placement.text, font, rect, placement.baseline, scale, blur);
+        let mut copies = vec![];
+        let stroke = font.stroke.width * scale;
+        if stroke > 0.0 && placement.shadow.is_none() {
+            // outline is drawn by glyph copies shifted around as by egui adapter
+            for [dx, dy] in OUTLINE {
+                copies.push(([dx * stroke, dy * stroke], outline));
+            }
+        }
+        copies.push(([0.0; 2], fill));
+        for ([dx, dy], color) in copies {
+            for quad in &quads {
+                let [x, y, width, height] = quad.rect;
+                let instance = Instance {
+                    rect: [x + dx, y + dy, width, height],
+                    radius: [0.0; 4],
+                    widths: [0.0; 4],
+                    colors: [color; 4],
+                    clip_rect: clip.rect,
+                    clip_radius: clip.radius,
+                    image: quad.uv,
+                    params: [0.0; 4],
+                    kind: [GLYPH, 0, 0, 0],
+                };
+                self.push(Texture::Glyphs, instance);
+            }
+        }
+        true
+    }
+}
+
+/// The directions of text copies drawing text outline.
+pub(crate) const OUTLINE: [[f32; 2]; 8] = [
+    [-1.0, -1.0],
+    [0.0, -1.0],
+    [1.0, -1.0],
+    [-1.0, 0.0],
+    [1.0, 0.0],
+    [-1.0, 1.0],
+    [0.0, 1.0],
+    [1.0, 1.0],
+];
+
+/// Returns border style code of shader, zero for invisible border.
+fn border_style(style: BorderStyle) -> u32 {
+    match style {
+        BorderStyle::None | BorderStyle::Hidden => 0,
+        BorderStyle::Solid => 1,
+        BorderStyle::Dashed => 2,
+        BorderStyle::Dotted => 3,
+        BorderStyle::Double => 4,
+    }
+}
+
+fn intersect(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    let left = a[0].max(b[0]);
+    let top = a[1].max(b[1]);
+    let right = (a[0] + a[2]).min(b[0] + b[2]);
+    let bottom = (a[1] + a[3]).min(b[1] + b[3]);
+    [left, top, (right - left).max(0.0), (bottom - top).max(0.0)]
+}
+
+fn fit_scale(fit: ObjectFit, image: [f32; 2], size: [f32; 2]) -> [f32; 2] {
+    let [width, height] = [size[0] / image[0], size[1] / image[1]];
+    let scale = match fit {
+        ObjectFit::Fill => return [width, height],
+        ObjectFit::Contain => width.min(height),
+        ObjectFit::Cover => width.max(height),
+        ObjectFit::None => 1.0,
+        ObjectFit::ScaleDown => width.min(height).min(1.0),
+    };
+    [scale, scale]
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bumaga::{BorderStyle, ColorStop, FontFace, LinearGradient, TextAlign};
+
+    fn font() -> FontFace {
+        FontFace {
+            family: "system-ui".into(),
+            size: 16.0,
+            style: "normal".into(),
+            weight: 400,
+            line_height: 1.0,
+            align: TextAlign::Start,
+            direction: Default::default(),
+            stroke: Default::default(),
+            hyphens: Default::default(),
+            lang: String::new(),
+            tab_size: Default::default(),
+        }
+    }
+
+    #[test]
+    pub fn test_batches_keep_paint_order() {
+        let red = [255, 0, 0, 255];
+        let commands = vec![
+            DisplayCommand::Rect {
+                position: [0.0, 0.0],
+                size: [10.0, 10.0],
+                color: red,
+                radius: [2.0; 4],
+            },
+            DisplayCommand::PushClip {
+                position: [5.0, 5.0],
+                size: [10.0, 10.0],
+                radius: [4.0; 4],
+            },
+            DisplayCommand::Transform {
+                translate: [1.0, 0.0],
+            },
+            DisplayCommand::Image {
+                src: "icon.png".to_string(),
+                position: [0.0, 0.0],
+                size: [20.0, 10.0],
+                offset: [0.0, 0.0],
+                fit: ObjectFit::Contain,
+                opacity: 0.5,
+                radius: [0.0; 4],
+                frame: [0, 1],
+            },
+            DisplayCommand::TextRun {
+                text: "Hello".to_string(),
+                position: [0.0, 0.0],
+                size: [10.0, 10.0],
+                color: red,
+                font: font(),
+                baseline: 8.0,
+            },
+            DisplayCommand::PopClip,
+            DisplayCommand::Border {
+                position: [0.0, 0.0],
+                size: [10.0, 10.0],
+                widths: [1.0; 4],
+                colors: [red; 4],
+                styles: [
+                    BorderStyle::Solid,
+                    BorderStyle::None,
+                    BorderStyle::Solid,
+                    BorderStyle::Dashed,
+                ],
+                radius: [0.0; 4],
+            },
+        ];
+        let mut glyphs = Glyphs::default();
+        let frame = Frame::build(&commands, 2.0, false, |_| Some([10.0, 10.0]), &mut glyphs);
+        let batches: Vec<_> = frame
+            .batches
+            .iter()
+            .map(|batch| (batch.texture.clone(), batch.instances.clone()))
+            .collect();
+        let icon = Texture::Image("icon.png".to_string());
+        assert_eq!(
+            batches,
+            [(Texture::Blank, 0..1), (icon, 1..2), (Texture::Blank, 2..3)]
+        );
+        let [fill, image, border] = [0, 1, 2].map(|index| frame.instances[index]);
+        assert_eq!(fill.rect, [0.0, 0.0, 20.0, 20.0]);
+        assert_eq!(fill.radius, [4.0; 4]);
+        assert_eq!(image.clip_rect, [10.0, 10.0, 20.0, 20.0]);
+        assert_eq!(image.clip_radius, [8.0; 4]);
+        assert_eq!(
+            image.image,
+            [12.0, 0.0, 20.0, 20.0],
+            "contained and centered"
+        );
+        assert_eq!(image.colors[0], [1.0, 1.0, 1.0, 0.5]);
+        assert_eq!(image.kind, [IMAGE, 0, 1, 0]);
+        assert_eq!(border.widths, [2.0, 0.0, 2.0, 2.0]);
+        assert_eq!(border.kind, [BORDER, 0x2101, 0, 0], "styles by 4 bits");
+        assert_eq!(border.clip_radius, [0.0; 4], "clip popped");
+        assert_eq!(frame.text[0].position, [2.0, 0.0]);
+        assert_eq!(frame.text[0].clip, Some([10.0, 10.0, 20.0, 20.0]));
+        assert_eq!(frame.text[0].baseline, 16.0);
+    }
+
+    #[test]
+    pub fn test_shadows_and_gradient_segments() {
+        let stop = |offset: f32| ColorStop {
+            color: [255, 0, 0, 255],
+            offset,
+        };
+        let commands = vec![
+            DisplayCommand::BoxShadow {
+                position: [10.0, 10.0],
+                size: [20.0, 10.0],
+                radius: [4.0, 0.0, 4.0, 0.0],
+                shadow: Shadow {
+                    offset: [1.0, 2.0],
+                    blur: 4.0,
+                    spread: 1.0,
+                    color: [0, 0, 0, 255],
+                    inset: false,
+                },
+            },
+            DisplayCommand::BoxShadow {
+                position: [10.0, 10.0],
+                size: [20.0, 10.0],
+                radius: [0.0; 4],
+                shadow: Shadow {
+                    offset: [0.0, 0.0],
+                    blur: 0.0,
+                    spread: 2.0,
+                    color: [0, 0, 0, 255],
+                    inset: true,
+                },
+            },
+            DisplayCommand::Gradient {
+                position: [0.0, 0.0],
+                size: [10.0, 10.0],
+                radius: [0.0; 4],
+                gradient: LinearGradient {
+                    angle: 90.0,
+                    stops: (0..6).map(|index| stop(index as f32 * 0.2)).collect(),
+                },
+            },
+        ];
+        let mut glyphs = Glyphs::default();
+        let frame = Frame::build(&commands, 2.0, false, |_| None, &mut glyphs);
+        let [outer, inset, first, second] = [0, 1, 2, 3].map(|index| frame.instances[index]);
+        assert_eq!(outer.kind[0], SHADOW);
+        assert_eq!(
+            outer.rect,
+            [20.0, 20.0, 40.0, 20.0],
+            "box to mask shadow out"
+        );
+        assert_eq!(
+            outer.image,
+            [20.0, 22.0, 44.0, 24.0],
+            "moved and spread shape"
+        );
+        assert_eq!(
+            outer.widths,
+            [10.0, 0.0, 10.0, 0.0],
+            "spread rounded corners"
+        );
+        assert_eq!(outer.params, [4.0, 0.0, 0.0, 2.0 + 4.0 + 12.0 + 1.0]);
+        assert_eq!(inset.image, [24.0, 24.0, 32.0, 12.0], "shrunk shape");
+        assert_eq!(inset.params, [0.0, 1.0, 0.0, 0.0], "drawn inside of box");
+        assert_eq!(frame.instances.len(), 4, "two segments of four stops");
+        assert_eq!(first.kind[0], GRADIENT);
+        assert_eq!(first.widths, [0.0, 0.2, 0.4, 0.6]);
+        assert_eq!(first.params[1..3], [4.0, 1.0], "first segment");
+        assert_eq!(second.widths, [0.6, 0.8, 1.0, 1.0]);
+        assert_eq!(second.params[1..3], [3.0, 2.0], "last segment");
+    }
+
+    #[test]
+    pub fn test_text_drawn_by_glyphs() {
+        let text = |text: &str, width: f32| DisplayCommand::TextRun {
+            text: text.to_string(),
+            position: [0.0, 0.0],
+            size: [width, 40.0],
+            color: [0, 0, 0, 255],
+            font: font(),
+            baseline: 12.0,
+        };
+        let shadow = DisplayCommand::TextShadow {
+            text: "Hello".to_string(),
+            position: [0.0, 0.0],
+            size: [100.0, 40.0],
+            font: font(),
+            baseline: 12.0,
+            shadow: Shadow {
+                offset: [1.0, 2.0],
+                color: [0, 0, 255, 255],
+                ..Default::default()
+            },
+        };
+        let commands = vec![shadow.clone(), text("Hello", 100.0)];
+        let placed = Frame::build(&commands, 1.0, false, |_| None, &mut Glyphs::default());
+        assert_eq!(placed.text.len(), 2, "placed without fonts");
+        assert_eq!(placed.text[0].color, [0, 0, 255, 255]);
+        assert!(placed.text[0].shadow.is_some() && placed.text[1].shadow.is_none());
+        let mut glyphs = Glyphs::default();
+        let roboto = include_bytes!("../../examples/shared/Roboto/Roboto-Regular.ttf");
+        glyphs.set_font("Roboto", roboto.to_vec()).unwrap();
+        let frame = Frame::build(&commands, 2.0, false, |_| None, &mut glyphs);
+        assert!(frame.text.is_empty(), "drawn by first font");
+        assert_eq!(frame.batches.len(), 1);
+        assert_eq!(frame.batches[0].texture, Texture::Glyphs);
+        let (shadows, letters) = frame.instances.split_at(frame.instances.len() / 2);
+        assert_eq!(letters.len(), 5);
+        for (shadow, letter) in shadows.iter().zip(letters) {
+            assert_eq!(letter.kind[0], GLYPH);
+            let [x, y, width, height] = letter.rect;
+            assert_eq!(
+                shadow.rect,
+                [x + 2.0, y + 4.0, width, height],
+                "moved by offset"
+            );
+            assert_eq!(shadow.image, letter.image, "same glyph in atlas");
+            assert_eq!(shadow.colors[0], [0.0, 0.0, 1.0, 1.0]);
+        }
+        assert!(glyphs.take_dirty().is_some() && !glyphs.pixels().is_empty());
+        let frame = Frame::build(
+            &[text("Hello Hello", 10.0)],
+            2.0,
+            false,
+            |_| None,
+            &mut glyphs,
+        );
+        let lines = frame
+            .instances
+            .iter()
+            .map(|glyph| glyph.rect[1] + glyph.rect[3]);
+        let bottoms: Vec<f32> = lines.collect();
+        assert!(bottoms[4] < bottoms[5], "second word wrapped");
+        assert_eq!(glyphs.take_dirty(), None, "glyphs cached");
+    }
+}
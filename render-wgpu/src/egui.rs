@@ -0,0 +1,139 @@
+//! The adapter drawing view into egui area by wgpu paint callback, so screens made by
+//! HTML and CSS can be embedded into egui applications gradually. Text runs and their
+//! shadows are drawn by egui painter over view, so fonts set to renderer are not used,
+//! input is converted by `bumaga::egui_input`.
+//!
+//! ```ignore
+//! bumaga_render_wgpu::egui::install(frame.wgpu_render_state().unwrap());
+//! // every frame
+//! let (rect, _) = ui.allocate_exact_size(ui.available_size(), egui::Sense::hover());
+//! let input = ui.input(|state| egui_input.take_input(state, rect));
+//! view.update(input, value)?;
+//! bumaga_render_wgpu::egui::paint(ui.painter(), rect, view.display_list()?);
+//! ```
+
+use crate::batch::{Frame, OUTLINE};
+use crate::glyphs::Glyphs;
+use crate::WgpuRenderer;
+use bumaga::{DisplayCommand, TextAlign, TextDirection};
+use egui::{Color32, FontId, Painter, Rect, Vec2};
+use egui_wgpu::{CallbackResources, CallbackTrait, RenderState, ScreenDescriptor};
+
+/// Registers renderer in egui paint callback resources, must be called once
+/// before view painted. Images are uploaded to renderer via [`with_renderer`].
+pub fn install(render_state: &RenderState) {
+    let renderer = WgpuRenderer::new(&render_state.device, render_state.target_format);
+    render_state
+        .renderer
+        .write()
+        .callback_resources
+        .insert(renderer);
+}
+
+/// Gives access to renderer registered by [`install`], e.g. to set images.
+pub fn with_renderer<R>(
+    render_state: &RenderState,
+    action: impl FnOnce(&mut WgpuRenderer, &wgpu::Device, &wgpu::Queue) -> R,
+) -> Option<R> {
+    let mut egui = render_state.renderer.write();
+    let renderer = egui.callback_resources.get_mut::<WgpuRenderer>()?;
+    Some(action(renderer, &render_state.device, &render_state.queue))
+}
+
+/// Paints display list of view into rect in points. Renderer keeps one prepared
+/// display list, so only one view can be painted per frame.
+pub fn paint(painter: &Painter, rect: Rect, commands: Vec<DisplayCommand>) {
+    let frame = Frame::build(&commands, 1.0, false, |_| None, &mut Glyphs::default());
+    let callback = ViewCallback {
+        commands: commands
+            .into_iter()
+            .filter(|command| {
+                !matches!(
+                    command,
+                    DisplayCommand::TextRun { .. } | DisplayCommand::TextShadow { .. }
+                )
+            })
+            .collect(),
+        size: rect.size(),
+    };
+    painter.add(egui_wgpu::Callback::new_paint_callback(rect, callback));
+    for run in frame.text {
+        let [x, y] = run.position;
+        let [width, _] = run.size;
+        let [r, g, b, a] = run.color;
+        let color = Color32::from_rgba_unmultiplied(r, g, b, a);
+        let font = FontId::proportional(run.font.size);
+        let stroke = run.font.stroke;
+        // egui has no blur, shadow is drawn by text copy in shadow color moved by offset
+        let shift = run.shadow.map(|shadow| Vec2::from(shadow.offset));
+        let outline = (stroke.width > 0.0 && shift.is_none()).then(|| {
+            let [r, g, b, a] = stroke.color.unwrap_or(run.color);
+            let color = Color32::from_rgba_unmultiplied(r, g, b, a);
+            (
+                painter.layout(run.text.clone(), font.clone(), color, width),
+                color,
+            )
+        });
+        let galley = painter.layout(run.text, font, color, width);
+        let rtl = run.font.direction == TextDirection::Rtl;
+        let offset = match (&run.font.align, rtl) {
+            (TextAlign::Center, _) => (width - galley.size().x) / 2.0,
+            (TextAlign::Right, _) | (TextAlign::End, false) | (TextAlign::Start, true) => {
+                width - galley.size().x
+            }
+            _ => 0.0,
+        };
+        let clip = match run.clip {
+            Some([x, y, width, height]) => {
+                Rect::from_min_size(rect.min + Vec2::new(x, y), Vec2::new(width, height))
+            }
+            None => rect,
+        };
+        let position = rect.min + Vec2::new(x + offset, y) + shift.unwrap_or_default();
+        let painter = painter.with_clip_rect(painter.clip_rect().intersect(clip.intersect(rect)));
+        if let Some((outline, color)) = outline {
+            // egui has no glyph stroke, outline is drawn by text copies shifted around
+            for direction in OUTLINE {
+                let shift = Vec2::from(direction) * stroke.width;
+                painter.galley(position + shift, outline.clone(), color);
+            }
+        }
+        painter.galley(position, galley, color);
+    }
+}
+
+struct ViewCallback {
+    commands: Vec<DisplayCommand>,
+    /// The size of view area in points.
+    size: Vec2,
+}
+
+impl CallbackTrait for ViewCallback {
+    fn prepare(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        screen: &ScreenDescriptor,
+        _encoder: &mut wgpu::CommandEncoder,
+        resources: &mut CallbackResources,
+    ) -> Vec<wgpu::CommandBuffer> {
+        if let Some(renderer) = resources.get_mut::<WgpuRenderer>() {
+            let scale = screen.pixels_per_point;
+            let size = self.size * scale;
+            let size = [size.x.round() as u32, size.y.round() as u32];
+            renderer.prepare(device, queue, &self.commands, size, scale);
+        }
+        vec![]
+    }
+
+    fn paint(
+        &self,
+        _info: egui::PaintCallbackInfo,
+        pass: &mut wgpu::RenderPass<'static>,
+        resources: &CallbackResources,
+    ) {
+        if let Some(renderer) = resources.get::<WgpuRenderer>() {
+            renderer.render(pass);
+        }
+    }
+}
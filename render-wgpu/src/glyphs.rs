@@ -0,0 +1,315 @@
+use bumaga::{FontFace, TextAlign, TextDirection};
+use fontdue::{Font, FontSettings};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// The size of square glyph atlas texture in pixels.
+pub(crate) const ATLAS_SIZE: u32 = 1024;
+
+/// The glyph of text run placed on screen, all values in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct GlyphQuad {
+    /// The glyph rectangle as x, y, width, height, aligned to pixel grid.
+    pub rect: [f32; 4],
+    /// The glyph rectangle in atlas as normalized x, y, width, height.
+    pub uv: [f32; 4],
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: usize,
+    glyph: u16,
+    size: u32,
+    blur: u32,
+}
+
+/// The glyph bitmap in atlas, offset is position of bitmap top-left corner
+/// relative to pen position on baseline.
+#[derive(Clone, Copy)]
+struct AtlasGlyph {
+    rect: [u32; 4],
+    offset: [i32; 2],
+}
+
+/// The glyph of line with font, glyph index and pen position from line start.
+struct LineGlyph {
+    font: usize,
+    glyph: u16,
+    x: f32,
+}
+
+/// The fonts and glyph atlas of renderer, glyphs are rasterized on CPU once per
+/// font size and blur, atlas is uploaded by rows changed since last upload.
+#[derive(Default)]
+pub(crate) struct Glyphs {
+    fonts: Vec<(String, Font)>,
+    /// The RGBA pixels of atlas, white color with glyph coverage in alpha.
+    pixels: Vec<u8>,
+    glyphs: HashMap<GlyphKey, Option<AtlasGlyph>>,
+    /// The position of next glyph and height of current shelf.
+    cursor: [u32; 2],
+    shelf: u32,
+    dirty: Option<Range<u32>>,
+    overflow: bool,
+}
+
+impl Glyphs {
+    pub fn set_font(&mut self, family: &str, data: Vec<u8>) -> Result<(), &'static str> {
+        let font = Font::from_bytes(data, FontSettings::default())?;
+        match self.fonts.iter_mut().find(|(name, _)| name == family) {
+            Some((_, current)) => *current = font,
+            None => self.fonts.push((family.to_string(), font)),
+        }
+        self.clear();
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fonts.is_empty()
+    }
+
+    /// Checks atlas run out of space since last clear, so some glyphs are missing.
+    pub fn is_overflow(&self) -> bool {
+        self.overflow
+    }
+
+    /// Removes all glyphs from atlas, whole atlas is uploaded next time.
+    pub fn clear(&mut self) {
+        self.pixels.clear();
+        self.glyphs.clear();
+        self.cursor = [0; 2];
+        self.shelf = 0;
+        self.dirty = Some(0..ATLAS_SIZE);
+        self.overflow = false;
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Returns atlas rows changed since last call.
+    pub fn take_dirty(&mut self) -> Option<Range<u32>> {
+        self.dirty.take()
+    }
+
+    /// Lays out text into rectangle and returns its glyphs. Lines are broken greedily
+    /// at spaces to fit rectangle width, first line baseline is offset from top of
+    /// rectangle, blur is standard deviation of gaussian blur of glyphs.
+    pub fn layout(
+        &mut self,
+        text: &str,
+        face: &FontFace,
+        rect: [f32; 4],
+        baseline: f32,
+        scale: f32,
+        blur: f32,
+    ) -> Vec<GlyphQuad> {
+        let mut quads = vec![];
+        // text of unknown font family is drawn by first font
+        let primary = self
+            .fonts
+            .iter()
+            .position(|(name, _)| *name == face.family)
+            .unwrap_or(0);
+        let size = face.size * scale;
+        let line_height = face.size * face.line_height * scale;
+        let [x, y, width, _] = rect;
+        let lines = self.break_lines(text, primary, size, width);
+        for (index, (glyphs, line_width)) in lines.into_iter().enumerate() {
+            let rtl = face.direction == TextDirection::Rtl;
+            let offset = match (&face.align, rtl) {
+                (TextAlign::Center, _) => (width - line_width) / 2.0,
+                (TextAlign::Right, _) | (TextAlign::End, false) | (TextAlign::Start, true) => {
+                    width - line_width
+                }
+                _ => 0.0,
+            };
+            let pen_y = (y + baseline + line_height * index as f32).round();
+            for glyph in glyphs {
+                let key = GlyphKey {
+                    font: glyph.font,
+                    glyph: glyph.glyph,
+                    size: size.to_bits(),
+                    blur: blur.to_bits(),
+                };
+                let placed = match self.glyphs.get(&key) {
+                    Some(placed) => *placed,
+                    None => {
+                        let placed = self.rasterize(key, size, blur);
+                        self.glyphs.insert(key, placed);
+                        placed
+                    }
+                };
+                if let Some(AtlasGlyph {
+                    rect,
+                    offset: [left, top],
+                }) = placed
+                {
+                    let pen_x = (x + offset + glyph.x).round();
+                    let [u, v, w, h] = rect.map(|value| value as f32);
+                    let atlas = ATLAS_SIZE as f32;
+                    quads.push(GlyphQuad {
+                        rect: [pen_x + left as f32, pen_y + top as f32, w, h],
+                        uv: [u / atlas, v / atlas, w / atlas, h / atlas],
+                    });
+                }
+            }
+        }
+        quads
+    }
+
+    /// Breaks text into lines fitting width, returns glyphs of lines and widths of
+    /// lines without trailing spaces. Each word is measured once.
+    fn break_lines(
+        &self,
+        text: &str,
+        primary: usize,
+        size: f32,
+        width: f32,
+    ) -> Vec<(Vec<LineGlyph>, f32)> {
+        let mut lines = vec![];
+        for paragraph in text.split('\n') {
+            let mut line = vec![];
+            let mut pen = 0.0;
+            let mut line_width = 0.0;
+            for word in paragraph.split_inclusive(' ') {
+                let mut glyphs = vec![];
+                let mut advance = 0.0;
+                let mut visible = 0.0;
+                let mut previous: Option<(usize, u16)> = None;
+                for character in word.chars() {
+                    let font = self.find_font(primary, character);
+                    let (_, face) = &self.fonts[font];
+                    let glyph = face.lookup_glyph_index(character);
+                    if let Some((previous_font, previous_glyph)) = previous {
+                        if previous_font == font {
+                            advance += face
+                                .horizontal_kern_indexed(previous_glyph, glyph, size)
+                                .unwrap_or(0.0);
+                        }
+                    }
+                    glyphs.push(LineGlyph {
+                        font,
+                        glyph,
+                        x: advance,
+                    });
+                    advance += face.metrics_indexed(glyph, size).advance_width;
+                    if !character.is_whitespace() {
+                        visible = advance;
+                    }
+                    previous = Some((font, glyph));
+                }
+                if !line.is_empty() && pen + visible > width + 0.5 {
+                    lines.push((line, line_width));
+                    line = vec![];
+                    pen = 0.0;
+                }
+                line_width = pen + visible;
+                for mut glyph in glyphs {
+                    glyph.x += pen;
+                    line.push(glyph);
+                }
+                pen += advance;
+            }
+            lines.push((line, line_width));
+        }
+        lines
+    }
+
+    /// Returns index of font with glyph of character, primary font if no font has it.
+    fn find_font(&self, primary: usize, character: char) -> usize {
+        if self.fonts[primary].1.lookup_glyph_index(character) != 0 {
+            return primary;
+        }
+        self.fonts
+            .iter()
+            .position(|(_, font)| font.lookup_glyph_index(character) != 0)
+            .unwrap_or(primary)
+    }
+
+    fn rasterize(&mut self, key: GlyphKey, size: f32, blur: f32) -> Option<AtlasGlyph> {
+        let (_, font) = &self.fonts[key.font];
+        let (metrics, bitmap) = font.rasterize_indexed(key.glyph, size);
+        if metrics.width == 0 || metrics.height == 0 {
+            return None;
+        }
+        // blurred glyph is padded to fit its faded edges
+        let padding = (blur * 3.0).ceil() as usize;
+        let width = metrics.width + 2 * padding;
+        let height = metrics.height + 2 * padding;
+        let mut coverage = vec![0; width * height];
+        for row in 0..metrics.height {
+            let source = &bitmap[row * metrics.width..(row + 1) * metrics.width];
+            let start = (row + padding) * width + padding;
+            coverage[start..start + metrics.width].copy_from_slice(source);
+        }
+        blur_coverage(&mut coverage, [width, height], blur);
+        let [x, y] = self.allocate(width as u32, height as u32)?;
+        if self.pixels.is_empty() {
+            self.pixels = vec![0; (ATLAS_SIZE * ATLAS_SIZE * 4) as usize];
+        }
+        for row in 0..height {
+            for column in 0..width {
+                let offset = ((y as usize + row) * ATLAS_SIZE as usize + x as usize + column) * 4;
+                let alpha = coverage[row * width + column];
+                self.pixels[offset..offset + 4].copy_from_slice(&[255, 255, 255, alpha]);
+            }
+        }
+        let rows = y..y + height as u32;
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.start.min(rows.start)..dirty.end.max(rows.end),
+            None => rows,
+        });
+        let top = -(metrics.ymin + metrics.height as i32) - padding as i32;
+        Some(AtlasGlyph {
+            rect: [x, y, width as u32, height as u32],
+            offset: [metrics.xmin - padding as i32, top],
+        })
+    }
+
+    /// Finds place of bitmap in atlas by shelves of glyphs, glyphs are separated by
+    /// one pixel so linear sampling does not bleed into neighbours.
+    fn allocate(&mut self, width: u32, height: u32) -> Option<[u32; 2]> {
+        let [mut x, mut y] = self.cursor;
+        if x + width > ATLAS_SIZE {
+            x = 0;
+            y += self.shelf + 1;
+            self.shelf = 0;
+        }
+        if x + width > ATLAS_SIZE || y + height > ATLAS_SIZE {
+            self.overflow = true;
+            return None;
+        }
+        self.cursor = [x + width + 1, y];
+        self.shelf = self.shelf.max(height);
+        Some([x, y])
+    }
+}
+
+/// Blurs coverage by three box blurs approximating gaussian blur with standard deviation.
+fn blur_coverage(coverage: &mut [u8], [width, height]: [usize; 2], sigma: f32) {
+    // box width of three passes with same variance as gaussian
+    let radius = (((4.0 * sigma * sigma + 1.0).sqrt() - 1.0) / 2.0).round() as usize;
+    if radius == 0 {
+        return;
+    }
+    let window = (2 * radius + 1) as u32;
+    let mut sums = vec![0u32; width.max(height) + 1];
+    for _ in 0..3 {
+        for ([length, lines], [step, stride]) in
+            [([width, height], [1, width]), ([height, width], [width, 1])]
+        {
+            for line in 0..lines {
+                let start = line * stride;
+                for index in 0..length {
+                    sums[index + 1] = sums[index] + coverage[start + index * step] as u32;
+                }
+                for index in 0..length {
+                    let end = (index + radius + 1).min(length);
+                    let sum = sums[end] - sums[index.saturating_sub(radius)];
+                    coverage[start + index * step] = ((sum + window / 2) / window) as u8;
+                }
+            }
+        }
+    }
+}
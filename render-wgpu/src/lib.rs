@@ -0,0 +1,386 @@
+//! The reference renderer of bumaga view on wgpu. Renderer draws display list of view,
+//! rounded boxes, borders, shadows, gradients, images and rounded clips are drawn by
+//! instanced quads with signed distance shading. Text is drawn by glyphs rasterized
+//! into atlas from fonts set to renderer, without fonts text runs are placed for
+//! application text renderer.
+//!
+//! ```ignore
+//! let mut renderer = WgpuRenderer::new(&device, surface_format);
+//! renderer.set_image(&device, &queue, "icon.png", width, height, &rgba);
+//! renderer.set_font("Roboto", font_data)?;
+//! renderer.prepare(&device, &queue, &view.display_list()?, [width, height], scale);
+//! renderer.render(&mut pass);
+//! for text in renderer.text() {
+//!     // draw text not drawn by renderer, e.g. with glyphon
+//! }
+//! ```
+
+mod batch;
+#[cfg(feature = "egui")]
+pub mod egui;
+mod glyphs;
+
+pub use batch::TextPlacement;
+
+use crate::batch::{Batch, Frame, Instance, Texture};
+use crate::glyphs::{Glyphs, ATLAS_SIZE};
+use bumaga::DisplayCommand;
+use std::collections::HashMap;
+use wgpu::util::DeviceExt;
+
+pub struct WgpuRenderer {
+    pipeline: wgpu::RenderPipeline,
+    globals: wgpu::Buffer,
+    globals_group: wgpu::BindGroup,
+    image_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    /// The empty texture bound for instances without image.
+    blank: wgpu::BindGroup,
+    images: HashMap<String, Image>,
+    glyphs: Glyphs,
+    /// The glyph atlas texture, created once text is drawn by glyphs.
+    atlas: Option<(wgpu::Texture, wgpu::BindGroup)>,
+    instances: wgpu::Buffer,
+    capacity: usize,
+    batches: Vec<Batch>,
+    text: Vec<TextPlacement>,
+    /// Whether target format is sRGB, so colors must be converted to linear space.
+    linear: bool,
+}
+
+struct Image {
+    size: [f32; 2],
+    group: wgpu::BindGroup,
+}
+
+impl WgpuRenderer {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("bumaga shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
+        });
+        let globals_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("bumaga globals layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let image_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("bumaga image layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let globals = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("bumaga globals"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let globals_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("bumaga globals"),
+            layout: &globals_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: globals.as_entire_binding(),
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("bumaga pipeline layout"),
+            bind_group_layouts: &[&globals_layout, &image_layout],
+            push_constant_ranges: &[],
+        });
+        const ATTRIBUTES: [wgpu::VertexAttribute; 12] = wgpu::vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+            4 => Float32x4,
+            5 => Float32x4,
+            6 => Float32x4,
+            7 => Float32x4,
+            8 => Float32x4,
+            9 => Float32x4,
+            10 => Float32x4,
+            11 => Uint32x4,
+        ];
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("bumaga pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<Instance>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &ATTRIBUTES,
+                }],
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+            cache: None,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("bumaga sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let linear = format.is_srgb();
+        let blank = device.create_texture(&texture_descriptor(1, 1, linear));
+        let blank = create_image_group(device, &image_layout, &sampler, &blank);
+        Self {
+            pipeline,
+            globals,
+            globals_group,
+            image_layout,
+            sampler,
+            blank,
+            images: HashMap::new(),
+            glyphs: Glyphs::default(),
+            atlas: None,
+            instances: create_instances_buffer(device, 0),
+            capacity: 0,
+            batches: vec![],
+            text: vec![],
+            linear,
+        }
+    }
+
+    /// Uploads RGBA image of resource, e.g. once view reports it in resource hints.
+    pub fn set_image(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        src: &str,
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) {
+        let texture = device.create_texture_with_data(
+            queue,
+            &texture_descriptor(width, height, self.linear),
+            wgpu::util::TextureDataOrder::LayerMajor,
+            rgba,
+        );
+        let group = create_image_group(device, &self.image_layout, &self.sampler, &texture);
+        let size = [width as f32, height as f32];
+        self.images.insert(src.to_string(), Image { size, group });
+    }
+
+    /// Frees image of resource no longer referenced by view.
+    pub fn remove_image(&mut self, src: &str) {
+        self.images.remove(src);
+    }
+
+    /// Sets font of family drawing text, text of families without font is drawn by
+    /// first font set. Without fonts text runs are only placed, see [`Self::text`].
+    pub fn set_font(&mut self, family: &str, data: Vec<u8>) -> Result<(), &'static str> {
+        self.glyphs.set_font(family, data)
+    }
+
+    /// Prepares display list to render into target of size in physical pixels,
+    /// scale is physical pixels per view unit.
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        commands: &[DisplayCommand],
+        size: [u32; 2],
+        scale: f32,
+    ) {
+        let image_size = |src: &str| self.images.get(src).map(|image| image.size);
+        let mut frame = Frame::build(commands, scale, self.linear, image_size, &mut self.glyphs);
+        if self.glyphs.is_overflow() {
+            // glyphs of previous frames are dropped to fit glyphs of this one
+            self.glyphs.clear();
+            frame = Frame::build(commands, scale, self.linear, image_size, &mut self.glyphs);
+        }
+        if let Some(rows) = self.glyphs.take_dirty() {
+            self.upload_atlas(device, queue, rows);
+        }
+        let globals = [size[0] as f32, size[1] as f32, 0.0, 0.0];
+        queue.write_buffer(&self.globals, 0, bytemuck::cast_slice(&globals));
+        if frame.instances.len() > self.capacity {
+            self.capacity = frame.instances.len().next_power_of_two();
+            self.instances = create_instances_buffer(device, self.capacity);
+        }
+        if !frame.instances.is_empty() {
+            queue.write_buffer(&self.instances, 0, bytemuck::cast_slice(&frame.instances));
+        }
+        self.batches = frame.batches;
+        self.text = frame.text;
+    }
+
+    /// Records draw calls of prepared display list.
+    pub fn render(&self, pass: &mut wgpu::RenderPass<'_>) {
+        if self.batches.is_empty() {
+            return;
+        }
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.globals_group, &[]);
+        pass.set_vertex_buffer(0, self.instances.slice(..));
+        for batch in &self.batches {
+            let group = match &batch.texture {
+                Texture::Blank => None,
+                Texture::Image(src) => self.images.get(src).map(|image| &image.group),
+                Texture::Glyphs => self.atlas.as_ref().map(|(_, group)| group),
+            };
+            let group = group.unwrap_or(&self.blank);
+            pass.set_bind_group(1, group, &[]);
+            pass.draw(0..4, batch.instances.clone());
+        }
+    }
+
+    /// Returns text runs of prepared display list not drawn by renderer because
+    /// no font is set, application should draw them after view with its own text renderer.
+    pub fn text(&self) -> &[TextPlacement] {
+        &self.text
+    }
+
+    fn upload_atlas(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        rows: std::ops::Range<u32>,
+    ) {
+        let pixels = self.glyphs.pixels();
+        if pixels.is_empty() || rows.is_empty() {
+            return;
+        }
+        let (texture, _) = self.atlas.get_or_insert_with(|| {
+            let texture = device.create_texture(&texture_descriptor(ATLAS_SIZE, ATLAS_SIZE, false));
+            let group = create_image_group(device, &self.image_layout, &self.sampler, &texture);
+            (texture, group)
+        });
+        let row = (ATLAS_SIZE * 4) as usize;
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: 0,
+                    y: rows.start,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            &pixels[rows.start as usize * row..rows.end as usize * row],
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(ATLAS_SIZE * 4),
+                rows_per_image: None,
+            },
+            wgpu::Extent3d {
+                width: ATLAS_SIZE,
+                height: rows.len() as u32,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+
+fn texture_descriptor(width: u32, height: u32, linear: bool) -> wgpu::TextureDescriptor<'static> {
+    let format = if linear {
+        wgpu::TextureFormat::Rgba8UnormSrgb
+    } else {
+        wgpu::TextureFormat::Rgba8Unorm
+    };
+    wgpu::TextureDescriptor {
+        label: Some("bumaga image"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    }
+}
+
+fn create_image_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    texture: &wgpu::Texture,
+) -> wgpu::BindGroup {
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("bumaga image"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    })
+}
+
+fn create_instances_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("bumaga instances"),
+        size: (capacity.max(1) * size_of::<Instance>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    pub fn test_shader_is_valid() {
+        let module = naga::front::wgsl::parse_str(include_str!("shader.wgsl")).unwrap();
+        let mut validator = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        );
+        validator.validate(&module).unwrap();
+    }
+}
@@ -0,0 +1,203 @@
+struct Globals {
+    viewport: vec2<f32>,
+    padding: vec2<f32>,
+};
+
+@group(0) @binding(0) var<uniform> globals: Globals;
+@group(1) @binding(0) var image: texture_2d<f32>;
+@group(1) @binding(1) var image_sampler: sampler;
+
+struct Instance {
+    @location(0) rect: vec4<f32>,
+    @location(1) radius: vec4<f32>,
+    @location(2) widths: vec4<f32>,
+    @location(3) color_top: vec4<f32>,
+    @location(4) color_right: vec4<f32>,
+    @location(5) color_bottom: vec4<f32>,
+    @location(6) color_left: vec4<f32>,
+    @location(7) clip_rect: vec4<f32>,
+    @location(8) clip_radius: vec4<f32>,
+    @location(9) image: vec4<f32>,
+    @location(10) params: vec4<f32>,
+    @location(11) kind: vec4<u32>,
+};
+
+struct Fragment {
+    @builtin(position) position: vec4<f32>,
+    @location(0) point: vec2<f32>,
+    @location(1) @interpolate(flat) rect: vec4<f32>,
+    @location(2) @interpolate(flat) radius: vec4<f32>,
+    @location(3) @interpolate(flat) widths: vec4<f32>,
+    @location(4) @interpolate(flat) color_top: vec4<f32>,
+    @location(5) @interpolate(flat) color_right: vec4<f32>,
+    @location(6) @interpolate(flat) color_bottom: vec4<f32>,
+    @location(7) @interpolate(flat) color_left: vec4<f32>,
+    @location(8) @interpolate(flat) clip_rect: vec4<f32>,
+    @location(9) @interpolate(flat) clip_radius: vec4<f32>,
+    @location(10) @interpolate(flat) image: vec4<f32>,
+    @location(11) @interpolate(flat) params: vec4<f32>,
+    @location(12) @interpolate(flat) kind: vec4<u32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex: u32, instance: Instance) -> Fragment {
+    // quad drawn as triangle strip of 4 vertices
+    let corner = vec2<f32>(f32(vertex & 1u), f32((vertex >> 1u) & 1u));
+    // quad is drawn beyond box by outset, e.g. for blurred shadow
+    let outset = instance.params.w;
+    let point = instance.rect.xy - outset + corner * (instance.rect.zw + 2.0 * outset);
+    let ndc = point / globals.viewport * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0);
+    var out: Fragment;
+    out.position = vec4<f32>(ndc, 0.0, 1.0);
+    out.point = point;
+    out.rect = instance.rect;
+    out.radius = instance.radius;
+    out.widths = instance.widths;
+    out.color_top = instance.color_top;
+    out.color_right = instance.color_right;
+    out.color_bottom = instance.color_bottom;
+    out.color_left = instance.color_left;
+    out.clip_rect = instance.clip_rect;
+    out.clip_radius = instance.clip_radius;
+    out.image = instance.image;
+    out.params = instance.params;
+    out.kind = instance.kind;
+    return out;
+}
+
+// signed distance to rounded box, radii in order top-left, top-right, bottom-right, bottom-left
+fn rounded_box(point: vec2<f32>, rect: vec4<f32>, radius: vec4<f32>) -> f32 {
+    let half = rect.zw * 0.5;
+    let q = point - (rect.xy + half);
+    let top = select(radius.x, radius.y, q.x > 0.0);
+    let bottom = select(radius.w, radius.z, q.x > 0.0);
+    let r = min(select(bottom, top, q.y < 0.0), min(half.x, half.y));
+    let d = abs(q) - half + vec2<f32>(r, r);
+    return length(max(d, vec2<f32>(0.0, 0.0))) + min(max(d.x, d.y), 0.0) - r;
+}
+
+// error function approximation with maximum error 5e-4
+fn erf(x: f32) -> f32 {
+    let a = abs(x);
+    let t = 1.0 + (0.278393 + (0.230389 + 0.078108 * a * a) * a) * a;
+    let r = t * t;
+    return sign(x) * (1.0 - 1.0 / (r * r));
+}
+
+// coverage of border side pattern by style, position along side and depth from outer edge
+fn border_pattern(style: u32, along: f32, depth: f32, width: f32) -> f32 {
+    if style == 2u {
+        // dashes of three widths separated by one width
+        return select(0.0, 1.0, along % (width * 4.0) < width * 3.0);
+    } else if style == 3u {
+        // round dots separated by one width
+        let center = vec2<f32>(along % (width * 2.0) - width * 0.5, depth - width * 0.5);
+        return clamp(0.5 - (length(center) - width * 0.5), 0.0, 1.0);
+    } else if style == 4u {
+        // two lines of third of width at outer and inner edges
+        let third = width / 3.0;
+        return clamp(third + 0.5 - depth, 0.0, 1.0) + clamp(depth - (width - third) + 0.5, 0.0, 1.0);
+    }
+    return 1.0;
+}
+
+@fragment
+fn fs_main(in: Fragment) -> @location(0) vec4<f32> {
+    // sampled in uniform control flow, used only by image and glyph instances
+    let uv = (in.point - in.image.xy) / max(in.image.zw, vec2<f32>(1.0, 1.0));
+    // sprite-sheet frames laid out horizontally
+    let frame = vec2<f32>(f32(in.kind.y), f32(max(in.kind.z, 1u)));
+    let frame_uv = clamp(uv, vec2<f32>(0.0), vec2<f32>(1.0)) * vec2<f32>(1.0 / frame.y, 1.0);
+    let image_uv = frame_uv + vec2<f32>(frame.x / frame.y, 0.0);
+    // glyph rectangle in atlas is stretched over box
+    let glyph_uv = in.image.xy + (in.point - in.rect.xy) / max(in.rect.zw, vec2<f32>(1.0)) * in.image.zw;
+    let texel = textureSample(image, image_sampler, select(image_uv, glyph_uv, in.kind.x == 5u));
+    let inside = clamp(0.5 - rounded_box(in.point, in.rect, in.radius), 0.0, 1.0);
+    var alpha = inside;
+    alpha *= clamp(0.5 - rounded_box(in.point, in.clip_rect, in.clip_radius), 0.0, 1.0);
+    var color = in.color_top;
+    if in.kind.x == 1u {
+        let w = in.widths;
+        let inner_rect = vec4<f32>(
+            in.rect.x + w.w,
+            in.rect.y + w.x,
+            in.rect.z - w.y - w.w,
+            in.rect.w - w.x - w.z,
+        );
+        let inner_radius = max(
+            in.radius - vec4<f32>(max(w.x, w.w), max(w.x, w.y), max(w.z, w.y), max(w.z, w.w)),
+            vec4<f32>(0.0),
+        );
+        alpha *= clamp(0.5 + rounded_box(in.point, inner_rect, inner_radius), 0.0, 1.0);
+        // side is chosen by distance to edge relative to its width
+        let p = in.point - in.rect.xy;
+        let epsilon = 0.0001;
+        let top = select(1e9, p.y / max(w.x, epsilon), w.x > 0.0);
+        let right = select(1e9, (in.rect.z - p.x) / max(w.y, epsilon), w.y > 0.0);
+        let bottom = select(1e9, (in.rect.w - p.y) / max(w.z, epsilon), w.z > 0.0);
+        let left = select(1e9, p.x / max(w.w, epsilon), w.w > 0.0);
+        let nearest = min(min(top, right), min(bottom, left));
+        // styles of sides packed by 4 bits in order top, right, bottom, left
+        let styles = in.kind.y;
+        if nearest >= 1e9 {
+            alpha = 0.0;
+        } else if nearest == top {
+            color = in.color_top;
+            alpha *= border_pattern(styles & 15u, p.x, p.y, w.x);
+        } else if nearest == right {
+            color = in.color_right;
+            alpha *= border_pattern((styles >> 4u) & 15u, p.y, in.rect.z - p.x, w.y);
+        } else if nearest == bottom {
+            color = in.color_bottom;
+            alpha *= border_pattern((styles >> 8u) & 15u, in.rect.z - p.x, in.rect.w - p.y, w.z);
+        } else {
+            color = in.color_left;
+            alpha *= border_pattern((styles >> 12u) & 15u, in.rect.w - p.y, p.x, w.w);
+        }
+    } else if in.kind.x == 2u {
+        let inside = all(uv >= vec2<f32>(0.0)) && all(uv <= vec2<f32>(1.0));
+        color = vec4<f32>(texel.rgb, texel.a * in.color_top.a);
+        alpha *= select(0.0, 1.0, inside);
+    } else if in.kind.x == 3u {
+        // shadow shape blurred by gaussian, approximated along distance to shape
+        let sigma = in.params.x;
+        let distance = rounded_box(in.point, in.image, in.widths);
+        var shadow = clamp(0.5 - distance, 0.0, 1.0);
+        if sigma > 0.0 {
+            shadow = 0.5 - 0.5 * erf(distance / (sigma * sqrt(2.0)));
+        }
+        let clip = clamp(0.5 - rounded_box(in.point, in.clip_rect, in.clip_radius), 0.0, 1.0);
+        if in.params.y > 0.0 {
+            alpha = inside * (1.0 - shadow) * clip;
+        } else {
+            alpha = (1.0 - inside) * shadow * clip;
+        }
+    } else if in.kind.x == 4u {
+        // gradient line goes through center at angle and reaches corners
+        let angle = in.params.x;
+        let direction = vec2<f32>(sin(angle), -cos(angle));
+        let half = abs(in.rect.z * direction.x) + abs(in.rect.w * direction.y);
+        let center = in.rect.xy + in.rect.zw * 0.5;
+        let t = dot(in.point - center, direction) / max(half, 1.0) + 0.5;
+        var stops = array<vec4<f32>, 4>(in.color_top, in.color_right, in.color_bottom, in.color_left);
+        let count = u32(in.params.y);
+        color = stops[0];
+        for (var index = 1u; index < count; index++) {
+            let start = in.widths[index - 1u];
+            let end = in.widths[index];
+            if t > start {
+                color = mix(stops[index - 1u], stops[index], clamp((t - start) / max(end - start, 0.0001), 0.0, 1.0));
+            }
+        }
+        // segments of many stops cover own part of gradient line only
+        let flags = u32(in.params.z);
+        let first = in.widths[0];
+        let last = in.widths[count - 1u];
+        if ((flags & 1u) == 0u && t < first) || ((flags & 2u) == 0u && t >= last) {
+            alpha = 0.0;
+        }
+    } else if in.kind.x == 5u {
+        alpha *= texel.a;
+    }
+    return vec4<f32>(color.rgb, color.a * alpha);
+}
@@ -0,0 +1,219 @@
+use crate::controls::is_text_input;
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, ViewError};
+use serde::{Deserialize, Serialize};
+use taffy::{NodeId, TaffyTree};
+
+/// The node of accessibility snapshot, hosts can feed it to AccessKit
+/// or other platform accessibility API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessNode {
+    /// The id of element, stable while element exists.
+    pub id: u64,
+    pub role: Role,
+    /// The accessible name from aria-label, alt, text content or title.
+    pub name: Option<String>,
+    /// The value of text inputs.
+    pub value: Option<String>,
+    pub states: AccessStates,
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub children: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccessStates {
+    pub focused: bool,
+    pub disabled: bool,
+    pub hovered: bool,
+    pub pressed: bool,
+    /// The checked state of checkboxes and radio buttons, None for other roles.
+    pub checked: Option<bool>,
+    pub invalid: bool,
+}
+
+/// The role of element inferred from tag, may be overridden by role attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Role {
+    Document,
+    Generic,
+    Text,
+    Paragraph,
+    Heading,
+    Button,
+    Link,
+    Image,
+    CheckBox,
+    RadioButton,
+    Slider,
+    TextInput,
+    MultilineTextInput,
+    Label,
+    List,
+    ListItem,
+    Form,
+    Dialog,
+}
+
+impl Role {
+    /// Returns role specified by value of role attribute.
+    pub fn from_attribute(value: &str) -> Option<Role> {
+        let role = match value {
+            "document" => Role::Document,
+            "generic" | "group" | "presentation" | "none" => Role::Generic,
+            "paragraph" => Role::Paragraph,
+            "heading" => Role::Heading,
+            "button" => Role::Button,
+            "link" => Role::Link,
+            "img" | "image" => Role::Image,
+            "checkbox" | "switch" => Role::CheckBox,
+            "radio" => Role::RadioButton,
+            "slider" => Role::Slider,
+            "textbox" => Role::TextInput,
+            "list" => Role::List,
+            "listitem" => Role::ListItem,
+            "form" => Role::Form,
+            "dialog" | "alertdialog" => Role::Dialog,
+            _ => return None,
+        };
+        Some(role)
+    }
+
+    fn infer(element: &Element) -> Role {
+        if element.text.is_some() {
+            return Role::Text;
+        }
+        let kind = element.attrs.get("type").map(String::as_str);
+        match element.tag.as_str() {
+            "body" => Role::Document,
+            "p" => Role::Paragraph,
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => Role::Heading,
+            "button" => Role::Button,
+            "a" => Role::Link,
+            "img" => Role::Image,
+            "input" => match kind {
+                Some("checkbox") => Role::CheckBox,
+                Some("radio") => Role::RadioButton,
+                Some("submit" | "button") => Role::Button,
+                Some("range") => Role::Slider,
+                _ => Role::TextInput,
+            },
+            "textarea" => Role::MultilineTextInput,
+            "label" => Role::Label,
+            "ul" | "ol" => Role::List,
+            "li" => Role::ListItem,
+            "form" => Role::Form,
+            "dialog" => Role::Dialog,
+            _ => Role::Generic,
+        }
+    }
+
+    /// Checks element of role is named by its text content.
+    fn is_named_by_content(&self) -> bool {
+        matches!(
+            self,
+            Role::Heading
+                | Role::Button
+                | Role::Link
+                | Role::Label
+                | Role::ListItem
+                | Role::CheckBox
+                | Role::RadioButton
+        )
+    }
+}
+
+/// Collects accessibility snapshot of element and its descendants in document order,
+/// returns ids of collected nodes of element level. Elements with aria-hidden attribute
+/// are excluded, children of generated elements (e.g. image backgrounds) are lifted.
+pub(crate) fn collect_access_nodes(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    nodes: &mut Vec<AccessNode>,
+) -> Result<Vec<u64>, ViewError> {
+    let element = tree.get_element(node)?;
+    if element.attrs.get("aria-hidden").map(String::as_str) == Some("true") {
+        return Ok(vec![]);
+    }
+    let text = element.text.as_ref().map(|text| text.to_string());
+    if text.as_ref().is_some_and(|text| text.trim().is_empty()) {
+        return Ok(vec![]);
+    }
+    if element.tag.is_empty() && text.is_none() {
+        let mut children = vec![];
+        for child in tree.children(node)? {
+            children.extend(collect_access_nodes(tree, child, nodes)?);
+        }
+        return Ok(children);
+    }
+    let role = element
+        .attrs
+        .get("role")
+        .and_then(|role| Role::from_attribute(role));
+    let role = role.unwrap_or_else(|| Role::infer(element));
+    let name = match element.attrs.get("aria-label") {
+        Some(label) => Some(label.clone()),
+        None if role == Role::Image => element.attrs.get("alt").cloned(),
+        None if role == Role::Text => text.map(|text| text.trim().to_string()),
+        None if role.is_named_by_content() => {
+            let mut content = vec![];
+            collect_text_content(tree, node, &mut content)?;
+            Some(content.join(" ")).filter(|content| !content.is_empty())
+        }
+        None => None,
+    };
+    let name = name.or_else(|| element.attrs.get("title").cloned());
+    let checked = match role {
+        Role::CheckBox | Role::RadioButton => Some(element.attrs.contains_key("checked")),
+        _ => None,
+    };
+    let states = AccessStates {
+        focused: element.state.focus,
+        disabled: element.disabled(),
+        hovered: element.state.hover,
+        pressed: element.state.active,
+        checked,
+        invalid: element.state.invalid,
+    };
+    let value = if is_text_input(element) {
+        Some(element.value().cloned().unwrap_or_default())
+    } else {
+        None
+    };
+    let index = nodes.len();
+    nodes.push(AccessNode {
+        id: node.into(),
+        role,
+        name,
+        value,
+        states,
+        position: element.position,
+        size: element.size,
+        children: vec![],
+    });
+    let mut children = vec![];
+    for child in tree.children(node)? {
+        children.extend(collect_access_nodes(tree, child, nodes)?);
+    }
+    nodes[index].children = children;
+    Ok(vec![node.into()])
+}
+
+fn collect_text_content(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    content: &mut Vec<String>,
+) -> Result<(), ViewError> {
+    for child in tree.children(node)? {
+        let element = tree.get_element(child)?;
+        if let Some(text) = element.text.as_ref() {
+            let text = text.to_string();
+            let text = text.trim();
+            if !text.is_empty() {
+                content.push(text.to_string());
+            }
+        }
+        collect_text_content(tree, child, content)?;
+    }
+    Ok(())
+}
@@ -22,6 +22,14 @@ impl Default for Transition {
 }
 
 impl Transition {
+    /// Checks transition is in progress between different values.
+    pub fn is_active(&self) -> bool {
+        match self.range.as_ref() {
+            Some((from, to)) => from != to && self.animator.is_active(),
+            None => false,
+        }
+    }
+
     pub fn init_after_style_applied(&mut self, style: &mut ComputedStyle) {
         if self.range.is_some() {
             return;
@@ -114,6 +122,19 @@ impl Animator {
         self.time = 0.0;
     }
 
+    /// Checks animation still changes values, i.e. running and not finished.
+    pub fn is_active(&self) -> bool {
+        if !self.running || self.duration <= 0.0 {
+            return false;
+        }
+        match self.iterations {
+            AnimationIterations::Number(iterations) => {
+                self.time < self.delay + iterations * self.duration
+            }
+            AnimationIterations::Infinite => true,
+        }
+    }
+
     pub fn play(&mut self, time: f32, tracks: &Vec<AnimationTrack>, style: &mut ComputedStyle) {
         if let Some(time) = self.update(time) {
             let step = (time * 100.0) as u32;
@@ -0,0 +1,79 @@
+use crate::{TextDirection, TextFragment, TextLine};
+use unicode_bidi::{bidi_class, BidiClass, BidiInfo, Level};
+
+/// Returns whether text needs reordering, text of right-to-left base direction
+/// or containing right-to-left characters.
+pub(crate) fn is_bidi(text: &str, direction: TextDirection) -> bool {
+    direction == TextDirection::Rtl
+        || text.chars().any(|char| {
+            matches!(
+                bidi_class(char),
+                BidiClass::R | BidiClass::AL | BidiClass::RLE | BidiClass::RLO | BidiClass::RLI
+            )
+        })
+}
+
+fn base_level(direction: TextDirection) -> Level {
+    match direction {
+        TextDirection::Ltr => Level::ltr(),
+        TextDirection::Rtl => Level::rtl(),
+    }
+}
+
+/// Replaces text of lines broken from logical text by its visual order, so renderers
+/// draw lines left to right. Line ranges stay in logical text.
+pub(crate) fn reorder_lines(text: &str, direction: TextDirection, lines: &mut [TextLine]) {
+    if !is_bidi(text, direction) {
+        return;
+    }
+    let info = BidiInfo::new(text, Some(base_level(direction)));
+    for line in lines {
+        let [start, end] = line.range;
+        let paragraph = info
+            .paragraphs
+            .iter()
+            .find(|paragraph| paragraph.range.contains(&start));
+        if let Some(paragraph) = paragraph {
+            line.text = info.reorder_line(paragraph, start..end).into_owned();
+        }
+    }
+}
+
+/// Reorders fragments of one line by their embedding levels and text of fragments
+/// into visual order, line keeps its horizontal position.
+pub(crate) fn reorder_fragments(line: &mut [TextFragment], direction: TextDirection) {
+    let text: String = line.iter().map(|fragment| fragment.text.as_str()).collect();
+    if !is_bidi(&text, direction) {
+        return;
+    }
+    let info = BidiInfo::new(&text, Some(base_level(direction)));
+    let paragraph = match info.paragraphs.first() {
+        Some(paragraph) => paragraph,
+        None => return,
+    };
+    let mut levels = vec![];
+    let mut offset = 0;
+    for fragment in line.iter_mut() {
+        let length = fragment.text.len();
+        let range = offset..offset + length;
+        // fragment is ordered by its first strong character, leading space is neutral
+        let strong = length - fragment.text.trim_start().len();
+        let level = info
+            .levels
+            .get(offset + strong)
+            .copied()
+            .unwrap_or(paragraph.level);
+        levels.push(level);
+        fragment.text = info.reorder_line(paragraph, range).into_owned();
+        offset += length;
+    }
+    let mut x = line
+        .iter()
+        .map(|fragment| fragment.origin[0])
+        .fold(f32::MAX, f32::min);
+    for index in BidiInfo::reorder_visual(&levels) {
+        let fragment = &mut line[index];
+        fragment.origin[0] = x;
+        x += fragment.width;
+    }
+}
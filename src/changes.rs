@@ -0,0 +1,85 @@
+use crate::display_list::{element_material, element_translate, paint_element};
+use crate::tree::ViewTreeExtensions;
+use crate::{DisplayCommand, Element, ViewError};
+use std::collections::{HashMap, HashSet};
+use taffy::{NodeId, TaffyTree};
+
+/// The change of element since previous output, application with retained
+/// scene graph may patch its nodes instead of rebuilding them every frame.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementChange {
+    Created(ElementPatch),
+    Updated(ElementPatch),
+    Removed(NodeId),
+}
+
+/// The geometry and appearance of element, commands are element's own
+/// drawing commands without descendants.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementPatch {
+    pub node: NodeId,
+    pub parent: Option<NodeId>,
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    /// The translation of element accumulated with ancestors.
+    pub translate: [f32; 2],
+    pub z_index: Option<i32>,
+    /// The custom material of element, inherited from ancestors.
+    pub material: Option<String>,
+    pub commands: Vec<DisplayCommand>,
+}
+
+/// Collects patches of element and its attached descendants in document order.
+pub(crate) fn collect_patches(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    parent: Option<NodeId>,
+    translate: [f32; 2],
+    material: Option<&str>,
+    patches: &mut Vec<ElementPatch>,
+) -> Result<(), ViewError> {
+    let element = tree.get_element(node)?;
+    let translate = element_translate(element, translate);
+    let material = element_material(element, material);
+    let mut commands = vec![];
+    paint_element(element, &mut commands);
+    patches.push(ElementPatch {
+        node,
+        parent,
+        position: element.position,
+        size: element.size,
+        translate,
+        z_index: element.z_index,
+        material: material.map(String::from),
+        commands,
+    });
+    for child in tree.children(node)? {
+        collect_patches(tree, child, Some(node), translate, material, patches)?;
+    }
+    Ok(())
+}
+
+/// Returns changes of current patches compared to previous ones, created and
+/// updated elements in document order followed by removed elements.
+pub(crate) fn find_changes(
+    previous: &HashMap<NodeId, ElementPatch>,
+    current: &[ElementPatch],
+) -> Vec<ElementChange> {
+    let mut changes = vec![];
+    for patch in current {
+        match previous.get(&patch.node) {
+            Some(previous) if previous == patch => {}
+            Some(_) => changes.push(ElementChange::Updated(patch.clone())),
+            None => changes.push(ElementChange::Created(patch.clone())),
+        }
+    }
+    let attached: HashSet<NodeId> = current.iter().map(|patch| patch.node).collect();
+    let mut removed: Vec<NodeId> = previous
+        .keys()
+        .filter(|node| !attached.contains(*node))
+        .copied()
+        .collect();
+    removed.sort_by_key(|node| u64::from(*node));
+    changes.extend(removed.into_iter().map(ElementChange::Removed));
+    changes
+}
@@ -0,0 +1,185 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, MouseEvent, SwipeEvent, ViewError, ViewModel};
+use std::time::Duration;
+use taffy::{NodeId, TaffyTree};
+
+/// Counts consecutive clicks on the same element, so mouse events can report
+/// click count and double click can be detected.
+#[derive(Debug)]
+pub(crate) struct ClickCounter {
+    /// The max time in seconds between presses to count them as one series.
+    pub(crate) interval: f32,
+    target: Option<NodeId>,
+    elapsed: f32,
+    count: u32,
+}
+
+impl Default for ClickCounter {
+    fn default() -> Self {
+        Self {
+            interval: 0.5,
+            target: None,
+            elapsed: 0.0,
+            count: 0,
+        }
+    }
+}
+
+impl ClickCounter {
+    pub(crate) fn advance(&mut self, time: Duration) {
+        self.elapsed += time.as_secs_f32();
+    }
+
+    /// Registers button press on target element, returns click count of series.
+    pub(crate) fn press(&mut self, target: Option<NodeId>) -> u32 {
+        if target.is_none() || self.target != target || self.elapsed > self.interval {
+            self.count = 0;
+        }
+        self.target = target;
+        self.elapsed = 0.0;
+        self.count += 1;
+        self.count
+    }
+
+    /// Breaks series, e.g. when other than primary button pressed.
+    pub(crate) fn reset(&mut self) {
+        self.target = None;
+        self.count = 0;
+    }
+
+    pub(crate) fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// Measures how long primary button is held on elements to emit hold event.
+#[derive(Debug)]
+pub(crate) struct HoldTimer {
+    /// The time in seconds button must be held before hold event.
+    pub(crate) duration: f32,
+    elapsed: f32,
+    pressed: bool,
+    /// Whether hold event emitted for current press, so click is suppressed.
+    pub(crate) fired: bool,
+}
+
+impl Default for HoldTimer {
+    fn default() -> Self {
+        Self {
+            duration: 0.5,
+            elapsed: 0.0,
+            pressed: false,
+            fired: false,
+        }
+    }
+}
+
+impl HoldTimer {
+    pub(crate) fn advance(&mut self, time: Duration) {
+        if self.pressed {
+            self.elapsed += time.as_secs_f32();
+        }
+    }
+
+    pub(crate) fn press(&mut self) {
+        self.pressed = true;
+        self.fired = false;
+        self.elapsed = 0.0;
+    }
+
+    pub(crate) fn release(&mut self) {
+        self.pressed = false;
+    }
+}
+
+/// Recognizes fast directional drag of primary button or touch as swipe.
+#[derive(Debug)]
+pub(crate) struct SwipeTracker {
+    /// The min distance in pixels pointer must travel along swipe direction.
+    pub(crate) distance: f32,
+    /// The min mean speed in pixels per second of swipe.
+    pub(crate) velocity: f32,
+    origin: Option<[f32; 2]>,
+    elapsed: f32,
+}
+
+impl Default for SwipeTracker {
+    fn default() -> Self {
+        Self {
+            distance: 50.0,
+            velocity: 300.0,
+            origin: None,
+            elapsed: 0.0,
+        }
+    }
+}
+
+impl SwipeTracker {
+    pub(crate) fn advance(&mut self, time: Duration) {
+        if self.origin.is_some() {
+            self.elapsed += time.as_secs_f32();
+        }
+    }
+
+    pub(crate) fn press(&mut self, position: [f32; 2]) {
+        self.origin = Some(position);
+        self.elapsed = 0.0;
+    }
+
+    /// Returns name of swipe event and swipe displacement and velocity,
+    /// if pointer released fast and far enough along one axis.
+    pub(crate) fn release(
+        &mut self,
+        position: [f32; 2],
+    ) -> Option<(&'static str, [f32; 2], [f32; 2])> {
+        let [x, y] = self.origin.take()?;
+        let delta = [position[0] - x, position[1] - y];
+        let time = self.elapsed.max(f32::EPSILON);
+        let velocity = [delta[0] / time, delta[1] / time];
+        let [dx, dy] = [delta[0].abs(), delta[1].abs()];
+        let (name, distance, speed) = match dx >= dy {
+            true if delta[0] < 0.0 => ("onswipeleft", dx, velocity[0].abs()),
+            true => ("onswiperight", dx, velocity[0].abs()),
+            false if delta[1] < 0.0 => ("onswipeup", dy, velocity[1].abs()),
+            false => ("onswipedown", dy, velocity[1].abs()),
+        };
+        if distance < self.distance || speed < self.velocity {
+            return None;
+        }
+        Some((name, delta, velocity))
+    }
+}
+
+impl ViewModel {
+    /// Emits swipe event to pressed elements once primary button released,
+    /// returns whether any element handled swipe, so click is suppressed.
+    pub(crate) fn recognize_swipe(&mut self, tree: &TaffyTree<Element>) -> Result<bool, ViewError> {
+        let (name, delta, velocity) = match self.swipe.release(self.mouse) {
+            Some(swipe) => swipe,
+            None => return Ok(false),
+        };
+        let mut handled = false;
+        for node in self.elements_in_action.clone().into_iter().rev() {
+            let element = tree.get_element(node)?;
+            let event = SwipeEvent::new(self.mouse, delta, velocity, element);
+            handled |= element.listeners.contains_key(name);
+            self.emit(element, name, event);
+        }
+        Ok(handled)
+    }
+
+    /// Emits hold event to pressed elements once button held long enough.
+    pub(crate) fn update_hold(&mut self, tree: &TaffyTree<Element>) -> Result<(), ViewError> {
+        let hold = &mut self.hold;
+        if !hold.pressed || hold.fired || hold.elapsed < hold.duration || self.drag.is_some() {
+            return Ok(());
+        }
+        hold.fired = true;
+        for node in self.elements_in_action.clone() {
+            let element = tree.get_element(node)?;
+            let event = MouseEvent::new(self.mouse, self.modifiers, element);
+            self.emit(element, "onhold", event);
+        }
+        Ok(())
+    }
+}
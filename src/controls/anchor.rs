@@ -0,0 +1,31 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, ViewError, ViewModel};
+use serde_json::json;
+use taffy::{NodeId, TaffyTree};
+
+impl ViewModel {
+    /// Emits navigation message for anchor element, so application can route
+    /// in-game pages or open external links.
+    pub(crate) fn navigate_anchor(
+        &mut self,
+        anchor: NodeId,
+        tree: &TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        let element = tree.get_element(anchor)?;
+        if element.tag != "a" || element.disabled() {
+            return Ok(());
+        }
+        let href = match element.attrs.get("href") {
+            Some(href) => href,
+            None => return Ok(()),
+        };
+        let message = json!({
+            "navigate": {
+                "href": href,
+                "target": element.attrs.get("target")
+            }
+        });
+        self.output.messages.push(message);
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+use crate::controls::EditKind;
+use crate::fonts::{measure_line, resolve_face};
+use crate::tree::ViewTreeExtensions;
+use crate::{
+    Caret, Element, FaceId, FontFace, Fonts, Highlight, Keys, Modifiers, ViewError, ViewModel,
+};
+use taffy::{NodeId, TaffyTree};
+
+/// The caret and selection anchor of focused text input, in chars of value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TextCursor {
+    pub(crate) input: NodeId,
+    pub(crate) anchor: usize,
+    pub(crate) caret: usize,
+}
+
+impl TextCursor {
+    /// Returns selected char range, empty if nothing selected.
+    pub(crate) fn range(&self) -> [usize; 2] {
+        [self.anchor.min(self.caret), self.anchor.max(self.caret)]
+    }
+}
+
+/// Text input is textarea or input element with editable text value.
+pub(crate) fn is_text_input(element: &Element) -> bool {
+    let kind = element.attrs.get("type").map(String::as_str);
+    match element.tag.as_str() {
+        "textarea" => true,
+        "input" => !matches!(kind, Some("checkbox" | "radio" | "submit" | "button")),
+        _ => false,
+    }
+}
+
+impl ViewModel {
+    /// Returns cursor of text input, caret placed at the end of value once input focused.
+    pub(crate) fn text_cursor(&mut self, input: NodeId, element: &Element) -> TextCursor {
+        let length = element.value().map(|value| value.chars().count());
+        let length = length.unwrap_or_default();
+        let cursor = match self.cursor {
+            Some(cursor) if cursor.input == input => TextCursor {
+                input,
+                anchor: cursor.anchor.min(length),
+                caret: cursor.caret.min(length),
+            },
+            _ => TextCursor {
+                input,
+                anchor: length,
+                caret: length,
+            },
+        };
+        self.cursor = Some(cursor);
+        cursor
+    }
+
+    /// Returns selected text of input, whole value if nothing selected.
+    pub(crate) fn selected_text(&mut self, input: NodeId, element: &Element) -> String {
+        let value = element.value().cloned().unwrap_or_default();
+        let [start, end] = self.text_cursor(input, element).range();
+        if start == end {
+            return value;
+        }
+        value.chars().skip(start).take(end - start).collect()
+    }
+
+    /// Moves caret of text input by navigation key, Shift extends selection.
+    pub(crate) fn move_caret(
+        &mut self,
+        input: NodeId,
+        tree: &TaffyTree<Element>,
+        key: Keys,
+    ) -> Result<(), ViewError> {
+        let element = tree.get_element(input)?;
+        if !is_text_input(element) {
+            return Ok(());
+        }
+        let length = element.value().map(|value| value.chars().count());
+        let length = length.unwrap_or_default();
+        let mut cursor = self.text_cursor(input, element);
+        let [start, end] = cursor.range();
+        let extend = self.modifiers.contains(Modifiers::SHIFT);
+        cursor.caret = match key {
+            // collapses selection to its edge
+            Keys::ArrowLeft if !extend && start != end => start,
+            Keys::ArrowRight if !extend && start != end => end,
+            Keys::ArrowLeft => cursor.caret.saturating_sub(1),
+            Keys::ArrowRight => (cursor.caret + 1).min(length),
+            Keys::Home => 0,
+            Keys::End => length,
+            _ => return Ok(()),
+        };
+        if !extend {
+            cursor.anchor = cursor.caret;
+        }
+        self.cursor = Some(cursor);
+        Ok(())
+    }
+
+    /// Selects whole value of text input.
+    pub(crate) fn select_all(
+        &mut self,
+        input: NodeId,
+        tree: &TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        let element = tree.get_element(input)?;
+        if is_text_input(element) {
+            let mut cursor = self.text_cursor(input, element);
+            cursor.anchor = 0;
+            cursor.caret = element
+                .value()
+                .map(|value| value.chars().count())
+                .unwrap_or_default();
+            self.cursor = Some(cursor);
+        }
+        Ok(())
+    }
+
+    /// Places caret of text input at mouse position, extend keeps selection anchor.
+    pub(crate) fn point_caret(
+        &mut self,
+        input: NodeId,
+        tree: &TaffyTree<Element>,
+        fonts: &dyn Fonts,
+        extend: bool,
+    ) -> Result<(), ViewError> {
+        let element = tree.get_element(input)?;
+        if !is_text_input(element) {
+            return Ok(());
+        }
+        let value = element.value().cloned().unwrap_or_default();
+        let [x, y] = content_origin(input, tree)?;
+        let fonts = self.monospace.select(fonts, &element.font);
+        let id = resolve_face(fonts, &element.font);
+        let line_height = line_height(fonts, id, &element.font);
+        let [mouse_x, mouse_y] = self.mouse;
+        let lines: Vec<&str> = value.split('\n').collect();
+        let line = ((mouse_y - y) / line_height).max(0.0) as usize;
+        let line = line.min(lines.len() - 1);
+        // the char index of line start
+        let offset: usize = lines[..line]
+            .iter()
+            .map(|text| text.chars().count() + 1)
+            .sum();
+        let text = lines[line];
+        let mut caret = 0;
+        let mut distance = f32::MAX;
+        for index in 0..=text.chars().count() {
+            let prefix: String = text.chars().take(index).collect();
+            let [width, _] = measure_line(fonts, id, &prefix, &element.font);
+            let current = (mouse_x - x - width).abs();
+            if current < distance {
+                distance = current;
+                caret = index;
+            }
+        }
+        let mut cursor = self.text_cursor(input, element);
+        cursor.caret = offset + caret;
+        if !extend {
+            cursor.anchor = cursor.caret;
+        }
+        self.cursor = Some(cursor);
+        Ok(())
+    }
+
+    /// Replaces selected text of input with text, or inserts it at caret.
+    pub(crate) fn insert_input_text(
+        &mut self,
+        input: NodeId,
+        tree: &mut TaffyTree<Element>,
+        kind: EditKind,
+        text: &str,
+    ) -> Result<(), ViewError> {
+        let cursor = self.text_cursor(input, tree.get_element(input)?);
+        let [start, end] = cursor.range();
+        let edited = self.edit_input_value(input, tree, kind, |value| {
+            let range = byte_index(value, start)..byte_index(value, end);
+            value.replace_range(range, text);
+        })?;
+        if edited {
+            let caret = start + text.chars().count();
+            self.cursor = Some(TextCursor {
+                input,
+                anchor: caret,
+                caret,
+            });
+        }
+        Ok(())
+    }
+
+    /// Deletes selected text of input, or single char before or after caret.
+    pub(crate) fn delete_input_text(
+        &mut self,
+        input: NodeId,
+        tree: &mut TaffyTree<Element>,
+        forward: bool,
+    ) -> Result<(), ViewError> {
+        let cursor = self.text_cursor(input, tree.get_element(input)?);
+        let [mut start, mut end] = cursor.range();
+        if start == end {
+            if forward {
+                end += 1;
+            } else {
+                start = start.saturating_sub(1);
+            }
+        }
+        let edited = self.edit_input_value(input, tree, EditKind::Delete, |value| {
+            let range = byte_index(value, start)..byte_index(value, end);
+            value.replace_range(range, "");
+        })?;
+        if edited {
+            self.cursor = Some(TextCursor {
+                input,
+                anchor: start,
+                caret: start,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns caret and selection rectangles of focused text input.
+    pub(crate) fn caret_output(
+        &mut self,
+        tree: &TaffyTree<Element>,
+        fonts: &dyn Fonts,
+    ) -> Result<Option<Caret>, ViewError> {
+        let input = match self.focus {
+            Some(input) => input,
+            None => return Ok(None),
+        };
+        let element = tree.get_element(input)?;
+        if !is_text_input(element) {
+            return Ok(None);
+        }
+        let cursor = self.text_cursor(input, element);
+        let value = element.value().cloned().unwrap_or_default();
+        let [x, y] = content_origin(input, tree)?;
+        let fonts = self.monospace.select(fonts, &element.font);
+        let id = resolve_face(fonts, &element.font);
+        let line_height = line_height(fonts, id, &element.font);
+        let [start, end] = cursor.range();
+        let mut selection = vec![];
+        let mut position = [x, y];
+        let mut offset = 0;
+        for (line, text) in value.split('\n').enumerate() {
+            let length = text.chars().count();
+            let measure = |index: usize| {
+                let prefix: String = text.chars().take(index - offset).collect();
+                measure_line(fonts, id, &prefix, &element.font)[0]
+            };
+            let y = y + line as f32 * line_height;
+            if (offset..=offset + length).contains(&cursor.caret) {
+                position = [x + measure(cursor.caret), y];
+            }
+            let [from, to] = [start.max(offset), end.min(offset + length)];
+            if start != end && from < to {
+                let left = measure(from);
+                selection.push(Highlight {
+                    position: [x + left, y],
+                    size: [measure(to) - left, line_height],
+                });
+            }
+            offset += length + 1;
+        }
+        Ok(Some(Caret {
+            index: cursor.caret,
+            range: [start, end],
+            position,
+            size: [1.0, line_height],
+            selection,
+        }))
+    }
+}
+
+/// Returns position of text content of element, inside of borders and paddings.
+fn content_origin(node: NodeId, tree: &TaffyTree<Element>) -> Result<[f32; 2], ViewError> {
+    let element = tree.get_element(node)?;
+    let layout = tree.layout(node)?;
+    let [x, y] = element.position;
+    let left = layout.border.left + layout.padding.left;
+    let top = layout.border.top + layout.padding.top;
+    Ok([x + left, y + top])
+}
+
+fn line_height(fonts: &dyn Fonts, id: FaceId, font: &FontFace) -> f32 {
+    match fonts.measure(id, " ", font, None) {
+        [_, height] if height > 0.0 => height,
+        _ => font.size,
+    }
+}
+
+fn byte_index(value: &str, index: usize) -> usize {
+    match value.char_indices().nth(index) {
+        Some((index, _)) => index,
+        None => value.len(),
+    }
+}
@@ -0,0 +1,146 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, SubmitEvent, ViewError, ViewModel};
+use log::error;
+use regex_lite::Regex;
+use serde_json::{Map, Value};
+use taffy::{NodeId, TaffyTree};
+
+impl ViewModel {
+    /// Submits the form of control, emits single submit event with values
+    /// of all named form fields.
+    pub(crate) fn submit_form(
+        &mut self,
+        control: NodeId,
+        tree: &TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        if tree.get_element(control)?.disabled() {
+            return Ok(());
+        }
+        let mut form = tree.parent(control);
+        while let Some(node) = form {
+            if tree.get_element(node)?.tag == "form" {
+                break;
+            }
+            form = tree.parent(node);
+        }
+        let form = match form {
+            Some(form) => form,
+            None => return Ok(()),
+        };
+        let mut values = Map::new();
+        let mut invalid = vec![];
+        collect_form_values(tree, form, &mut values, &mut invalid)?;
+        let element = tree.get_element(form)?;
+        let event = SubmitEvent::new(values, invalid, element);
+        self.emit(element, "onsubmit", event);
+        Ok(())
+    }
+}
+
+/// Submit button is button without explicit type or of submit type.
+pub(crate) fn is_submit_button(element: &Element) -> bool {
+    let kind = element.attrs.get("type").map(String::as_str);
+    match element.tag.as_str() {
+        "button" => matches!(kind, None | Some("submit")),
+        "input" => kind == Some("submit"),
+        _ => false,
+    }
+}
+
+impl Element {
+    /// Checks value satisfies required, pattern, minlength and maxlength
+    /// constraints of input element.
+    pub fn check_validity(&self) -> bool {
+        if (self.tag != "input" && self.tag != "textarea") || self.disabled() {
+            return true;
+        }
+        if self.attrs.get("type").map(String::as_str) == Some("checkbox") {
+            return !self.attrs.contains_key("required") || self.attrs.contains_key("checked");
+        }
+        let value = self.value().map(String::as_str).unwrap_or_default();
+        if value.is_empty() {
+            return !self.attrs.contains_key("required");
+        }
+        let length = value.chars().count();
+        let limit = |key: &str| self.attrs.get(key).and_then(|limit| limit.parse().ok());
+        if limit("minlength").is_some_and(|min: usize| length < min) {
+            return false;
+        }
+        if limit("maxlength").is_some_and(|max: usize| length > max) {
+            return false;
+        }
+        if let Some(source) = self.attrs.get("pattern") {
+            // pattern compiled on attribute change, others are compiled on demand
+            match &self.pattern {
+                Some(pattern) if pattern.source == *source => {
+                    if let Some(regex) = &pattern.regex {
+                        return regex.is_match(value);
+                    }
+                }
+                _ => {
+                    if let Some(regex) = compile_pattern(source) {
+                        return regex.is_match(value);
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Evaluates validity state matched by :valid and :invalid pseudo-classes.
+    pub(crate) fn update_validity(&mut self) {
+        let source = self.attrs.get("pattern");
+        if self.pattern.as_ref().map(|pattern| &pattern.source) != source {
+            self.pattern = source.map(|source| InputPattern {
+                source: source.clone(),
+                regex: compile_pattern(source),
+            });
+        }
+        self.state.invalid = !self.check_validity();
+    }
+}
+
+/// The regex compiled from pattern attribute once it set, None if pattern is invalid.
+pub(crate) struct InputPattern {
+    source: String,
+    regex: Option<Regex>,
+}
+
+fn compile_pattern(pattern: &str) -> Option<Regex> {
+    // pattern must match the entire value
+    match Regex::new(&format!("^(?:{pattern})$")) {
+        Ok(regex) => Some(regex),
+        Err(error) => {
+            error!("unable to validate input, invalid pattern, {error}");
+            None
+        }
+    }
+}
+
+fn collect_form_values(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    values: &mut Map<String, Value>,
+    invalid: &mut Vec<String>,
+) -> Result<(), ViewError> {
+    for child in tree.children(node)? {
+        let element = tree.get_element(child)?;
+        let is_field = element.tag == "input" || element.tag == "textarea";
+        if let Some(name) = element.attrs.get("name").filter(|_| is_field) {
+            if !element.disabled() {
+                let value = match element.attrs.get("type").map(String::as_str) {
+                    Some("submit") => continue,
+                    Some("checkbox") => Value::Bool(element.attrs.contains_key("checked")),
+                    Some("radio") if !element.attrs.contains_key("checked") => continue,
+                    _ => Value::String(element.value().cloned().unwrap_or_default()),
+                };
+                if element.state.invalid {
+                    invalid.push(name.clone());
+                }
+                values.insert(name.clone(), value);
+            }
+        }
+        collect_form_values(tree, child, values, invalid)?;
+    }
+    Ok(())
+}
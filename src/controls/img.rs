@@ -1,26 +1,39 @@
 use crate::rendering::Renderer;
 use crate::tree::ViewTreeExtensions;
-use crate::{Element, ViewError, ViewModel};
+use crate::{Element, Input, ResourceEvent, ResourceState, ViewError, ViewModel};
+use std::collections::HashMap;
 use taffy::{NodeId, TaffyTree};
 
 const BACKGROUND: usize = 0;
 
 impl Renderer {
     pub(crate) fn render_img(&mut self, img: &mut Element) -> Result<[NodeId; 1], ViewError> {
-        let undefined = String::new();
-        let src = img.attrs.get("src").unwrap_or(&undefined);
-        let background = self.render_bg_image(src.clone())?;
+        let src = select_img_src(img, 1.0);
+        let background = self.render_bg_image(src)?;
+        self.images.push(img.node);
         Ok([background])
     }
 }
 
+/// Tracks state of image resources requested by view and reported by application.
+#[derive(Default)]
+pub(crate) struct Resources {
+    pub(crate) pixel_ratio: f32,
+    pub(crate) images: HashMap<NodeId, String>,
+    pub(crate) states: HashMap<String, ResourceState>,
+}
+
 impl ViewModel {
     pub(crate) fn update_img_src(
         &mut self,
         img: NodeId,
-        src: String,
         tree: &mut TaffyTree<Element>,
     ) -> Result<(), ViewError> {
+        let src = select_img_src(tree.get_element(img)?, self.resources.pixel_ratio);
+        if self.resources.images.get(&img) == Some(&src) {
+            return Ok(());
+        }
+        self.resources.images.insert(img, src.clone());
         let child_node = tree.child_at_index(img, BACKGROUND)?;
         let child = tree.get_element_mut(child_node)?;
         child.get_background_mut(0).image = Some(src);
@@ -28,4 +41,86 @@ impl ViewModel {
         tree.mark_dirty(child_node)?;
         Ok(())
     }
+
+    /// Selects image variants for current device pixel ratio.
+    pub(crate) fn update_images_density(
+        &mut self,
+        input: &Input,
+        tree: &mut TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        if self.resources.pixel_ratio == input.density() {
+            return Ok(());
+        }
+        self.resources.pixel_ratio = input.density();
+        let images: Vec<NodeId> = self.resources.images.keys().copied().collect();
+        for img in images {
+            self.update_img_src(img, tree)?;
+        }
+        Ok(())
+    }
+
+    /// Applies resource states reported by application and notifies image elements.
+    pub(crate) fn handle_resources(
+        &mut self,
+        input: &Input,
+        tree: &TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        for (src, state) in &input.resources {
+            if self.resources.states.get(src) == Some(state) {
+                continue;
+            }
+            self.resources.states.insert(src.clone(), *state);
+            let handler = match state {
+                ResourceState::Loaded => "onload",
+                ResourceState::Failed => "onerror",
+            };
+            let images: Vec<NodeId> = self
+                .resources
+                .images
+                .iter()
+                .filter(|(_, image)| *image == src)
+                .map(|(node, _)| *node)
+                .collect();
+            for img in images {
+                let element = tree.get_element(img)?;
+                let event = ResourceEvent::new(src, element);
+                self.emit(element, handler, event);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Selects the image source best matching pixel ratio from srcset candidates,
+/// e.g. srcset="icon.png 1x, icon@2x.png 2x", falls back to src attribute.
+fn select_img_src(img: &Element, pixel_ratio: f32) -> String {
+    let src = img.attrs.get("src").cloned().unwrap_or_default();
+    let srcset = match img.attrs.get("srcset") {
+        Some(srcset) => srcset,
+        None => return src,
+    };
+    let mut candidates = vec![];
+    for candidate in srcset.split(',') {
+        let mut parts = candidate.split_whitespace();
+        let url = match parts.next() {
+            Some(url) => url,
+            None => continue,
+        };
+        let density = parts
+            .next()
+            .and_then(|descriptor| descriptor.strip_suffix('x'))
+            .and_then(|density| density.parse::<f32>().ok())
+            .unwrap_or(1.0);
+        candidates.push((density, url));
+    }
+    if !src.is_empty() && !candidates.iter().any(|(density, _)| *density == 1.0) {
+        candidates.push((1.0, src.as_str()));
+    }
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+    candidates
+        .iter()
+        .find(|(density, _)| *density >= pixel_ratio)
+        .or(candidates.last())
+        .map(|(_, url)| url.to_string())
+        .unwrap_or(src)
 }
@@ -0,0 +1,242 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{ClipboardEvent, Composition, Element, ViewError, ViewModel};
+use serde_json::Value;
+use std::collections::HashMap;
+use taffy::{NodeId, TaffyTree};
+
+/// Checks char typed with Ctrl is letter key, Ctrl combinations
+/// may be reported as ASCII control chars.
+pub(crate) fn is_ctrl_char(char: char, key: char) -> bool {
+    let key = key.to_ascii_lowercase();
+    let control = (key as u32).wrapping_sub('a' as u32 - 1);
+    char.to_ascii_lowercase() == key || char as u32 == control
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EditKind {
+    Insert,
+    Delete,
+    /// The pasted text, always separate history entry.
+    Paste,
+}
+
+/// The edit history of text inputs, consecutive edits of same kind are grouped
+/// until whitespace, so undo reverts words rather than single chars.
+#[derive(Debug)]
+pub(crate) struct EditHistory {
+    /// The keys pressed with Ctrl to undo and redo edits.
+    pub(crate) keys: [char; 2],
+    pub(crate) capacity: usize,
+    inputs: HashMap<NodeId, InputHistory>,
+}
+
+#[derive(Debug, Default)]
+struct InputHistory {
+    undo: Vec<String>,
+    redo: Vec<String>,
+    last: Option<EditKind>,
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        Self {
+            keys: ['z', 'y'],
+            capacity: 100,
+            inputs: HashMap::new(),
+        }
+    }
+}
+
+impl EditHistory {
+    /// Returns true if char typed with Ctrl means undo, false if redo, None if char is
+    /// not history shortcut.
+    pub(crate) fn shortcut(&self, char: char) -> Option<bool> {
+        let [undo, redo] = self.keys;
+        if is_ctrl_char(char, undo) {
+            Some(true)
+        } else if is_ctrl_char(char, redo) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    fn record(&mut self, input: NodeId, value: String, kind: EditKind) {
+        let history = self.inputs.entry(input).or_default();
+        history.redo.clear();
+        if kind == EditKind::Paste
+            || history.last != Some(kind)
+            || value.ends_with(char::is_whitespace)
+        {
+            history.undo.push(value);
+            if history.undo.len() > self.capacity {
+                history.undo.remove(0);
+            }
+        }
+        history.last = Some(kind);
+    }
+
+    fn undo(&mut self, input: NodeId, current: String) -> Option<String> {
+        let history = self.inputs.get_mut(&input)?;
+        let value = history.undo.pop()?;
+        history.redo.push(current);
+        history.last = None;
+        Some(value)
+    }
+
+    fn redo(&mut self, input: NodeId, current: String) -> Option<String> {
+        let history = self.inputs.get_mut(&input)?;
+        let value = history.redo.pop()?;
+        history.undo.push(current);
+        history.last = None;
+        Some(value)
+    }
+}
+
+impl ViewModel {
+    pub(crate) fn edit_input_value(
+        &mut self,
+        input: NodeId,
+        tree: &mut TaffyTree<Element>,
+        kind: EditKind,
+        edit: impl FnOnce(&mut String),
+    ) -> Result<bool, ViewError> {
+        let element = tree.get_element_mut(input)?;
+        let path = match element.model_bindings.get("value") {
+            Some(path) => path.clone(),
+            None => return Ok(false),
+        };
+        let current = element.attrs.get("value").cloned().unwrap_or_default();
+        let mut value = current.clone();
+        edit(&mut value);
+        if value == current {
+            return Ok(false);
+        }
+        self.history.record(input, current, kind);
+        self.set_input_value(element, &path, value);
+        Ok(true)
+    }
+
+    /// Reverts last group of edits of input value, or repeats reverted one if redo.
+    pub(crate) fn undo_input_value(
+        &mut self,
+        input: NodeId,
+        tree: &mut TaffyTree<Element>,
+        redo: bool,
+    ) -> Result<(), ViewError> {
+        let element = tree.get_element_mut(input)?;
+        let path = match element.model_bindings.get("value") {
+            Some(path) => path.clone(),
+            None => return Ok(()),
+        };
+        let current = element.attrs.get("value").cloned().unwrap_or_default();
+        let value = if redo {
+            self.history.redo(input, current)
+        } else {
+            self.history.undo(input, current)
+        };
+        if let Some(value) = value {
+            self.set_input_value(element, &path, value);
+            // caret moves to the end of restored value
+            self.cursor = None;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn paste_input_value(
+        &mut self,
+        input: NodeId,
+        tree: &mut TaffyTree<Element>,
+        text: &str,
+    ) -> Result<(), ViewError> {
+        let element = tree.get_element(input)?;
+        self.emit(element, "onpaste", ClipboardEvent::new(text, element));
+        self.insert_input_text(input, tree, EditKind::Paste, text)
+    }
+
+    pub(crate) fn compose_input_value(
+        &mut self,
+        input: NodeId,
+        tree: &TaffyTree<Element>,
+        text: String,
+        cursor: Option<[usize; 2]>,
+    ) -> Result<(), ViewError> {
+        let element = tree.get_element(input)?;
+        let is_text_input = element.tag == "input" || element.tag == "textarea";
+        if text.is_empty() || !is_text_input {
+            self.composition = None;
+            return Ok(());
+        }
+        let composition = Composition {
+            text,
+            cursor,
+            position: element.position,
+            size: element.size,
+        };
+        self.composition = Some((input, composition));
+        Ok(())
+    }
+
+    pub(crate) fn commit_input_value(
+        &mut self,
+        input: NodeId,
+        tree: &mut TaffyTree<Element>,
+        text: &str,
+    ) -> Result<(), ViewError> {
+        self.composition = None;
+        self.insert_input_text(input, tree, EditKind::Paste, text)
+    }
+
+    /// Puts selected text or whole input value to Output clipboard, cut also deletes it.
+    pub(crate) fn copy_input_value(
+        &mut self,
+        input: NodeId,
+        tree: &mut TaffyTree<Element>,
+        cut: bool,
+    ) -> Result<(), ViewError> {
+        let element = tree.get_element(input)?;
+        let text = self.selected_text(input, element);
+        let handler = if cut { "oncut" } else { "oncopy" };
+        self.emit(element, handler, ClipboardEvent::new(&text, element));
+        self.output.clipboard = Some(text);
+        if cut {
+            let [start, end] = self.text_cursor(input, element).range();
+            if start == end {
+                self.select_all(input, tree)?;
+            }
+            self.delete_input_text(input, tree, false)?;
+        }
+        Ok(())
+    }
+
+    fn set_input_value(&mut self, element: &mut Element, path: &str, value: String) {
+        if let Some(attribute) = element.attrs_bindings.get_mut("value") {
+            attribute.set(0, value.clone());
+        }
+        element.attrs.insert("value".into(), value.clone());
+        element.update_validity();
+        self.change(path, Value::String(value));
+    }
+
+    pub(crate) fn toggle_input_checked(
+        &mut self,
+        input: NodeId,
+        tree: &mut TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        let element = tree.get_element_mut(input)?;
+        let path = match element.model_bindings.get("checked") {
+            Some(path) => path.clone(),
+            None => return Ok(()),
+        };
+        let checked = !element.attrs.contains_key("checked");
+        element.state.checked = checked;
+        if checked {
+            element.attrs.insert("checked".into(), "checked".to_string());
+        } else {
+            element.attrs.remove("checked");
+        }
+        element.update_validity();
+        self.change(&path, Value::Bool(checked));
+        Ok(())
+    }
+}
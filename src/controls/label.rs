@@ -0,0 +1,59 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, MouseEvent, ViewError, ViewModel};
+use taffy::{NodeId, TaffyTree};
+
+impl ViewModel {
+    /// Activates the labeled control, the element referenced by "for" attribute
+    /// or the first focusable descendant of label.
+    pub(crate) fn activate_label(
+        &mut self,
+        label: NodeId,
+        body: NodeId,
+        tree: &mut TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        let control = match tree.get_element(label)?.attrs.get("for") {
+            Some(id) => find_element(tree, body, &|element| {
+                element.attrs.get("id") == Some(id)
+            })?,
+            None => find_element(tree, label, &|element| {
+                element.focusable() || element.model_bindings.contains_key("checked")
+            })?,
+        };
+        let control = match control {
+            Some(control) if control != label => control,
+            _ => return Ok(()),
+        };
+        if self.elements_under_mouse.contains(&control) {
+            // control already handles click itself
+            return Ok(());
+        }
+        let element = tree.get_element(control)?;
+        if element.disabled() {
+            return Ok(());
+        }
+        let focusable = element.focusable();
+        let event = MouseEvent::new(self.mouse, self.modifiers, element);
+        self.emit(element, "onclick", event);
+        self.toggle_input_checked(control, tree)?;
+        if focusable {
+            self.set_focus(Some(control), tree)?;
+        }
+        Ok(())
+    }
+}
+
+fn find_element(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    predicate: &impl Fn(&Element) -> bool,
+) -> Result<Option<NodeId>, ViewError> {
+    for child in tree.children(node)? {
+        if predicate(tree.get_element(child)?) {
+            return Ok(Some(child));
+        }
+        if let Some(found) = find_element(tree, child, predicate)? {
+            return Ok(Some(found));
+        }
+    }
+    Ok(None)
+}
@@ -1 +1,11 @@
+pub(crate) use caret::*;
+pub(crate) use form::*;
+pub(crate) use img::*;
+pub(crate) use input::*;
+
+mod anchor;
+mod caret;
+mod form;
 mod img;
+mod input;
+mod label;
@@ -0,0 +1,190 @@
+use crate::css::{Complex, Simple, Style};
+use crate::Element;
+use smol_str::SmolStr;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// The styles of stylesheet bucketed by key selector, the id, class or type of
+/// rightmost compound selector, so element is matched only against plausible styles.
+#[derive(Debug, Default)]
+pub struct StyleIndex {
+    ids: HashMap<SmolStr, Vec<usize>>,
+    classes: HashMap<SmolStr, Vec<usize>>,
+    types: HashMap<SmolStr, Vec<usize>>,
+    universal: Vec<usize>,
+}
+
+impl StyleIndex {
+    pub fn new(styles: &[Style]) -> Self {
+        let mut index = Self::default();
+        for (position, style) in styles.iter().enumerate() {
+            for complex in &style.selectors {
+                let bucket = match key_selector(complex) {
+                    Some(Simple::Id(id)) => index.ids.entry(id.clone()).or_default(),
+                    Some(Simple::Class(class)) => index.classes.entry(class.clone()).or_default(),
+                    Some(Simple::Type(tag)) => index.types.entry(tag.clone()).or_default(),
+                    _ => &mut index.universal,
+                };
+                // selector list may put style in bucket twice
+                if bucket.last() != Some(&position) {
+                    bucket.push(position);
+                }
+            }
+        }
+        index
+    }
+
+    /// Returns positions of styles element may match in stylesheet order, styles
+    /// keyed by any class or id are candidates if element binds them dynamically.
+    pub fn candidates(&self, element: &Element) -> Vec<usize> {
+        let hints = &element.style_hints;
+        let dynamic_id = hints.has_dynamic_id || hints.dynamic_attrs.contains("id");
+        let dynamic_classes = hints.has_dynamic_classes || hints.dynamic_attrs.contains("class");
+        let mut candidates = self.universal.clone();
+        if let Some(styles) = self.types.get(&element.tag) {
+            candidates.extend(styles);
+        }
+        if dynamic_id {
+            candidates.extend(self.ids.values().flatten());
+        } else if let Some(styles) = element
+            .attrs
+            .get("id")
+            .and_then(|id| self.ids.get(id.as_str()))
+        {
+            candidates.extend(styles);
+        }
+        if dynamic_classes {
+            candidates.extend(self.classes.values().flatten());
+        } else if let Some(classes) = element.attrs.get("class") {
+            for class in classes.split(" ") {
+                if let Some(styles) = self.classes.get(class) {
+                    candidates.extend(styles);
+                }
+            }
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+    }
+}
+
+/// Returns the most specific id, class or type of rightmost compound selector.
+fn key_selector(complex: &Complex) -> Option<&Simple> {
+    let compound = complex
+        .selectors
+        .iter()
+        .rev()
+        .take_while(|selector| selector.as_combinator().is_none());
+    let rank = |selector: &Simple| match selector {
+        Simple::Id(_) => 3,
+        Simple::Class(_) => 2,
+        Simple::Type(_) => 1,
+        _ => 0,
+    };
+    compound
+        .filter(|selector| rank(selector) > 0)
+        .max_by_key(|selector| rank(selector))
+}
+
+/// The bloom filter of ids, classes and types of element ancestors, so selectors
+/// requiring missing parent are rejected without tree traversal.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AncestorFilter {
+    bits: [u64; 4],
+}
+
+impl AncestorFilter {
+    /// Adds ids, classes and type of ancestor element.
+    pub fn insert(&mut self, element: &Element) {
+        self.set('t', &element.tag);
+        if let Some(id) = element.attrs.get("id") {
+            self.set('#', id);
+        }
+        if let Some(classes) = element.attrs.get("class") {
+            for class in classes.split(" ") {
+                self.set('.', class);
+            }
+        }
+    }
+
+    /// Returns false if no selector of style can match because compound selector
+    /// of some parent requires id, class or type not present in ancestors.
+    pub fn may_match(&self, style: &Style) -> bool {
+        style.selectors.iter().any(|complex| {
+            let mut relation = None;
+            for selector in complex.selectors.iter().rev() {
+                match selector {
+                    Simple::Combinator(combinator) => relation = Some(*combinator),
+                    _ if relation != Some('>') => {}
+                    Simple::Id(id) if !self.contains('#', id) => return false,
+                    Simple::Class(class) if !self.contains('.', class) => return false,
+                    Simple::Type(tag) if !self.contains('t', tag) => return false,
+                    _ => {}
+                }
+            }
+            true
+        })
+    }
+
+    fn set(&mut self, kind: char, name: &str) {
+        for bit in bloom_bits(kind, name) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    fn contains(&self, kind: char, name: &str) -> bool {
+        bloom_bits(kind, name)
+            .iter()
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+}
+
+fn bloom_bits(kind: char, name: &str) -> [usize; 2] {
+    let mut hasher = DefaultHasher::new();
+    (kind, name).hash(&mut hasher);
+    let hash = hasher.finish() as usize;
+    [hash & 255, (hash >> 8) & 255]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css::read_css;
+    use crate::styles::create_element;
+    use taffy::NodeId;
+
+    fn element(tag: &str, attrs: &[(&str, &str)]) -> Element {
+        let mut element = create_element(NodeId::from(0u64));
+        element.tag = tag.into();
+        for (key, value) in attrs {
+            element.attrs.insert((*key).into(), value.to_string());
+        }
+        element
+    }
+
+    #[test]
+    pub fn test_styles_bucketed_by_key_selector() {
+        let css = read_css("* {} #a {} .b {} div.b {} span {} .c, div {}").unwrap();
+        let index = StyleIndex::new(&css.styles);
+        let div = element("div", &[("id", "a"), ("class", "b")]);
+        assert_eq!(index.candidates(&div), [0, 1, 2, 3, 5]);
+        let mut span = element("span", &[]);
+        assert_eq!(index.candidates(&span), [0, 4]);
+        span.style_hints.has_dynamic_classes = true;
+        assert_eq!(index.candidates(&span), [0, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    pub fn test_missing_parent_rejected() {
+        let css = read_css(".a > div {} .b > div {} .b + div {} .a > .b + div {}").unwrap();
+        let mut filter = AncestorFilter::default();
+        filter.insert(&element("section", &[("class", "a")]));
+        let matches: Vec<bool> = css
+            .styles
+            .iter()
+            .map(|style| filter.may_match(style))
+            .collect();
+        assert_eq!(matches, [true, false, true, true]);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::css::{Complex, Matcher, Simple, Style};
 use crate::Element;
 use log::error;
+use smol_str::SmolStr;
 use std::collections::HashSet;
 
 use taffy::{NodeId, TaffyTree};
@@ -138,7 +139,7 @@ impl Style {
         })
     }
 
-    pub fn has_attrs_selector(&self, attrs: &HashSet<String>) -> bool {
+    pub fn has_attrs_selector(&self, attrs: &HashSet<SmolStr>) -> bool {
         if attrs.is_empty() {
             return false;
         }
@@ -150,6 +151,15 @@ impl Style {
         })
     }
 
+    pub fn has_sibling_combinator(&self) -> bool {
+        self.selectors.iter().any(|complex| {
+            complex
+                .selectors
+                .iter()
+                .any(|selector| selector.as_combinator() == Some('+'))
+        })
+    }
+
     pub fn has_pseudo_class_selector(&self) -> bool {
         self.selectors.iter().any(|complex| {
             complex.selectors.iter().any(|selector| match selector {
@@ -1,7 +1,9 @@
+pub use index::*;
 pub use matching::*;
 pub use model::*;
 pub use reader::*;
 
+mod index;
 mod matching;
 mod model;
 mod reader;
@@ -1,4 +1,6 @@
+use smol_str::SmolStr;
 use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display, Formatter};
 
 #[derive(Debug)]
 pub struct Css {
@@ -37,13 +39,13 @@ pub struct Complex {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Simple {
     All,
-    Id(String),
-    Class(String),
-    Type(String),
-    Attribute(String, Matcher, String),
+    Id(SmolStr),
+    Class(SmolStr),
+    Type(SmolStr),
+    Attribute(SmolStr, Matcher, String),
     Root,
-    PseudoClass(String),
-    PseudoElement(String),
+    PseudoClass(SmolStr),
+    PseudoElement(SmolStr),
     Combinator(char),
 }
 
@@ -76,6 +78,43 @@ impl Simple {
     }
 }
 
+impl Display for Simple {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Simple::All => write!(f, "*"),
+            Simple::Id(id) => write!(f, "#{id}"),
+            Simple::Class(class) => write!(f, ".{class}"),
+            Simple::Type(tag) => write!(f, "{tag}"),
+            Simple::Attribute(key, matcher, value) => {
+                let matcher = match matcher {
+                    Matcher::Exist => return write!(f, "[{key}]"),
+                    Matcher::Equal => "=",
+                    Matcher::Include => "~=",
+                    Matcher::DashMatch => "|=",
+                    Matcher::Prefix => "^=",
+                    Matcher::Substring => "*=",
+                    Matcher::Suffix => "$=",
+                };
+                write!(f, "[{key}{matcher}\"{value}\"]")
+            }
+            Simple::Root => write!(f, ":root"),
+            Simple::PseudoClass(class) => write!(f, ":{class}"),
+            Simple::PseudoElement(element) => write!(f, "::{element}"),
+            Simple::Combinator(' ') => write!(f, " "),
+            Simple::Combinator(combinator) => write!(f, " {combinator} "),
+        }
+    }
+}
+
+impl Display for Complex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for selector in &self.selectors {
+            write!(f, "{selector}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Style {
     /// A selector list is a comma-separated list of selectors.
@@ -89,6 +128,17 @@ pub enum Declaration {
     Property(Property),
 }
 
+impl Declaration {
+    /// Returns true if both declarations specify the same property or variable.
+    pub fn has_same_key(&self, other: &Declaration) -> bool {
+        match (self, other) {
+            (Declaration::Variable(this), Declaration::Variable(other)) => this.key == other.key,
+            (Declaration::Property(this), Declaration::Property(other)) => this.key == other.key,
+            _ => false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Variable {
     pub key: String,
@@ -120,7 +170,7 @@ pub enum Definition {
 // Explicitly specified in styles or as a result of computations.
 #[derive(Clone, Debug, PartialEq)]
 pub enum ComputedValue {
-    Keyword(String),
+    Keyword(SmolStr),
     Zero,
     Percentage(f32),
     Time(f32),
@@ -128,6 +178,10 @@ pub enum ComputedValue {
     Number(f32),
     Color([u8; 4]),
     Str(String),
+    /// The values of one shadow in order of declaration, see `box-shadow`.
+    Shadow(Vec<ComputedValue>),
+    /// The arguments of `linear-gradient()` function.
+    Gradient(Vec<ComputedValue>),
     Error,
 }
 
@@ -167,6 +221,7 @@ pub enum Units {
     Px,
     Em,
     Rem,
+    Deg,
 }
 
 impl Units {
@@ -179,6 +234,7 @@ impl Units {
             "px" => Units::Px,
             "em" => Units::Em,
             "rem" => Units::Rem,
+            "deg" => Units::Deg,
             _ => return None,
         };
         Some(units)
@@ -803,6 +859,19 @@ pub enum PropertyKey {
     WritingMode,
     ZIndex,
     Zoom,
+    //
+    // Bumaga extensions
+    //
+    /// The frame count of sprite-sheet background image, frames laid out horizontally.
+    BumagaBackgroundFrames,
+    /// The duration of one loop through all sprite-sheet frames.
+    BumagaBackgroundDuration,
+    /// The outline of text glyphs as width and color.
+    BumagaTextStroke,
+    BumagaTextStrokeColor,
+    BumagaTextStrokeWidth,
+    /// The name of custom material engine binds to element, passed through untouched.
+    BumagaMaterial,
 }
 
 impl PropertyKey {
@@ -861,19 +930,19 @@ impl PropertyKey {
             "border-block-color" => Self::BorderBlockColor,
             "border-block-end" => Self::BorderBlockEnd,
             "border-block-end-color" => Self::BorderBlockEndColor,
-            "border-block-end-styles" => Self::BorderBlockEndStyle,
+            "border-block-end-style" => Self::BorderBlockEndStyle,
             "border-block-end-width" => Self::BorderBlockEndWidth,
             "border-block-start" => Self::BorderBlockStart,
             "border-block-start-color" => Self::BorderBlockStartColor,
-            "border-block-start-styles" => Self::BorderBlockStartStyle,
+            "border-block-start-style" => Self::BorderBlockStartStyle,
             "border-block-start-width" => Self::BorderBlockStartWidth,
-            "border-block-styles" => Self::BorderBlockStyle,
+            "border-block-style" => Self::BorderBlockStyle,
             "border-block-width" => Self::BorderBlockWidth,
             "border-bottom" => Self::BorderBottom,
             "border-bottom-color" => Self::BorderBottomColor,
             "border-bottom-left-radius" => Self::BorderBottomLeftRadius,
             "border-bottom-right-radius" => Self::BorderBottomRightRadius,
-            "border-bottom-styles" => Self::BorderBottomStyle,
+            "border-bottom-style" => Self::BorderBottomStyle,
             "border-bottom-width" => Self::BorderBottomWidth,
             "border-boundary" => Self::BorderBoundary,
             "border-collapse" => Self::BorderCollapse,
@@ -890,32 +959,32 @@ impl PropertyKey {
             "border-inline-color" => Self::BorderInlineColor,
             "border-inline-end" => Self::BorderInlineEnd,
             "border-inline-end-color" => Self::BorderInlineEndColor,
-            "border-inline-end-styles" => Self::BorderInlineEndStyle,
+            "border-inline-end-style" => Self::BorderInlineEndStyle,
             "border-inline-end-width" => Self::BorderInlineEndWidth,
             "border-inline-start" => Self::BorderInlineStart,
             "border-inline-start-color" => Self::BorderInlineStartColor,
-            "border-inline-start-styles" => Self::BorderInlineStartStyle,
+            "border-inline-start-style" => Self::BorderInlineStartStyle,
             "border-inline-start-width" => Self::BorderInlineStartWidth,
-            "border-inline-styles" => Self::BorderInlineStyle,
+            "border-inline-style" => Self::BorderInlineStyle,
             "border-inline-width" => Self::BorderInlineWidth,
             "border-left" => Self::BorderLeft,
             "border-left-color" => Self::BorderLeftColor,
-            "border-left-styles" => Self::BorderLeftStyle,
+            "border-left-style" => Self::BorderLeftStyle,
             "border-left-width" => Self::BorderLeftWidth,
             "border-radius" => Self::BorderRadius,
             "border-right" => Self::BorderRight,
             "border-right-color" => Self::BorderRightColor,
-            "border-right-styles" => Self::BorderRightStyle,
+            "border-right-style" => Self::BorderRightStyle,
             "border-right-width" => Self::BorderRightWidth,
             "border-spacing" => Self::BorderSpacing,
             "border-start-end-radius" => Self::BorderStartEndRadius,
             "border-start-start-radius" => Self::BorderStartStartRadius,
-            "border-styles" => Self::BorderStyle,
+            "border-style" => Self::BorderStyle,
             "border-top" => Self::BorderTop,
             "border-top-color" => Self::BorderTopColor,
             "border-top-left-radius" => Self::BorderTopLeftRadius,
             "border-top-right-radius" => Self::BorderTopRightRadius,
-            "border-top-styles" => Self::BorderTopStyle,
+            "border-top-style" => Self::BorderTopStyle,
             "border-top-width" => Self::BorderTopWidth,
             "border-width" => Self::BorderWidth,
             "bottom" => Self::Bottom,
@@ -1398,6 +1467,12 @@ impl PropertyKey {
             "wrap-through" => Self::WrapThrough,
             "writing-mode" => Self::WritingMode,
             "z-index" => Self::ZIndex,
+            "-bumaga-background-frames" => Self::BumagaBackgroundFrames,
+            "-bumaga-background-duration" => Self::BumagaBackgroundDuration,
+            "-bumaga-text-stroke" => Self::BumagaTextStroke,
+            "-bumaga-text-stroke-color" => Self::BumagaTextStrokeColor,
+            "-bumaga-text-stroke-width" => Self::BumagaTextStrokeWidth,
+            "-bumaga-material" => Self::BumagaMaterial,
             "zoom" => Self::Zoom,
             _ => return None,
         };
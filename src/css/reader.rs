@@ -8,6 +8,7 @@ use pest::error::Error;
 use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
+use smol_str::SmolStr;
 use std::collections::HashMap;
 
 #[derive(Parser)]
@@ -87,8 +88,8 @@ pub fn read_css(css: &str) -> Result<Css, ReaderError> {
                                     let mut iter = simple.into_inner();
                                     let ident = iter
                                         .next()
-                                        .map(|pair| pair.as_str().to_string())
-                                        .unwrap_or(String::new());
+                                        .map(|pair| SmolStr::new(pair.as_str()))
+                                        .unwrap_or_default();
                                     let component = match simple_rule {
                                         Rule::All => Simple::All,
                                         Rule::Id => Simple::Id(ident),
@@ -219,7 +220,7 @@ fn read_value_def(pair: Pair<Rule>) -> Definition {
 
 fn read_explicit_value(pair: Pair<Rule>) -> ComputedValue {
     match pair.as_rule() {
-        Rule::Keyword => ComputedValue::Keyword(pair.as_str().to_string()),
+        Rule::Keyword => ComputedValue::Keyword(pair.as_str().into()),
         Rule::Color => ComputedValue::Color(read_color(pair)),
         Rule::Zero => ComputedValue::Zero,
         Rule::Time => ComputedValue::Time(read_seconds(pair)),
@@ -418,6 +419,20 @@ mod tests {
         assert_eq!(css.first_short(), expected);
     }
 
+    #[test]
+    pub fn test_component_value_function_whitespace_separated() {
+        let css = css("div { background-image: linear-gradient( to right, red 20%, blue ); }");
+        let arguments = [kw("to"), kw("right"), kw("red"), perc(0.2), kw("blue")];
+        assert_eq!(css.first_short(), &[func("linear-gradient", &arguments)]);
+    }
+
+    #[test]
+    pub fn test_component_value_function_angle() {
+        let css = css("div { background-image: linear-gradient(45deg, red, blue); }");
+        let arguments = [deg(45.0), kw("red"), kw("blue")];
+        assert_eq!(css.first_short(), &[func("linear-gradient", &arguments)]);
+    }
+
     #[test]
     pub fn test_zero_value() {
         let css = css("div { padding: 0; }");
@@ -449,7 +464,7 @@ mod tests {
 
         assert_eq!(
             selectors[0],
-            &Simple::Attribute("data-something".to_string(), Matcher::Exist, "".to_string())
+            &Simple::Attribute("data-something".into(), Matcher::Exist, "".to_string())
         );
     }
 
@@ -459,11 +474,7 @@ mod tests {
         let selectors = style_selectors(&css);
         assert_eq!(
             selectors[0],
-            &Simple::Attribute(
-                "data-something".to_string(),
-                Matcher::Equal,
-                "abc".to_string()
-            )
+            &Simple::Attribute("data-something".into(), Matcher::Equal, "abc".to_string())
         );
     }
 
@@ -473,11 +484,7 @@ mod tests {
         let selectors = style_selectors(&css);
         assert_eq!(
             selectors[0],
-            &Simple::Attribute(
-                "data-something".to_string(),
-                Matcher::Equal,
-                "abc".to_string()
-            )
+            &Simple::Attribute("data-something".into(), Matcher::Equal, "abc".to_string())
         );
     }
 
@@ -573,12 +580,16 @@ mod tests {
         Definition::Explicit(ComputedValue::Dimension(Dim::new(value, Units::Em)))
     }
 
+    fn deg(value: f32) -> Definition {
+        Definition::Explicit(ComputedValue::Dimension(Dim::new(value, Units::Deg)))
+    }
+
     fn var(value: &str) -> Definition {
         Definition::Var(value.to_string())
     }
 
     fn kw(value: &str) -> Definition {
-        Definition::Explicit(ComputedValue::Keyword(value.to_string()))
+        Definition::Explicit(ComputedValue::Keyword(value.into()))
     }
 
     fn f(value: f32) -> Definition {
@@ -0,0 +1,421 @@
+use crate::bidi::is_bidi;
+use crate::tree::ViewTreeExtensions;
+use crate::view::clips_content;
+use crate::{
+    BorderStyle, Element, FontFace, FontMetrics, LinearGradient, ObjectFit, Rect, Rgba, Shadow,
+    TransformFunction, ViewError,
+};
+use std::collections::BTreeMap;
+use taffy::{NodeId, TaffyTree};
+
+/// The primitive drawing command of view, renderer draws view by executing
+/// commands of display list in order, see [`crate::View::display_list`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisplayCommand {
+    /// Clips following commands by rounded rectangle until matching PopClip,
+    /// nested clips intersect, corner radii in order top-left, top-right,
+    /// bottom-right, bottom-left.
+    PushClip {
+        position: [f32; 2],
+        size: [f32; 2],
+        radius: [f32; 4],
+    },
+    PopClip,
+    /// Sets translation of following commands, replaces previous translation.
+    Transform {
+        translate: [f32; 2],
+    },
+    /// Sets custom material of following commands, replaces previous material,
+    /// None for default drawing.
+    Material {
+        name: Option<String>,
+    },
+    /// The filled rectangle with corner radii in order top-left, top-right,
+    /// bottom-right, bottom-left.
+    Rect {
+        position: [f32; 2],
+        size: [f32; 2],
+        color: Rgba,
+        radius: [f32; 4],
+    },
+    /// The shadow of rectangle with corner radii, outer shadow is drawn outside of
+    /// rectangle only, inset shadow inside of it only. Shadow shape is rectangle
+    /// moved by offset and expanded by spread, shrunk by it for inset shadow.
+    BoxShadow {
+        position: [f32; 2],
+        size: [f32; 2],
+        radius: [f32; 4],
+        shadow: Shadow,
+    },
+    /// The linear gradient filling rectangle with corner radii, gradient line goes
+    /// through center of rectangle and fits its corners.
+    Gradient {
+        position: [f32; 2],
+        size: [f32; 2],
+        radius: [f32; 4],
+        gradient: LinearGradient,
+    },
+    /// The border drawn inside of rectangle, widths, colors and styles of sides
+    /// in order top, right, bottom, left.
+    Border {
+        position: [f32; 2],
+        size: [f32; 2],
+        widths: [f32; 4],
+        colors: [Rgba; 4],
+        styles: [BorderStyle; 4],
+        radius: [f32; 4],
+    },
+    /// The image fitted into rectangle, offset is image source position.
+    Image {
+        src: String,
+        position: [f32; 2],
+        size: [f32; 2],
+        offset: [f32; 2],
+        fit: ObjectFit,
+        opacity: f32,
+        radius: [f32; 4],
+        /// The shown frame index and frame count of sprite-sheet image laid out
+        /// horizontally, fit and offset apply to one frame.
+        frame: [u32; 2],
+    },
+    /// The text laid out into rectangle, bidirectional text is in visual order
+    /// with lines separated by line feeds.
+    TextRun {
+        text: String,
+        position: [f32; 2],
+        size: [f32; 2],
+        color: Rgba,
+        font: FontFace,
+        /// The offset of first line baseline from top of rectangle, renderers drawing
+        /// glyphs at baseline place text by it.
+        baseline: f32,
+    },
+    /// The shadow of following text run, text is drawn in shadow color moved by
+    /// shadow offset and blurred.
+    TextShadow {
+        text: String,
+        position: [f32; 2],
+        size: [f32; 2],
+        font: FontFace,
+        baseline: f32,
+        shadow: Shadow,
+    },
+}
+
+/// Appends commands of element and its descendants to display list, colors are
+/// multiplied by element opacity.
+pub(crate) fn collect_display_list(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    translate: [f32; 2],
+    material: Option<&str>,
+    commands: &mut Vec<DisplayCommand>,
+) -> Result<(), ViewError> {
+    let element = tree.get_element(node)?;
+    let current = element_translate(element, translate);
+    if current != translate {
+        commands.push(DisplayCommand::Transform { translate: current });
+    }
+    let current_material = element_material(element, material);
+    if current_material != material {
+        let name = current_material.map(String::from);
+        commands.push(DisplayCommand::Material { name });
+    }
+    paint_element(element, commands);
+    let children = tree.children(node)?;
+    let overflow = tree.style(node)?.overflow;
+    // children carry clip area of element if it clips content
+    let clipping = children
+        .first()
+        .and_then(|child| tree.get_element(*child).ok())
+        .and_then(|child| child.clipping.as_ref())
+        .filter(|_| clips_content(element, overflow));
+    let clipped = clipping.is_some();
+    if let Some(clipping) = clipping {
+        commands.push(DisplayCommand::PushClip {
+            position: clipping.position,
+            size: clipping.size,
+            radius: clipping.radius,
+        });
+    }
+    for child in children {
+        collect_display_list(tree, child, current, current_material, commands)?;
+    }
+    if clipped {
+        commands.push(DisplayCommand::PopClip);
+    }
+    if current_material != material {
+        let name = material.map(String::from);
+        commands.push(DisplayCommand::Material { name });
+    }
+    if current != translate {
+        commands.push(DisplayCommand::Transform { translate });
+    }
+    Ok(())
+}
+
+/// The paint commands of element and their screen area, compared between
+/// updates to find damaged areas.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PaintRecord {
+    bounds: Rect,
+    commands: Vec<DisplayCommand>,
+}
+
+/// Collects paint records of element and its descendants which paint something,
+/// by node id in order, so damage is reported deterministically.
+pub(crate) fn collect_paint_records(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    translate: [f32; 2],
+    records: &mut BTreeMap<u64, PaintRecord>,
+) -> Result<(), ViewError> {
+    let element = tree.get_element(node)?;
+    let translate = element_translate(element, translate);
+    let mut commands = vec![];
+    paint_element(element, &mut commands);
+    if !commands.is_empty() {
+        let [x, y] = element.position;
+        let [width, height] = element.size;
+        let [left, top, right, bottom] = shadow_outsets(element);
+        let mut bounds = [x - left, y - top, x + width + right, y + height + bottom];
+        if let Some(clipping) = element.clipping.as_ref() {
+            let [left, top, right, bottom] = clipping.bounds();
+            bounds = [
+                bounds[0].max(left),
+                bounds[1].max(top),
+                bounds[2].min(right),
+                bounds[3].min(bottom),
+            ];
+        }
+        let [left, top, right, bottom] = bounds;
+        let bounds = Rect {
+            position: [left + translate[0], top + translate[1]],
+            size: [(right - left).max(0.0), (bottom - top).max(0.0)],
+        };
+        records.insert(node.into(), PaintRecord { bounds, commands });
+    }
+    for child in tree.children(node)? {
+        collect_paint_records(tree, child, translate, records)?;
+    }
+    Ok(())
+}
+
+/// Returns screen areas painted differently by previous and current records,
+/// both old and new areas of changed elements are damaged.
+pub(crate) fn find_damage(
+    previous: &BTreeMap<u64, PaintRecord>,
+    current: &BTreeMap<u64, PaintRecord>,
+) -> Vec<Rect> {
+    let mut damage: Vec<Rect> = vec![];
+    let mut add = |bounds: &Rect| {
+        let [width, height] = bounds.size;
+        if width > 0.0 && height > 0.0 && !damage.contains(bounds) {
+            damage.push(*bounds);
+        }
+    };
+    for (node, record) in current {
+        match previous.get(node) {
+            Some(previous) if previous == record => {}
+            Some(previous) => {
+                add(&previous.bounds);
+                add(&record.bounds);
+            }
+            None => add(&record.bounds),
+        }
+    }
+    for (node, record) in previous {
+        if !current.contains_key(node) {
+            add(&record.bounds);
+        }
+    }
+    damage
+}
+
+/// Returns translation of element and its descendants, accumulated with ancestors.
+pub(crate) fn element_translate(element: &Element, translate: [f32; 2]) -> [f32; 2] {
+    let [width, height] = element.size;
+    let mut translate = translate;
+    for transform in &element.transforms {
+        match transform {
+            TransformFunction::Translate { x, y, .. } => {
+                translate[0] += x.resolve(width);
+                translate[1] += y.resolve(height);
+            }
+        }
+    }
+    translate
+}
+
+/// Returns material of element and its descendants, inherited from ancestors if not set.
+pub(crate) fn element_material<'e>(
+    element: &'e Element,
+    material: Option<&'e str>,
+) -> Option<&'e str> {
+    element.material.as_deref().or(material)
+}
+
+/// Appends commands of element itself, shadows, backgrounds, borders and text.
+pub(crate) fn paint_element(element: &Element, commands: &mut Vec<DisplayCommand>) {
+    if element.inline {
+        // painted by paragraph lead
+        return;
+    }
+    let position = element.position;
+    let size = element.size;
+    let radius = element.radius;
+    let opacity = element.opacity;
+    let shadows = |inset: bool, commands: &mut Vec<DisplayCommand>| {
+        for shadow in element.shadows.iter().rev() {
+            let color = fade(shadow.color, opacity);
+            if shadow.inset == inset && color[3] > 0 {
+                commands.push(DisplayCommand::BoxShadow {
+                    position,
+                    size,
+                    radius,
+                    shadow: Shadow { color, ..*shadow },
+                });
+            }
+        }
+    };
+    shadows(false, commands);
+    for background in element.backgrounds.iter().rev() {
+        let color = fade(background.color, opacity);
+        if color[3] > 0 {
+            commands.push(DisplayCommand::Rect {
+                position,
+                size,
+                color,
+                radius,
+            });
+        }
+        if let Some(src) = background.image.as_ref() {
+            commands.push(DisplayCommand::Image {
+                src: src.clone(),
+                position,
+                size,
+                offset: background.src,
+                fit: element.object_fit,
+                opacity,
+                radius,
+                frame: [background.frame, background.frames],
+            });
+        }
+        if let Some(gradient) = background.gradient.as_ref() {
+            let mut gradient = gradient.clone();
+            for stop in gradient.stops.iter_mut() {
+                stop.color = fade(stop.color, opacity);
+            }
+            commands.push(DisplayCommand::Gradient {
+                position,
+                size,
+                radius,
+                gradient,
+            });
+        }
+    }
+    shadows(true, commands);
+    let borders = &element.borders;
+    let sides = [borders.top, borders.right, borders.bottom, borders.left];
+    if sides
+        .iter()
+        .any(|side| side.width > 0.0 && side.style.is_visible())
+    {
+        commands.push(DisplayCommand::Border {
+            position,
+            size,
+            widths: sides.map(|side| side.width),
+            colors: sides.map(|side| fade(side.color, opacity)),
+            styles: sides.map(|side| side.style),
+            radius,
+        });
+    }
+    for fragment in &element.fragments {
+        let styled = &element.styled[fragment.span];
+        let color = fade(styled.color, opacity);
+        if !fragment.text.trim().is_empty() && color[3] > 0 {
+            for shadow in styled.shadows.iter().rev() {
+                commands.push(DisplayCommand::TextShadow {
+                    text: fragment.text.clone(),
+                    position: fragment.origin,
+                    size: [fragment.width, fragment.height],
+                    font: styled.font.clone(),
+                    baseline: fragment.baseline,
+                    shadow: Shadow {
+                        color: fade(shadow.color, opacity),
+                        ..*shadow
+                    },
+                });
+            }
+            commands.push(DisplayCommand::TextRun {
+                text: fragment.text.clone(),
+                position: fragment.origin,
+                size: [fragment.width, fragment.height],
+                color,
+                font: styled.font.clone(),
+                baseline: fragment.baseline,
+            });
+        }
+    }
+    if let Some(text) = element.text.as_ref().filter(|_| element.styled.is_empty()) {
+        let mut text = text.to_string();
+        if is_bidi(&text, element.font.direction) && !element.lines.is_empty() {
+            // lines reordered visually, renderers must not break them differently
+            let lines: Vec<&str> = element
+                .lines
+                .iter()
+                .map(|line| line.text.as_str())
+                .collect();
+            text = lines.join("\n");
+        }
+        let color = fade(element.color, opacity);
+        let baseline = match element.lines.first() {
+            Some(line) => line.origin[1] - position[1] + line.baseline,
+            None => FontMetrics::approximate(element.font.size).baseline(element.font.size),
+        };
+        if !text.trim().is_empty() && color[3] > 0 {
+            for shadow in element.text_shadows.iter().rev() {
+                commands.push(DisplayCommand::TextShadow {
+                    text: text.clone(),
+                    position,
+                    size,
+                    font: element.font.clone(),
+                    baseline,
+                    shadow: Shadow {
+                        color: fade(shadow.color, opacity),
+                        ..*shadow
+                    },
+                });
+            }
+            commands.push(DisplayCommand::TextRun {
+                text,
+                position,
+                size,
+                color,
+                font: element.font.clone(),
+                baseline,
+            });
+        }
+    }
+}
+
+/// Returns how far outer shadows of element paint beyond its border box, in order
+/// left, top, right, bottom.
+fn shadow_outsets(element: &Element) -> [f32; 4] {
+    let mut outsets = [0.0f32; 4];
+    let outer = element.shadows.iter().filter(|shadow| !shadow.inset);
+    let spans = element.styled.iter().flat_map(|span| &span.shadows);
+    for shadow in outer.chain(&element.text_shadows).chain(spans) {
+        let extent = shadow.blur + shadow.spread.max(0.0);
+        let [x, y] = shadow.offset;
+        outsets[0] = outsets[0].max(extent - x);
+        outsets[1] = outsets[1].max(extent - y);
+        outsets[2] = outsets[2].max(extent + x);
+        outsets[3] = outsets[3].max(extent + y);
+    }
+    outsets
+}
+
+fn fade([r, g, b, a]: Rgba, opacity: f32) -> Rgba {
+    [r, g, b, (a as f32 * opacity).round() as u8]
+}
@@ -0,0 +1,219 @@
+use crate::{Input, InputEvent, Keys, MouseButtons};
+use egui::{Event, ImeEvent, InputState, Key, Modifiers, MouseWheelUnit, PointerButton, Rect};
+use egui::{Pos2, TouchPhase};
+use std::time::Duration;
+
+/// Converts egui frame input to input of view embedded into egui area, so view
+/// can be placed into existing egui screens. Positions are converted to physical
+/// pixels relative to area, egui pixels per point is passed to Input.
+#[derive(Debug)]
+pub struct EguiInput {
+    modifiers: Modifiers,
+    /// The scroll distance in logical pixels of one wheel line.
+    pub line_height: f32,
+}
+
+impl EguiInput {
+    pub fn new() -> Self {
+        Self {
+            modifiers: Modifiers::NONE,
+            line_height: 50.0,
+        }
+    }
+
+    /// Returns input of events egui received this frame, rect is area of view in points.
+    pub fn take_input(&mut self, input: &InputState, rect: Rect) -> Input {
+        let scale = input.pixels_per_point();
+        let position = |pos: Pos2| {
+            let pos = pos - rect.min;
+            [pos.x * scale, pos.y * scale]
+        };
+        let mut events = vec![];
+        // egui reports modifiers as state of each event instead of key events
+        let modifiers = [
+            (Modifiers::ALT, Keys::Alt),
+            (Modifiers::CTRL, Keys::Ctrl),
+            (Modifiers::SHIFT, Keys::Shift),
+            (Modifiers::MAC_CMD, Keys::Meta),
+        ];
+        for (modifier, key) in modifiers {
+            let pressed = input.modifiers.contains(modifier);
+            if pressed != self.modifiers.contains(modifier) {
+                events.push(match pressed {
+                    true => InputEvent::KeyDown(key),
+                    false => InputEvent::KeyUp(key),
+                });
+            }
+        }
+        self.modifiers = input.modifiers;
+        for event in &input.events {
+            match event {
+                Event::PointerMoved(pos) => events.push(InputEvent::MouseMove(position(*pos))),
+                Event::PointerButton {
+                    pos,
+                    button,
+                    pressed,
+                    ..
+                } => {
+                    let button = match button {
+                        PointerButton::Primary => MouseButtons::Left,
+                        PointerButton::Secondary => MouseButtons::Right,
+                        _ => continue,
+                    };
+                    events.push(InputEvent::MouseMove(position(*pos)));
+                    events.push(match pressed {
+                        true => InputEvent::MouseButtonDown(button),
+                        false => InputEvent::MouseButtonUp(button),
+                    });
+                }
+                Event::MouseWheel { unit, delta, .. } => {
+                    // egui positive x reveals content on the left
+                    let factor = match unit {
+                        MouseWheelUnit::Point => 1.0,
+                        MouseWheelUnit::Line => self.line_height,
+                        MouseWheelUnit::Page => rect.height(),
+                    };
+                    events.push(InputEvent::MouseWheel([
+                        -delta.x * factor,
+                        delta.y * factor,
+                    ]));
+                }
+                Event::Key {
+                    key,
+                    pressed,
+                    repeat,
+                    ..
+                } => {
+                    // view repeats held editing and navigation keys itself
+                    let key = map_key(*key);
+                    match (pressed, repeat) {
+                        (true, false) => events.push(InputEvent::KeyDown(key)),
+                        (true, true) => {}
+                        (false, _) => events.push(InputEvent::KeyUp(key)),
+                    }
+                }
+                Event::Text(text) => events.extend(text.chars().map(InputEvent::Char)),
+                Event::Paste(text) => events.push(InputEvent::Paste(text.clone())),
+                Event::Copy => events.push(InputEvent::Copy),
+                Event::Cut => events.push(InputEvent::Cut),
+                Event::Ime(ImeEvent::Preedit(text)) => {
+                    events.push(InputEvent::ImeCompose(text.clone(), None))
+                }
+                Event::Ime(ImeEvent::Commit(text)) => {
+                    events.push(InputEvent::ImeCommit(text.clone()))
+                }
+                Event::Touch { id, phase, pos, .. } => {
+                    let position = position(*pos);
+                    events.push(match phase {
+                        TouchPhase::Start => InputEvent::TouchStart(id.0, position),
+                        TouchPhase::Move => InputEvent::TouchMove(id.0, position),
+                        TouchPhase::End | TouchPhase::Cancel => {
+                            InputEvent::TouchEnd(id.0, position)
+                        }
+                    });
+                }
+                _ => {}
+            }
+        }
+        Input::new()
+            .time(Duration::from_secs_f64(input.time.max(0.0)))
+            .viewport([rect.width() * scale, rect.height() * scale])
+            .scale(scale)
+            .scroll_sensitivity(1.0)
+            .events(events)
+    }
+}
+
+impl Default for EguiInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns key of egui logical key, printable keys without bumaga key are Unknown.
+pub fn map_key(key: Key) -> Keys {
+    match key {
+        // UI keys
+        Key::Escape => Keys::Escape,
+        // Editing keys
+        Key::Backspace => Keys::Backspace,
+        Key::Delete => Keys::Delete,
+        Key::Insert => Keys::Insert,
+        // Whitespace keys
+        Key::Enter => Keys::Enter,
+        Key::Tab => Keys::Tab,
+        Key::Space => Keys::Space,
+        // Navigation keys
+        Key::ArrowUp => Keys::ArrowUp,
+        Key::ArrowDown => Keys::ArrowDown,
+        Key::ArrowLeft => Keys::ArrowLeft,
+        Key::ArrowRight => Keys::ArrowRight,
+        Key::End => Keys::End,
+        Key::Home => Keys::Home,
+        Key::PageDown => Keys::PageDown,
+        Key::PageUp => Keys::PageUp,
+        // Punctuation keys
+        Key::Minus => Keys::Minus,
+        Key::Equals => Keys::Equal,
+        Key::OpenBracket => Keys::BracketLeft,
+        Key::CloseBracket => Keys::BracketRight,
+        Key::Backslash => Keys::Backslash,
+        Key::Semicolon => Keys::Semicolon,
+        Key::Quote => Keys::Quote,
+        Key::Backtick => Keys::Backquote,
+        Key::Comma => Keys::Comma,
+        Key::Period => Keys::Period,
+        Key::Slash => Keys::Slash,
+        // Function keys
+        Key::F1 => Keys::F1,
+        Key::F2 => Keys::F2,
+        Key::F3 => Keys::F3,
+        Key::F4 => Keys::F4,
+        Key::F5 => Keys::F5,
+        Key::F6 => Keys::F6,
+        Key::F7 => Keys::F7,
+        Key::F8 => Keys::F8,
+        Key::F9 => Keys::F9,
+        Key::F10 => Keys::F10,
+        Key::F11 => Keys::F11,
+        Key::F12 => Keys::F12,
+        // Letter and digit keys
+        Key::A => Keys::Character('a'),
+        Key::B => Keys::Character('b'),
+        Key::C => Keys::Character('c'),
+        Key::D => Keys::Character('d'),
+        Key::E => Keys::Character('e'),
+        Key::F => Keys::Character('f'),
+        Key::G => Keys::Character('g'),
+        Key::H => Keys::Character('h'),
+        Key::I => Keys::Character('i'),
+        Key::J => Keys::Character('j'),
+        Key::K => Keys::Character('k'),
+        Key::L => Keys::Character('l'),
+        Key::M => Keys::Character('m'),
+        Key::N => Keys::Character('n'),
+        Key::O => Keys::Character('o'),
+        Key::P => Keys::Character('p'),
+        Key::Q => Keys::Character('q'),
+        Key::R => Keys::Character('r'),
+        Key::S => Keys::Character('s'),
+        Key::T => Keys::Character('t'),
+        Key::U => Keys::Character('u'),
+        Key::V => Keys::Character('v'),
+        Key::W => Keys::Character('w'),
+        Key::X => Keys::Character('x'),
+        Key::Y => Keys::Character('y'),
+        Key::Z => Keys::Character('z'),
+        Key::Num0 => Keys::Character('0'),
+        Key::Num1 => Keys::Character('1'),
+        Key::Num2 => Keys::Character('2'),
+        Key::Num3 => Keys::Character('3'),
+        Key::Num4 => Keys::Character('4'),
+        Key::Num5 => Keys::Character('5'),
+        Key::Num6 => Keys::Character('6'),
+        Key::Num7 => Keys::Character('7'),
+        Key::Num8 => Keys::Character('8'),
+        Key::Num9 => Keys::Character('9'),
+        _ => Keys::Unknown,
+    }
+}
@@ -1,11 +1,17 @@
 use log::error;
 use std::collections::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
-use taffy::{Layout, NodeId};
+use serde_json::Value;
+use smol_str::SmolStr;
+use std::sync::Arc;
+use std::time::Duration;
+use taffy::{Display, NodeId};
 
 use crate::animation::{Animator, Transition};
+use crate::controls::InputPattern;
 use crate::css::{Declaration, Style};
-use crate::styles::Scrolling;
+use crate::styles::{Scrolling, Variables};
+use crate::{Pipe, StyledText, TextFragment, TextLine};
 
 /// The most fundamental object for building a UI, Element contains layout and appearance.
 /// Element maps directly to the native rectangle view equivalent on whatever graphics engine
@@ -13,27 +19,62 @@ use crate::styles::Scrolling;
 pub struct Element {
     pub node: NodeId,
     pub children: Vec<NodeId>,
-    pub tag: String,
+    pub tag: SmolStr,
     pub text: Option<TextContent>,
-    pub attrs: HashMap<String, String>,
-    pub attrs_bindings: HashMap<String, TextContent>,
+    /// The line boxes of text positioned by final layout, origins are absolute.
+    pub lines: Vec<TextLine>,
+    /// The styled texts of paragraph led by element text, including own text, empty
+    /// if element text is not paragraph lead.
+    pub styled: Vec<StyledText>,
+    /// The fragments of styled texts positioned by final layout, origins are absolute.
+    pub fragments: Vec<TextFragment>,
+    pub attrs: HashMap<SmolStr, String>,
+    pub attrs_bindings: HashMap<SmolStr, TextContent>,
+    /// The two-way bindings, maps attribute key to JSON pointer of model value.
+    pub model_bindings: HashMap<SmolStr, String>,
     pub position: [f32; 2],
     pub size: [f32; 2],
     pub content_size: [f32; 2],
     pub object_fit: ObjectFit,
     pub backgrounds: Vec<Background>,
+    /// The shadows of border box, first shadow is painted on top.
+    pub shadows: Vec<Shadow>,
     pub borders: Borders,
+    /// The final corner radii of border box in pixels, in order top-left, top-right,
+    /// bottom-right, bottom-left, computed during layout.
+    pub radius: [f32; 4],
     /// The foreground color of element (most often text color).
     pub color: Rgba,
     /// The different properties of an element's text font.
     pub font: FontFace,
-    pub listeners: HashMap<String, Handler>,
+    /// The shadows of text inherited by descendants, first shadow is painted on top.
+    pub text_shadows: Vec<Shadow>,
+    /// The event handlers in binding order, element may have many handlers of one event.
+    pub listeners: HashMap<String, Vec<Handler>>,
     pub self_opacity: f32,
     pub opacity: f32,
     pub transforms: Vec<TransformFunction>,
     pub scrolling: Option<Scrolling>,
-    pub clipping: Option<Layout>,
+    /// The clip area of nearest clipping ancestor, None if element is not clipped.
+    pub clipping: Option<Clipping>,
     pub pointer_events: PointerEvents,
+    /// The paint order of element in stacking context, None if auto.
+    pub z_index: Option<i32>,
+    /// The properties expected to change, listed in will-change.
+    pub will_change: Vec<String>,
+    /// Whether element subtree should be rendered into its own cached layer,
+    /// hinted by will-change or running animation, so it can be composited
+    /// instead of redrawn every frame.
+    pub layer: bool,
+    /// The custom material of element and its descendants, set by -bumaga-material
+    /// or data-material attribute, engines may bind own shaders by it.
+    pub material: Option<String>,
+    /// Whether element is laid out and painted as part of paragraph, see `styled`.
+    pub inline: bool,
+    /// The display computed by cascade, restored when element stops being inline.
+    pub(crate) inline_display: Display,
+    /// The vertical alignment of inline element text within line of paragraph.
+    pub vertical_align: VerticalAlign,
 
     pub style_hints: ElementStyleHints,
     pub styles: Vec<ElementStyle>,
@@ -41,18 +82,41 @@ pub struct Element {
     pub(crate) animators: Vec<Animator>,
     pub(crate) state: ElementState,
     pub(crate) transitions: Vec<Transition>,
+    /// The hash of element properties matched by selectors at last cascade, None if
+    /// styles of element must be recalculated.
+    pub(crate) style_key: Option<u64>,
+    /// The CSS variables computed by last cascade, inherited by children.
+    pub(crate) variables: Variables,
+    /// The compiled pattern attribute of input, see [`Element::check_validity`].
+    pub(crate) pattern: Option<InputPattern>,
 }
 
 #[derive(Debug)]
 pub struct Handler {
     pub arguments: Vec<HandlerArgument>,
+    pub rate: Option<RateLimit>,
+}
+
+/// The limit of handler messages rate, e.g. `^onmousemove.throttle:50ms`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimit {
+    /// Emits first message and drops others until period elapsed.
+    Throttle(Duration),
+    /// Emits last message once no events happened during period.
+    Debounce(Duration),
+    /// Emits message every period, e.g. `^ontick="Poll 0.5s"`.
+    Interval(Duration),
 }
 
 #[derive(Debug)]
 pub enum HandlerArgument {
     Keyword(String),
-    Event,
-    Binder { path: String, pipe: Vec<String> },
+    /// The path of selected event field, empty path selects whole event.
+    Event(Vec<String>),
+    Binder {
+        path: String,
+        pipe: Vec<Pipe>,
+    },
 }
 
 #[derive(Debug)]
@@ -63,7 +127,7 @@ pub enum ElementStyle {
 
 #[derive(Debug, Default, Clone)]
 pub struct ElementStyleHints {
-    pub dynamic_attrs: HashSet<String>,
+    pub dynamic_attrs: HashSet<SmolStr>,
     pub has_dynamic_classes: bool,
     pub has_dynamic_id: bool,
 }
@@ -89,11 +153,67 @@ impl Element {
         }
     }
 
+    /// Returns data carried by dragging of element, specified by data-drag attribute
+    /// and parsed as JSON if possible, e.g. `@data-drag="{item.id}"`.
+    pub fn drag_data(&self) -> Value {
+        match self.attrs.get("data-drag") {
+            Some(data) => {
+                serde_json::from_str(data).unwrap_or_else(|_| Value::String(data.clone()))
+            }
+            None => Value::Null,
+        }
+    }
+
+    /// Disabled element does not respond to user interaction.
+    #[inline(always)]
+    pub fn disabled(&self) -> bool {
+        self.attrs.contains_key("disabled")
+    }
+
+    #[inline(always)]
+    pub fn hidden(&self) -> bool {
+        self.attrs.contains_key("hidden")
+    }
+
+    /// Returns the position of element in sequential keyboard navigation,
+    /// None if element can't receive focus. Negative index means element can be focused
+    /// by mouse but not reachable with Tab key.
+    pub fn tab_index(&self) -> Option<i32> {
+        if self.disabled() {
+            return None;
+        }
+        match self.attrs.get("tabindex") {
+            Some(index) => index.parse().ok(),
+            None if self.listeners.contains_key("oninput")
+                || self.model_bindings.contains_key("value")
+                || (self.tag == "a" && self.attrs.contains_key("href")) =>
+            {
+                Some(0)
+            }
+            None => None,
+        }
+    }
+
+    /// Element can receive focus if it handles text input, edits model value
+    /// or explicitly specifies tabindex.
+    #[inline(always)]
+    pub fn focusable(&self) -> bool {
+        self.tab_index().is_some()
+    }
+
     #[inline(always)]
     pub fn value(&self) -> Option<&String> {
         self.attrs.get("value")
     }
 
+    /// Element is animated while it plays animations, transitions or animated
+    /// backgrounds, so its styles must be recalculated every update.
+    pub(crate) fn is_animated(&self) -> bool {
+        self.animators.iter().any(Animator::is_active)
+            || self.transitions.iter().any(Transition::is_active)
+            || self.backgrounds.iter().any(Background::is_animated)
+    }
+
     pub fn get_background_mut(&mut self, index: usize) -> &mut Background {
         if index >= self.backgrounds.len() {
             self.backgrounds.resize_with(index + 1, Background::default);
@@ -185,6 +305,83 @@ impl TransformFunction {
 
 pub type Rgba = [u8; 4];
 
+/// The rounded rectangle of clipping ancestor padding box, element is visible
+/// only within intersection of all clip areas in chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clipping {
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    /// The corner radii in order top-left, top-right, bottom-right, bottom-left.
+    pub radius: [f32; 4],
+    /// The clip area of next clipping ancestor.
+    pub parent: Option<Arc<Clipping>>,
+}
+
+impl Clipping {
+    /// Returns clip areas from nearest to outermost ancestor.
+    pub fn chain(&self) -> impl Iterator<Item = &Clipping> {
+        let mut next = Some(self);
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = current.parent.as_deref();
+            Some(current)
+        })
+    }
+
+    /// Checks point is within all clip areas of chain, including rounded corners.
+    pub fn contains(&self, point: [f32; 2]) -> bool {
+        self.chain().all(|clipping| clipping.contains_area(point))
+    }
+
+    fn contains_area(&self, [x, y]: [f32; 2]) -> bool {
+        let [left, top] = self.position;
+        let [right, bottom] = [left + self.size[0], top + self.size[1]];
+        if x < left || x > right || y < top || y > bottom {
+            return false;
+        }
+        let [top_left, top_right, bottom_right, bottom_left] = self.radius;
+        // corner radius, circle center and direction of corner from center
+        let corners = [
+            (top_left, [left + top_left, top + top_left], [-1.0, -1.0]),
+            (top_right, [right - top_right, top + top_right], [1.0, -1.0]),
+            (
+                bottom_right,
+                [right - bottom_right, bottom - bottom_right],
+                [1.0, 1.0],
+            ),
+            (
+                bottom_left,
+                [left + bottom_left, bottom - bottom_left],
+                [-1.0, 1.0],
+            ),
+        ];
+        for (radius, [cx, cy], [sx, sy]) in corners {
+            let [dx, dy] = [x - cx, y - cy];
+            if radius > 0.0 && dx * sx > 0.0 && dy * sy > 0.0 && dx * dx + dy * dy > radius * radius
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns intersection of clip areas rectangles as left, top, right, bottom edges.
+    pub fn bounds(&self) -> [f32; 4] {
+        let mut bounds = [f32::MIN, f32::MIN, f32::MAX, f32::MAX];
+        for clipping in self.chain() {
+            let [x, y] = clipping.position;
+            let [width, height] = clipping.size;
+            bounds = [
+                bounds[0].max(x),
+                bounds[1].max(y),
+                bounds[2].min(x + width),
+                bounds[3].min(y + height),
+            ];
+        }
+        bounds
+    }
+}
+
 #[derive(Clone)]
 pub struct Borders {
     pub top: MyBorder,
@@ -195,6 +392,27 @@ pub struct Borders {
 }
 
 impl Borders {
+    /// Resolves corner radii in pixels for box size, radii are scaled down
+    /// proportionally if sum of adjacent radii exceeds side length.
+    pub fn resolve_radius(&self, size: [f32; 2]) -> [f32; 4] {
+        let [width, height] = size;
+        let radius = self.radius.map(|radius| radius.resolve(width).max(0.0));
+        let [top_left, top_right, bottom_right, bottom_left] = radius;
+        let sides = [
+            (width, top_left + top_right),
+            (height, top_right + bottom_right),
+            (width, bottom_right + bottom_left),
+            (height, bottom_left + top_left),
+        ];
+        let mut factor: f32 = 1.0;
+        for (length, sum) in sides {
+            if sum > 0.0 {
+                factor = factor.min(length.max(0.0) / sum);
+            }
+        }
+        radius.map(|radius| radius * factor)
+    }
+
     pub fn top(&self) -> Option<MyBorder> {
         if self.top.width > 0.0 {
             Some(self.top)
@@ -232,17 +450,45 @@ impl Borders {
 pub struct MyBorder {
     pub width: f32,
     pub color: Rgba,
+    pub style: BorderStyle,
+}
+
+/// The line style of border side, borders without specified style are solid.
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum BorderStyle {
+    None,
+    Hidden,
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+    Double,
+}
+
+impl BorderStyle {
+    /// Checks border side with this style is drawn.
+    pub fn is_visible(&self) -> bool {
+        !matches!(self, BorderStyle::None | BorderStyle::Hidden)
+    }
 }
 
 #[derive(Clone)]
 pub struct Background {
     /// The background image.
     pub image: Option<String>,
+    /// The gradient painted instead of background image.
+    pub gradient: Option<LinearGradient>,
     pub is_src: bool,
     /// The background color.
     pub color: Rgba,
     // The background position.
     pub src: [f32; 2],
+    /// The frame count of sprite-sheet image, frames laid out horizontally.
+    pub frames: u32,
+    /// The duration in seconds of one loop through all frames.
+    pub duration: f32,
+    /// The frame of sprite-sheet shown at input time.
+    pub frame: u32,
     // pub position: BackgroundPosition,
     // /// How the background image should repeat.
     // pub repeat: BackgroundRepeat,
@@ -260,13 +506,53 @@ impl Default for Background {
     fn default() -> Self {
         Self {
             image: None,
+            gradient: None,
             is_src: false,
             color: [0; 4],
             src: [0.0; 2],
+            frames: 1,
+            duration: 0.0,
+            frame: 0,
         }
     }
 }
 
+impl Background {
+    /// Whether background image is sprite-sheet changing frames over time.
+    pub fn is_animated(&self) -> bool {
+        self.frames > 1 && self.duration > 0.0
+    }
+}
+
+/// The linear gradient of background, see `linear-gradient()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearGradient {
+    /// The direction of gradient line in degrees clockwise, 0 is to top.
+    pub angle: f32,
+    /// The colors along gradient line in order of offsets.
+    pub stops: Vec<ColorStop>,
+}
+
+/// The color of gradient at offset along gradient line, from 0 at start to 1 at end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    pub color: Rgba,
+    pub offset: f32,
+}
+
+/// The shadow of box or text, see `box-shadow` and `text-shadow`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Shadow {
+    pub offset: [f32; 2],
+    /// The blur radius, shadow is blurred with standard deviation of half of it.
+    pub blur: f32,
+    /// The distance shadow of box is expanded by, zero for text.
+    pub spread: f32,
+    pub color: Rgba,
+    /// Whether shadow of box is cast inside of border box instead of outside.
+    pub inset: bool,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ObjectFit {
     Contain,
@@ -276,14 +562,14 @@ pub enum ObjectFit {
     ScaleDown,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct FontFace {
     /// The font family.
-    pub family: String,
+    pub family: SmolStr,
     /// The font size.
     pub size: f32,
     // The font styles.
-    pub style: String,
+    pub style: SmolStr,
     /// The font weight.
     pub weight: u16,
     // The font stretch.
@@ -293,9 +579,27 @@ pub struct FontFace {
     // The text overflow wrap.
     // pub wrap: OverflowWrap,
     pub align: TextAlign,
+    /// The base direction of text, start and end alignment follow it.
+    pub direction: TextDirection,
+    /// The outline drawn around glyphs, e.g. to keep HUD text readable over scene.
+    pub stroke: TextStroke,
+    /// How words are hyphenated when broken between lines.
+    pub hyphens: Hyphens,
+    /// The language of text set by lang attribute, empty if view locale.
+    pub lang: String,
+    /// The distance between tab stops tab characters are expanded to.
+    pub tab_size: TabSize,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextStroke {
+    /// The stroke width, zero if text has no outline.
+    pub width: f32,
+    /// The stroke color, None if stroke drawn with text color.
+    pub color: Option<Rgba>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum TextAlign {
     Start,
     End,
@@ -307,12 +611,62 @@ pub enum TextAlign {
     MatchParent,
 }
 
+/// The base direction of bidirectional text ordered by Unicode bidi algorithm.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
 #[derive(Default, Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct ElementState {
     pub active: bool,
     pub hover: bool,
     pub focus: bool,
+    /// Whether focus should be indicated, e.g. focus came from keyboard navigation.
+    pub focus_visible: bool,
     pub checked: bool,
+    /// The number of element bindings with pending values.
+    pub pending: usize,
+    /// Whether element overlaps clipped visible area of its scroll container.
+    pub visible: bool,
+    /// Whether input value violates its constraints, see [`Element::check_validity`].
+    pub invalid: bool,
+}
+
+/// The distance between tab stops in widths of space or pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TabSize {
+    Spaces(f32),
+    Length(f32),
+}
+
+impl Default for TabSize {
+    fn default() -> Self {
+        Self::Spaces(8.0)
+    }
+}
+
+/// The hyphenation of words, manual breaks words only at soft hyphens, auto also at
+/// hyphenation points of language found by application hyphenator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Hyphens {
+    None,
+    #[default]
+    Manual,
+    Auto,
+}
+
+/// The vertical alignment of text within line box, baseline aligns baselines of
+/// differently sized texts, others align text box edges to line box edges.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VerticalAlign {
+    #[default]
+    Baseline,
+    Top,
+    Middle,
+    Bottom,
 }
 
 #[derive(Default, Debug, PartialEq, Clone, Copy)]
@@ -0,0 +1,109 @@
+use unicode_properties::emoji::{EmojiStatus, UnicodeEmoji};
+
+/// The part of text of one presentation, emoji runs are measured and drawn by color
+/// emoji face, see [`crate::Fonts::resolve_emoji`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PresentationRun {
+    /// The byte range of run in text.
+    pub range: [usize; 2],
+    pub emoji: bool,
+}
+
+/// Splits text into runs of text and emoji presentation, sequences of flags, keycaps,
+/// skin tones and ZWJ families stay in one emoji run.
+pub fn split_presentation(text: &str) -> Vec<PresentationRun> {
+    let mut runs: Vec<PresentationRun> = vec![];
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, char)) = chars.next() {
+        let next = chars.peek().map(|(_, next)| *next);
+        let emoji = is_emoji_start(char, next);
+        if emoji {
+            let regional = is_regional_indicator(char);
+            let mut joined = false;
+            while let Some(&(_, next)) = chars.peek() {
+                let continues = match next {
+                    '\u{FE0F}' | '\u{20E3}' | '\u{200D}' => true,
+                    '\u{1F3FB}'..='\u{1F3FF}' | '\u{E0020}'..='\u{E007F}' => true,
+                    next if regional && is_regional_indicator(next) => true,
+                    next => joined && next.is_emoji_char(),
+                };
+                if !continues {
+                    break;
+                }
+                joined = next == '\u{200D}';
+                chars.next();
+                if is_regional_indicator(next) {
+                    break;
+                }
+            }
+        }
+        let end = chars.peek().map(|(index, _)| *index).unwrap_or(text.len());
+        match runs.last_mut() {
+            Some(run) if run.emoji == emoji => run.range[1] = end,
+            _ => runs.push(PresentationRun {
+                range: [start, end],
+                emoji,
+            }),
+        }
+    }
+    runs
+}
+
+/// Returns whether char starts emoji sequence, by default presentation of char or
+/// by following emoji variation selector or keycap.
+fn is_emoji_start(char: char, next: Option<char>) -> bool {
+    match next {
+        Some('\u{FE0E}') => false,
+        Some('\u{FE0F}' | '\u{20E3}') => char.is_emoji_char_or_emoji_component(),
+        _ => matches!(
+            char.emoji_status(),
+            EmojiStatus::EmojiPresentation
+                | EmojiStatus::EmojiPresentationAndModifierBase
+                | EmojiStatus::EmojiPresentationAndEmojiComponent
+                | EmojiStatus::EmojiPresentationAndModifierAndEmojiComponent
+        ),
+    }
+}
+
+fn is_regional_indicator(char: char) -> bool {
+    matches!(char, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(text: &str) -> Vec<(&str, bool)> {
+        split_presentation(text)
+            .into_iter()
+            .map(|run| (&text[run.range[0]..run.range[1]], run.emoji))
+            .collect()
+    }
+
+    #[test]
+    pub fn test_plain_text_is_one_run() {
+        assert_eq!(runs("Hello, 1 © world"), [("Hello, 1 © world", false)]);
+    }
+
+    #[test]
+    pub fn test_emoji_runs() {
+        assert_eq!(
+            runs("gg 😀👍🏽 wp"),
+            [("gg ", false), ("😀👍🏽", true), (" wp", false)]
+        );
+    }
+
+    #[test]
+    pub fn test_emoji_sequences() {
+        assert_eq!(runs("🇺🇦🇵🇱"), [("🇺🇦🇵🇱", true)]);
+        assert_eq!(runs("a👨‍👩‍👧b"), [("a", false), ("👨‍👩‍👧", true), ("b", false)]);
+        assert_eq!(runs("1️⃣2"), [("1️⃣", true), ("2", false)]);
+    }
+
+    #[test]
+    pub fn test_variation_selectors() {
+        assert_eq!(runs("❤"), [("❤", false)]);
+        assert_eq!(runs("❤️"), [("❤️", true)]);
+        assert_eq!(runs("😀\u{FE0E}"), [("😀\u{FE0E}", false)]);
+    }
+}
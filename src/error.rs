@@ -9,6 +9,7 @@ use crate::html;
 pub enum ViewError {
     Layout(TaffyError),
     ElementNotFound(NodeId),
+    ElementIdNotFound(String),
     ElementTextContentNotFound,
     ParentNotFound(NodeId),
     ChildNotFound(NodeId),
@@ -19,6 +20,8 @@ pub enum ViewError {
     ElementInvalidBehaviour,
     AttributeBindingNotFound(String),
     TemplateNotFound(String),
+    ModelValueNotFound(String),
+    FontInvalid(String),
 }
 
 impl From<TaffyError> for ViewError {
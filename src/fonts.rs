@@ -1,13 +1,584 @@
-use crate::FontFace;
+use crate::bidi::{reorder_fragments, reorder_lines};
+use crate::{
+    split_presentation, FontFace, Hyphens, Rgba, Shadow, TabSize, TextAlign, TextDirection,
+    VerticalAlign, SOFT_HYPHEN,
+};
+use std::borrow::Cow;
+use std::collections::HashMap;
 
 pub trait Fonts {
-    fn measure(&self, text: &str, face: &FontFace, max_width: Option<f32>) -> [f32; 2];
+    /// Resolves typeface of font family, weight and style (normal, italic or oblique)
+    /// text is measured by, by default all text measured by one face.
+    fn resolve(&self, family: &str, weight: u16, style: &str) -> FaceId {
+        let _ = (family, weight, style);
+        FaceId::default()
+    }
+
+    /// Returns whether faces of font family are loaded, text of families not loaded
+    /// is listed in output pending fonts until application reports them loaded with
+    /// [`crate::Input::font_loaded`], by default all families are loaded.
+    fn is_loaded(&self, family: &str) -> bool {
+        let _ = family;
+        true
+    }
+
+    /// Resolves color emoji face of emoji runs within text of face, see
+    /// [`split_presentation`], by default emoji measured by the same face.
+    fn resolve_emoji(&self, id: FaceId) -> FaceId {
+        id
+    }
+
+    /// Returns vertical metrics of face, texts of paragraph are aligned and renderers
+    /// place glyphs by baseline, by default approximate metrics of font size.
+    fn metrics(&self, id: FaceId, face: &FontFace) -> FontMetrics {
+        let _ = id;
+        FontMetrics::approximate(face.size)
+    }
+
+    /// Returns size of text, view breaks lines itself by measuring words and lines
+    /// without max width, so implementations unable to wrap text may ignore it.
+    fn measure(&self, id: FaceId, text: &str, face: &FontFace, max_width: Option<f32>) -> [f32; 2];
+
+    /// Breaks text into line boxes fitting max width, by default greedily at whitespace
+    /// and soft hyphens (unless hyphens none) using measure. Implementations shaping
+    /// text should override it so lines match rendering exactly.
+    fn layout(
+        &self,
+        id: FaceId,
+        text: &str,
+        face: &FontFace,
+        max_width: Option<f32>,
+    ) -> Vec<TextLine> {
+        break_lines(self, id, text, face, max_width)
+    }
+
+    /// Returns fonts if they can be used by many threads, so `parallel` feature
+    /// measures texts in advance by many threads, by default fonts are used by one.
+    fn as_sync(&self) -> Option<&(dyn Fonts + Sync)> {
+        None
+    }
+}
+
+/// The typeface identifier resolved by fonts, meaning is up to implementation,
+/// e.g. index of loaded face.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FaceId(pub usize);
+
+/// The vertical metrics of font face in pixels, ascent and descent are distances
+/// above and below baseline.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FontMetrics {
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_gap: f32,
+}
+
+impl FontMetrics {
+    /// Returns metrics typical for Latin fonts of size.
+    pub fn approximate(size: f32) -> Self {
+        Self {
+            ascent: size * 0.8,
+            descent: size * 0.2,
+            line_gap: 0.0,
+        }
+    }
+
+    /// Returns offset of baseline from top of line box of height, extra space of line
+    /// (leading) is split equally above and below glyphs like in browsers.
+    pub fn baseline(&self, height: f32) -> f32 {
+        (height - self.ascent - self.descent) / 2.0 + self.ascent
+    }
+}
+
+/// Measures one line of text by runs of its presentation, emoji by emoji face, tabs
+/// are measured expanded.
+pub(crate) fn measure_line<F: Fonts + ?Sized>(
+    fonts: &F,
+    id: FaceId,
+    text: &str,
+    face: &FontFace,
+) -> [f32; 2] {
+    let text = expand_tabs(fonts, id, text, face);
+    let text = text.as_ref();
+    let runs = split_presentation(text);
+    if !runs.iter().any(|run| run.emoji) {
+        return fonts.measure(id, text, face, None);
+    }
+    let emoji = fonts.resolve_emoji(id);
+    runs.iter().fold([0.0, 0.0], |[width, height], run| {
+        let [start, end] = run.range;
+        let id = if run.emoji { emoji } else { id };
+        let [run_width, run_height] = fonts.measure(id, &text[start..end], face, None);
+        [width + run_width, height.max(run_height)]
+    })
+}
+
+/// Replaces tabs of one line of text by spaces up to next tab stop, the last space
+/// is kept if it takes less than half of space to stop.
+pub(crate) fn expand_tabs<'t, F: Fonts + ?Sized>(
+    fonts: &F,
+    id: FaceId,
+    text: &'t str,
+    face: &FontFace,
+) -> Cow<'t, str> {
+    if !text.contains('\t') {
+        return Cow::Borrowed(text);
+    }
+    let [space, _] = fonts.measure(id, " ", face, None);
+    let stop = match face.tab_size {
+        TabSize::Spaces(spaces) => spaces * space,
+        TabSize::Length(length) => length,
+    };
+    let mut expanded = String::with_capacity(text.len());
+    for (index, part) in text.split('\t').enumerate() {
+        if index > 0 && stop > 0.0 && space > 0.0 {
+            let [x, _] = measure_line(fonts, id, &expanded, face);
+            let next = ((x / stop).floor() + 1.0) * stop;
+            let count = ((next - x) / space).round().max(1.0) as usize;
+            expanded.extend(std::iter::repeat_n(' ', count));
+        }
+        expanded.push_str(part);
+    }
+    Cow::Owned(expanded)
+}
+
+/// Returns typeface of font face resolved by fonts.
+pub(crate) fn resolve_face<F: Fonts + ?Sized>(fonts: &F, face: &FontFace) -> FaceId {
+    fonts.resolve(&face.family, face.weight, &face.style)
+}
+
+/// The line box of laid out text, origin is relative to text box top-left corner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    pub text: String,
+    /// The byte range of line in element text, text of `hyphens: auto` includes soft
+    /// hyphens inserted by hyphenator.
+    pub range: [usize; 2],
+    pub origin: [f32; 2],
+    pub width: f32,
+    pub height: f32,
+    /// The offset of baseline from line top.
+    pub baseline: f32,
+}
+
+fn break_lines<F: Fonts + ?Sized>(
+    fonts: &F,
+    id: FaceId,
+    text: &str,
+    face: &FontFace,
+    max_width: Option<f32>,
+) -> Vec<TextLine> {
+    let mut words = vec![];
+    let mut start = None;
+    for (index, char) in text.char_indices() {
+        match (start, char.is_whitespace()) {
+            (None, false) => start = Some(index),
+            (Some(word), true) => {
+                words.push([word, index]);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(word) = start {
+        words.push([word, text.len()]);
+    }
+    let measure = |start: usize, end: usize, hyphen: bool| {
+        let line = line_text(&text[start..end], hyphen);
+        let [width, _] = measure_line(fonts, id, &line, face);
+        width
+    };
+    // line widths are summed from widths of words and preceding spaces, so each
+    // word is measured once, except tabs expanded depending on line position
+    let tabs = text.contains('\t');
+    let extend = |[start, end]: [usize; 2], width: f32, next: usize, hyphen: bool| match tabs {
+        true => measure(start, next, hyphen),
+        false => width + measure(end, next, hyphen),
+    };
+    let fits = |width: f32| {
+        max_width
+            .map(|max_width| width <= max_width)
+            .unwrap_or(true)
+    };
+    let hyphens = face.hyphens != Hyphens::None;
+    // line ranges, whether line is ended by hyphen of broken word and line width
+    let mut ranges: Vec<([usize; 2], bool, f32)> = vec![];
+    for [word_start, word_end] in words {
+        if let Some((line, _, line_width)) = ranges.last_mut() {
+            let width = extend(*line, *line_width, word_end, false);
+            if fits(width) {
+                line[1] = word_end;
+                *line_width = width;
+                continue;
+            }
+        }
+        let mut start = word_start;
+        let points: Vec<usize> = match hyphens {
+            true => text[word_start..word_end]
+                .match_indices(SOFT_HYPHEN)
+                .map(|(index, _)| word_start + index)
+                .collect(),
+            false => vec![],
+        };
+        // word broken at longest fitting part, on new line at least at first part
+        if let Some((line, hyphen, line_width)) = ranges.last_mut() {
+            let point = points
+                .iter()
+                .rev()
+                .find(|point| fits(extend(*line, *line_width, **point, true)));
+            if let Some(point) = point {
+                line[1] = *point;
+                *hyphen = true;
+                start = point + SOFT_HYPHEN.len_utf8();
+            }
+        }
+        let mut width = measure(start, word_end, false);
+        while !fits(width) {
+            let mut rest = points.iter().filter(|point| **point > start);
+            let first = match rest.next() {
+                Some(first) => *first,
+                None => break,
+            };
+            let point = rest
+                .rev()
+                .find(|point| fits(measure(start, **point, true)))
+                .copied()
+                .unwrap_or(first);
+            ranges.push(([start, point], true, 0.0));
+            start = point + SOFT_HYPHEN.len_utf8();
+            width = measure(start, word_end, false);
+        }
+        ranges.push(([start, word_end], false, width));
+    }
+    let metrics = fonts.metrics(id, face);
+    let mut lines = vec![];
+    let mut y = 0.0;
+    for ([start, end], hyphen, _) in ranges {
+        let line = line_text(&text[start..end], hyphen);
+        let line = expand_tabs(fonts, id, &line, face).into_owned();
+        let [width, height] = measure_line(fonts, id, &line, face);
+        let space = max_width.map(|max_width| max_width - width).unwrap_or(0.0);
+        let x = align_offset(face, space);
+        lines.push(TextLine {
+            text: line,
+            range: [start, end],
+            origin: [x.max(0.0), y],
+            width,
+            height,
+            baseline: metrics.baseline(height),
+        });
+        y += height;
+    }
+    reorder_lines(text, face.direction, &mut lines);
+    lines
+}
+
+/// Returns text of line without soft hyphens, ended by hyphen if word is broken.
+fn line_text(line: &str, hyphen: bool) -> String {
+    let mut text: String = line.chars().filter(|char| *char != SOFT_HYPHEN).collect();
+    if hyphen {
+        text.push('-');
+    }
+    text
+}
+
+/// Returns offset of line in free space of text box, start and end follow direction.
+fn align_offset(face: &FontFace, space: f32) -> f32 {
+    let rtl = face.direction == TextDirection::Rtl;
+    match (&face.align, rtl) {
+        (TextAlign::Center, _) => space / 2.0,
+        (TextAlign::Right, _) | (TextAlign::End, false) | (TextAlign::Start, true) => space,
+        _ => 0.0,
+    }
+}
+
+/// The text of paragraph with own style, e.g. text of <b> element within paragraph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyledText {
+    pub text: String,
+    pub font: FontFace,
+    pub color: Rgba,
+    pub align: VerticalAlign,
+    pub shadows: Vec<Shadow>,
+}
+
+/// The part of styled text placed on one line of paragraph, origin is relative to
+/// text box top-left corner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextFragment {
+    /// The index of styled text in paragraph.
+    pub span: usize,
+    pub text: String,
+    pub origin: [f32; 2],
+    pub width: f32,
+    pub height: f32,
+    /// The offset of baseline from fragment top.
+    pub baseline: f32,
+}
+
+/// The word part of one styled text, words may consist of many styles and are split
+/// at soft hyphens.
+struct Piece<'t> {
+    span: usize,
+    text: &'t str,
+    width: f32,
+    /// Whether piece is separated from previous one on line by whitespace.
+    space: bool,
+    /// Whether word can be broken after piece by hyphen.
+    breakable: bool,
+    /// Whether line ends with piece of broken word.
+    hyphen: bool,
+}
+
+/// Breaks styled texts of paragraph into fragments fitting max width, greedily at
+/// whitespace of any style and at soft hyphens (unless hyphens none), consecutive
+/// pieces of one style on line are merged. Texts of registered monospace families are
+/// measured arithmetically. Fragments of line are aligned by their vertical align,
+/// baselines by default.
+pub(crate) fn break_styled_lines(
+    fonts: &dyn Fonts,
+    monospace: &MonospaceFonts,
+    spans: &[StyledText],
+    face: &FontFace,
+    max_width: Option<f32>,
+) -> Vec<TextFragment> {
+    let fonts: Vec<&dyn Fonts> = spans
+        .iter()
+        .map(|span| monospace.select(fonts, &span.font))
+        .collect();
+    let ids: Vec<FaceId> = spans
+        .iter()
+        .zip(&fonts)
+        .map(|(span, fonts)| resolve_face(*fonts, &span.font))
+        .collect();
+    let metrics: Vec<FontMetrics> = spans
+        .iter()
+        .zip(&ids)
+        .zip(&fonts)
+        .map(|((span, id), fonts)| fonts.metrics(*id, &span.font))
+        .collect();
+    let measure =
+        |span: usize, text: &str| measure_line(fonts[span], ids[span], text, &spans[span].font);
+    let mut words: Vec<Vec<Piece>> = vec![];
+    let mut space = false;
+    for (span, styled) in spans.iter().enumerate() {
+        let hyphens = styled.font.hyphens != Hyphens::None;
+        let mut rest = styled.text.as_str();
+        loop {
+            let trimmed = rest.trim_start();
+            space |= trimmed.len() != rest.len();
+            if trimmed.is_empty() {
+                break;
+            }
+            let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+            if space || words.is_empty() {
+                words.push(vec![]);
+            }
+            if let Some(word) = words.last_mut() {
+                let mut parts = trimmed[..end].split(SOFT_HYPHEN).peekable();
+                while let Some(text) = parts.next() {
+                    let breakable = hyphens && parts.peek().is_some();
+                    if text.is_empty() {
+                        continue;
+                    }
+                    let [width, _] = measure(span, text);
+                    word.push(Piece {
+                        span,
+                        text,
+                        width,
+                        space: false,
+                        breakable,
+                        hyphen: false,
+                    });
+                }
+            }
+            space = false;
+            rest = &trimmed[end..];
+        }
+    }
+    let fits = |width: f32| {
+        max_width
+            .map(|max_width| width <= max_width)
+            .unwrap_or(true)
+    };
+    let width_of = |pieces: &[Piece]| pieces.iter().map(|piece| piece.width).sum::<f32>();
+    // returns index of last piece of longest word part fitting width with hyphen
+    let break_word = |word: &[Piece], width: f32| {
+        let mut part = 0.0;
+        let mut point = None;
+        for (index, piece) in word.iter().enumerate().take(word.len() - 1) {
+            part += piece.width;
+            if piece.breakable {
+                let [hyphen, _] = measure(piece.span, "-");
+                if fits(width + part + hyphen) {
+                    point = Some(index);
+                }
+            }
+        }
+        point
+    };
+    let mut lines: Vec<(Vec<Piece>, f32)> = vec![];
+    for mut word in words {
+        if word.is_empty() {
+            continue;
+        }
+        let width = width_of(&word);
+        if let Some((line, line_width)) = lines.last_mut() {
+            let [space, _] = measure(word[0].span, " ");
+            if fits(*line_width + space + width) {
+                word[0].space = true;
+                *line_width += space + width;
+                line.extend(word);
+                continue;
+            }
+            // word broken at longest fitting part, rest goes to new lines
+            if let Some(point) = break_word(&word, *line_width + space) {
+                let rest = word.split_off(point + 1);
+                word[0].space = true;
+                word[point].hyphen = true;
+                *line_width += space + width_of(&word);
+                line.extend(word);
+                word = rest;
+            }
+        }
+        loop {
+            let width = width_of(&word);
+            if fits(width) || !word.iter().rev().skip(1).any(|piece| piece.breakable) {
+                lines.push((word, width));
+                break;
+            }
+            let first = word.iter().position(|piece| piece.breakable);
+            let point = break_word(&word, 0.0).or(first).unwrap_or_default();
+            let rest = word.split_off(point + 1);
+            word[point].hyphen = true;
+            let width = width_of(&word);
+            lines.push((word, width));
+            word = rest;
+        }
+    }
+    let mut fragments = vec![];
+    let mut y = 0.0;
+    for (pieces, _) in lines {
+        let line_start = fragments.len();
+        let mut line: Vec<(usize, String)> = vec![];
+        for piece in pieces {
+            let separator = if piece.space { " " } else { "" };
+            let hyphen = if piece.hyphen { "-" } else { "" };
+            match line.last_mut() {
+                Some((span, text)) if *span == piece.span => {
+                    text.push_str(separator);
+                    text.push_str(piece.text);
+                    text.push_str(hyphen);
+                }
+                _ => line.push((piece.span, format!("{separator}{}{hyphen}", piece.text))),
+            }
+        }
+        let mut x = 0.0;
+        let mut ascent: f32 = 0.0;
+        let mut descent: f32 = 0.0;
+        let mut line_height: f32 = 0.0;
+        for (span, text) in line {
+            let [width, height] = measure(span, &text);
+            let baseline = metrics[span].baseline(height);
+            if spans[span].align == VerticalAlign::Baseline {
+                ascent = ascent.max(baseline);
+                descent = descent.max(height - baseline);
+            }
+            fragments.push(TextFragment {
+                span,
+                text,
+                origin: [x, 0.0],
+                width,
+                height,
+                baseline,
+            });
+            x += width;
+            line_height = line_height.max(height);
+        }
+        let line_height = line_height.max(ascent + descent);
+        let space = max_width.map(|max_width| max_width - x).unwrap_or(0.0);
+        let offset = align_offset(face, space);
+        for fragment in &mut fragments[line_start..] {
+            let top = match spans[fragment.span].align {
+                VerticalAlign::Baseline => ascent - fragment.baseline,
+                VerticalAlign::Top => 0.0,
+                VerticalAlign::Middle => (line_height - fragment.height) / 2.0,
+                VerticalAlign::Bottom => line_height - fragment.height,
+            };
+            fragment.origin = [fragment.origin[0] + offset.max(0.0), y + top];
+        }
+        reorder_fragments(&mut fragments[line_start..], face.direction);
+        y += line_height;
+    }
+    fragments
+}
+
+/// Returns fonts of view until application provides own, fonts of resources directory
+/// if shaping feature enabled and there are any, approximate measurements otherwise.
+#[cfg_attr(not(feature = "shaping"), allow(unused_variables))]
+pub(crate) fn default_fonts(resources: &str) -> Box<dyn Fonts> {
+    #[cfg(feature = "shaping")]
+    if !resources.is_empty() {
+        match crate::ShapedFonts::from_directory(resources) {
+            Ok(fonts) if !fonts.is_empty() => return Box::new(fonts),
+            Ok(_) => {}
+            Err(error) => log::error!("unable to load fonts of {resources}, {error:?}"),
+        }
+    }
+    Box::new(DummyFonts)
+}
+
+/// The monospace font families registered in view with advance of every char in font
+/// sizes, their text is measured arithmetically instead of by application fonts.
+#[derive(Default)]
+pub(crate) struct MonospaceFonts {
+    advances: HashMap<String, f32>,
+}
+
+impl MonospaceFonts {
+    pub(crate) fn register(&mut self, family: &str, advance: f32) {
+        self.advances.insert(family.to_lowercase(), advance);
+    }
+
+    /// Returns whether face family is registered monospace.
+    pub(crate) fn contains(&self, face: &FontFace) -> bool {
+        self.advance(&face.family).is_some()
+    }
+
+    fn advance(&self, family: &str) -> Option<f32> {
+        if self.advances.is_empty() {
+            return None;
+        }
+        self.advances.get(&family.to_lowercase()).copied()
+    }
+
+    /// Returns fonts measuring text of face, self if face family is monospace.
+    pub(crate) fn select<'f>(&'f self, fonts: &'f dyn Fonts, face: &FontFace) -> &'f dyn Fonts {
+        match self.contains(face) {
+            true => self,
+            false => fonts,
+        }
+    }
+}
+
+impl Fonts for MonospaceFonts {
+    fn measure(&self, _id: FaceId, text: &str, face: &FontFace, _: Option<f32>) -> [f32; 2] {
+        let advance = self.advance(&face.family).unwrap_or_default() * face.size;
+        let width = text.chars().count() as f32 * advance;
+        [width, face.size * face.line_height]
+    }
 }
 
 pub(crate) struct DummyFonts;
 
 impl Fonts for DummyFonts {
-    fn measure(&self, text: &str, face: &FontFace, max_width: Option<f32>) -> [f32; 2] {
+    fn measure(
+        &self,
+        _id: FaceId,
+        text: &str,
+        face: &FontFace,
+        max_width: Option<f32>,
+    ) -> [f32; 2] {
         // NOTE: incorrect implementation, approximately calculates the text size
         // you should provide your own Fonts implementation
         let width = text.len() as f32 * face.size * 0.75;
@@ -23,4 +594,8 @@ impl Fonts for DummyFonts {
             }
         }
     }
+
+    fn as_sync(&self) -> Option<&(dyn Fonts + Sync)> {
+        Some(self)
+    }
 }
@@ -0,0 +1,86 @@
+use crate::controls::is_text_input;
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, Keys, Modifiers, MouseEvent, ViewError, ViewModel};
+use taffy::{NodeId, TaffyTree};
+
+/// Checks key pressed with modifiers matches chord, e.g. `i`, `f5` or `ctrl+shift+s`.
+/// Chord key is named as in key event listener filters, see [`Keys::name`].
+pub(crate) fn match_chord(chord: &str, key: Keys, modifiers: Modifiers) -> bool {
+    let chord = chord.trim().to_lowercase();
+    let mut parts: Vec<&str> = chord.split('+').map(str::trim).collect();
+    let name = match parts.pop() {
+        Some(name) if !name.is_empty() => name,
+        // the plus key itself, e.g. ctrl++
+        _ => "+",
+    };
+    if name != key.name() {
+        return false;
+    }
+    let mut required = Modifiers::default();
+    for part in parts.into_iter().filter(|part| !part.is_empty()) {
+        let modifier = match part {
+            "ctrl" | "control" => Modifiers::CTRL,
+            "shift" => Modifiers::SHIFT,
+            "alt" => Modifiers::ALT,
+            "meta" | "cmd" | "super" => Modifiers::META,
+            _ => return false,
+        };
+        required.set(modifier, true);
+    }
+    required == modifiers
+}
+
+impl ViewModel {
+    /// Triggers click of first element in document order with accesskey or data-hotkey
+    /// attribute matching pressed key, returns whether element triggered. Keys without
+    /// modifiers are ignored while text input focused, so typing does not trigger them.
+    pub(crate) fn trigger_hotkey(
+        &mut self,
+        key: Keys,
+        body: NodeId,
+        tree: &mut TaffyTree<Element>,
+    ) -> Result<bool, ViewError> {
+        if Modifiers::from_key(key).is_some() {
+            return Ok(false);
+        }
+        if self.modifiers == Modifiers::default() {
+            if let Some(focus) = self.focus {
+                if is_text_input(tree.get_element(focus)?) {
+                    return Ok(false);
+                }
+            }
+        }
+        let node = match self.find_hotkey_element(key, body, tree)? {
+            Some(node) => node,
+            None => return Ok(false),
+        };
+        let element = tree.get_element(node)?;
+        let event = MouseEvent::new(self.mouse, self.modifiers, element);
+        self.emit(element, "onclick", event);
+        self.activate(node, body, tree)?;
+        Ok(true)
+    }
+
+    fn find_hotkey_element(
+        &self,
+        key: Keys,
+        node: NodeId,
+        tree: &TaffyTree<Element>,
+    ) -> Result<Option<NodeId>, ViewError> {
+        let element = tree.get_element(node)?;
+        let chord = element
+            .attrs
+            .get("accesskey")
+            .or_else(|| element.attrs.get("data-hotkey"));
+        let matches = chord.is_some_and(|chord| match_chord(chord, key, self.modifiers));
+        if matches && !element.disabled() {
+            return Ok(Some(node));
+        }
+        for child in tree.children(node)? {
+            if let Some(node) = self.find_hotkey_element(key, child, tree)? {
+                return Ok(Some(node));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -1,4 +1,6 @@
-use crate::view_model::Binder;
+use crate::inline::is_inline_tag;
+use crate::view_model::{Binder, Pipe};
+use crate::RateLimit;
 
 use log::error;
 use pest::error::Error;
@@ -6,7 +8,10 @@ use pest::iterators::Pair;
 
 use pest::Parser;
 use pest_derive::Parser;
+use serde_json::Value;
+use smol_str::SmolStr;
 use std::collections::HashMap;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[grammar = "html/html.pest"]
@@ -29,7 +34,7 @@ impl From<Error<Rule>> for ReaderError {
 /// wherein each node is an object representing a part of the document.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Html {
-    pub tag: String,
+    pub tag: SmolStr,
     pub bindings: Vec<ElementBinding>,
     pub text: Option<TextBinding>,
     pub children: Vec<Html>,
@@ -38,7 +43,7 @@ pub struct Html {
 impl Html {
     pub fn empty() -> Self {
         Html {
-            tag: "".to_string(),
+            tag: SmolStr::default(),
             bindings: vec![],
             text: None,
             children: vec![],
@@ -65,19 +70,39 @@ impl Html {
         None
     }
 
+    /// Collects the content of all embedded style sheets of document.
+    pub fn collect_styles(&self, css: &mut String) {
+        if self.tag == "style" {
+            if let Some(text) = self.text.as_ref() {
+                for span in &text.spans {
+                    if let TextSpan::String(stylesheet) = span {
+                        css.push('\n');
+                        css.push_str(stylesheet);
+                    }
+                }
+            }
+        }
+        for child in &self.children {
+            child.collect_styles(css);
+        }
+    }
+
     pub fn as_visibility(&self) -> Option<(bool, &Binder)> {
         for binding in &self.bindings {
-            if let ElementBinding::Visibility(visible, binder) = binding {
-                return Some((*visible, binder));
+            match binding {
+                ElementBinding::Visibility(visible, binder) => return Some((*visible, binder)),
+                // hidden element must be detached from layout same as negative condition
+                ElementBinding::Tag(key, binder) if key == "hidden" => return Some((false, binder)),
+                _ => {}
             }
         }
         None
     }
 
-    pub fn as_repeat(&self) -> Option<(&str, usize, &Binder)> {
+    pub fn as_repeat(&self) -> Option<(&str, usize, &Binder, Option<&str>)> {
         for binding in &self.bindings {
-            if let ElementBinding::Repeat(name, count, binder) = binding {
-                return Some((name, *count, binder));
+            if let ElementBinding::Repeat(name, count, binder, key) = binding {
+                return Some((name, *count, binder, key.as_deref()));
             }
         }
         None
@@ -86,19 +111,25 @@ impl Html {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ElementBinding {
-    None(String, String),
+    None(SmolStr, String),
     Alias(String, Binder),
-    Tag(String, Binder),
-    Attribute(String, TextBinding),
-    Repeat(String, usize, Binder),
-    Callback(String, Vec<CallbackArgument>),
+    /// The local state name and its default value.
+    Local(String, Value),
+    Tag(SmolStr, Binder),
+    Attribute(SmolStr, TextBinding),
+    /// The item alias, elements count, array binder and optional item identity key field.
+    Repeat(String, usize, Binder, Option<String>),
+    /// The listener, handler arguments and optional rate limit of handler messages.
+    Callback(String, Vec<CallbackArgument>, Option<RateLimit>),
+    Model(SmolStr, Binder),
     Visibility(bool, Binder),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum CallbackArgument {
     Keyword(String),
-    Event,
+    /// The event or its field selected by path like `$event.target.size`.
+    Event(Vec<String>),
     Binder(Binder),
 }
 
@@ -121,13 +152,21 @@ impl TextBinding {
 pub enum TextSpan {
     String(String),
     Binder(Binder),
+    Translation(Translation),
+}
+
+/// The localized text reference, e.g. `{t "greeting" name=user.name}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Translation {
+    pub key: String,
+    pub placeholders: Vec<(String, Binder)>,
 }
 
 pub fn read_html(html: &str) -> Result<Html, ReaderError> {
     let document = HtmlParser::parse(Rule::Document, html)?
         .next()
         .ok_or(ReaderError::EmptyDocument)?;
-    let content = parse_content(document, true);
+    let content = parse_content(document, false, true);
     Ok(content)
 }
 
@@ -135,7 +174,8 @@ pub fn read_html(html: &str) -> Result<Html, ReaderError> {
 /// Pest parser guarantees that pairs will contain only rules defined in grammar.
 /// So, knowing the exact order of rules and it parameters we can unwrap iterators
 /// without error handling. Macro unreachable! can be used for the same reason.
-fn parse_content(pair: Pair<Rule>, is_last_content: bool) -> Html {
+/// Content is spaced if whitespace separates it from previous sibling content.
+fn parse_content(pair: Pair<Rule>, is_spaced: bool, is_last_content: bool) -> Html {
     match pair.as_rule() {
         Rule::Element => {
             let mut iter = pair.into_inner();
@@ -154,15 +194,44 @@ fn parse_content(pair: Pair<Rule>, is_last_content: bool) -> Html {
             }
             let children: Vec<Pair<Rule>> = children.into_inner().collect();
             let children_count = children.len();
+            // whitespace matters only in paragraph of texts and inline elements
+            let is_paragraph = children.iter().all(|child| match child.as_rule() {
+                Rule::Text => true,
+                Rule::Element => is_inline_tag(child.clone().into_inner().next().unwrap().as_str()),
+                _ => false,
+            });
+            let mut previous_end = None;
+            let mut contents = vec![];
+            for (index, child) in children.into_iter().enumerate() {
+                let span = child.as_span();
+                let is_spaced =
+                    is_paragraph && previous_end.map(|end| span.start() > end).unwrap_or(false);
+                previous_end = Some(span.end());
+                let content = parse_content(child, is_spaced, index + 1 == children_count);
+                // whitespace between inline elements separates their words
+                if is_spaced
+                    && is_inline_tag(&content.tag)
+                    && contents
+                        .last()
+                        .map(|previous: &Html| is_inline_tag(&previous.tag))
+                        .unwrap_or(false)
+                {
+                    contents.push(Html {
+                        tag: SmolStr::default(),
+                        bindings: vec![],
+                        text: Some(TextBinding {
+                            spans: vec![TextSpan::String(" ".to_string())],
+                        }),
+                        children: vec![],
+                    });
+                }
+                contents.push(content);
+            }
             Html {
-                tag: tag.to_string(),
+                tag: tag.into(),
                 bindings,
                 text: None,
-                children: children
-                    .into_iter()
-                    .enumerate()
-                    .map(|(index, child)| parse_content(child, index + 1 == children_count))
-                    .collect(),
+                children: contents,
             }
         }
         Rule::Text => {
@@ -171,6 +240,9 @@ fn parse_content(pair: Pair<Rule>, is_last_content: bool) -> Html {
                 match span.as_rule() {
                     Rule::String => prefetch.push(TextSpan::String(span.as_str().to_string())),
                     Rule::Binder => prefetch.push(TextSpan::Binder(parse_binder(span))),
+                    Rule::Translation => {
+                        prefetch.push(TextSpan::Translation(parse_translation(span)))
+                    }
                     _ => unreachable!(),
                 }
             }
@@ -178,11 +250,10 @@ fn parse_content(pair: Pair<Rule>, is_last_content: bool) -> Html {
             let mut spans = vec![];
 
             for index in 0..count {
-                let is_next_binding = if let Some(TextSpan::Binder(_)) = prefetch.get(index + 1) {
-                    true
-                } else {
-                    false
-                };
+                let is_next_binding = matches!(
+                    prefetch.get(index + 1),
+                    Some(TextSpan::Binder(_) | TextSpan::Translation(_))
+                );
                 let span = prefetch[index].clone();
                 match span {
                     TextSpan::String(string) => {
@@ -192,6 +263,14 @@ fn parse_content(pair: Pair<Rule>, is_last_content: bool) -> Html {
                             .filter(|fragment| !fragment.is_empty())
                             .collect();
                         let mut text = fragments.join(" ").trim().to_string();
+                        // keeps words apart from previous binding or inline element
+                        let is_spaced = match index {
+                            0 => is_spaced,
+                            _ => string.starts_with(char::is_whitespace),
+                        };
+                        if is_spaced && !text.is_empty() {
+                            text.insert(0, ' ');
+                        }
                         if is_next_binding || !is_last_content {
                             text.push(' ');
                         }
@@ -233,7 +312,7 @@ fn parse_content(pair: Pair<Rule>, is_last_content: bool) -> Html {
             // }
             let text = TextBinding { spans };
             Html {
-                tag: "".to_string(),
+                tag: SmolStr::default(),
                 bindings: vec![],
                 text: Some(text),
                 children: vec![],
@@ -256,14 +335,25 @@ fn parse_content(pair: Pair<Rule>, is_last_content: bool) -> Html {
             }
 
             Html {
-                tag: tag.to_string(),
+                tag: tag.into(),
                 bindings,
                 text: None,
                 children: vec![],
             }
         }
+        Rule::Style => {
+            let stylesheet = pair.into_inner().next().unwrap().as_str().to_string();
+            Html {
+                tag: SmolStr::new_static("style"),
+                bindings: vec![],
+                text: Some(TextBinding {
+                    spans: vec![TextSpan::String(stylesheet)],
+                }),
+                children: vec![],
+            }
+        }
         Rule::Script => Html {
-            tag: "script".to_string(),
+            tag: SmolStr::new_static("script"),
             bindings: vec![],
             text: None,
             children: vec![],
@@ -283,13 +373,77 @@ fn parse_binder(pair: Pair<Rule>) -> Binder {
                     .map(|key| key.as_str().to_string())
                     .collect();
             }
-            Rule::Transformer => pipe.push(next.as_str().to_string()),
+            Rule::Transformer => pipe.push(parse_pipe(next)),
             _ => unreachable!(),
         }
     }
     Binder { path, pipe }
 }
 
+fn parse_translation(pair: Pair<Rule>) -> Translation {
+    let mut iter = pair.into_inner();
+    let key = iter.next().unwrap().into_inner().next().unwrap();
+    let key = key.as_str().to_string();
+    let placeholders = iter
+        .map(|placeholder| {
+            let mut iter = placeholder.into_inner();
+            let name = iter.next().unwrap().as_str().to_string();
+            let path = iter
+                .next()
+                .unwrap()
+                .into_inner()
+                .map(|key| key.as_str().to_string())
+                .collect();
+            let binder = Binder { path, pipe: vec![] };
+            (name, binder)
+        })
+        .collect();
+    Translation { key, placeholders }
+}
+
+fn parse_pipe(pair: Pair<Rule>) -> Pipe {
+    let mut iter = pair.into_inner();
+    let name = iter.next().unwrap().as_str().to_string();
+    let arguments = iter
+        .map(|argument| match argument.as_rule() {
+            Rule::Number => match argument.as_str().parse::<i64>() {
+                Ok(number) => Value::from(number),
+                Err(_) => Value::from(argument.as_str().parse::<f64>().unwrap_or_default()),
+            },
+            Rule::Literal => {
+                let literal = argument.into_inner().next().unwrap();
+                Value::String(literal.as_str().to_string())
+            }
+            _ => Value::String(argument.as_str().to_string()),
+        })
+        .collect();
+    Pipe { name, arguments }
+}
+
+fn parse_rate_limit(pair: Pair<Rule>) -> RateLimit {
+    let mut iter = pair.into_inner();
+    let kind = iter.next().unwrap().as_str();
+    let period = parse_period(iter.next().unwrap());
+    match kind {
+        "throttle" => RateLimit::Throttle(period),
+        _ => RateLimit::Debounce(period),
+    }
+}
+
+fn parse_period(pair: Pair<Rule>) -> Duration {
+    let mut period = pair.into_inner();
+    let value = period.next().unwrap().as_str();
+    let value = value.parse::<f64>().unwrap_or_else(|error| {
+        error!("unable to parse rate limit period '{value}', {error}");
+        0.0
+    });
+    let micros = match period.next().unwrap().as_str() {
+        "ms" => value * 1_000.0,
+        _ => value * 1_000_000.0,
+    };
+    Duration::from_micros(micros.round() as u64)
+}
+
 fn parse_element_bindings(pair: Pair<Rule>) -> Vec<ElementBinding> {
     let mut bindings = vec![];
     for pair in pair.into_inner() {
@@ -304,15 +458,22 @@ fn parse_element_bindings(pair: Pair<Rule>) -> Vec<ElementBinding> {
                     0
                 });
                 let binder = parse_binder(iter.next().unwrap());
-                ElementBinding::Repeat(name, count, binder)
+                let key = iter.next().map(|key| key.as_str().to_string());
+                ElementBinding::Repeat(name, count, binder, key)
             }
             Rule::AliasBinding => {
                 let binder = parse_binder(iter.next().unwrap());
                 ElementBinding::Alias(name, binder)
             }
+            Rule::LocalBinding => {
+                let value = iter.next().unwrap().as_str();
+                let value = serde_json::from_str(value)
+                    .unwrap_or_else(|_| Value::String(value.to_string()));
+                ElementBinding::Local(name, value)
+            }
             Rule::TagBinding => {
                 let binder = parse_binder(iter.next().unwrap());
-                ElementBinding::Tag(name, binder)
+                ElementBinding::Tag(name.into(), binder)
             }
             Rule::AttributeBinding => {
                 let mut spans = vec![];
@@ -326,20 +487,39 @@ fn parse_element_bindings(pair: Pair<Rule>) -> Vec<ElementBinding> {
                     }
                 }
                 let text = TextBinding { spans };
-                ElementBinding::Attribute(name, text)
+                ElementBinding::Attribute(name.into(), text)
             }
             Rule::CallbackBinding => {
                 let mut arguments = vec![];
+                let mut rate = None;
                 for pair in iter {
                     let argument = match pair.as_rule() {
-                        Rule::Key => CallbackArgument::Keyword(pair.as_str().to_string()),
+                        Rule::RateLimit => {
+                            rate = Some(parse_rate_limit(pair));
+                            continue;
+                        }
+                        Rule::Interval if name == "ontick" && rate.is_none() => {
+                            let period = parse_period(pair.into_inner().next().unwrap());
+                            rate = Some(RateLimit::Interval(period));
+                            continue;
+                        }
+                        Rule::Key | Rule::Interval => {
+                            CallbackArgument::Keyword(pair.as_str().to_string())
+                        }
                         Rule::Binder => CallbackArgument::Binder(parse_binder(pair)),
-                        Rule::Event => CallbackArgument::Event,
+                        Rule::Event => {
+                            let fields = pair.into_inner().map(|key| key.as_str().to_string());
+                            CallbackArgument::Event(fields.collect())
+                        }
                         _ => unreachable!(),
                     };
                     arguments.push(argument);
                 }
-                ElementBinding::Callback(name, arguments)
+                ElementBinding::Callback(name, arguments, rate)
+            }
+            Rule::ModelBinding => {
+                let binder = parse_binder(iter.next().unwrap());
+                ElementBinding::Model(name.into(), binder)
             }
             Rule::VisibilityBinding => {
                 let visible = name == "?";
@@ -348,16 +528,16 @@ fn parse_element_bindings(pair: Pair<Rule>) -> Vec<ElementBinding> {
             }
             Rule::DoubleQuoted => {
                 let value = iter.next().unwrap().as_str().to_string();
-                ElementBinding::None(name, value)
+                ElementBinding::None(name.into(), value)
             }
             Rule::Unquoted => {
                 let value = iter.next().unwrap().as_str().to_string();
-                ElementBinding::None(name, value)
+                ElementBinding::None(name.into(), value)
             }
             Rule::Empty => {
                 // empty attribute syntax is exactly equivalent to specifying the empty string
                 // https://www.w3.org/TR/2012/WD-html-markup-20120329/syntax.html#syntax-attributes
-                ElementBinding::None(name, "".to_string())
+                ElementBinding::None(name.into(), String::new())
             }
             _ => unreachable!(),
         };
@@ -370,6 +550,7 @@ fn parse_element_bindings(pair: Pair<Rule>) -> Vec<ElementBinding> {
 mod tests {
     use super::*;
     use crate::testing::setup_tests_logging;
+    use serde_json::json;
 
     #[test]
     pub fn test_ignore_script_tag() {
@@ -384,6 +565,19 @@ mod tests {
         assert_eq!(html.children.len(), 0);
     }
 
+    #[test]
+    pub fn test_parse_style_tag() {
+        let html = html(
+            r#"<html><style>
+                div { width: 10px; }
+            </style><body></body></html>"#,
+        );
+        let mut css = String::new();
+        html.collect_styles(&mut css);
+        assert_eq!(html.children[0].tag, "style");
+        assert_eq!(css.trim(), "div { width: 10px; }");
+    }
+
     #[test]
     pub fn test_parse_img_tag() {
         let html = html(r#"<img alt="member.png" src="./images/member.png"/>"#);
@@ -418,6 +612,30 @@ mod tests {
         assert_eq!(html.children[0].text, expected)
     }
 
+    #[test]
+    pub fn test_text_whitespace_after_sibling_element() {
+        let html = html(r#"<div><b>Note:</b> read {topic}</div>"#);
+        assert_eq!(html.children[1].text, text(&[t(" read "), b("topic")]))
+    }
+
+    #[test]
+    pub fn test_text_whitespace_after_block_element() {
+        let html = html("<div><h1>Title</h1>\n text</div>");
+        assert_eq!(html.children[1].text, text(&[t("text")]))
+    }
+
+    #[test]
+    pub fn test_text_whitespace_between_inline_elements() {
+        let html = html(r#"<div><b>a</b> <i>b</i><span>c</span></div>"#);
+        let tags: Vec<&str> = html
+            .children
+            .iter()
+            .map(|child| child.tag.as_str())
+            .collect();
+        assert_eq!(tags, ["b", "", "i", "span"]);
+        assert_eq!(html.children[1].text, text(&[t(" ")]))
+    }
+
     #[test]
     pub fn test_binding_text_multiple_spans_no_space() {
         let html = html(r#"<div>Hello, {first}{last}</div>"#);
@@ -521,6 +739,13 @@ mod tests {
         assert_eq!(html.bindings, expected)
     }
 
+    #[test]
+    pub fn test_binding_model() {
+        let html = html(r#"<input &value="{todo}" />"#);
+        let expected = ElementBinding::Model("value".into(), binder("todo"));
+        assert_eq!(html.bindings, [expected])
+    }
+
     #[test]
     pub fn test_binding_repeat() {
         let html = html(r#"<option *option="10 {options}"></option>"#);
@@ -533,6 +758,23 @@ mod tests {
         assert_eq!(html.bindings, [repeat("effect", 8, "effects")])
     }
 
+    #[test]
+    pub fn test_binding_repeat_with_key() {
+        let html = html(r#"<div *row="8 {rows} by id"></div>"#);
+        let binding = ElementBinding::Repeat("row".into(), 8, binder("rows"), Some("id".into()));
+        assert_eq!(html.bindings, [binding])
+    }
+
+    #[test]
+    pub fn test_binding_local_state() {
+        let html = html(r#"<div $expanded="false" $tab="general"></div>"#);
+        let bindings = [
+            ElementBinding::Local("expanded".into(), json!(false)),
+            ElementBinding::Local("tab".into(), json!("general")),
+        ];
+        assert_eq!(html.bindings, bindings)
+    }
+
     #[test]
     pub fn test_binding_repeat_with_shorthand() {
         let html = html(r#"<option *_="10 {options}"></option>"#);
@@ -545,6 +787,7 @@ mod tests {
         let binding = ElementBinding::Callback(
             "onclick".into(),
             vec![CallbackArgument::Binder(binder("my_data"))],
+            None,
         );
         assert_eq!(html.bindings, vec![binding])
     }
@@ -556,19 +799,141 @@ mod tests {
             "onclick".into(),
             vec![
                 CallbackArgument::Keyword("MyMessage".into()),
-                CallbackArgument::Event,
+                CallbackArgument::Event(vec![]),
+            ],
+            None,
+        );
+        assert_eq!(html.bindings, vec![binding])
+    }
+
+    #[test]
+    pub fn test_binding_callback_event_field_arguments() {
+        let html = html(r#"<button ^onclick="Move $event.position.x $event.key"></button>"#);
+        let binding = ElementBinding::Callback(
+            "onclick".into(),
+            vec![
+                CallbackArgument::Keyword("Move".into()),
+                CallbackArgument::Event(vec!["position".into(), "x".into()]),
+                CallbackArgument::Event(vec!["key".into()]),
             ],
+            None,
         );
         assert_eq!(html.bindings, vec![binding])
     }
 
+    #[test]
+    pub fn test_binding_callback_key_filter() {
+        let html = html(r#"<input ^onkeydown.enter="submit" />"#);
+        let binding = ElementBinding::Callback(
+            "onkeydown.enter".into(),
+            vec![CallbackArgument::Keyword("submit".into())],
+            None,
+        );
+        assert_eq!(html.bindings, vec![binding])
+    }
+
+    #[test]
+    pub fn test_binding_callback_rate_limit() {
+        let html = html(
+            r#"<div ^onmousemove.throttle:50ms="Move" ^onkeydown.enter.debounce:0.5s="Search"></div>"#,
+        );
+        let bindings = [
+            ElementBinding::Callback(
+                "onmousemove".into(),
+                vec![CallbackArgument::Keyword("Move".into())],
+                Some(RateLimit::Throttle(Duration::from_millis(50))),
+            ),
+            ElementBinding::Callback(
+                "onkeydown.enter".into(),
+                vec![CallbackArgument::Keyword("Search".into())],
+                Some(RateLimit::Debounce(Duration::from_millis(500))),
+            ),
+        ];
+        assert_eq!(html.bindings, bindings)
+    }
+
+    #[test]
+    pub fn test_binding_callback_tick_interval() {
+        let html = html(r#"<div ^ontick="Poll {id} 0.5s" ^onclick="Wait 5s"></div>"#);
+        let bindings = [
+            ElementBinding::Callback(
+                "ontick".into(),
+                vec![
+                    CallbackArgument::Keyword("Poll".into()),
+                    CallbackArgument::Binder(binder("id")),
+                ],
+                Some(RateLimit::Interval(Duration::from_millis(500))),
+            ),
+            ElementBinding::Callback(
+                "onclick".into(),
+                vec![
+                    CallbackArgument::Keyword("Wait".into()),
+                    CallbackArgument::Keyword("5s".into()),
+                ],
+                None,
+            ),
+        ];
+        assert_eq!(html.bindings, bindings)
+    }
+
+    #[test]
+    pub fn test_binding_pipe_arguments() {
+        let html = html(
+            r#"<div @title="{price | round:2 | prefix:"$" | pad:'-':-1.5 | date:short}"></div>"#,
+        );
+        let mut price = binder("price");
+        price.pipe = vec![
+            Pipe {
+                name: "round".into(),
+                arguments: vec![json!(2)],
+            },
+            Pipe {
+                name: "prefix".into(),
+                arguments: vec![json!("$")],
+            },
+            Pipe {
+                name: "pad".into(),
+                arguments: vec![json!("-"), json!(-1.5)],
+            },
+            Pipe {
+                name: "date".into(),
+                arguments: vec![json!("short")],
+            },
+        ];
+        assert_eq!(
+            html.bindings,
+            vec![attr("title", &[TextSpan::Binder(price)])]
+        )
+    }
+
+    #[test]
+    pub fn test_text_translation() {
+        let html = html(r#"<div>{t "menu.start"} {t "greeting" name=user.name}</div>"#);
+        let start = Translation {
+            key: "menu.start".into(),
+            placeholders: vec![],
+        };
+        let greeting = Translation {
+            key: "greeting".into(),
+            placeholders: vec![("name".into(), binder("user.name"))],
+        };
+        assert_eq!(
+            html.children[0].text,
+            text(&[
+                TextSpan::Translation(start),
+                t(" "),
+                TextSpan::Translation(greeting)
+            ])
+        )
+    }
+
     fn repeat(name: &str, count: usize, path: &str) -> ElementBinding {
-        ElementBinding::Repeat(name.to_string(), count, binder(path))
+        ElementBinding::Repeat(name.to_string(), count, binder(path), None)
     }
 
     fn attr(key: &str, spans: &[TextSpan]) -> ElementBinding {
         ElementBinding::Attribute(
-            key.to_string(),
+            key.into(),
             TextBinding {
                 spans: spans.to_vec(),
             },
@@ -580,7 +945,7 @@ mod tests {
     }
 
     fn tag(name: &str, path: &str) -> ElementBinding {
-        ElementBinding::Tag(name.to_string(), binder(path))
+        ElementBinding::Tag(name.into(), binder(path))
     }
 
     fn if_(path: &str) -> ElementBinding {
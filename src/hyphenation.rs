@@ -0,0 +1,119 @@
+use crate::{FontFace, Hyphens};
+use std::borrow::Cow;
+
+/// The soft hyphen, invisible hyphenation point of word, line broken at it ends
+/// with visible hyphen.
+pub const SOFT_HYPHEN: char = '\u{AD}';
+
+/// The hyphenation dictionaries of application, used to break long words of text
+/// with `hyphens: auto` in lines, see [`crate::View::hyphenator`].
+pub trait Hyphenator {
+    /// Returns byte indices of word where it can be broken by hyphen, language is
+    /// lang attribute of element or view locale.
+    fn hyphenate(&self, lang: &str, word: &str) -> Vec<usize>;
+}
+
+impl<F> Hyphenator for F
+where
+    F: Fn(&str, &str) -> Vec<usize>,
+{
+    fn hyphenate(&self, lang: &str, word: &str) -> Vec<usize> {
+        self(lang, word)
+    }
+}
+
+/// Returns text with soft hyphens inserted at hyphenation points of words if text of
+/// face is hyphenated automatically, words with soft hyphens are left as is.
+pub(crate) fn hyphenate_text<'t>(
+    hyphenator: Option<&dyn Hyphenator>,
+    locale: &str,
+    text: &'t str,
+    face: &FontFace,
+) -> Cow<'t, str> {
+    let hyphenator = match hyphenator {
+        Some(hyphenator) if face.hyphens == Hyphens::Auto => hyphenator,
+        _ => return Cow::Borrowed(text),
+    };
+    let lang = if face.lang.is_empty() {
+        locale
+    } else {
+        face.lang.as_str()
+    };
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        let start = rest.find(|char: char| !char.is_whitespace());
+        let start = start.unwrap_or(rest.len());
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        push_hyphenated(&mut result, hyphenator, lang, &rest[..end]);
+        rest = &rest[end..];
+    }
+    Cow::Owned(result)
+}
+
+fn push_hyphenated(result: &mut String, hyphenator: &dyn Hyphenator, lang: &str, word: &str) {
+    if word.contains(SOFT_HYPHEN) {
+        result.push_str(word);
+        return;
+    }
+    let mut points = hyphenator.hyphenate(lang, word);
+    points.sort_unstable();
+    let mut start = 0;
+    for point in points {
+        if point > start && point < word.len() && word.is_char_boundary(point) {
+            result.push_str(&word[start..point]);
+            result.push(SOFT_HYPHEN);
+            start = point;
+        }
+    }
+    result.push_str(&word[start..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TextAlign;
+
+    fn hyphenate(text: &str, hyphens: Hyphens) -> String {
+        let hyphenator = |lang: &str, word: &str| match (lang, word) {
+            ("de", "Donaudampfschiff") => vec![10, 5],
+            _ => vec![],
+        };
+        let face = FontFace {
+            family: "system-ui".into(),
+            size: 16.0,
+            style: "normal".into(),
+            weight: 400,
+            line_height: 1.0,
+            align: TextAlign::Start,
+            direction: Default::default(),
+            stroke: Default::default(),
+            hyphens,
+            lang: String::new(),
+            tab_size: Default::default(),
+        };
+        hyphenate_text(Some(&hyphenator), "de", text, &face).replace(SOFT_HYPHEN, "|")
+    }
+
+    #[test]
+    pub fn test_soft_hyphens_inserted() {
+        assert_eq!(
+            hyphenate(" Ein  Donaudampfschiff\n", Hyphens::Auto),
+            " Ein  Donau|dampf|schiff\n"
+        );
+    }
+
+    #[test]
+    pub fn test_manual_hyphens_kept() {
+        assert_eq!(
+            hyphenate("Donaudampfschiff", Hyphens::Manual),
+            "Donaudampfschiff"
+        );
+        assert_eq!(
+            hyphenate("Donau\u{AD}dampfschiff", Hyphens::Auto),
+            "Donau|dampfschiff"
+        );
+    }
+}
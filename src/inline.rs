@@ -0,0 +1,141 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, StyledText, VerticalAlign, ViewError};
+use taffy::{Display, NodeId, TaffyTree};
+
+/// Returns whether element of tag flows within text of paragraph, like inline elements
+/// of browsers, instead of being laid out as box.
+pub(crate) fn is_inline_tag(tag: &str) -> bool {
+    matches!(
+        tag,
+        "b" | "code" | "em" | "i" | "mark" | "s" | "small" | "span" | "strong" | "u"
+    )
+}
+
+/// Finds paragraphs of element and its descendants and prepares their styled texts.
+///
+/// Paragraph is element of text and inline elements with text only, e.g.
+/// `<p>Hello, <b>{name}</b>!</p>`. First text of paragraph becomes its lead, lead is
+/// measured and painted with styled texts of all paragraph in document order, other
+/// texts and inline elements are excluded from layout.
+pub(crate) fn flow_inline_text(
+    tree: &mut TaffyTree<Element>,
+    node: NodeId,
+) -> Result<(), ViewError> {
+    let children = tree.children(node)?;
+    let lead = find_paragraph_lead(tree, &children)?;
+    let lead = match lead {
+        Some(lead) => lead,
+        None => {
+            for child in children {
+                set_inline(tree, child, false)?;
+                let element = tree.get_element_mut(child)?;
+                if !element.styled.is_empty() {
+                    element.styled.clear();
+                    tree.mark_dirty(child)?;
+                }
+                flow_inline_text(tree, child)?;
+            }
+            return Ok(());
+        }
+    };
+    let mut spans = vec![];
+    for child in children {
+        collect_styled_texts(tree, child, lead, VerticalAlign::Baseline, &mut spans)?;
+    }
+    let element = tree.get_element_mut(lead)?;
+    if element.styled != spans {
+        element.styled = spans;
+        tree.mark_dirty(lead)?;
+    }
+    Ok(())
+}
+
+fn find_paragraph_lead(
+    tree: &TaffyTree<Element>,
+    children: &[NodeId],
+) -> Result<Option<NodeId>, ViewError> {
+    let mut lead = None;
+    let mut inline = false;
+    for child in children {
+        let element = tree.get_element(*child)?;
+        match element.text.as_ref() {
+            Some(_) => lead = lead.or(Some(*child)),
+            None if is_phrasing(tree, *child)? => inline = true,
+            None => return Ok(None),
+        }
+    }
+    Ok(lead.filter(|_| inline))
+}
+
+/// Returns whether node is text or inline element of phrasing content only.
+fn is_phrasing(tree: &TaffyTree<Element>, node: NodeId) -> Result<bool, ViewError> {
+    let element = tree.get_element(node)?;
+    if element.text.is_some() {
+        return Ok(true);
+    }
+    if !is_inline_tag(&element.tag) {
+        return Ok(false);
+    }
+    for child in tree.children(node)? {
+        if !is_phrasing(tree, child)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn collect_styled_texts(
+    tree: &mut TaffyTree<Element>,
+    node: NodeId,
+    lead: NodeId,
+    align: VerticalAlign,
+    spans: &mut Vec<StyledText>,
+) -> Result<(), ViewError> {
+    set_inline(tree, node, node != lead)?;
+    let element = tree.get_element_mut(node)?;
+    if node != lead {
+        element.styled.clear();
+    }
+    if let Some(text) = element.text.as_ref() {
+        spans.push(StyledText {
+            text: text.to_string(),
+            font: element.font.clone(),
+            color: element.color,
+            align,
+            shadows: element.text_shadows.clone(),
+        });
+        return Ok(());
+    }
+    // texts are aligned by nearest inline element aligned other than by baseline
+    let align = match element.vertical_align {
+        VerticalAlign::Baseline => align,
+        own => own,
+    };
+    for child in tree.children(node)? {
+        collect_styled_texts(tree, child, lead, align, spans)?;
+    }
+    Ok(())
+}
+
+/// Excludes inline element from layout or returns it back, style changed only if
+/// necessary to keep layout cache.
+fn set_inline(tree: &mut TaffyTree<Element>, node: NodeId, inline: bool) -> Result<(), ViewError> {
+    let display = tree.style(node)?.display;
+    let element = tree.get_element_mut(node)?;
+    let was_inline = element.inline;
+    element.inline = inline;
+    let display = match (inline, display) {
+        (true, Display::None) => return Ok(()),
+        (true, display) => {
+            // cascade keeps hidden display, so computed one is remembered to restore it
+            element.inline_display = display;
+            Display::None
+        }
+        (false, Display::None) if was_inline => element.inline_display,
+        (false, _) => return Ok(()),
+    };
+    let mut style = tree.style(node)?.clone();
+    style.display = display;
+    tree.set_style(node, style)?;
+    Ok(())
+}
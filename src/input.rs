@@ -1,10 +1,16 @@
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::time::Duration;
 
 pub struct Input {
     pub(crate) time: Duration,
     pub(crate) viewport: [f32; 2],
     pub(crate) events: Vec<InputEvent>,
+    pub(crate) pixel_ratio: Option<f32>,
+    pub(crate) scale: f32,
+    pub(crate) scroll_sensitivity: f32,
+    pub(crate) resources: Vec<(String, ResourceState)>,
+    pub(crate) fonts_loaded: Vec<String>,
 }
 
 impl<'f> Input {
@@ -13,6 +19,11 @@ impl<'f> Input {
             time: Duration::from_micros(0),
             viewport: [800.0, 600.0],
             events: vec![],
+            pixel_ratio: None,
+            scale: 1.0,
+            scroll_sensitivity: 50.0,
+            resources: vec![],
+            fonts_loaded: vec![],
         }
     }
 
@@ -35,9 +46,85 @@ impl<'f> Input {
         self.events.push(event);
         self
     }
+
+    /// Sets the ratio of physical pixels to logical pixels of display,
+    /// used to choose image variants from srcset, scale by default.
+    pub fn pixel_ratio(mut self, pixel_ratio: f32) -> Self {
+        self.pixel_ratio = Some(pixel_ratio);
+        self
+    }
+
+    /// Sets the ratio of device pixels to layout pixels, viewport and pointer
+    /// positions are given in device pixels, layout is computed in logical pixels
+    /// and element rectangles are aligned to device pixel grid.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Returns pixel density image variants are chosen for, scale unless pixel
+    /// ratio is set.
+    pub(crate) fn density(&self) -> f32 {
+        self.pixel_ratio.unwrap_or(self.scale)
+    }
+
+    /// Returns viewport size in logical pixels.
+    pub(crate) fn logical_viewport(&self) -> [f32; 2] {
+        let [width, height] = self.viewport;
+        [width / self.scale, height / self.scale]
+    }
+
+    /// Returns events with pointer positions converted to logical pixels.
+    pub(crate) fn logical_events(&self) -> Vec<InputEvent> {
+        let scale = |[x, y]: [f32; 2]| [x / self.scale, y / self.scale];
+        self.events
+            .iter()
+            .cloned()
+            .map(|event| match event {
+                InputEvent::MouseMove(position) => InputEvent::MouseMove(scale(position)),
+                InputEvent::TouchStart(id, position) => InputEvent::TouchStart(id, scale(position)),
+                InputEvent::TouchMove(id, position) => InputEvent::TouchMove(id, scale(position)),
+                InputEvent::TouchEnd(id, position) => InputEvent::TouchEnd(id, scale(position)),
+                InputEvent::Pen(phase, pen) => InputEvent::Pen(
+                    phase,
+                    Pen {
+                        position: scale(pen.position),
+                        ..pen
+                    },
+                ),
+                event => event,
+            })
+            .collect()
+    }
+
+    /// Sets scrolled distance in pixels per wheel delta unit, 50 by default
+    /// for line-based wheels, trackpads reporting pixel deltas need 1.0.
+    pub fn scroll_sensitivity(mut self, sensitivity: f32) -> Self {
+        self.scroll_sensitivity = sensitivity;
+        self
+    }
+
+    /// Reports the loading result of resource requested in Output.
+    pub fn resource(mut self, src: &str, state: ResourceState) -> Self {
+        self.resources.push((src.to_string(), state));
+        self
+    }
+
+    /// Reports font family pending in Output loaded by fonts, texts of family
+    /// are measured again.
+    pub fn font_loaded(mut self, family: &str) -> Self {
+        self.fonts_loaded.push(family.to_string());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceState {
+    Loaded,
+    Failed,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum InputEvent {
     Unknown,
     MouseMove([f32; 2]),
@@ -47,6 +134,57 @@ pub enum InputEvent {
     KeyDown(Keys),
     KeyUp(Keys),
     Char(char),
+    /// The text from system clipboard pasted to focused element.
+    Paste(String),
+    /// The system copy command, focused element value appears in Output clipboard.
+    Copy,
+    /// The system cut command, same as copy but also clears focused element value.
+    Cut,
+    /// The touch point with id pressed on screen, first touch also acts as mouse.
+    TouchStart(u64, [f32; 2]),
+    TouchMove(u64, [f32; 2]),
+    TouchEnd(u64, [f32; 2]),
+    /// The stylus contact, movement or lift, pen acts as mouse with left button.
+    Pen(PenPhase, Pen),
+    /// The in-progress IME composition text and selected char range within it,
+    /// empty text cancels composition.
+    ImeCompose(String, Option<[usize; 2]>),
+    /// The final IME composition text inserted to focused element.
+    ImeCommit(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PenPhase {
+    Down,
+    /// The pen moves on surface or hovers above it with zero pressure.
+    Move,
+    Up,
+}
+
+/// The stylus state reported by tablet or touchscreen.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pen {
+    pub position: [f32; 2],
+    /// The normalized pressure of pen tip, from 0.0 to 1.0.
+    pub pressure: f32,
+    /// The angles in degrees between pen and surface normal along X and Y axes,
+    /// from -90.0 to 90.0.
+    pub tilt: [f32; 2],
+}
+
+impl Pen {
+    pub fn new(position: [f32; 2], pressure: f32) -> Self {
+        Self {
+            position,
+            pressure,
+            tilt: [0.0; 2],
+        }
+    }
+
+    pub fn tilt(mut self, tilt: [f32; 2]) -> Self {
+        self.tilt = tilt;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -86,4 +224,188 @@ pub enum Keys {
     CapsLock,
     Ctrl,
     Shift,
+    /// The Windows, Command or Super key.
+    Meta,
+    NumLock,
+    ScrollLock,
+    // Whitespace and punctuation keys, named by US layout
+    Space,
+    Minus,
+    Equal,
+    BracketLeft,
+    BracketRight,
+    Backslash,
+    Semicolon,
+    Quote,
+    Backquote,
+    Comma,
+    Period,
+    Slash,
+    // Function keys
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    // Numpad keys
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadSubtract,
+    NumpadMultiply,
+    NumpadDivide,
+    NumpadDecimal,
+    NumpadEnter,
+    // Device and media keys
+    PrintScreen,
+    Pause,
+    ContextMenu,
+    MediaPlayPause,
+    MediaStop,
+    MediaTrackNext,
+    MediaTrackPrevious,
+    AudioVolumeUp,
+    AudioVolumeDown,
+    AudioVolumeMute,
+    /// The letter or digit key by its unshifted lowercase character,
+    /// so key bindings can capture keys typed as text.
+    Character(char),
+    /// The platform scancode of key not covered by other variants.
+    Scancode(u32),
+}
+
+impl Keys {
+    /// Returns kebab-case name of key, used to filter keyboard callbacks
+    /// in templates, e.g. ^onkeydown.enter or ^onkeyup.arrow-up
+    pub fn name(&self) -> Cow<'static, str> {
+        let name = match self {
+            Keys::Unknown => "unknown",
+            Keys::Escape => "escape",
+            Keys::Backspace => "backspace",
+            Keys::Delete => "delete",
+            Keys::Insert => "insert",
+            Keys::Enter => "enter",
+            Keys::Tab => "tab",
+            Keys::ArrowUp => "arrow-up",
+            Keys::ArrowDown => "arrow-down",
+            Keys::ArrowLeft => "arrow-left",
+            Keys::ArrowRight => "arrow-right",
+            Keys::End => "end",
+            Keys::Home => "home",
+            Keys::PageDown => "page-down",
+            Keys::PageUp => "page-up",
+            Keys::Alt => "alt",
+            Keys::CapsLock => "caps-lock",
+            Keys::Ctrl => "ctrl",
+            Keys::Shift => "shift",
+            Keys::Meta => "meta",
+            Keys::NumLock => "num-lock",
+            Keys::ScrollLock => "scroll-lock",
+            Keys::Space => "space",
+            Keys::Minus => "minus",
+            Keys::Equal => "equal",
+            Keys::BracketLeft => "bracket-left",
+            Keys::BracketRight => "bracket-right",
+            Keys::Backslash => "backslash",
+            Keys::Semicolon => "semicolon",
+            Keys::Quote => "quote",
+            Keys::Backquote => "backquote",
+            Keys::Comma => "comma",
+            Keys::Period => "period",
+            Keys::Slash => "slash",
+            Keys::F1 => "f1",
+            Keys::F2 => "f2",
+            Keys::F3 => "f3",
+            Keys::F4 => "f4",
+            Keys::F5 => "f5",
+            Keys::F6 => "f6",
+            Keys::F7 => "f7",
+            Keys::F8 => "f8",
+            Keys::F9 => "f9",
+            Keys::F10 => "f10",
+            Keys::F11 => "f11",
+            Keys::F12 => "f12",
+            Keys::Numpad0 => "numpad-0",
+            Keys::Numpad1 => "numpad-1",
+            Keys::Numpad2 => "numpad-2",
+            Keys::Numpad3 => "numpad-3",
+            Keys::Numpad4 => "numpad-4",
+            Keys::Numpad5 => "numpad-5",
+            Keys::Numpad6 => "numpad-6",
+            Keys::Numpad7 => "numpad-7",
+            Keys::Numpad8 => "numpad-8",
+            Keys::Numpad9 => "numpad-9",
+            Keys::NumpadAdd => "numpad-add",
+            Keys::NumpadSubtract => "numpad-subtract",
+            Keys::NumpadMultiply => "numpad-multiply",
+            Keys::NumpadDivide => "numpad-divide",
+            Keys::NumpadDecimal => "numpad-decimal",
+            Keys::NumpadEnter => "numpad-enter",
+            Keys::PrintScreen => "print-screen",
+            Keys::Pause => "pause",
+            Keys::ContextMenu => "context-menu",
+            Keys::MediaPlayPause => "media-play-pause",
+            Keys::MediaStop => "media-stop",
+            Keys::MediaTrackNext => "media-track-next",
+            Keys::MediaTrackPrevious => "media-track-previous",
+            Keys::AudioVolumeUp => "audio-volume-up",
+            Keys::AudioVolumeDown => "audio-volume-down",
+            Keys::AudioVolumeMute => "audio-volume-mute",
+            // e.g. ^onkeydown.q or ^onkeydown.scancode-100
+            Keys::Character(char) => return Cow::Owned(char.to_lowercase().collect()),
+            Keys::Scancode(code) => return Cow::Owned(format!("scancode-{code}")),
+        };
+        Cow::Borrowed(name)
+    }
+}
+
+/// The bitset of pressed modifier keys, tracked by view from key events.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Modifiers(pub u8);
+
+impl Modifiers {
+    pub const CTRL: Modifiers = Modifiers(1);
+    pub const SHIFT: Modifiers = Modifiers(1 << 1);
+    pub const ALT: Modifiers = Modifiers(1 << 2);
+    pub const META: Modifiers = Modifiers(1 << 3);
+
+    /// Returns modifier of key, None if key is not modifier.
+    pub fn from_key(key: Keys) -> Option<Modifiers> {
+        match key {
+            Keys::Ctrl => Some(Modifiers::CTRL),
+            Keys::Shift => Some(Modifiers::SHIFT),
+            Keys::Alt => Some(Modifiers::ALT),
+            Keys::Meta => Some(Modifiers::META),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, modifiers: Modifiers) -> bool {
+        self.0 & modifiers.0 == modifiers.0
+    }
+
+    pub fn set(&mut self, modifiers: Modifiers, pressed: bool) {
+        if pressed {
+            self.0 |= modifiers.0;
+        } else {
+            self.0 &= !modifiers.0;
+        }
+    }
 }
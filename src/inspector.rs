@@ -0,0 +1,152 @@
+use crate::css::{match_style, PseudoClassMatcher};
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, ElementStyle, Rect, ViewError};
+use std::fmt::{self, Display, Formatter};
+use taffy::{NodeId, TaffyTree};
+
+/// The snapshot of element and its descendants for troubleshooting layout and styles,
+/// see [`crate::View::debug_tree`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugNode {
+    pub node: NodeId,
+    pub tag: String,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    pub text: Option<String>,
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub content_size: [f32; 2],
+    /// The layout style summary, e.g. `Flex Relative`.
+    pub layout: String,
+    /// The selectors of CSS rules matching element currently, in cascade order.
+    pub rules: Vec<String>,
+    pub animations: Vec<DebugAnimation>,
+    pub visible: bool,
+    pub children: Vec<DebugNode>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugAnimation {
+    pub name: String,
+    /// The elapsed time of animation in seconds.
+    pub time: f32,
+    pub running: bool,
+}
+
+impl DebugNode {
+    /// Returns rectangles of node and its descendants, so application can draw
+    /// them as overlay over view.
+    pub fn overlay(&self) -> Vec<Rect> {
+        let mut overlay = vec![];
+        self.collect_overlay(&mut overlay);
+        overlay
+    }
+
+    fn collect_overlay(&self, overlay: &mut Vec<Rect>) {
+        overlay.push(Rect {
+            position: self.position,
+            size: self.size,
+        });
+        for child in &self.children {
+            child.collect_overlay(overlay);
+        }
+    }
+
+    fn write_indented(&self, f: &mut Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", self.tag, indent = depth * 2)?;
+        if let Some(id) = self.id.as_ref() {
+            write!(f, "#{id}")?;
+        }
+        for class in &self.classes {
+            write!(f, ".{class}")?;
+        }
+        if let Some(text) = self.text.as_ref() {
+            let separator = if self.tag.is_empty() { "" } else { " " };
+            write!(f, "{separator}{text:?}")?;
+        }
+        let [x, y] = self.position;
+        let [width, height] = self.size;
+        write!(f, " {width}x{height} at {x},{y} ({})", self.layout)?;
+        if !self.visible {
+            write!(f, " hidden")?;
+        }
+        if !self.rules.is_empty() {
+            write!(f, " rules: {}", self.rules.join(", "))?;
+        }
+        for animation in &self.animations {
+            let state = if animation.running {
+                "running"
+            } else {
+                "paused"
+            };
+            write!(
+                f,
+                " animation: {} {state} {}s",
+                animation.name, animation.time
+            )?;
+        }
+        writeln!(f)?;
+        for child in &self.children {
+            child.write_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for DebugNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+pub(crate) fn collect_debug_node(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    matcher: &impl PseudoClassMatcher,
+) -> Result<DebugNode, ViewError> {
+    let element = tree.get_element(node)?;
+    let style = tree.style(node)?;
+    let mut rules = vec![];
+    for element_style in &element.styles {
+        let style = match element_style {
+            ElementStyle::Static(style) => style,
+            ElementStyle::Dynamic(style) if match_style(style, node, tree, matcher) => style,
+            ElementStyle::Dynamic(_) => continue,
+        };
+        let selectors: Vec<String> = style.selectors.iter().map(ToString::to_string).collect();
+        rules.push(selectors.join(", "));
+    }
+    let animations = element
+        .animators
+        .iter()
+        .map(|animator| DebugAnimation {
+            name: animator.name.clone(),
+            time: animator.time,
+            running: animator.running,
+        })
+        .collect();
+    let classes = element
+        .attrs
+        .get("class")
+        .map(|classes| classes.split_whitespace().map(String::from).collect())
+        .unwrap_or_default();
+    let mut children = vec![];
+    for child in tree.children(node)? {
+        children.push(collect_debug_node(tree, child, matcher)?);
+    }
+    Ok(DebugNode {
+        node,
+        tag: element.tag.to_string(),
+        id: element.attrs.get("id").cloned(),
+        classes,
+        text: element.text.as_ref().map(|text| text.to_string()),
+        position: element.position,
+        size: element.size,
+        content_size: element.content_size,
+        layout: format!("{:?} {:?}", style.display, style.position),
+        rules,
+        animations,
+        visible: element.state.visible,
+        children,
+    })
+}
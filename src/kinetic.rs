@@ -0,0 +1,57 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, ViewError, ViewModel};
+use taffy::{NodeId, TaffyTree};
+
+/// Keeps scrollable elements moving after wheel or touch fling,
+/// velocity decays with friction over time.
+#[derive(Debug, Default)]
+pub(crate) struct KineticScrolling {
+    /// The velocity decay rate per second, None scrolls wheel notches immediately.
+    pub(crate) friction: Option<f32>,
+    /// The time in seconds of current update.
+    pub(crate) frame: f32,
+    /// The scroll distance in pixels per wheel delta unit of current update.
+    pub(crate) sensitivity: f32,
+    moving: Vec<NodeId>,
+}
+
+impl KineticScrolling {
+    pub(crate) fn start(&mut self, node: NodeId) {
+        if !self.moving.contains(&node) {
+            self.moving.push(node);
+        }
+    }
+}
+
+impl ViewModel {
+    /// Moves scrollable elements by their velocity, stops them once velocity is small
+    /// or scroll limit reached.
+    pub(crate) fn advance_kinetic_scrolling(
+        &mut self,
+        tree: &mut TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        let kinetic = &mut self.kinetic;
+        let friction = kinetic.friction.unwrap_or(f32::INFINITY).max(f32::EPSILON);
+        let time = kinetic.frame;
+        let decay = (-friction * time).exp();
+        // distance of exponentially decaying velocity integrated over frame time
+        let travel = (1.0 - decay) / friction;
+        let mut moving = vec![];
+        for node in kinetic.moving.drain(..) {
+            let scrolling = match tree.get_element_mut(node)?.scrolling.as_mut() {
+                Some(scrolling) => scrolling,
+                None => continue,
+            };
+            let [x, y] = scrolling.velocity;
+            let moved = scrolling.scroll_by([x * travel, y * travel]);
+            scrolling.velocity = [x * decay, y * decay];
+            if moved && x.hypot(y) * decay > 1.0 {
+                moving.push(node);
+            } else {
+                scrolling.velocity = [0.0; 2];
+            }
+        }
+        kinetic.moving = moving;
+        Ok(())
+    }
+}
@@ -1,27 +1,84 @@
+pub use accessibility::*;
+pub use changes::*;
+pub use display_list::*;
 pub use element::*;
+pub use emoji::*;
 pub use error::*;
 pub use fonts::*;
+pub use hyphenation::*;
 pub use input::*;
+pub use inspector::*;
+pub use manifest::*;
 pub use output::*;
+#[cfg(feature = "raster")]
+pub use raster::*;
+#[cfg(feature = "shaping")]
+pub use shaping::*;
+pub use smol_str::SmolStr;
+pub use snapshot::*;
+pub use state::*;
+pub use tooltip::*;
+pub use validation::*;
 pub use value::*;
 pub use view::*;
 pub use view_model::*;
 
+#[cfg(feature = "derive")]
+pub use bumaga_derive::ViewState;
+
+#[cfg(feature = "egui-input")]
+pub mod egui_input;
+#[cfg(feature = "sdl2-input")]
+pub mod sdl2_input;
+#[cfg(feature = "winit-input")]
+pub mod winit_input;
+
+mod accessibility;
 mod animation;
+mod bidi;
+mod changes;
+mod clicks;
 mod controls;
 mod css;
+mod display_list;
 mod element;
+mod emoji;
 mod error;
 mod fonts;
+mod hotkeys;
 mod html;
+mod hyphenation;
+mod inline;
 mod input;
+mod inspector;
+mod kinetic;
+mod limiter;
+mod local_state;
+mod manifest;
 mod metrics;
 mod output;
+mod paint_order;
+mod pen;
+mod pending_fonts;
+#[cfg(feature = "raster")]
+mod raster;
 mod rendering;
+mod repeat;
+mod restyle;
+#[cfg(feature = "shaping")]
+mod shaping;
+mod snapshot;
+mod state;
 mod styles;
 #[cfg(test)]
 mod testing;
+mod text_cache;
+mod tooltip;
+mod touch;
+mod transformers;
+mod translations;
 mod tree;
+mod validation;
 mod value;
 mod view;
 mod view_model;
@@ -0,0 +1,79 @@
+use crate::RateLimit;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use taffy::NodeId;
+
+/// The element node, listener and index of rate limited handler.
+pub(crate) type HandlerId = (NodeId, String, usize);
+
+/// Limits handler messages rate according to handler [`RateLimit`].
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    /// The time elapsed since view creation.
+    time: Duration,
+    emitted: HashMap<HandlerId, Duration>,
+    /// The last messages of debounced handlers and times they are due.
+    debounced: Vec<(HandlerId, Duration, Value)>,
+    /// The displayed elements with tick handlers, collected by layout.
+    pub(crate) tickers: Vec<NodeId>,
+}
+
+impl RateLimiter {
+    pub(crate) fn advance(&mut self, time: Duration) {
+        self.time += time;
+    }
+
+    pub(crate) fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Returns message if it can be emitted now, debounced message is kept until due.
+    pub(crate) fn limit(
+        &mut self,
+        handler: HandlerId,
+        rate: RateLimit,
+        message: Value,
+    ) -> Option<Value> {
+        match rate {
+            RateLimit::Throttle(period) => {
+                if let Some(emitted) = self.emitted.get(&handler) {
+                    if self.time < *emitted + period {
+                        return None;
+                    }
+                }
+                self.emitted.insert(handler, self.time);
+                Some(message)
+            }
+            RateLimit::Interval(period) => {
+                let emitted = self.emitted.entry(handler).or_insert(self.time);
+                if self.time < *emitted + period {
+                    return None;
+                }
+                *emitted = self.time;
+                Some(message)
+            }
+            RateLimit::Debounce(period) => {
+                let due = self.time + period;
+                self.debounced.retain(|(id, _, _)| *id != handler);
+                self.debounced.push((handler, due, message));
+                None
+            }
+        }
+    }
+
+    /// Takes debounced messages which are due, in order of events.
+    pub(crate) fn flush(&mut self) -> Vec<Value> {
+        let mut messages = vec![];
+        let time = self.time;
+        self.debounced.retain_mut(|(_, due, message)| {
+            if *due <= time {
+                messages.push(message.take());
+                false
+            } else {
+                true
+            }
+        });
+        messages
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{Handler, HandlerArgument, Reaction, ValueExtensions, ViewError, ViewModel};
+use log::error;
+use serde_json::Value;
+
+/// The state declared in template like `$expanded="false"`, lives inside view
+/// instead of application model and never produces output messages.
+#[derive(Debug, Default)]
+pub(crate) struct LocalState {
+    /// The JSON pointers and default values of declared state.
+    pub(crate) defaults: Vec<(String, Value)>,
+    /// The reactions to state changes made by event handlers.
+    pub(crate) reactions: Vec<Reaction>,
+}
+
+impl LocalState {
+    pub(crate) const KEY: &'static str = "@local";
+
+    pub(crate) fn contains(path: &str) -> bool {
+        path.strip_prefix('/')
+            .map(|path| path.starts_with(Self::KEY))
+            .unwrap_or(false)
+    }
+}
+
+impl ViewModel {
+    pub(crate) fn init_local_state(&mut self) -> Result<Vec<Reaction>, ViewError> {
+        let mut reactions = vec![];
+        for (pointer, value) in self.local.defaults.clone() {
+            reactions.extend(self.set(&pointer, &value)?);
+        }
+        Ok(reactions)
+    }
+
+    pub(crate) fn set_local_state(&mut self, path: &str, value: Value) {
+        match self.set(path, &value) {
+            Ok(reactions) => self.local.reactions.extend(reactions),
+            Err(error) => error!("unable to set local state {path:?}, {error:?}"),
+        }
+    }
+
+    /// Mutates local state by handlers like `^onclick="toggle {expanded}"` or
+    /// `^onclick="set {tab} general"`, returns false if handler is not local state action.
+    pub(crate) fn handle_local_action(
+        &mut self,
+        handler: &Handler,
+        key: &str,
+        arguments: &[Value],
+    ) -> bool {
+        let path = match handler.arguments.get(1) {
+            Some(HandlerArgument::Binder { path, .. }) if LocalState::contains(path) => path,
+            _ => return false,
+        };
+        let value = match key {
+            "toggle" => {
+                let value = arguments.first().map(|value| value.eval_boolean());
+                Value::Bool(!value.unwrap_or(false))
+            }
+            "set" => match (handler.arguments.get(2), arguments.get(1)) {
+                (Some(HandlerArgument::Keyword(keyword)), _) => {
+                    serde_json::from_str(keyword).unwrap_or_else(|_| Value::String(keyword.clone()))
+                }
+                (_, Some(value)) => value.clone(),
+                (_, None) => Value::Null,
+            },
+            _ => return false,
+        };
+        self.set_local_state(path, value);
+        true
+    }
+}
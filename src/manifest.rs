@@ -0,0 +1,50 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, ViewError};
+use std::collections::BTreeSet;
+use taffy::{NodeId, TaffyTree};
+
+/// The change of resources referenced by view, application may preload textures
+/// once resource is referenced and free them once it is not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceHint {
+    /// The resource is referenced by view since this output.
+    Load(String),
+    /// The resource is no longer referenced by view.
+    Unload(String),
+}
+
+/// Collects urls referenced by element and its attached descendants,
+/// background images and image sources.
+pub(crate) fn collect_resources(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    resources: &mut BTreeSet<String>,
+) -> Result<(), ViewError> {
+    let element = tree.get_element(node)?;
+    for background in &element.backgrounds {
+        if let Some(image) = background.image.as_ref() {
+            if !image.is_empty() {
+                resources.insert(image.clone());
+            }
+        }
+    }
+    for child in tree.children(node)? {
+        collect_resources(tree, child, resources)?;
+    }
+    Ok(())
+}
+
+/// Returns hints to load resources referenced since previous manifest
+/// and unload resources no longer referenced, in url order.
+pub(crate) fn find_resource_hints(
+    previous: &BTreeSet<String>,
+    current: &BTreeSet<String>,
+) -> Vec<ResourceHint> {
+    let unload = previous
+        .difference(current)
+        .map(|src| ResourceHint::Unload(src.clone()));
+    let load = current
+        .difference(previous)
+        .map(|src| ResourceHint::Load(src.clone()));
+    unload.chain(load).collect()
+}
@@ -1,13 +1,198 @@
+use crate::{AccessNode, DragPayload, ElementChange, InputEvent, ResourceHint, Tooltip};
+use log::debug;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
+use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Default)]
 pub struct Output {
     pub is_input_captured: bool,
+    /// The input events not consumed by view, e.g. clicks outside of elements or
+    /// wheel not scrolling anything, application can forward them to gameplay.
+    pub uncaptured: Vec<InputEvent>,
     pub messages: Vec<Value>,
+    /// The tooltip of hovered element, appears after delay.
+    pub tooltip: Option<Tooltip>,
+    /// The payload of element being dragged, renderer can draw drag ghost with it.
+    pub drag: Option<DragPayload>,
+    /// The IME composition of focused text input, application should draw it
+    /// underlined over input until committed.
+    pub composition: Option<Composition>,
+    /// The caret and selection of focused text input, renderer should draw them
+    /// over input value.
+    pub caret: Option<Caret>,
+    /// The identified elements which size changed by layout this frame,
+    /// application may regenerate render targets of canvas-like regions.
+    pub resized: Vec<Resize>,
+    /// The screen areas painted differently than in previous output, application
+    /// rendering view into persistent texture may repaint only them.
+    pub damage: Vec<Rect>,
+    /// The elements created, updated or removed since previous output,
+    /// collected only if enabled by [`View::track_changes`](crate::View::track_changes).
+    pub changes: Vec<ElementChange>,
+    /// The image sources and background images view needs, in url order, application
+    /// should load them and report loading result via Input.
+    pub resources: Vec<String>,
+    /// The changes of all urls referenced by view since previous output,
+    /// application may preload textures instead of loading them mid-frame.
+    pub resource_hints: Vec<ResourceHint>,
+    /// The font families of texts not loaded by fonts yet, in name order, text is
+    /// measured by fallback until application reports family loaded via Input.
+    pub pending_fonts: Vec<String>,
+    /// The accessibility snapshot of view in document order, body first,
+    /// collected only if enabled by [`View::accessibility`](crate::View::accessibility).
+    pub accessibility: Vec<AccessNode>,
+    /// The text application should put to system clipboard, copied or cut by user.
+    pub clipboard: Option<String>,
+    /// Whether user requested paste with Ctrl+V, application should read system clipboard
+    /// and pass text with [`InputEvent::Paste`](crate::InputEvent::Paste).
+    pub paste_requested: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Composition {
+    pub text: String,
+    /// The selected char range of composition text, usually converted clause.
+    pub cursor: Option<[usize; 2]>,
+    /// The position of text input element.
+    pub position: [f32; 2],
+    /// The size of text input element.
+    pub size: [f32; 2],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caret {
+    /// The char index of caret in input value.
+    pub index: usize,
+    /// The selected char range of input value, empty if nothing selected.
+    pub range: [usize; 2],
+    /// The position of caret line.
+    pub position: [f32; 2],
+    /// The size of caret line, one pixel wide and line high.
+    pub size: [f32; 2],
+    /// The rectangles of selected text, one per line.
+    pub selection: Vec<Highlight>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Highlight {
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+}
+
+/// The rectangle on screen in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resize {
+    /// The id attribute of element.
+    pub id: String,
+    pub size: [f32; 2],
 }
 
 impl Output {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Deserializes messages into application type, messages of other format are skipped.
+    ///
+    /// Callback messages follow externally tagged enum representation of serde,
+    /// so `^onclick="Close"` matches `Close` variant, `^onclick="Select {id}"` matches
+    /// `Select(u32)` and `^onclick="Move {x} {y}"` matches `Move(f32, f32)`.
+    pub fn messages_as<T: DeserializeOwned>(&self) -> Vec<T> {
+        self.messages
+            .iter()
+            .filter_map(|message| match T::deserialize(message) {
+                Ok(message) => Some(message),
+                Err(error) => {
+                    debug!("unable to deserialize message {message}, {error}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Returns messages as calls, so they can be matched by signature:
+    ///
+    /// ```ignore
+    /// for call in output.calls() {
+    ///     match call.signature() {
+    ///         ("Close", []) => close(),
+    ///         ("Move", [x, y]) => move_to(x, y),
+    ///         _ => println!("unknown {}", call.describe()),
+    ///     }
+    /// }
+    /// ```
+    pub fn calls(&self) -> Vec<Call> {
+        self.messages
+            .iter()
+            .filter_map(Call::from_message)
+            .collect()
+    }
+}
+
+impl IntoIterator for Output {
+    type Item = Call;
+    type IntoIter = std::vec::IntoIter<Call>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.calls().into_iter()
+    }
+}
+
+/// The handler message as name and arguments, e.g. `Move` with `[x, y]`
+/// for `^onclick="Move {x} {y}"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub name: String,
+    pub arguments: Vec<Value>,
+}
+
+impl Call {
+    /// Reads message of handler, single array argument is not distinguishable from
+    /// many arguments and treated as arguments.
+    pub fn from_message(message: &Value) -> Option<Self> {
+        match message {
+            Value::String(name) => Some(Self {
+                name: name.clone(),
+                arguments: vec![],
+            }),
+            Value::Object(object) if object.len() == 1 => {
+                let (name, arguments) = object.iter().next()?;
+                let arguments = match arguments {
+                    Value::Array(arguments) => arguments.clone(),
+                    argument => vec![argument.clone()],
+                };
+                Some(Self {
+                    name: name.clone(),
+                    arguments,
+                })
+            }
+            _ => {
+                debug!("unable to read call from message {message}");
+                None
+            }
+        }
+    }
+
+    pub fn signature(&self) -> (&str, &[Value]) {
+        (self.name.as_str(), self.arguments.as_slice())
+    }
+
+    /// Returns human readable call description like `Move(1, 2)`.
+    pub fn describe(&self) -> String {
+        format!("{self}")
+    }
+}
+
+impl Display for Call {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let arguments: Vec<String> = self.arguments.iter().map(Value::to_string).collect();
+        write!(f, "{}({})", self.name, arguments.join(", "))
+    }
 }
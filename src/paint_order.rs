@@ -0,0 +1,83 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, ViewError};
+use taffy::{NodeId, Position, TaffyTree};
+
+/// The descendants of stacking context grouped by paint layer.
+#[derive(Default)]
+struct StackingLayers {
+    /// The stacking contexts with negative z-index, painted below flow.
+    negative: Vec<(i32, NodeId)>,
+    /// The non-positioned descendants in tree order.
+    flow: Vec<NodeId>,
+    /// The positioned descendants and stacking contexts with zero or positive z-index.
+    positioned: Vec<(i32, Layer)>,
+}
+
+enum Layer {
+    /// The stacking context painted together with its descendants.
+    Context(NodeId),
+    /// The positioned element without stacking context followed by its non-positioned
+    /// descendants, other descendants are painted by enclosing stacking context.
+    Positioned(Vec<NodeId>),
+}
+
+/// Appends element and its descendants in back-to-front paint order.
+///
+/// Element with z-index, opacity less than one or transforms establishes stacking
+/// context, its descendants painted together with it. Absolutely positioned elements
+/// without z-index painted after flow elements of enclosing stacking context, their
+/// positioned descendants take part in enclosing stacking context.
+pub(crate) fn collect_paint_order(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    order: &mut Vec<NodeId>,
+) -> Result<(), ViewError> {
+    order.push(node);
+    let mut layers = StackingLayers::default();
+    collect_stacking_layers(tree, node, &mut layers)?;
+    // stable sort keeps tree order of equal z-index
+    layers.negative.sort_by_key(|(z_index, _)| *z_index);
+    layers.positioned.sort_by_key(|(z_index, _)| *z_index);
+    for (_, node) in layers.negative {
+        collect_paint_order(tree, node, order)?;
+    }
+    order.extend(layers.flow);
+    for (_, layer) in layers.positioned {
+        match layer {
+            Layer::Context(node) => collect_paint_order(tree, node, order)?,
+            Layer::Positioned(nodes) => order.extend(nodes),
+        }
+    }
+    Ok(())
+}
+
+fn collect_stacking_layers(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    layers: &mut StackingLayers,
+) -> Result<(), ViewError> {
+    for child in tree.children(node)? {
+        let element = tree.get_element(child)?;
+        let absolute = tree.style(child)?.position == Position::Absolute;
+        let context = element.self_opacity < 1.0 || !element.transforms.is_empty();
+        match element.z_index {
+            Some(z_index) if z_index < 0 => layers.negative.push((z_index, child)),
+            Some(z_index) => layers.positioned.push((z_index, Layer::Context(child))),
+            None if context => layers.positioned.push((0, Layer::Context(child))),
+            None if absolute => {
+                let mut inner = StackingLayers::default();
+                collect_stacking_layers(tree, child, &mut inner)?;
+                let mut nodes = vec![child];
+                nodes.extend(inner.flow);
+                layers.positioned.push((0, Layer::Positioned(nodes)));
+                layers.negative.extend(inner.negative);
+                layers.positioned.extend(inner.positioned);
+            }
+            None => {
+                layers.flow.push(child);
+                collect_stacking_layers(tree, child, layers)?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{
+    Element, Fonts, InputEvent, MouseButtons, Pen, PenEvent, PenPhase, ViewError, ViewModel,
+};
+use taffy::{NodeId, TaffyTree};
+
+impl ViewModel {
+    /// Handles pen as mouse with left button, so templates made for mouse work
+    /// with stylus, and emits pen events with pressure and tilt to the same elements.
+    pub(crate) fn handle_pen_input(
+        &mut self,
+        phase: PenPhase,
+        pen: Pen,
+        body: NodeId,
+        tree: &mut TaffyTree<Element>,
+        fonts: &dyn Fonts,
+    ) -> Result<(), ViewError> {
+        self.mouse = pen.position;
+        let movement = vec![InputEvent::MouseMove(pen.position)];
+        self.handle_elements_input(movement, body, tree, fonts)?;
+        match phase {
+            PenPhase::Down => {
+                let press = vec![InputEvent::MouseButtonDown(MouseButtons::Left)];
+                self.handle_elements_input(press, body, tree, fonts)?;
+                self.emit_pen("onpendown", pen, tree)?;
+            }
+            PenPhase::Move => self.emit_pen("onpenmove", pen, tree)?,
+            PenPhase::Up => {
+                self.emit_pen("onpenup", pen, tree)?;
+                let release = vec![InputEvent::MouseButtonUp(MouseButtons::Left)];
+                self.handle_elements_input(release, body, tree, fonts)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Emits pen event to elements under pen and elements pressed by it.
+    fn emit_pen(
+        &mut self,
+        name: &str,
+        pen: Pen,
+        tree: &TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        let under_pen = self.elements_under_mouse.iter().rev().copied();
+        let targets: Vec<NodeId> = under_pen.chain(self.mouse_capture()).collect();
+        for node in targets {
+            let element = tree.get_element(node)?;
+            let event = PenEvent::new(pen, self.modifiers, element);
+            self.emit(element, name, event);
+        }
+        Ok(())
+    }
+}
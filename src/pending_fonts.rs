@@ -0,0 +1,87 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, Fonts, ViewError};
+use std::collections::BTreeSet;
+use taffy::{NodeId, TaffyTree};
+
+/// Collects font families of texts of element and its descendants not loaded by
+/// fonts yet, so application can load them asynchronously.
+pub(crate) fn collect_pending_fonts(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    fonts: &dyn Fonts,
+    pending: &mut BTreeSet<String>,
+) -> Result<(), ViewError> {
+    let element = tree.get_element(node)?;
+    if element.text.is_some() {
+        let spans = element.styled.iter().map(|span| &span.font);
+        for font in [&element.font].into_iter().chain(spans) {
+            if !fonts.is_loaded(&font.family) {
+                pending.insert(font.family.to_string());
+            }
+        }
+    }
+    for child in tree.children(node)? {
+        collect_pending_fonts(tree, child, fonts, pending)?;
+    }
+    Ok(())
+}
+
+/// Marks texts of loaded font families dirty, so they are measured again instead of
+/// keeping layout measured by fallback font.
+pub(crate) fn remeasure_loaded_fonts(
+    tree: &mut TaffyTree<Element>,
+    node: NodeId,
+    loaded: &[String],
+) -> Result<(), ViewError> {
+    let element = tree.get_element(node)?;
+    let uses = |family: &str| loaded.iter().any(|font| font.eq_ignore_ascii_case(family));
+    let styled = element.styled.iter().any(|span| uses(&span.font.family));
+    if element.text.is_some() && (uses(&element.font.family) || styled) {
+        tree.mark_dirty(node)?;
+    }
+    for child in tree.children(node)? {
+        remeasure_loaded_fonts(tree, child, loaded)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::{create_element, default_layout};
+    use crate::{FaceId, FontFace, StyledText, TextContent};
+
+    struct FancyFonts;
+
+    impl Fonts for FancyFonts {
+        fn is_loaded(&self, family: &str) -> bool {
+            family != "Fancy"
+        }
+
+        fn measure(&self, _id: FaceId, _: &str, _: &FontFace, _: Option<f32>) -> [f32; 2] {
+            [0.0; 2]
+        }
+    }
+
+    #[test]
+    pub fn test_pending_font_of_styled_span() {
+        let mut tree = TaffyTree::new();
+        let node = tree.new_leaf(default_layout()).expect("node created");
+        let mut element = create_element(node);
+        element.text = Some(TextContent::new(vec!["Hello world".to_string()]));
+        let mut font = element.font.clone();
+        font.family = "Fancy".into();
+        element.styled.push(StyledText {
+            text: "world".to_string(),
+            font,
+            color: element.color,
+            align: element.vertical_align,
+            shadows: vec![],
+        });
+        tree.set_node_context(node, Some(element))
+            .expect("element set");
+        let mut pending = BTreeSet::new();
+        collect_pending_fonts(&tree, node, &FancyFonts, &mut pending).expect("fonts collected");
+        assert_eq!(pending.into_iter().collect::<Vec<_>>(), ["Fancy"]);
+    }
+}
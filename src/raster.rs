@@ -0,0 +1,676 @@
+use crate::{
+    BorderStyle, DisplayCommand, LinearGradient, ObjectFit, Rgba, Shadow, View, ViewError,
+};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use tiny_skia::{
+    Color, FillRule, FilterQuality, GradientStop, IntRect, IntSize, LineCap, Mask, Paint, Path,
+    PathBuilder, Pattern, Pixmap, Point, Rect, Shader, SpreadMode, Stroke, StrokeDash, Transform,
+};
+
+/// The RGBA image of rasterized view, pixels are not premultiplied by alpha.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RasterImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RasterImage {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Returns color of pixel, transparent if out of image.
+    pub fn pixel(&self, x: u32, y: u32) -> Rgba {
+        if x >= self.width || y >= self.height {
+            return [0; 4];
+        }
+        let offset = (y * self.width + x) as usize * 4;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.pixels[offset..offset + 4]);
+        pixel
+    }
+}
+
+/// The headless renderer drawing display list into RGBA image on CPU, so layouts
+/// and animations can be tested by pixel snapshots without GPU or window.
+/// Text runs and their shadows are not drawn, there is no font rasterization in tiny-skia.
+pub struct Rasterizer {
+    scale: f32,
+    images: HashMap<String, Pixmap>,
+}
+
+impl Rasterizer {
+    pub fn new() -> Self {
+        Self {
+            scale: 1.0,
+            images: HashMap::new(),
+        }
+    }
+
+    /// Sets pixels per view unit, e.g. 2.0 to rasterize for high density display.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Provides image drawn for source, images not provided are not drawn.
+    pub fn image(mut self, src: &str, image: &RasterImage) -> Self {
+        let data = image
+            .pixels
+            .chunks(4)
+            .flat_map(|pixel| {
+                let alpha = pixel[3] as u32;
+                let premultiply = |channel: u8| ((channel as u32 * alpha + 127) / 255) as u8;
+                [
+                    premultiply(pixel[0]),
+                    premultiply(pixel[1]),
+                    premultiply(pixel[2]),
+                    pixel[3],
+                ]
+            })
+            .collect();
+        let pixmap = IntSize::from_wh(image.width, image.height)
+            .and_then(|size| Pixmap::from_vec(data, size));
+        match pixmap {
+            Some(pixmap) => {
+                self.images.insert(src.to_string(), pixmap);
+            }
+            None => log::error!("unable to use image {src}, invalid size"),
+        }
+        self
+    }
+
+    /// Draws commands into image of size in pixels.
+    pub fn draw(&self, commands: &[DisplayCommand], size: [u32; 2]) -> RasterImage {
+        let [width, height] = size;
+        let mut pixmap = match Pixmap::new(width, height) {
+            Some(pixmap) => pixmap,
+            None => return RasterImage::new(width, height, vec![]),
+        };
+        let mut clips: Vec<Mask> = vec![];
+        let mut transform = Transform::from_scale(self.scale, self.scale);
+        for command in commands {
+            let mask = clips.last();
+            match command {
+                DisplayCommand::PushClip {
+                    position,
+                    size,
+                    radius,
+                } => {
+                    // clip area of zero size clips everything
+                    let mut clip = match (mask, Mask::new(width, height)) {
+                        (Some(mask), _) => mask.clone(),
+                        (None, Some(clip)) => clip,
+                        (None, None) => continue,
+                    };
+                    match rounded_rect(*position, *size, *radius) {
+                        Some(path) if mask.is_some() => {
+                            clip.intersect_path(&path, FillRule::Winding, true, transform)
+                        }
+                        Some(path) => clip.fill_path(&path, FillRule::Winding, true, transform),
+                        None => clip.clear(),
+                    }
+                    clips.push(clip);
+                }
+                DisplayCommand::PopClip => {
+                    clips.pop();
+                }
+                DisplayCommand::Transform { translate } => {
+                    transform = Transform::from_scale(self.scale, self.scale)
+                        .pre_translate(translate[0], translate[1]);
+                }
+                DisplayCommand::Rect {
+                    position,
+                    size,
+                    color,
+                    radius,
+                } => {
+                    if let Some(path) = rounded_rect(*position, *size, *radius) {
+                        let paint = solid(*color);
+                        pixmap.fill_path(&path, &paint, FillRule::Winding, transform, mask);
+                    }
+                }
+                DisplayCommand::BoxShadow {
+                    position,
+                    size,
+                    radius,
+                    shadow,
+                } => {
+                    let blur = shadow.blur / 2.0 * self.scale;
+                    draw_shadow(
+                        &mut pixmap,
+                        *position,
+                        *size,
+                        *radius,
+                        shadow,
+                        blur,
+                        transform,
+                        mask,
+                    );
+                }
+                DisplayCommand::Gradient {
+                    position,
+                    size,
+                    radius,
+                    gradient,
+                } => {
+                    let path = rounded_rect(*position, *size, *radius);
+                    let shader = gradient_shader(*position, *size, gradient);
+                    if let (Some(path), Some(shader)) = (path, shader) {
+                        let paint = Paint {
+                            shader,
+                            anti_alias: true,
+                            ..Default::default()
+                        };
+                        pixmap.fill_path(&path, &paint, FillRule::Winding, transform, mask);
+                    }
+                }
+                DisplayCommand::Border {
+                    position,
+                    size,
+                    widths,
+                    colors,
+                    styles,
+                    radius,
+                } => {
+                    let border = Border {
+                        position: *position,
+                        size: *size,
+                        widths: *widths,
+                        colors: *colors,
+                        styles: *styles,
+                        radius: *radius,
+                    };
+                    border.draw(&mut pixmap, transform, mask);
+                }
+                DisplayCommand::Image {
+                    src,
+                    position,
+                    size,
+                    offset,
+                    fit,
+                    opacity,
+                    radius,
+                    frame,
+                } => {
+                    let image = self.images.get(src);
+                    let image = match image.and_then(|image| sprite_frame(image, *frame)) {
+                        Some(image) => image,
+                        None => continue,
+                    };
+                    let image: &Pixmap = &image;
+                    let path = match rounded_rect(*position, *size, *radius) {
+                        Some(path) => path,
+                        None => continue,
+                    };
+                    let image_size = [image.width() as f32, image.height() as f32];
+                    let [sx, sy] = fit_scale(*fit, image_size, *size);
+                    let x = position[0] + (size[0] - image_size[0] * sx) / 2.0 - offset[0] * sx;
+                    let y = position[1] + (size[1] - image_size[1] * sy) / 2.0 - offset[1] * sy;
+                    let paint = Paint {
+                        shader: Pattern::new(
+                            image.as_ref(),
+                            SpreadMode::Pad,
+                            FilterQuality::Bilinear,
+                            *opacity,
+                            Transform::from_row(sx, 0.0, 0.0, sy, x, y),
+                        ),
+                        anti_alias: true,
+                        ..Default::default()
+                    };
+                    pixmap.fill_path(&path, &paint, FillRule::Winding, transform, mask);
+                }
+                // custom materials are bound by engines, drawn by default here
+                DisplayCommand::TextRun { .. }
+                | DisplayCommand::TextShadow { .. }
+                | DisplayCommand::Material { .. } => {}
+            }
+        }
+        let pixels = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+        RasterImage::new(width, height, pixels)
+    }
+}
+
+impl Default for Rasterizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl View {
+    /// Draws view by headless renderer into image of size in pixels,
+    /// see [`Rasterizer`] to provide images or scale.
+    pub fn rasterize(&self, size: [u32; 2]) -> Result<RasterImage, ViewError> {
+        let commands = self.display_list()?;
+        Ok(Rasterizer::new().draw(&commands, size))
+    }
+}
+
+struct Border {
+    position: [f32; 2],
+    size: [f32; 2],
+    widths: [f32; 4],
+    colors: [Rgba; 4],
+    styles: [BorderStyle; 4],
+    radius: [f32; 4],
+}
+
+impl Border {
+    fn draw(&self, pixmap: &mut Pixmap, transform: Transform, mask: Option<&Mask>) {
+        let [x, y] = self.position;
+        let [width, height] = self.size;
+        let [top, right, bottom, left] = self.widths;
+        if self.radius.iter().any(|radius| *radius > 0.0) {
+            // rounded border drawn as ring of top side color and style
+            let outer = rounded_rect(self.position, self.size, self.radius);
+            let inner = rounded_rect(
+                [x + left, y + top],
+                [width - left - right, height - top - bottom],
+                self.radius.map(|radius| (radius - top.max(left)).max(0.0)),
+            );
+            if !self.styles[0].is_visible() {
+                return;
+            }
+            let mut builder = PathBuilder::new();
+            for path in [outer, inner].into_iter().flatten() {
+                builder.push_path(&path);
+            }
+            if let Some(path) = builder.finish() {
+                let paint = solid(self.colors[0]);
+                pixmap.fill_path(&path, &paint, FillRule::EvenOdd, transform, mask);
+            }
+            return;
+        }
+        let [right_edge, bottom_edge] = [x + width, y + height];
+        // side center lines from start to end in clockwise order
+        let sides = [
+            ([x, y + top / 2.0], [right_edge, y + top / 2.0]),
+            (
+                [right_edge - right / 2.0, y],
+                [right_edge - right / 2.0, bottom_edge],
+            ),
+            (
+                [right_edge, bottom_edge - bottom / 2.0],
+                [x, bottom_edge - bottom / 2.0],
+            ),
+            ([x + left / 2.0, bottom_edge], [x + left / 2.0, y]),
+        ];
+        for (index, (start, end)) in sides.into_iter().enumerate() {
+            let width = self.widths[index];
+            let style = self.styles[index];
+            if width <= 0.0 || !style.is_visible() {
+                continue;
+            }
+            let paint = solid(self.colors[index]);
+            let mut stroke = Stroke {
+                width,
+                ..Default::default()
+            };
+            let mut lines = vec![(start, end, width)];
+            match style {
+                BorderStyle::Dashed => stroke.dash = StrokeDash::new(vec![width * 3.0, width], 0.0),
+                BorderStyle::Dotted => {
+                    stroke.line_cap = LineCap::Round;
+                    stroke.dash = StrokeDash::new(vec![0.0, width * 2.0], width / 2.0);
+                }
+                BorderStyle::Double => {
+                    // two lines of third of width at outer and inner edges
+                    let third = width / 3.0;
+                    let normal = side_normal(start, end, third);
+                    let shift =
+                        |[x, y]: [f32; 2], sign: f32| [x + normal[0] * sign, y + normal[1] * sign];
+                    lines = vec![
+                        (shift(start, -1.0), shift(end, -1.0), third),
+                        (shift(start, 1.0), shift(end, 1.0), third),
+                    ];
+                }
+                _ => {}
+            }
+            for (start, end, width) in lines {
+                let mut builder = PathBuilder::new();
+                builder.move_to(start[0], start[1]);
+                builder.line_to(end[0], end[1]);
+                if let Some(path) = builder.finish() {
+                    stroke.width = width;
+                    pixmap.stroke_path(&path, &paint, &stroke, transform, mask);
+                }
+            }
+        }
+    }
+}
+
+/// Draws shadow of rounded rectangle blurred with standard deviation in pixels,
+/// outer shadow is masked out inside of rectangle and inset shadow outside of it.
+#[allow(clippy::too_many_arguments)]
+fn draw_shadow(
+    pixmap: &mut Pixmap,
+    position: [f32; 2],
+    size: [f32; 2],
+    radius: [f32; 4],
+    shadow: &Shadow,
+    blur: f32,
+    transform: Transform,
+    clip: Option<&Mask>,
+) {
+    let [width, height] = [pixmap.width(), pixmap.height()];
+    let (mut coverage, mut area) = match (Mask::new(width, height), Mask::new(width, height)) {
+        (Some(coverage), Some(area)) => (coverage, area),
+        _ => return,
+    };
+    let spread = if shadow.inset {
+        -shadow.spread
+    } else {
+        shadow.spread
+    };
+    let [x, y] = position;
+    let [dx, dy] = shadow.offset;
+    let shape = rounded_rect(
+        [x + dx - spread, y + dy - spread],
+        [size[0] + 2.0 * spread, size[1] + 2.0 * spread],
+        radius.map(|radius| match radius > 0.0 {
+            true => (radius + spread).max(0.0),
+            false => 0.0,
+        }),
+    );
+    if let Some(shape) = shape {
+        coverage.fill_path(&shape, FillRule::Winding, true, transform);
+    }
+    blur_mask(&mut coverage, blur);
+    if let Some(bounds) = rounded_rect(position, size, radius) {
+        area.fill_path(&bounds, FillRule::Winding, true, transform);
+    }
+    if shadow.inset {
+        coverage.invert();
+    } else {
+        area.invert();
+    }
+    let clip = clip.map(|clip| clip.data());
+    for (index, (value, area)) in coverage.data_mut().iter_mut().zip(area.data()).enumerate() {
+        let mut alpha = *value as u32 * *area as u32 / 255;
+        if let Some(clip) = clip {
+            alpha = alpha * clip[index] as u32 / 255;
+        }
+        *value = alpha as u8;
+    }
+    if let Some(rect) = Rect::from_xywh(0.0, 0.0, width as f32, height as f32) {
+        let paint = solid(shadow.color);
+        pixmap.fill_rect(rect, &paint, Transform::identity(), Some(&coverage));
+    }
+}
+
+/// Blurs mask by three box blurs approximating gaussian blur with standard deviation.
+fn blur_mask(mask: &mut Mask, sigma: f32) {
+    if sigma <= 0.0 {
+        return;
+    }
+    // box width of three passes with same variance as gaussian
+    let radius = (((4.0 * sigma * sigma + 1.0).sqrt() - 1.0) / 2.0).round() as usize;
+    if radius == 0 {
+        return;
+    }
+    let [width, height] = [mask.width() as usize, mask.height() as usize];
+    let data = mask.data_mut();
+    for _ in 0..3 {
+        box_blur(data, [width, height], [1, width], radius);
+        box_blur(data, [height, width], [width, 1], radius);
+    }
+}
+
+/// Blurs lines of data in place, lines are of length with step between values,
+/// stride between lines, values outside of line are zero.
+fn box_blur(
+    data: &mut [u8],
+    [length, lines]: [usize; 2],
+    [step, stride]: [usize; 2],
+    radius: usize,
+) {
+    let window = (2 * radius + 1) as u32;
+    let mut sums = vec![0u32; length + 1];
+    for line in 0..lines {
+        let start = line * stride;
+        for index in 0..length {
+            sums[index + 1] = sums[index] + data[start + index * step] as u32;
+        }
+        for index in 0..length {
+            let end = (index + radius + 1).min(length);
+            let sum = sums[end] - sums[index.saturating_sub(radius)];
+            data[start + index * step] = ((sum + window / 2) / window) as u8;
+        }
+    }
+}
+
+/// Returns linear gradient shader filling rectangle, gradient line goes through its
+/// center at angle and is long enough to reach its corners.
+fn gradient_shader(
+    position: [f32; 2],
+    size: [f32; 2],
+    gradient: &LinearGradient,
+) -> Option<Shader<'static>> {
+    let angle = gradient.angle.to_radians();
+    let [dx, dy] = [angle.sin(), -angle.cos()];
+    let [width, height] = size;
+    let half = (width * dx.abs() + height * dy.abs()) / 2.0;
+    let [cx, cy] = [position[0] + width / 2.0, position[1] + height / 2.0];
+    let stops = gradient
+        .stops
+        .iter()
+        .map(|stop| {
+            let [r, g, b, a] = stop.color;
+            GradientStop::new(stop.offset, Color::from_rgba8(r, g, b, a))
+        })
+        .collect();
+    tiny_skia::LinearGradient::new(
+        Point::from_xy(cx - dx * half, cy - dy * half),
+        Point::from_xy(cx + dx * half, cy + dy * half),
+        stops,
+        SpreadMode::Pad,
+        Transform::identity(),
+    )
+}
+
+/// Returns perpendicular of side line with length, pointing into box.
+fn side_normal(start: [f32; 2], end: [f32; 2], length: f32) -> [f32; 2] {
+    let [dx, dy] = [end[0] - start[0], end[1] - start[1]];
+    let norm = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+    [-dy / norm * length, dx / norm * length]
+}
+
+/// Returns shown frame of sprite-sheet image, frames laid out horizontally.
+fn sprite_frame(image: &Pixmap, [index, count]: [u32; 2]) -> Option<Cow<'_, Pixmap>> {
+    if count <= 1 {
+        return Some(Cow::Borrowed(image));
+    }
+    let width = image.width() / count;
+    let rect = IntRect::from_xywh((index * width) as i32, 0, width, image.height())?;
+    image.clone_rect(rect).map(Cow::Owned)
+}
+
+fn fit_scale(fit: ObjectFit, image: [f32; 2], size: [f32; 2]) -> [f32; 2] {
+    let [width, height] = [size[0] / image[0], size[1] / image[1]];
+    let scale = match fit {
+        ObjectFit::Fill => return [width, height],
+        ObjectFit::Contain => width.min(height),
+        ObjectFit::Cover => width.max(height),
+        ObjectFit::None => 1.0,
+        ObjectFit::ScaleDown => width.min(height).min(1.0),
+    };
+    [scale, scale]
+}
+
+fn solid([r, g, b, a]: Rgba) -> Paint<'static> {
+    Paint {
+        shader: Shader::SolidColor(Color::from_rgba8(r, g, b, a)),
+        anti_alias: true,
+        ..Default::default()
+    }
+}
+
+/// Returns path of rectangle with circular corners approximated by cubic curves.
+fn rounded_rect(position: [f32; 2], size: [f32; 2], radius: [f32; 4]) -> Option<Path> {
+    let [x, y] = position;
+    let [width, height] = size;
+    if radius.iter().all(|radius| *radius <= 0.0) {
+        return Some(PathBuilder::from_rect(Rect::from_xywh(
+            x, y, width, height,
+        )?));
+    }
+    const KAPPA: f32 = 0.552_284_8;
+    let [top_left, top_right, bottom_right, bottom_left] = radius;
+    let [right, bottom] = [x + width, y + height];
+    let mut builder = PathBuilder::new();
+    builder.move_to(x + top_left, y);
+    builder.line_to(right - top_right, y);
+    let k = top_right * KAPPA;
+    builder.cubic_to(
+        right - top_right + k,
+        y,
+        right,
+        y + top_right - k,
+        right,
+        y + top_right,
+    );
+    builder.line_to(right, bottom - bottom_right);
+    let k = bottom_right * KAPPA;
+    builder.cubic_to(
+        right,
+        bottom - bottom_right + k,
+        right - bottom_right + k,
+        bottom,
+        right - bottom_right,
+        bottom,
+    );
+    builder.line_to(x + bottom_left, bottom);
+    let k = bottom_left * KAPPA;
+    builder.cubic_to(
+        x + bottom_left - k,
+        bottom,
+        x,
+        bottom - bottom_left + k,
+        x,
+        bottom - bottom_left,
+    );
+    builder.line_to(x, y + top_left);
+    let k = top_left * KAPPA;
+    builder.cubic_to(x, y + top_left - k, x + top_left - k, y, x + top_left, y);
+    builder.close();
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Input, RasterImage, Rasterizer, View};
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    pub fn test_rasterize_boxes_and_clipping() {
+        let css = r#"
+            body { width: 40px; height: 40px; }
+            #red { width: 20px; height: 20px; background-color: #ff0000; }
+            #frame {
+                width: 20px;
+                height: 20px;
+                border: 2px dashed #0000ff;
+                border-radius: 10px;
+                overflow: hidden;
+            }
+            #fill { width: 40px; height: 40px; background-color: #00ff00; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="red"></div>
+            <div id="frame"><div id="fill"></div></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let image = view.rasterize([40, 60]).expect("raster valid");
+        assert_eq!(image.pixel(10, 10), [255, 0, 0, 255]);
+        assert_eq!(image.pixel(30, 10), [0; 4]);
+        assert_eq!(image.pixel(12, 32), [0, 255, 0, 255], "clipped fill inside");
+        assert_eq!(image.pixel(2, 22), [0; 4], "clipped by rounded corner");
+        assert_eq!(image.pixel(30, 32), [0; 4], "clipped by frame");
+    }
+
+    #[test]
+    pub fn test_rasterize_shadows_and_gradient() {
+        let css = r#"
+            body { width: 40px; height: 100px; }
+            div { width: 20px; height: 20px; margin: 10px; }
+            #card {
+                background-image: linear-gradient(to right, #ff0000, #0000ff);
+                box-shadow: 4px 0 #00ff00;
+            }
+            #well { box-shadow: inset 0 0 0 2px #000000; }
+            #glow { box-shadow: 0 0 8px #000000; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="card"></div>
+            <div id="well"></div>
+            <div id="glow"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let image = view.rasterize([40, 100]).expect("raster valid");
+        let [left, right] = [image.pixel(10, 20), image.pixel(29, 20)];
+        assert!(left[0] > right[0] && left[2] < right[2], "red to blue");
+        assert_eq!(image.pixel(32, 20), [0, 255, 0, 255], "shadow moved right");
+        assert_eq!(image.pixel(8, 20), [0; 4], "shadow not moved left");
+        assert_eq!(image.pixel(11, 50), [0, 0, 0, 255], "inset shadow at edge");
+        assert_eq!(image.pixel(20, 50), [0; 4], "inset shadow not in center");
+        let fading = [
+            image.pixel(9, 80)[3],
+            image.pixel(5, 80)[3],
+            image.pixel(1, 80)[3],
+        ];
+        assert!(
+            fading[0] > fading[1] && fading[1] > fading[2],
+            "blurred {fading:?}"
+        );
+        assert_eq!(image.pixel(20, 80), [0; 4], "outer shadow not inside");
+    }
+
+    #[test]
+    pub fn test_rasterize_sprite_frame() {
+        let css = r#"
+            #sprite {
+                width: 10px;
+                height: 10px;
+                background-image: url("sprite.png");
+                -bumaga-background-frames: 4;
+                -bumaga-background-duration: 0.4s;
+            }
+        "#;
+        let html = r#"<html><body><div id="sprite"></div></body></html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let input = Input::new().time(Duration::from_secs_f32(0.25));
+        view.update(input, json!({})).expect("valid update");
+        let colors = [
+            [255, 0, 0, 255],
+            [0, 255, 0, 255],
+            [0, 0, 255, 255],
+            [255; 4],
+        ];
+        let sprite = RasterImage::new(4, 1, colors.concat());
+        let rasterizer = Rasterizer::new().image("sprite.png", &sprite);
+        let image = rasterizer.draw(&view.display_list().unwrap(), [10, 10]);
+        assert_eq!(image.pixel(5, 5), [0, 0, 255, 255], "third frame shown");
+        assert_eq!(image.pixel(0, 0), [0, 0, 255, 255]);
+        assert_eq!(image.pixel(9, 9), [0, 0, 255, 255]);
+    }
+}
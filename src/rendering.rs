@@ -1,11 +1,14 @@
 use log::{error, warn};
+use serde_json::Value;
 use std::collections::{BTreeMap, HashMap};
 use taffy::{Dimension, NodeId, Size, TaffyTree};
 
 use crate::css::read_inline_css;
 use crate::html::{CallbackArgument, ElementBinding, Html, TextBinding, TextSpan};
+use crate::local_state::LocalState;
 use crate::styles::{create_element, default_layout};
-use crate::view_model::{Binding, Bindings, Schema};
+use crate::translations::TranslatedText;
+use crate::view_model::{arrays_path, Binder, Binding, Bindings, DerivedValue, Schema};
 use crate::{BindingParams, Element, Handler, HandlerArgument, TextContent, ViewError};
 
 pub struct Renderer {
@@ -15,6 +18,13 @@ pub struct Renderer {
     pub schema: Schema,
     pub templates: HashMap<String, Html>,
     pub static_id: HashMap<String, NodeId>,
+    pub images: Vec<NodeId>,
+    pub translations: HashMap<(NodeId, usize), TranslatedText>,
+    pub derived: Vec<DerivedValue>,
+    /// The item identity key fields of keyed arrays by arrays path.
+    pub keys: HashMap<String, String>,
+    /// The JSON pointers and default values of template local state.
+    pub local_state: Vec<(String, Value)>,
 }
 
 impl Renderer {
@@ -31,6 +41,11 @@ impl Renderer {
             schema,
             templates,
             static_id,
+            images: vec![],
+            translations: HashMap::new(),
+            derived: vec![],
+            keys: HashMap::new(),
+            local_state: vec![],
         }
     }
 
@@ -70,6 +85,26 @@ impl Renderer {
                     self.bindings.entry(path).or_default().push(binding);
                     binder.to_string()
                 }
+                TextSpan::Translation(translation) => {
+                    let mut placeholders = vec![];
+                    for (name, binder) in translation.placeholders {
+                        let path = self.schema.field(&binder, &self.locals);
+                        let params = BindingParams::Translation(node, index);
+                        let binding = Binding {
+                            params,
+                            pipe: vec![],
+                        };
+                        self.bindings.entry(path.clone()).or_default().push(binding);
+                        placeholders.push((name, path));
+                    }
+                    let key = translation.key;
+                    let text = TranslatedText {
+                        key: key.clone(),
+                        placeholders,
+                    };
+                    self.translations.insert((node, index), text);
+                    key
+                }
             })
             .collect();
         let text = TextContent::new(spans);
@@ -102,6 +137,7 @@ impl Renderer {
         let node = self.tree.new_leaf(layout)?;
         let mut element = create_element(node);
         element.tag = template.tag.clone();
+        let overridden_locals = self.declare_local_state(node, &template.bindings);
         for binding in template.bindings {
             match binding {
                 ElementBinding::None(key, value) => {
@@ -146,6 +182,13 @@ impl Renderer {
                         .enumerate()
                         .map(|(index, span)| match span {
                             TextSpan::String(span) => span.to_string(),
+                            TextSpan::Translation(translation) => {
+                                warn!(
+                                    "element {} attribute {} translation not supported",
+                                    element.tag, key
+                                );
+                                translation.key
+                            }
                             TextSpan::Binder(binder) => {
                                 let path = self.schema.field(&binder, &mut self.locals);
                                 let params = BindingParams::Attribute(node, key.clone(), index);
@@ -169,12 +212,12 @@ impl Renderer {
                     element.attrs.insert(key.clone(), attribute.to_string());
                     element.attrs_bindings.insert(key, attribute);
                 }
-                ElementBinding::Callback(event, arguments) => {
+                ElementBinding::Callback(event, arguments, rate) => {
                     let arguments = arguments
                         .into_iter()
                         .map(|argument| match argument {
                             CallbackArgument::Keyword(key) => HandlerArgument::Keyword(key),
-                            CallbackArgument::Event => HandlerArgument::Event,
+                            CallbackArgument::Event(path) => HandlerArgument::Event(path),
                             CallbackArgument::Binder(binder) => {
                                 let path = self.schema.field(&binder, &mut self.locals);
                                 let pipe = binder.pipe.clone();
@@ -182,11 +225,31 @@ impl Renderer {
                             }
                         })
                         .collect();
-                    element.listeners.insert(event, Handler { arguments });
+                    let handlers = element.listeners.entry(event).or_default();
+                    handlers.push(Handler { arguments, rate });
+                }
+                ElementBinding::Model(key, binder) => {
+                    let path = self.schema.field(&binder, &self.locals);
+                    let params = if key == "checked" {
+                        BindingParams::Tag(node, key.clone())
+                    } else {
+                        let attribute = TextContent::new(vec![binder.to_string()]);
+                        element.attrs.insert(key.clone(), attribute.to_string());
+                        element.attrs_bindings.insert(key.clone(), attribute);
+                        BindingParams::Attribute(node, key.clone(), 0)
+                    };
+                    let binding = Binding {
+                        params,
+                        pipe: binder.pipe.clone(),
+                    };
+                    self.bindings.entry(path.clone()).or_default().push(binding);
+                    element.style_hints.dynamic_attrs.insert(key.clone());
+                    element.model_bindings.insert(key, path);
                 }
                 // used on other rendering stages
                 ElementBinding::Alias(_, _) => {}
-                ElementBinding::Repeat(_, _, _) => {}
+                ElementBinding::Local(_, _) => {}
+                ElementBinding::Repeat(_, _, _, _) => {}
                 ElementBinding::Visibility(_, _) => {}
             }
         }
@@ -214,6 +277,10 @@ impl Renderer {
             "wbr" => {}
             _ => {
                 for child in template.children {
+                    if child.tag == "style" {
+                        // embedded style sheets are part of view CSS, not elements
+                        continue;
+                    }
                     let child = if let Some((id, mut bindings)) = child.as_template_link() {
                         let mut template = self
                             .templates
@@ -248,7 +315,14 @@ impl Renderer {
                         let params = BindingParams::Visibility(node, child_id, visible);
                         let binding = Binding { params, pipe };
                         self.bindings.entry(path).or_default().push(binding);
-                    } else if let Some((name, count, binder)) = child.as_repeat() {
+                    } else if let Some((name, count, binder, key)) = child.as_repeat() {
+                        let derived;
+                        let binder = if binder.pipe.is_empty() {
+                            binder
+                        } else {
+                            derived = self.derive_array(binder);
+                            &derived
+                        };
                         let array = self.schema.field(binder, &self.locals);
                         let start = children.len();
                         let params = BindingParams::Repeat(node, start, count);
@@ -264,10 +338,26 @@ impl Renderer {
                         for n in 0..count {
                             let path = self.schema.index(binder, n, &self.locals);
                             self.locals.insert(name.to_string(), path);
+                            if let Some(key) = key {
+                                let identity = Binder {
+                                    path: vec![name.to_string(), key.to_string()],
+                                    pipe: vec![],
+                                };
+                                self.schema.field(&identity, &self.locals);
+                            }
                             let child = child.clone();
                             let child = self.render_node(child)?;
                             children.push(child);
                         }
+                        if let Some(key) = key {
+                            self.keys.insert(arrays_path(&array), key.to_string());
+                            let slots = children[start..].to_vec();
+                            let binding = Binding {
+                                params: BindingParams::Order(node, slots),
+                                pipe: vec![],
+                            };
+                            self.bindings.entry(array).or_default().push(binding);
+                        }
                         if let Some(overridden) = overridden {
                             self.locals.insert(name.to_string(), overridden);
                         } else {
@@ -288,10 +378,56 @@ impl Renderer {
                 }
             }
         }
+        element.state.checked = element.attrs.contains_key("checked");
+        element.update_validity();
         element.children = children.clone();
         self.tree.set_node_context(node, Some(element))?;
         self.tree.set_children(node, &children)?;
+        for (name, path) in overridden_locals {
+            if let Some(path) = path {
+                self.locals.insert(name, path);
+            } else {
+                self.locals.remove(&name);
+            }
+        }
         // todo:
         Ok(node)
     }
+
+    /// Defines element local state as view internal model value,
+    /// each rendered element instance gets its own state.
+    fn declare_local_state(
+        &mut self,
+        node: NodeId,
+        bindings: &[ElementBinding],
+    ) -> Vec<(String, Option<String>)> {
+        let mut overridden = vec![];
+        for binding in bindings {
+            if let ElementBinding::Local(name, value) = binding {
+                let key = format!("{}-{}", LocalState::KEY, u64::from(node));
+                let binder = Binder {
+                    path: vec![key, name.clone()],
+                    pipe: vec![],
+                };
+                let path = self.schema.field(&binder, &self.locals);
+                self.local_state.push((path.clone(), value.clone()));
+                overridden.push((name.clone(), self.locals.insert(name.clone(), path)));
+            }
+        }
+        overridden
+    }
+
+    /// Defines piped array as derived model value, repeated elements are bound to it.
+    fn derive_array(&mut self, binder: &Binder) -> Binder {
+        self.schema.index(binder, 0, &self.locals);
+        let source = self.schema.field(binder, &self.locals);
+        let key = format!("{}repeat-{}", DerivedValue::PREFIX, self.derived.len());
+        let target = format!("/{key}");
+        let derived = DerivedValue::new(source, target, binder.pipe.clone());
+        self.derived.push(derived);
+        Binder {
+            path: vec![key],
+            pipe: vec![],
+        }
+    }
 }
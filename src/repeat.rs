@@ -0,0 +1,67 @@
+use crate::Keys;
+use std::time::Duration;
+
+/// Repeats held editing and navigation keys, so application does not need
+/// to synthesize repeated key events.
+#[derive(Debug)]
+pub(crate) struct KeyRepeat {
+    /// The time in seconds key must be held before first repeat.
+    pub(crate) delay: f32,
+    /// The time in seconds between repeats.
+    pub(crate) interval: f32,
+    key: Option<Keys>,
+    elapsed: f32,
+    next: f32,
+}
+
+impl Default for KeyRepeat {
+    fn default() -> Self {
+        Self {
+            delay: 0.5,
+            interval: 0.05,
+            key: None,
+            elapsed: 0.0,
+            next: 0.0,
+        }
+    }
+}
+
+impl KeyRepeat {
+    pub(crate) fn press(&mut self, key: Keys) {
+        if self.key == Some(key) {
+            // system repeat of held key
+            return;
+        }
+        self.key = match key {
+            Keys::Backspace
+            | Keys::Delete
+            | Keys::ArrowUp
+            | Keys::ArrowDown
+            | Keys::ArrowLeft
+            | Keys::ArrowRight
+            | Keys::PageUp
+            | Keys::PageDown => Some(key),
+            _ => None,
+        };
+        self.elapsed = 0.0;
+        self.next = self.delay;
+    }
+
+    pub(crate) fn release(&mut self, key: Keys) {
+        if self.key == Some(key) {
+            self.key = None;
+        }
+    }
+
+    /// Returns held key and number of its repeats due after time elapsed.
+    pub(crate) fn advance(&mut self, time: Duration) -> Option<(Keys, usize)> {
+        let key = self.key?;
+        self.elapsed += time.as_secs_f32();
+        let mut count = 0;
+        while self.elapsed >= self.next {
+            count += 1;
+            self.next += self.interval.max(f32::EPSILON);
+        }
+        Some((key, count)).filter(|_| count > 0)
+    }
+}
@@ -0,0 +1,306 @@
+use crate::css::{Css, PseudoClassMatcher};
+use crate::styles::{
+    create_element, inherit, match_styles, Cascade, CascadeStats, Sizes, Variables,
+};
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, Input, ViewError};
+use log::error;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::mem::{swap, take};
+use taffy::{NodeId, TaffyTree};
+
+/// The stylesheet and input styles of elements are recalculated with.
+pub(crate) struct StyleScope<'s> {
+    pub css: &'s Css,
+    pub input: &'s Input,
+    /// Whether stylesheet matches elements by previous sibling.
+    pub siblings: bool,
+}
+
+/// The statistics of cascade collected while styles of elements recalculated.
+#[derive(Default)]
+pub(crate) struct Restyled {
+    pub layouts: usize,
+    pub stats: CascadeStats,
+    pub cascades: usize,
+    /// The text elements font of which changed, so they can be measured in advance.
+    #[cfg(feature = "parallel")]
+    pub texts: Vec<NodeId>,
+}
+
+impl Restyled {
+    #[cfg(feature = "parallel")]
+    fn merge(&mut self, other: Restyled) {
+        self.layouts += other.layouts;
+        self.stats.matches_static += other.stats.matches_static;
+        self.stats.matches_dynamic += other.stats.matches_dynamic;
+        self.stats.apply_ok += other.stats.apply_ok;
+        self.stats.apply_error += other.stats.apply_error;
+        self.cascades += other.cascades;
+        self.texts.extend(other.texts);
+    }
+}
+
+/// The element taken out of tree with descendants to recalculate their styles,
+/// so sibling subtrees are restyled independently, by many threads if `parallel`
+/// feature enabled.
+struct Subtree {
+    node: NodeId,
+    element: Element,
+    /// The style key, matched styles and layout of dirty element, clean element
+    /// is taken only as parent of dirty descendants.
+    dirty: Option<(u64, Vec<bool>, taffy::Style)>,
+    children: Vec<Subtree>,
+}
+
+/// Recalculates styles of element and its descendants if they are dirty: parent
+/// restyled, properties matched by selectors changed or element animated.
+///
+/// Selectors read tree, so styles of all dirty elements are matched first, then
+/// dirty subtrees are detached from tree, restyled and put back.
+pub(crate) fn restyle(
+    tree: &mut TaffyTree<Element>,
+    scope: &StyleScope,
+    node: NodeId,
+    sizes: Sizes,
+    variables: &Variables,
+    dirty: bool,
+    restyled: &mut Restyled,
+) -> Result<(), ViewError> {
+    let parent = tree.parent(node).ok_or(ViewError::ParentNotFound(node))?;
+    let (_, subtree) = match_subtree(tree, scope, node, None, dirty, restyled)?;
+    let mut subtree = match subtree {
+        Some(subtree) => subtree,
+        None => return Ok(()),
+    };
+    detach(tree, &mut subtree)?;
+    let parent = tree.get_element(parent)?;
+    cascade(&mut subtree, scope, parent, sizes, variables, restyled);
+    attach(tree, subtree, restyled)
+}
+
+/// Matches styles of dirty elements, returns whether element is dirty and its
+/// subtree if element or any of its descendants is dirty.
+fn match_subtree(
+    tree: &TaffyTree<Element>,
+    scope: &StyleScope,
+    node: NodeId,
+    previous: Option<NodeId>,
+    dirty: bool,
+    restyled: &mut Restyled,
+) -> Result<(bool, Option<Subtree>), ViewError> {
+    let element = tree.get_element(node)?;
+    if element.text.is_some() {
+        let subtree = dirty.then(|| Subtree {
+            node,
+            element: create_element(node),
+            dirty: None,
+            children: vec![],
+        });
+        return Ok((dirty, subtree));
+    }
+
+    let key = style_key(tree, node, previous)?;
+    let dirty = dirty || element.style_key != Some(key) || element.is_animated();
+    let styles = if dirty {
+        let matches = match_styles(node, tree, &StateMatcher, &mut restyled.stats);
+        Some((key, matches, tree.style(node)?.clone()))
+    } else {
+        None
+    };
+
+    let mut children = vec![];
+    match element.tag.as_str() {
+        "img" | "input" | "area" | "base" | "br" | "col" | "command" | "embed" | "hr"
+        | "keygen" | "link" | "meta" | "param" | "source" | "track" | "wbr" => {}
+        _ => {
+            let mut previous = None;
+            // element matched by sibling combinator is dirty if previous sibling restyled
+            let mut sibling_dirty = false;
+            for child in tree.children(node)? {
+                let child_dirty = dirty || sibling_dirty;
+                let (restyled, subtree) =
+                    match_subtree(tree, scope, child, previous, child_dirty, restyled)?;
+                sibling_dirty = scope.siblings && restyled;
+                previous = Some(child);
+                children.extend(subtree);
+            }
+        }
+    }
+    if styles.is_none() && children.is_empty() {
+        return Ok((false, None));
+    }
+    let subtree = Subtree {
+        node,
+        element: create_element(node),
+        dirty: styles,
+        children,
+    };
+    Ok((dirty, Some(subtree)))
+}
+
+/// Takes elements of subtree out of tree, blank elements are left in place until
+/// elements are put back.
+fn detach(tree: &mut TaffyTree<Element>, subtree: &mut Subtree) -> Result<(), ViewError> {
+    swap(tree.get_element_mut(subtree.node)?, &mut subtree.element);
+    for child in subtree.children.iter_mut() {
+        detach(tree, child)?;
+    }
+    Ok(())
+}
+
+/// Puts elements of subtree back to tree, layouts are updated only if changes
+/// detected to support Taffy cache system.
+fn attach(
+    tree: &mut TaffyTree<Element>,
+    subtree: Subtree,
+    restyled: &mut Restyled,
+) -> Result<(), ViewError> {
+    *tree.get_element_mut(subtree.node)? = subtree.element;
+    if let Some((_, _, layout)) = subtree.dirty {
+        if tree.style(subtree.node)? != &layout {
+            restyled.layouts += 1;
+            tree.set_style(subtree.node, layout)?;
+        }
+    }
+    for child in subtree.children {
+        attach(tree, child, restyled)?;
+    }
+    Ok(())
+}
+
+/// Applies matched styles to dirty elements of subtree, texts inherit styles of
+/// parent element.
+fn cascade(
+    subtree: &mut Subtree,
+    scope: &StyleScope,
+    parent: &Element,
+    mut sizes: Sizes,
+    variables: &Variables,
+    restyled: &mut Restyled,
+) {
+    #[cfg(feature = "parallel")]
+    let node = subtree.node;
+    let Subtree {
+        element,
+        dirty,
+        children,
+        ..
+    } = subtree;
+    if element.text.is_some() {
+        #[cfg(feature = "parallel")]
+        let font = element.font.clone();
+        inherit(parent, element);
+        #[cfg(feature = "parallel")]
+        if element.font != font {
+            restyled.texts.push(node);
+        }
+        return;
+    }
+    let variables = match dirty.as_mut() {
+        Some((key, matches, layout)) => {
+            restyled.cascades += 1;
+            let mut cascade = Cascade::new(scope.css, sizes, variables.clone());
+            cascade.apply_styles(scope.input, parent, layout, element, matches);
+            element.style_key = Some(*key);
+            let stats = cascade.stats;
+            restyled.stats.apply_ok += stats.apply_ok;
+            restyled.stats.apply_error += stats.apply_error;
+            cascade.take_variables()
+        }
+        // clean element keeps variables of last cascade for its children
+        None => take(&mut element.variables),
+    };
+    sizes.parent_font_size = element.font.size;
+    sizes.parent_color = element.color;
+    #[cfg(feature = "parallel")]
+    if children.len() > 1 {
+        use rayon::prelude::*;
+        let parent = &*element;
+        let subtrees: Vec<Restyled> = children
+            .par_iter_mut()
+            .map(|child| {
+                let mut subtree = Restyled::default();
+                cascade(child, scope, parent, sizes, &variables, &mut subtree);
+                subtree
+            })
+            .collect();
+        for subtree in subtrees {
+            restyled.merge(subtree);
+        }
+        element.variables = variables;
+        return;
+    }
+    for child in children.iter_mut() {
+        cascade(child, scope, element, sizes, &variables, restyled);
+    }
+    element.variables = variables;
+}
+
+/// Returns hash of element properties matched by selectors: tag, attributes, state
+/// and previous sibling, so styles of element are recalculated only if hash changes.
+pub(crate) fn style_key(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    previous: Option<NodeId>,
+) -> Result<u64, ViewError> {
+    let element = tree.get_element(node)?;
+    let mut hasher = DefaultHasher::new();
+    element.tag.hash(&mut hasher);
+    // attributes are hashed regardless of map order
+    let attrs = element.attrs.iter().fold(0u64, |sum, attribute| {
+        let mut hasher = DefaultHasher::new();
+        attribute.hash(&mut hasher);
+        sum.wrapping_add(hasher.finish())
+    });
+    attrs.hash(&mut hasher);
+    let state = &element.state;
+    state.active.hash(&mut hasher);
+    state.hover.hash(&mut hasher);
+    state.focus.hash(&mut hasher);
+    state.focus_visible.hash(&mut hasher);
+    state.checked.hash(&mut hasher);
+    state.pending.hash(&mut hasher);
+    state.invalid.hash(&mut hasher);
+    previous.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// Matches pseudo classes by interaction state of element.
+pub(crate) struct StateMatcher;
+
+impl PseudoClassMatcher for StateMatcher {
+    fn has_pseudo_class(&self, element: &Element, class: &str) -> bool {
+        match class {
+            "hover" => element.state.hover,
+            "active" => element.state.active,
+            // The :checked CSS pseudo-class represents any radio, checkbox, or option element
+            // that is checked or toggled to an "on" state.
+            "checked" => element.state.checked,
+            // The :focus CSS pseudo-class represents an element (such as a form input) that
+            // has received focus. It is generally triggered when the user clicks or taps
+            // on an element or selects it with the keyboard's Tab key.
+            "focus" => element.state.focus,
+            // The :focus-visible CSS pseudo-class represents focused element which
+            // should indicate focus, e.g. focused by keyboard rather than by pointer.
+            "focus-visible" => element.state.focus && element.state.focus_visible,
+            // The :blank CSS pseudo-class selects empty user input elements.
+            "blank" => false,
+            // The :disabled CSS pseudo-class represents any disabled element.
+            "disabled" => element.disabled(),
+            "enabled" => !element.disabled(),
+            // The :loading pseudo-class represents element bound to value
+            // still loaded by application, see `pending`.
+            "loading" => element.state.pending > 0,
+            // The :valid and :invalid CSS pseudo-classes represent input element
+            // whose value validates or fails constraints like required or pattern.
+            "valid" => !element.state.invalid,
+            "invalid" => element.state.invalid,
+            _ => {
+                error!("unable to match unknown pseudo class {class}");
+                false
+            }
+        }
+    }
+}
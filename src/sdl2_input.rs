@@ -0,0 +1,247 @@
+use crate::{Input, InputEvent, Keys, MouseButtons};
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Scancode};
+use sdl2::mouse::{MouseButton, MouseWheelDirection};
+use std::mem::take;
+
+/// Collects SDL2 events between frames and converts them to view input.
+#[derive(Debug)]
+pub struct Sdl2Input {
+    events: Vec<InputEvent>,
+    viewport: [f32; 2],
+    /// Whether IME composition in progress, so text input commits it.
+    composing: bool,
+    /// The scroll distance in pixels of one wheel line.
+    pub line_height: f32,
+}
+
+impl Sdl2Input {
+    /// Creates adapter of window with size in pixels.
+    pub fn new(viewport: [f32; 2]) -> Self {
+        Self {
+            events: vec![],
+            viewport,
+            composing: false,
+            line_height: 50.0,
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &Event) {
+        match event {
+            Event::Window {
+                win_event: WindowEvent::SizeChanged(width, height),
+                ..
+            } => {
+                self.viewport = [*width as f32, *height as f32];
+            }
+            Event::MouseMotion { x, y, .. } => {
+                self.events
+                    .push(InputEvent::MouseMove([*x as f32, *y as f32]));
+            }
+            Event::MouseButtonDown { mouse_btn, .. } => {
+                if let Some(button) = map_mouse_button(*mouse_btn) {
+                    self.events.push(InputEvent::MouseButtonDown(button));
+                }
+            }
+            Event::MouseButtonUp { mouse_btn, .. } => {
+                if let Some(button) = map_mouse_button(*mouse_btn) {
+                    self.events.push(InputEvent::MouseButtonUp(button));
+                }
+            }
+            Event::MouseWheel {
+                precise_x,
+                precise_y,
+                direction,
+                ..
+            } => {
+                let sign = match direction {
+                    MouseWheelDirection::Flipped => -1.0,
+                    _ => 1.0,
+                };
+                let x = precise_x * sign * self.line_height;
+                let y = precise_y * sign * self.line_height;
+                self.events.push(InputEvent::MouseWheel([x, y]));
+            }
+            // view repeats held editing and navigation keys itself
+            Event::KeyDown {
+                keycode,
+                scancode,
+                repeat: false,
+                ..
+            } => {
+                let key = map_keycode(*keycode, *scancode);
+                self.events.push(InputEvent::KeyDown(key));
+            }
+            Event::KeyUp {
+                keycode, scancode, ..
+            } => {
+                let key = map_keycode(*keycode, *scancode);
+                self.events.push(InputEvent::KeyUp(key));
+            }
+            Event::TextEditing {
+                text,
+                start,
+                length,
+                ..
+            } => {
+                self.composing = !text.is_empty();
+                let cursor = match *length {
+                    0 => None,
+                    length => Some([*start as usize, (*start + length) as usize]),
+                };
+                self.events
+                    .push(InputEvent::ImeCompose(text.clone(), cursor));
+            }
+            Event::TextInput { text, .. } => {
+                if take(&mut self.composing) {
+                    self.events.push(InputEvent::ImeCommit(text.clone()));
+                } else {
+                    self.events.extend(text.chars().map(InputEvent::Char));
+                }
+            }
+            Event::FingerDown {
+                finger_id, x, y, ..
+            } => {
+                let position = self.touch_position(*x, *y);
+                let id = *finger_id as u64;
+                self.events.push(InputEvent::TouchStart(id, position));
+            }
+            Event::FingerMotion {
+                finger_id, x, y, ..
+            } => {
+                let position = self.touch_position(*x, *y);
+                let id = *finger_id as u64;
+                self.events.push(InputEvent::TouchMove(id, position));
+            }
+            Event::FingerUp {
+                finger_id, x, y, ..
+            } => {
+                let position = self.touch_position(*x, *y);
+                let id = *finger_id as u64;
+                self.events.push(InputEvent::TouchEnd(id, position));
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns input of events collected since previous frame.
+    pub fn take_input(&mut self) -> Input {
+        Input::new()
+            .viewport(self.viewport)
+            .scroll_sensitivity(1.0)
+            .events(take(&mut self.events))
+    }
+
+    /// SDL reports touch positions normalized to window size.
+    fn touch_position(&self, x: f32, y: f32) -> [f32; 2] {
+        let [width, height] = self.viewport;
+        [x * width, y * height]
+    }
+}
+
+fn map_mouse_button(button: MouseButton) -> Option<MouseButtons> {
+    match button {
+        MouseButton::Left => Some(MouseButtons::Left),
+        MouseButton::Right => Some(MouseButtons::Right),
+        _ => None,
+    }
+}
+
+/// Returns key of SDL keycode, keys not covered by Keys are passed as scancode.
+pub fn map_keycode(keycode: Option<Keycode>, scancode: Option<Scancode>) -> Keys {
+    let keycode = match keycode {
+        Some(keycode) => keycode,
+        None => return scancode.map(map_scancode).unwrap_or(Keys::Unknown),
+    };
+    match keycode {
+        // UI keys
+        Keycode::ESCAPE => Keys::Escape,
+        // Editing keys
+        Keycode::BACKSPACE => Keys::Backspace,
+        Keycode::DELETE => Keys::Delete,
+        Keycode::INSERT => Keys::Insert,
+        // Whitespace keys
+        Keycode::RETURN => Keys::Enter,
+        Keycode::TAB => Keys::Tab,
+        Keycode::SPACE => Keys::Space,
+        // Navigation keys
+        Keycode::UP => Keys::ArrowUp,
+        Keycode::DOWN => Keys::ArrowDown,
+        Keycode::LEFT => Keys::ArrowLeft,
+        Keycode::RIGHT => Keys::ArrowRight,
+        Keycode::END => Keys::End,
+        Keycode::HOME => Keys::Home,
+        Keycode::PAGEDOWN => Keys::PageDown,
+        Keycode::PAGEUP => Keys::PageUp,
+        // Modifier keys
+        Keycode::LALT | Keycode::RALT => Keys::Alt,
+        Keycode::CAPSLOCK => Keys::CapsLock,
+        Keycode::LCTRL | Keycode::RCTRL => Keys::Ctrl,
+        Keycode::LSHIFT | Keycode::RSHIFT => Keys::Shift,
+        Keycode::LGUI | Keycode::RGUI => Keys::Meta,
+        Keycode::NUMLOCKCLEAR => Keys::NumLock,
+        Keycode::SCROLLLOCK => Keys::ScrollLock,
+        // Punctuation keys
+        Keycode::MINUS => Keys::Minus,
+        Keycode::EQUALS => Keys::Equal,
+        Keycode::LEFTBRACKET => Keys::BracketLeft,
+        Keycode::RIGHTBRACKET => Keys::BracketRight,
+        Keycode::BACKSLASH => Keys::Backslash,
+        Keycode::SEMICOLON => Keys::Semicolon,
+        Keycode::QUOTE => Keys::Quote,
+        Keycode::BACKQUOTE => Keys::Backquote,
+        Keycode::COMMA => Keys::Comma,
+        Keycode::PERIOD => Keys::Period,
+        Keycode::SLASH => Keys::Slash,
+        // Function keys
+        Keycode::F1 => Keys::F1,
+        Keycode::F2 => Keys::F2,
+        Keycode::F3 => Keys::F3,
+        Keycode::F4 => Keys::F4,
+        Keycode::F5 => Keys::F5,
+        Keycode::F6 => Keys::F6,
+        Keycode::F7 => Keys::F7,
+        Keycode::F8 => Keys::F8,
+        Keycode::F9 => Keys::F9,
+        Keycode::F10 => Keys::F10,
+        Keycode::F11 => Keys::F11,
+        Keycode::F12 => Keys::F12,
+        // Numpad keys
+        Keycode::KP_0 => Keys::Numpad0,
+        Keycode::KP_1 => Keys::Numpad1,
+        Keycode::KP_2 => Keys::Numpad2,
+        Keycode::KP_3 => Keys::Numpad3,
+        Keycode::KP_4 => Keys::Numpad4,
+        Keycode::KP_5 => Keys::Numpad5,
+        Keycode::KP_6 => Keys::Numpad6,
+        Keycode::KP_7 => Keys::Numpad7,
+        Keycode::KP_8 => Keys::Numpad8,
+        Keycode::KP_9 => Keys::Numpad9,
+        Keycode::KP_PLUS => Keys::NumpadAdd,
+        Keycode::KP_MINUS => Keys::NumpadSubtract,
+        Keycode::KP_MULTIPLY => Keys::NumpadMultiply,
+        Keycode::KP_DIVIDE => Keys::NumpadDivide,
+        Keycode::KP_PERIOD => Keys::NumpadDecimal,
+        Keycode::KP_ENTER => Keys::NumpadEnter,
+        // Device and media keys
+        Keycode::PRINTSCREEN => Keys::PrintScreen,
+        Keycode::PAUSE => Keys::Pause,
+        Keycode::APPLICATION => Keys::ContextMenu,
+        Keycode::AUDIOPLAY => Keys::MediaPlayPause,
+        Keycode::AUDIOSTOP => Keys::MediaStop,
+        Keycode::AUDIONEXT => Keys::MediaTrackNext,
+        Keycode::AUDIOPREV => Keys::MediaTrackPrevious,
+        Keycode::VOLUMEUP => Keys::AudioVolumeUp,
+        Keycode::VOLUMEDOWN => Keys::AudioVolumeDown,
+        Keycode::MUTE => Keys::AudioVolumeMute,
+        // SDL keycodes of letter and digit keys are their characters
+        keycode => match char::from_u32(keycode.into_i32() as u32) {
+            Some(char) if char.is_ascii_alphanumeric() => Keys::Character(char),
+            _ => scancode.map(map_scancode).unwrap_or(Keys::Unknown),
+        },
+    }
+}
+
+fn map_scancode(scancode: Scancode) -> Keys {
+    Keys::Scancode(scancode as u32)
+}
@@ -0,0 +1,285 @@
+use crate::fonts::DummyFonts;
+use crate::{FaceId, FontFace, FontMetrics, Fonts, ViewError};
+use fontdue::{Font, FontSettings};
+use log::error;
+use std::fs;
+use std::path::Path;
+use ttf_parser::name_id;
+
+/// The built-in fonts measuring text by glyph advances and kerning of font files,
+/// so view wraps text as it is rendered without application Fonts implementation.
+/// Characters missing in face are measured by other loaded faces.
+pub struct ShapedFonts {
+    faces: Vec<LoadedFace>,
+}
+
+struct LoadedFace {
+    /// The family, typographic family and full names in lower case.
+    names: Vec<String>,
+    weight: u16,
+    italic: bool,
+    /// Whether face has color glyphs, e.g. emoji font.
+    color: bool,
+    font: Font,
+}
+
+impl ShapedFonts {
+    pub fn new() -> Self {
+        Self { faces: vec![] }
+    }
+
+    /// Loads font files of directory and its subdirectories, e.g. view resources.
+    pub fn from_directory(path: impl AsRef<Path>) -> Result<Self, ViewError> {
+        let mut fonts = Self::new();
+        fonts.load_directory(path.as_ref())?;
+        Ok(fonts)
+    }
+
+    /// Loads all faces of font or font collection.
+    pub fn load(&mut self, data: &[u8]) -> Result<(), ViewError> {
+        let invalid = |error: String| ViewError::FontInvalid(error);
+        let count = ttf_parser::fonts_in_collection(data).unwrap_or(1);
+        for index in 0..count {
+            let face =
+                ttf_parser::Face::parse(data, index).map_err(|error| invalid(error.to_string()))?;
+            let mut names = vec![];
+            for name in face.names() {
+                let id = name.name_id;
+                if id == name_id::FAMILY
+                    || id == name_id::TYPOGRAPHIC_FAMILY
+                    || id == name_id::FULL_NAME
+                {
+                    if let Some(name) = name.to_string() {
+                        names.push(name.to_lowercase());
+                    }
+                }
+            }
+            let settings = FontSettings {
+                collection_index: index,
+                ..Default::default()
+            };
+            let font = Font::from_bytes(data, settings).map_err(|error| invalid(error.into()))?;
+            let tables = face.tables();
+            let color = tables.cbdt.is_some()
+                || tables.sbix.is_some()
+                || tables.colr.is_some()
+                || tables.svg.is_some();
+            self.faces.push(LoadedFace {
+                names,
+                weight: face.weight().to_number(),
+                italic: face.is_italic() || face.is_oblique(),
+                color,
+                font,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    fn load_directory(&mut self, path: &Path) -> Result<(), ViewError> {
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                self.load_directory(&path)?;
+                continue;
+            }
+            let extension = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .map(|extension| extension.to_lowercase());
+            if matches!(extension.as_deref(), Some("ttf" | "otf" | "ttc" | "otc")) {
+                if let Err(error) = self.load(&fs::read(&path)?) {
+                    error!("unable to load font {path:?}, {error:?}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn line_width(&self, text: &str, face: &LoadedFace, size: f32) -> f32 {
+        let mut width = 0.0;
+        let mut previous = None;
+        for char in text.chars() {
+            let font = if face.font.has_glyph(char) {
+                &face.font
+            } else {
+                self.faces
+                    .iter()
+                    .map(|fallback| &fallback.font)
+                    .find(|font| font.has_glyph(char))
+                    .unwrap_or(&face.font)
+            };
+            if let Some(previous) = previous {
+                width += font.horizontal_kern(previous, char, size).unwrap_or(0.0);
+            }
+            width += font.metrics(char, size).advance_width;
+            previous = Some(char);
+        }
+        width
+    }
+
+    fn line_height(&self, face: &LoadedFace, font: &FontFace) -> f32 {
+        let metrics = face.font.horizontal_line_metrics(font.size);
+        let height = metrics.map(|metrics| metrics.new_line_size);
+        height.unwrap_or(font.size) * font.line_height
+    }
+}
+
+impl Default for ShapedFonts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fonts for ShapedFonts {
+    /// Returns face of font family closest by style then weight, any loaded face
+    /// closest by style and weight if family not loaded.
+    fn resolve(&self, family: &str, weight: u16, style: &str) -> FaceId {
+        let family = family.to_lowercase();
+        let italic = style == "italic" || style == "oblique";
+        let closest = |family: Option<&str>| {
+            self.faces
+                .iter()
+                .enumerate()
+                .filter(|(_, loaded)| match family {
+                    Some(family) => loaded.names.iter().any(|name| name == family),
+                    None => true,
+                })
+                .min_by_key(|(_, loaded)| (loaded.italic != italic, loaded.weight.abs_diff(weight)))
+                .map(|(index, _)| FaceId(index))
+        };
+        closest(Some(&family))
+            .or_else(|| closest(None))
+            .unwrap_or_default()
+    }
+
+    /// Returns first loaded color face unless face has color glyphs itself.
+    fn resolve_emoji(&self, id: FaceId) -> FaceId {
+        match self.faces.get(id.0) {
+            Some(face) if face.color => id,
+            _ => self
+                .faces
+                .iter()
+                .position(|face| face.color)
+                .map(FaceId)
+                .unwrap_or(id),
+        }
+    }
+
+    fn metrics(&self, id: FaceId, face: &FontFace) -> FontMetrics {
+        let metrics = self
+            .faces
+            .get(id.0)
+            .and_then(|loaded| loaded.font.horizontal_line_metrics(face.size));
+        match metrics {
+            Some(metrics) => FontMetrics {
+                ascent: metrics.ascent,
+                descent: -metrics.descent,
+                line_gap: metrics.line_gap,
+            },
+            None => FontMetrics::approximate(face.size),
+        }
+    }
+
+    fn measure(&self, id: FaceId, text: &str, face: &FontFace, max_width: Option<f32>) -> [f32; 2] {
+        let loaded = match self.faces.get(id.0) {
+            Some(loaded) => loaded,
+            None => return DummyFonts.measure(id, text, face, max_width),
+        };
+        let line_height = self.line_height(loaded, face);
+        if max_width.is_none() {
+            return [self.line_width(text, loaded, face.size), line_height];
+        }
+        let lines = self.layout(id, text, face, max_width);
+        let width = lines.iter().map(|line| line.width).fold(0.0, f32::max);
+        let height = lines.iter().map(|line| line.height).sum::<f32>();
+        [width, height.max(line_height)]
+    }
+
+    fn as_sync(&self) -> Option<&(dyn Fonts + Sync)> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FontFace, Fonts, Input, ShapedFonts, TextAlign, View};
+    use serde_json::json;
+
+    const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/shared");
+
+    fn face(family: &str) -> FontFace {
+        FontFace {
+            family: family.into(),
+            size: 20.0,
+            style: "normal".into(),
+            weight: 400,
+            line_height: 1.0,
+            align: TextAlign::Start,
+            direction: Default::default(),
+            stroke: Default::default(),
+            hyphens: Default::default(),
+            lang: String::new(),
+            tab_size: Default::default(),
+        }
+    }
+
+    fn measure(fonts: &ShapedFonts, text: &str, face: &FontFace, max: Option<f32>) -> [f32; 2] {
+        let id = fonts.resolve(&face.family, face.weight, &face.style);
+        fonts.measure(id, text, face, max)
+    }
+
+    #[test]
+    pub fn test_shaped_text_measure() {
+        let fonts = ShapedFonts::from_directory(RESOURCES).expect("fonts loaded");
+        let [width, height] = measure(&fonts, "Hello", &face("Roboto Regular"), None);
+        let [narrow, _] = measure(&fonts, "iiiii", &face("roboto"), None);
+        let [wide, _] = measure(&fonts, "mmmmm", &face("roboto"), None);
+        assert!(width > 40.0 && width < 60.0, "glyph advances {width}");
+        assert!(narrow < wide / 2.0, "proportional font");
+        assert!(height > 20.0 && height < 30.0, "line height {height}");
+        let [wrapped, lines] = measure(&fonts, "Hello Hello", &face("system-ui"), Some(70.0));
+        assert_eq!([wrapped, lines], [width, height * 2.0], "fallback face");
+    }
+
+    #[test]
+    pub fn test_view_shapes_text_from_resources() {
+        let css = r#"
+            body { font-family: Roboto; font-size: 20px; }
+            div { width: 70px; }
+        "#;
+        let html = r#"<html><body><div>Hello Hello</div></body></html>"#;
+        let mut view = View::compile(html, css, RESOURCES).expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let body = view.body();
+        let div = body.children()[0];
+        let text = div.children()[0];
+        let lines: Vec<_> = text.lines.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(lines, ["Hello", "Hello"]);
+    }
+
+    #[test]
+    pub fn test_shaped_font_metrics() {
+        let fonts = ShapedFonts::from_directory(RESOURCES).expect("fonts loaded");
+        let face = face("roboto");
+        let id = fonts.resolve(&face.family, face.weight, &face.style);
+        let metrics = fonts.metrics(id, &face);
+        let [_, height] = fonts.measure(id, "Hello", &face, None);
+        assert!(
+            metrics.ascent > 15.0 && metrics.ascent < 20.0,
+            "{metrics:?}"
+        );
+        assert!(
+            metrics.descent > 3.0 && metrics.descent < 8.0,
+            "{metrics:?}"
+        );
+        let baseline = metrics.baseline(height);
+        assert!(
+            baseline >= metrics.ascent - 0.5 && baseline < height,
+            "{baseline}"
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, ViewError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use taffy::{NodeId, TaffyTree};
+
+/// The interaction state of view elements which is not part of application model,
+/// elements identified by id attribute or position in document.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ViewSnapshot {
+    pub focus: Option<String>,
+    pub elements: BTreeMap<String, ElementSnapshot>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ElementSnapshot {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scroll: Option<[f32; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checked: Option<bool>,
+    /// The names and elapsed times in seconds of element animations.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub animations: Vec<(String, f32)>,
+}
+
+impl ElementSnapshot {
+    fn capture(element: &Element) -> Self {
+        let is_input = element.tag == "input" || element.tag == "textarea";
+        Self {
+            scroll: element
+                .scrolling
+                .as_ref()
+                .map(|scroll| [scroll.x, scroll.y]),
+            value: element.value().filter(|_| is_input).cloned(),
+            checked: Some(element.state.checked).filter(|_| is_input),
+            animations: element
+                .animators
+                .iter()
+                .map(|animator| (animator.name.clone(), animator.time))
+                .collect(),
+        }
+    }
+
+    fn restore(&self, element: &mut Element) {
+        if let Some([x, y]) = self.scroll {
+            // scroll limits are recalculated on next layout
+            let scrolling = element.scrolling.get_or_insert_with(Default::default);
+            scrolling.x = x;
+            scrolling.y = y;
+            scrolling.scroll_x = scrolling.scroll_x.max(x);
+            scrolling.scroll_y = scrolling.scroll_y.max(y);
+        }
+        if let Some(value) = self.value.as_ref() {
+            if let Some(attribute) = element.attrs_bindings.get_mut("value") {
+                attribute.set(0, value.clone());
+            }
+            element.attrs.insert("value".into(), value.clone());
+        }
+        if let Some(checked) = self.checked {
+            element.state.checked = checked;
+            if checked {
+                let key = "checked";
+                element.attrs.insert(key.into(), key.to_string());
+            } else {
+                element.attrs.remove("checked");
+            }
+        }
+        element.update_validity();
+        element.style_key = None;
+        for (index, (name, time)) in self.animations.iter().enumerate() {
+            let animator = element.get_animator_mut(index);
+            animator.name = name.clone();
+            animator.time = *time;
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl ViewSnapshot {
+    pub(crate) fn capture(
+        tree: &TaffyTree<Element>,
+        body: NodeId,
+        focus: Option<NodeId>,
+    ) -> Result<Self, ViewError> {
+        let mut snapshot = Self::default();
+        let mut elements = vec![];
+        collect_elements(tree, body, String::new(), &mut elements)?;
+        for (key, node) in elements {
+            if focus == Some(node) {
+                snapshot.focus = Some(key.clone());
+            }
+            let element = ElementSnapshot::capture(tree.get_element(node)?);
+            if !element.is_empty() {
+                snapshot.elements.insert(key, element);
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Restores elements state, returns focused element.
+    pub(crate) fn restore(
+        &self,
+        tree: &mut TaffyTree<Element>,
+        body: NodeId,
+    ) -> Result<Option<NodeId>, ViewError> {
+        let mut focus = None;
+        let mut elements = vec![];
+        collect_elements(tree, body, String::new(), &mut elements)?;
+        for (key, node) in elements {
+            if self.focus.as_ref() == Some(&key) {
+                focus = Some(node);
+            }
+            if let Some(snapshot) = self.elements.get(&key) {
+                snapshot.restore(tree.get_element_mut(node)?);
+            }
+        }
+        Ok(focus)
+    }
+}
+
+/// Collects elements with their keys, id attribute or path of child indices.
+fn collect_elements(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    path: String,
+    elements: &mut Vec<(String, NodeId)>,
+) -> Result<(), ViewError> {
+    let element = tree.get_element(node)?;
+    if element.text.is_some() {
+        return Ok(());
+    }
+    let key = match element.attrs.get("id") {
+        Some(id) => format!("#{id}"),
+        None => path.clone(),
+    };
+    elements.push((key, node));
+    // definition children, hidden elements keep their state too
+    for (index, child) in element.children.iter().enumerate() {
+        collect_elements(tree, *child, format!("{path}/{index}"), elements)?;
+    }
+    Ok(())
+}
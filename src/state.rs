@@ -0,0 +1,30 @@
+use log::error;
+use serde::Serialize;
+use serde_json::Value;
+
+/// The JSON pointers and new values of changed state fields.
+pub type StateChanges = Vec<(String, Value)>;
+
+/// Application state bound to view directly, without whole JSON value conversion every frame.
+///
+/// Implementation compares fields with previous state and collects only changed ones,
+/// see `#[derive(ViewState)]` of `derive` feature.
+pub trait ViewState: Clone + 'static {
+    fn collect_changes(&self, previous: Option<&Self>, pointer: &str, changes: &mut StateChanges);
+}
+
+/// Collects value change if value differs from previous one or there is no previous state.
+pub fn collect_value_change<T: PartialEq + Serialize>(
+    value: &T,
+    previous: Option<&T>,
+    pointer: impl FnOnce() -> String,
+    changes: &mut StateChanges,
+) {
+    if previous == Some(value) {
+        return;
+    }
+    match serde_json::to_value(value) {
+        Ok(value) => changes.push((pointer(), value)),
+        Err(error) => error!("unable to serialize state value, {error:?}"),
+    }
+}
@@ -4,7 +4,11 @@ use crate::animation::{
 use crate::css::ComputedValue::{Keyword, Str, Time};
 use crate::css::{ComputedValue, Dim, PropertyKey, Units};
 use crate::styles::{Cascade, CascadeError};
-use crate::{Element, Length, PointerEvents, TextAlign, TransformFunction};
+use crate::{
+    BorderStyle, ColorStop, Element, Hyphens, Length, LinearGradient, PointerEvents, Rgba, Shadow,
+    TabSize, TextAlign, TextDirection, TransformFunction, VerticalAlign,
+};
+use smol_str::SmolStr;
 use taffy::{BoxSizing, Dimension, LengthPercentage, LengthPercentageAuto, Overflow};
 
 impl<'c> Cascade<'c> {
@@ -34,13 +38,32 @@ impl<'c> Cascade<'c> {
                     let background = element.get_background_mut(index);
                     background.image = None;
                 } else {
-                    return Err(CascadeError::InvalidKeyword(keyword.clone()));
+                    return Err(CascadeError::InvalidKeyword(keyword.to_string()));
                 }
             }
             (PropertyKey::BackgroundImage, Str(value)) => {
                 let background = element.get_background_mut(index);
                 background.image = Some(value.clone());
             }
+            (PropertyKey::BackgroundImage, ComputedValue::Gradient(arguments)) => {
+                let background = element.get_background_mut(index);
+                background.image = None;
+                background.gradient = Some(resolve_gradient(arguments, self)?);
+            }
+            (PropertyKey::BoxShadow, Keyword(keyword)) if keyword == "none" => {
+                element.shadows.clear();
+            }
+            (PropertyKey::BoxShadow, ComputedValue::Shadow(values)) => {
+                let shadow = resolve_shadow(values, self)?;
+                set_shadow(&mut element.shadows, index, shadow);
+            }
+            (PropertyKey::TextShadow, Keyword(keyword)) if keyword == "none" => {
+                element.text_shadows.clear();
+            }
+            (PropertyKey::TextShadow, ComputedValue::Shadow(values)) => {
+                let shadow = resolve_shadow(values, self)?;
+                set_shadow(&mut element.text_shadows, index, shadow);
+            }
             (PropertyKey::BackgroundPositionX, value) => {
                 // TODO: percentage
                 let background = element.get_background_mut(index);
@@ -51,6 +74,13 @@ impl<'c> Cascade<'c> {
                 let background = element.get_background_mut(index);
                 background.src[1] = -resolve_length(value, self, 0.0)?;
             }
+            (PropertyKey::BumagaBackgroundFrames, ComputedValue::Number(frames)) => {
+                let background = element.get_background_mut(index);
+                background.frames = (frames.round() as u32).max(1);
+            }
+            (PropertyKey::BumagaBackgroundDuration, Time(duration)) => {
+                element.get_background_mut(index).duration = *duration;
+            }
             (PropertyKey::Color, value) => element.color = resolve_color(value, self)?,
             (PropertyKey::FontSize, value) => {
                 element.font.size = resolve_length(value, self, self.sizes.parent_font_size)?;
@@ -58,12 +88,25 @@ impl<'c> Cascade<'c> {
             (PropertyKey::FontWeight, value) => {
                 element.font.weight = resolve_font_weight(value, self)?
             }
-            (PropertyKey::FontFamily, value) => element.font.family = resolve_string(value, self)?,
+            (PropertyKey::BumagaTextStrokeWidth, value) => {
+                element.font.stroke.width = dimension_length(value, self)?;
+            }
+            (PropertyKey::BumagaTextStrokeColor, Keyword(keyword))
+                if keyword.eq_ignore_ascii_case("currentcolor") =>
+            {
+                element.font.stroke.color = None;
+            }
+            (PropertyKey::BumagaTextStrokeColor, value) => {
+                element.font.stroke.color = Some(resolve_color(value, self)?);
+            }
+            (PropertyKey::FontFamily, value) => {
+                element.font.family = resolve_string(value, self)?.into()
+            }
             (PropertyKey::FontStyle, ComputedValue::Keyword(keyword)) => {
                 element.font.style = match keyword.as_str() {
-                    "normal" => "normal".to_string(),
-                    "italic" => "italic".to_string(),
-                    "oblique" => "oblique".to_string(),
+                    "normal" => SmolStr::new_static("normal"),
+                    "italic" => SmolStr::new_static("italic"),
+                    "oblique" => SmolStr::new_static("oblique"),
                     keyword => return CascadeError::invalid_keyword(keyword),
                 }
             }
@@ -80,6 +123,36 @@ impl<'c> Cascade<'c> {
                     keyword => return CascadeError::invalid_keyword(keyword),
                 }
             }
+            (PropertyKey::Direction, ComputedValue::Keyword(keyword)) => {
+                element.font.direction = match keyword.as_str() {
+                    "ltr" => TextDirection::Ltr,
+                    "rtl" => TextDirection::Rtl,
+                    keyword => return CascadeError::invalid_keyword(keyword),
+                }
+            }
+            (PropertyKey::Hyphens, ComputedValue::Keyword(keyword)) => {
+                element.font.hyphens = match keyword.as_str() {
+                    "none" => Hyphens::None,
+                    "manual" => Hyphens::Manual,
+                    "auto" => Hyphens::Auto,
+                    keyword => return CascadeError::invalid_keyword(keyword),
+                }
+            }
+            (PropertyKey::TabSize, ComputedValue::Number(spaces)) => {
+                element.font.tab_size = TabSize::Spaces(spaces.max(0.0))
+            }
+            (PropertyKey::TabSize, value) => {
+                element.font.tab_size = TabSize::Length(resolve_length(value, self, 0.0)?.max(0.0))
+            }
+            (PropertyKey::VerticalAlign, ComputedValue::Keyword(keyword)) => {
+                element.vertical_align = match keyword.as_str() {
+                    "baseline" => VerticalAlign::Baseline,
+                    "top" => VerticalAlign::Top,
+                    "middle" => VerticalAlign::Middle,
+                    "bottom" => VerticalAlign::Bottom,
+                    keyword => return CascadeError::invalid_keyword(keyword),
+                }
+            }
             (PropertyKey::PointerEvents, ComputedValue::Keyword(keyword)) => {
                 element.pointer_events = match keyword.as_str() {
                     "auto" => PointerEvents::Auto,
@@ -87,6 +160,28 @@ impl<'c> Cascade<'c> {
                     keyword => return CascadeError::invalid_keyword(keyword),
                 }
             }
+            (PropertyKey::BumagaMaterial, Keyword(keyword)) => {
+                element.material = match keyword.as_str() {
+                    "none" => None,
+                    name => Some(name.to_string()),
+                }
+            }
+            (PropertyKey::BumagaMaterial, Str(name)) => element.material = Some(name.clone()),
+            (PropertyKey::WillChange, Keyword(keyword)) => {
+                // values applied in arbitrary order, index keeps declaration order
+                if element.will_change.len() <= index {
+                    element.will_change.resize(index + 1, String::new());
+                }
+                element.will_change[index] = keyword.to_string();
+            }
+            (PropertyKey::ZIndex, Keyword(keyword)) => match keyword.as_str() {
+                "auto" => element.z_index = None,
+                keyword => return CascadeError::invalid_keyword(keyword),
+            },
+            (PropertyKey::ZIndex, ComputedValue::Zero) => element.z_index = Some(0),
+            (PropertyKey::ZIndex, ComputedValue::Number(value)) => {
+                element.z_index = Some(value.round() as i32)
+            }
             //
             // Element + Layout
             //
@@ -118,6 +213,18 @@ impl<'c> Cascade<'c> {
             (PropertyKey::BorderLeftColor, value) => {
                 element.borders.left.color = resolve_color(value, self)?;
             }
+            (PropertyKey::BorderTopStyle, value) => {
+                element.borders.top.style = resolve_border_style(value)?;
+            }
+            (PropertyKey::BorderRightStyle, value) => {
+                element.borders.right.style = resolve_border_style(value)?;
+            }
+            (PropertyKey::BorderBottomStyle, value) => {
+                element.borders.bottom.style = resolve_border_style(value)?;
+            }
+            (PropertyKey::BorderLeftStyle, value) => {
+                element.borders.left.style = resolve_border_style(value)?;
+            }
             (PropertyKey::BorderTopLeftRadius, value) => {
                 element.borders.radius[0] = length(value, self)?;
             }
@@ -289,6 +396,22 @@ impl<'c> Cascade<'c> {
     }
 }
 
+fn resolve_border_style(value: &ComputedValue) -> Result<BorderStyle, CascadeError> {
+    let value = match value {
+        ComputedValue::Keyword(keyword) => match keyword.as_str() {
+            "none" => BorderStyle::None,
+            "hidden" => BorderStyle::Hidden,
+            "solid" => BorderStyle::Solid,
+            "dashed" => BorderStyle::Dashed,
+            "dotted" => BorderStyle::Dotted,
+            "double" => BorderStyle::Double,
+            keyword => return Err(CascadeError::InvalidKeyword(keyword.to_string())),
+        },
+        _ => return Err(CascadeError::ValueNotSupported),
+    };
+    Ok(value)
+}
+
 fn resolve_font_weight(value: &ComputedValue, _cascade: &Cascade) -> Result<u16, CascadeError> {
     let value = match value {
         ComputedValue::Number(value) if *value >= 1.0 && *value <= 1000.0 => *value as u16,
@@ -320,6 +443,134 @@ fn resolve_color(value: &ComputedValue, cascade: &Cascade) -> Result<[u8; 4], Ca
     Ok(value)
 }
 
+fn set_shadow(shadows: &mut Vec<Shadow>, index: usize, shadow: Shadow) {
+    if shadows.len() <= index {
+        shadows.resize(index + 1, Shadow::default());
+    }
+    shadows[index] = shadow;
+}
+
+/// Resolves shadow from lengths in order offset x, offset y, blur and spread,
+/// optional color and `inset` keyword in any position.
+fn resolve_shadow(values: &[ComputedValue], cascade: &Cascade) -> Result<Shadow, CascadeError> {
+    let mut shadow = Shadow {
+        color: cascade.sizes.parent_color,
+        ..Shadow::default()
+    };
+    let mut lengths = vec![];
+    for value in values {
+        match value {
+            Keyword(keyword) if keyword == "inset" => shadow.inset = true,
+            ComputedValue::Zero | ComputedValue::Dimension(_) | ComputedValue::Number(_) => {
+                lengths.push(dimension_length(value, cascade)?)
+            }
+            value => shadow.color = resolve_color(value, cascade)?,
+        }
+    }
+    match lengths.as_slice() {
+        [x, y] => shadow.offset = [*x, *y],
+        [x, y, blur] => {
+            shadow.offset = [*x, *y];
+            shadow.blur = blur.max(0.0);
+        }
+        [x, y, blur, spread] => {
+            shadow.offset = [*x, *y];
+            shadow.blur = blur.max(0.0);
+            shadow.spread = *spread;
+        }
+        _ => return Err(CascadeError::ValueNotSupported),
+    }
+    Ok(shadow)
+}
+
+/// Resolves gradient from optional direction as angle or `to` side keywords,
+/// followed by colors with optional percentage offsets.
+fn resolve_gradient(
+    arguments: &[ComputedValue],
+    cascade: &Cascade,
+) -> Result<LinearGradient, CascadeError> {
+    let mut angle = 180.0;
+    let mut arguments = arguments;
+    match arguments {
+        [ComputedValue::Dimension(Dim {
+            value,
+            unit: Units::Deg,
+        }), rest @ ..] => {
+            angle = *value;
+            arguments = rest;
+        }
+        [Keyword(to), rest @ ..] if to == "to" => {
+            let sides = rest
+                .iter()
+                .take_while(|value| match value {
+                    Keyword(side) => matches!(side.as_str(), "top" | "right" | "bottom" | "left"),
+                    _ => false,
+                })
+                .count();
+            let (mut x, mut y) = (0.0, 0.0);
+            for side in &rest[..sides] {
+                if let Keyword(side) = side {
+                    match side.as_str() {
+                        "top" => y = -1.0,
+                        "bottom" => y = 1.0,
+                        "left" => x = -1.0,
+                        _ => x = 1.0,
+                    }
+                }
+            }
+            if sides == 0 {
+                return Err(CascadeError::ValueNotSupported);
+            }
+            angle = f32::atan2(x, -y).to_degrees();
+            arguments = &rest[sides..];
+        }
+        _ => {}
+    }
+    let mut stops: Vec<(Rgba, Option<f32>)> = vec![];
+    for value in arguments {
+        match value {
+            ComputedValue::Percentage(offset) => match stops.last_mut() {
+                Some((_, last @ None)) => *last = Some(*offset),
+                _ => return Err(CascadeError::ValueNotSupported),
+            },
+            value => stops.push((resolve_color(value, cascade)?, None)),
+        }
+    }
+    if stops.len() < 2 {
+        return Err(CascadeError::ValueNotSupported);
+    }
+    // colors without offsets are spaced evenly between neighbours
+    let last = stops.len() - 1;
+    let mut offsets: Vec<Option<f32>> = stops.iter().map(|(_, offset)| *offset).collect();
+    offsets[0] = Some(offsets[0].unwrap_or(0.0));
+    offsets[last] = Some(offsets[last].unwrap_or(1.0));
+    let mut start = 0;
+    for index in 1..=last {
+        if let Some(end) = offsets[index] {
+            let from = offsets[start].unwrap_or(0.0);
+            let step = (end - from) / (index - start) as f32;
+            for (n, offset) in offsets[start + 1..index].iter_mut().enumerate() {
+                *offset = Some(from + step * (n + 1) as f32);
+            }
+            start = index;
+        }
+    }
+    let mut previous = 0.0;
+    let stops = stops
+        .iter()
+        .zip(offsets)
+        .map(|((color, _), offset)| {
+            // offsets never go back along gradient line
+            previous = offset.unwrap_or(previous).max(previous);
+            ColorStop {
+                color: *color,
+                offset: previous,
+            }
+        })
+        .collect();
+    Ok(LinearGradient { angle, stops })
+}
+
 fn resolve_timing(
     value: &ComputedValue,
     _cascade: &Cascade,
@@ -462,6 +713,7 @@ fn parse_dimension_length(dimension: &Dim, cascade: &Cascade) -> Result<f32, Cas
         Units::Vh => sizes.viewport_height * value / 100.0,
         Units::Vmax => sizes.viewport_width.max(sizes.viewport_height) * value / 100.0,
         Units::Vmin => sizes.viewport_width.min(sizes.viewport_height) * value / 100.0,
+        Units::Deg => return Err(CascadeError::ValueNotSupported),
     };
     Ok(value)
 }
@@ -565,3 +817,83 @@ fn map_align_content(keyword: &str) -> Result<Option<taffy::AlignContent>, Casca
     };
     Ok(Some(align))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css::read_css;
+    use crate::styles::{Sizes, Variables};
+
+    fn resolve<T>(resolve: impl Fn(&Cascade) -> Result<T, CascadeError>) -> T {
+        let css = read_css("").expect("CSS valid");
+        let sizes = Sizes {
+            root_font_size: 16.0,
+            parent_font_size: 10.0,
+            parent_color: [1, 2, 3, 255],
+            viewport_width: 100.0,
+            viewport_height: 100.0,
+        };
+        let cascade = Cascade::new(&css, sizes, Variables::new());
+        resolve(&cascade).expect("value resolved")
+    }
+
+    fn px(value: f32) -> ComputedValue {
+        ComputedValue::Dimension(Dim::new(value, Units::Px))
+    }
+
+    fn em(value: f32) -> ComputedValue {
+        ComputedValue::Dimension(Dim::new(value, Units::Em))
+    }
+
+    fn kw(value: &str) -> ComputedValue {
+        Keyword(value.into())
+    }
+
+    #[test]
+    pub fn test_shadow_lengths_color_and_inset() {
+        let values = [kw("inset"), px(1.0), px(2.0), px(3.0), px(4.0), kw("red")];
+        let shadow = resolve(|cascade| resolve_shadow(&values, cascade));
+        let expected = Shadow {
+            offset: [1.0, 2.0],
+            blur: 3.0,
+            spread: 4.0,
+            color: [255, 0, 0, 255],
+            inset: true,
+        };
+        assert_eq!(shadow, expected);
+    }
+
+    #[test]
+    pub fn test_shadow_of_current_color() {
+        let values = [ComputedValue::Zero, em(1.0)];
+        let shadow = resolve(|cascade| resolve_shadow(&values, cascade));
+        assert_eq!(shadow.offset, [0.0, 10.0]);
+        assert_eq!(shadow.color, [1, 2, 3, 255]);
+    }
+
+    #[test]
+    pub fn test_gradient_to_side() {
+        let values = [kw("to"), kw("bottom"), kw("right"), kw("red"), kw("blue")];
+        let gradient = resolve(|cascade| resolve_gradient(&values, cascade));
+        assert_eq!(gradient.angle, 135.0);
+        let offsets: Vec<f32> = gradient.stops.iter().map(|stop| stop.offset).collect();
+        assert_eq!(offsets, [0.0, 1.0]);
+    }
+
+    #[test]
+    pub fn test_gradient_stops_spaced_evenly() {
+        let values = [
+            ComputedValue::Dimension(Dim::new(90.0, Units::Deg)),
+            kw("red"),
+            kw("green"),
+            kw("blue"),
+            ComputedValue::Percentage(0.8),
+            kw("black"),
+            ComputedValue::Percentage(0.5),
+        ];
+        let gradient = resolve(|cascade| resolve_gradient(&values, cascade));
+        assert_eq!(gradient.angle, 90.0);
+        let offsets: Vec<f32> = gradient.stops.iter().map(|stop| stop.offset).collect();
+        assert_eq!(offsets, [0.0, 0.4, 0.8, 0.8], "offsets never go back");
+    }
+}
@@ -1,4 +1,4 @@
-use crate::css::ComputedValue::{Color, Number, Str};
+use crate::css::ComputedValue::{Color, Gradient, Number, Str};
 use crate::css::{ComputedValue, Function};
 use crate::styles::Cascade;
 use log::error;
@@ -17,6 +17,7 @@ impl<'c> Cascade<'c> {
             }
             // ("url", [Str(path)]) => Str(format!("{}/{}", self.resources, path)),
             ("url", [Str(path)]) => Str(path.to_string()),
+            ("linear-gradient", arguments) if arguments.len() > 1 => Gradient(arguments.to_vec()),
             _ => {
                 error!("unable to compute function {name}({arguments:?}), not supported");
                 ComputedValue::Error
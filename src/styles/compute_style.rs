@@ -2,6 +2,7 @@ use crate::css::ComputedValue::{Keyword, Time};
 use crate::css::{ComputedStyle, ComputedValue, Definition, PropertyDescriptor, PropertyKey};
 use crate::styles::Cascade;
 use log::error;
+use smol_str::SmolStr;
 
 impl<'c> Cascade<'c> {
     pub(crate) fn compute_style(
@@ -23,9 +24,25 @@ impl<'c> Cascade<'c> {
             // Element
             //
             (PropertyKey::Background, [color]) => {
-                overwrite(PropertyKey::BackgroundImage, &Keyword("none".to_string()));
+                overwrite(
+                    PropertyKey::BackgroundImage,
+                    &Keyword(SmolStr::new_static("none")),
+                );
                 overwrite(PropertyKey::BackgroundColor, color);
             }
+            (PropertyKey::BumagaTextStroke, [Keyword(keyword)]) if keyword == "none" => {
+                overwrite(PropertyKey::BumagaTextStrokeWidth, &ComputedValue::Zero);
+            }
+            (PropertyKey::BumagaTextStroke, [width]) => {
+                overwrite(PropertyKey::BumagaTextStrokeWidth, width);
+            }
+            (PropertyKey::BumagaTextStroke, [width, color]) => {
+                overwrite(PropertyKey::BumagaTextStrokeWidth, width);
+                overwrite(PropertyKey::BumagaTextStrokeColor, color);
+            }
+            (PropertyKey::BoxShadow | PropertyKey::TextShadow, values) if values.len() > 1 => {
+                overwrite(key, &ComputedValue::Shadow(values.to_vec()));
+            }
             (PropertyKey::BackgroundPosition, [value]) => {
                 overwrite(PropertyKey::BackgroundPositionX, value);
                 overwrite(PropertyKey::BackgroundPositionY, value);
@@ -40,7 +57,12 @@ impl<'c> Cascade<'c> {
             (PropertyKey::Border, [Keyword(keyword)]) => match keyword.as_str() {
                 "none" => {
                     let width = &ComputedValue::Zero;
-                    let color = &ComputedValue::Keyword("currentcolor".to_string());
+                    let color = &ComputedValue::Keyword(SmolStr::new_static("currentcolor"));
+                    let style = &ComputedValue::Keyword(SmolStr::new_static("none"));
+                    overwrite(PropertyKey::BorderTopStyle, style);
+                    overwrite(PropertyKey::BorderRightStyle, style);
+                    overwrite(PropertyKey::BorderBottomStyle, style);
+                    overwrite(PropertyKey::BorderLeftStyle, style);
                     overwrite(PropertyKey::BorderTopWidth, width);
                     overwrite(PropertyKey::BorderTopColor, color);
                     overwrite(PropertyKey::BorderRightWidth, width);
@@ -54,7 +76,11 @@ impl<'c> Cascade<'c> {
                     error!("unable to compute styles, property {key:?} keyword {value:?} not supported");
                 }
             },
-            (PropertyKey::Border, [width, _style, color]) => {
+            (PropertyKey::Border, [width, style, color]) => {
+                overwrite(PropertyKey::BorderTopStyle, style);
+                overwrite(PropertyKey::BorderRightStyle, style);
+                overwrite(PropertyKey::BorderBottomStyle, style);
+                overwrite(PropertyKey::BorderLeftStyle, style);
                 overwrite(PropertyKey::BorderTopWidth, width);
                 overwrite(PropertyKey::BorderTopColor, color);
                 overwrite(PropertyKey::BorderRightWidth, width);
@@ -64,20 +90,24 @@ impl<'c> Cascade<'c> {
                 overwrite(PropertyKey::BorderLeftWidth, width);
                 overwrite(PropertyKey::BorderLeftColor, color);
             }
-            (PropertyKey::BorderTop, [width, _style, color]) => {
+            (PropertyKey::BorderTop, [width, style, color]) => {
                 overwrite(PropertyKey::BorderTopWidth, width);
+                overwrite(PropertyKey::BorderTopStyle, style);
                 overwrite(PropertyKey::BorderTopColor, color);
             }
-            (PropertyKey::BorderRight, [width, _style, color]) => {
+            (PropertyKey::BorderRight, [width, style, color]) => {
                 overwrite(PropertyKey::BorderRightWidth, width);
+                overwrite(PropertyKey::BorderRightStyle, style);
                 overwrite(PropertyKey::BorderRightColor, color);
             }
-            (PropertyKey::BorderBottom, [width, _style, color]) => {
+            (PropertyKey::BorderBottom, [width, style, color]) => {
                 overwrite(PropertyKey::BorderBottomWidth, width);
+                overwrite(PropertyKey::BorderBottomStyle, style);
                 overwrite(PropertyKey::BorderBottomColor, color);
             }
-            (PropertyKey::BorderLeft, [width, _style, color]) => {
+            (PropertyKey::BorderLeft, [width, style, color]) => {
                 overwrite(PropertyKey::BorderLeftWidth, width);
+                overwrite(PropertyKey::BorderLeftStyle, style);
                 overwrite(PropertyKey::BorderLeftColor, color);
             }
             (PropertyKey::BorderWidth, [top, right, bottom, left]) => {
@@ -128,6 +158,30 @@ impl<'c> Cascade<'c> {
                 overwrite(PropertyKey::BorderBottomColor, value);
                 overwrite(PropertyKey::BorderLeftColor, value);
             }
+            (PropertyKey::BorderStyle, [top, right, bottom, left]) => {
+                overwrite(PropertyKey::BorderTopStyle, top);
+                overwrite(PropertyKey::BorderRightStyle, right);
+                overwrite(PropertyKey::BorderBottomStyle, bottom);
+                overwrite(PropertyKey::BorderLeftStyle, left);
+            }
+            (PropertyKey::BorderStyle, [top, h, bottom]) => {
+                overwrite(PropertyKey::BorderTopStyle, top);
+                overwrite(PropertyKey::BorderRightStyle, h);
+                overwrite(PropertyKey::BorderBottomStyle, bottom);
+                overwrite(PropertyKey::BorderLeftStyle, h);
+            }
+            (PropertyKey::BorderStyle, [v, h]) => {
+                overwrite(PropertyKey::BorderTopStyle, v);
+                overwrite(PropertyKey::BorderRightStyle, h);
+                overwrite(PropertyKey::BorderBottomStyle, v);
+                overwrite(PropertyKey::BorderLeftStyle, h);
+            }
+            (PropertyKey::BorderStyle, [value]) => {
+                overwrite(PropertyKey::BorderTopStyle, value);
+                overwrite(PropertyKey::BorderRightStyle, value);
+                overwrite(PropertyKey::BorderBottomStyle, value);
+                overwrite(PropertyKey::BorderLeftStyle, value);
+            }
             (PropertyKey::BorderRadius, [a, b, c, d]) => {
                 overwrite(PropertyKey::BorderTopLeftRadius, a);
                 overwrite(PropertyKey::BorderTopRightRadius, b);
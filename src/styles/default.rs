@@ -1,4 +1,8 @@
-use crate::{Borders, Element, FontFace, Length, ObjectFit, TextAlign};
+use crate::{
+    Borders, Element, FontFace, Hyphens, Length, ObjectFit, TabSize, TextAlign, TextDirection,
+    TextStroke, VerticalAlign,
+};
+use smol_str::SmolStr;
 use taffy::{Dimension, NodeId, Overflow, Point, Rect};
 
 impl FontFace {
@@ -9,6 +13,7 @@ impl FontFace {
 
 pub(crate) fn reset_element_style(element: &mut Element) {
     element.backgrounds = vec![];
+    element.shadows = vec![];
     element.borders = Borders {
         top: Default::default(),
         bottom: Default::default(),
@@ -18,31 +23,56 @@ pub(crate) fn reset_element_style(element: &mut Element) {
     };
     element.color = [0, 0, 0, 255];
     element.font = FontFace {
-        family: FontFace::DEFAULT_FONT_FAMILY.to_string(),
+        family: SmolStr::new_static(FontFace::DEFAULT_FONT_FAMILY),
         size: 16.0,
-        style: "normal".to_string(),
+        style: SmolStr::new_static("normal"),
         weight: FontFace::DEFAULT_FONT_WEIGHT,
         // font_stretch: TextStyle::DEFAULT_FONT_STRETCH,
         line_height: 1.0,
         // wrap: OverflowWrap::Normal,
         align: TextAlign::Start,
+        direction: TextDirection::Ltr,
+        stroke: TextStroke::default(),
+        hyphens: Hyphens::Manual,
+        lang: String::new(),
+        tab_size: TabSize::Spaces(8.0),
     };
     element.self_opacity = 1.0;
+    element.z_index = None;
+    element.will_change = vec![];
+    element.material = None;
+    element.vertical_align = VerticalAlign::Baseline;
+}
+
+/// Applies default styles of phrasing elements over inherited ones, like user agent
+/// stylesheet of browsers, so rich text is formatted without CSS.
+pub(crate) fn apply_tag_style(element: &mut Element) {
+    match element.tag.as_str() {
+        "b" | "strong" => element.font.weight = 700,
+        "i" | "em" => element.font.style = SmolStr::new_static("italic"),
+        "code" => element.font.family = SmolStr::new_static("monospace"),
+        _ => {}
+    }
 }
 
 pub fn create_element(node: NodeId) -> Element {
     Element {
         node,
         children: vec![],
-        tag: "".to_string(),
+        tag: SmolStr::default(),
         text: None,
+        lines: vec![],
+        styled: vec![],
+        fragments: vec![],
         attrs: Default::default(),
         attrs_bindings: Default::default(),
+        model_bindings: Default::default(),
         position: [0.0; 2],
         size: [0.0; 2],
         content_size: [0.0; 2],
         object_fit: ObjectFit::Fill,
         backgrounds: vec![],
+        shadows: vec![],
         borders: Borders {
             top: Default::default(),
             bottom: Default::default(),
@@ -50,17 +80,24 @@ pub fn create_element(node: NodeId) -> Element {
             left: Default::default(),
             radius: [Length::zero(); 4],
         },
+        radius: [0.0; 4],
         color: [0, 0, 0, 255],
         font: FontFace {
-            family: FontFace::DEFAULT_FONT_FAMILY.to_string(),
+            family: SmolStr::new_static(FontFace::DEFAULT_FONT_FAMILY),
             size: 16.0,
-            style: "normal".to_string(),
+            style: SmolStr::new_static("normal"),
             weight: FontFace::DEFAULT_FONT_WEIGHT,
             // font_stretch: TextStyle::DEFAULT_FONT_STRETCH,
             line_height: 1.0,
             // wrap: OverflowWrap::Normal,
             align: TextAlign::Start,
+            direction: TextDirection::Ltr,
+            stroke: TextStroke::default(),
+            hyphens: Hyphens::Manual,
+            lang: String::new(),
+            tab_size: TabSize::Spaces(8.0),
         },
+        text_shadows: vec![],
         listeners: Default::default(),
         self_opacity: 1.0,
         opacity: 1.0,
@@ -71,9 +108,19 @@ pub fn create_element(node: NodeId) -> Element {
         transitions: vec![],
         state: Default::default(),
         pointer_events: Default::default(),
+        z_index: None,
+        will_change: vec![],
+        layer: false,
+        material: None,
+        inline: false,
+        inline_display: taffy::Display::Block,
+        vertical_align: VerticalAlign::Baseline,
         style_hints: Default::default(),
         styles: vec![],
         style: vec![],
+        style_key: None,
+        variables: Default::default(),
+        pattern: None,
     }
 }
 
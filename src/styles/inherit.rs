@@ -9,6 +9,7 @@ pub fn inherit(parent: &Element, element: &mut Element) {
 
     // cursor
     // direction
+    element.font.direction = parent.font.direction;
     // empty-cells
     // font-family
     element.font.family = parent.font.family.clone();
@@ -20,6 +21,9 @@ pub fn inherit(parent: &Element, element: &mut Element) {
     // font-weight
     element.font.weight = parent.font.weight;
     // font-size-adjust
+    // hyphens
+    element.font.hyphens = parent.font.hyphens;
+    element.font.lang = parent.font.lang.clone();
     // font-stretch
     //view.text_style.font_stretch = parent.text_style.font_stretch.clone();
     // font
@@ -33,12 +37,17 @@ pub fn inherit(parent: &Element, element: &mut Element) {
     // orphans
     // quotes
     // tab-size
+    element.font.tab_size = parent.font.tab_size;
     // text-align
+    element.font.align = parent.font.align.clone();
     // text-align-last
     // text-decoration-color
     // text-indent
     // text-justify
     // text-shadow
+    element.text_shadows = parent.text_shadows.clone();
+    // -bumaga-text-stroke
+    element.font.stroke = parent.font.stroke;
     // text-transform
     // visibility
     // white-space
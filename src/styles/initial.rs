@@ -311,6 +311,11 @@ pub fn initial(key: PropertyKey) -> ComputedValue {
         PropertyKey::WritingMode => keyword("horizontal-tb"),
         PropertyKey::ZIndex => keyword("auto"),
         PropertyKey::Zoom => keyword("normal"),
+        PropertyKey::BumagaBackgroundFrames => n(1),
+        PropertyKey::BumagaBackgroundDuration => s(0),
+        PropertyKey::BumagaTextStrokeColor => keyword("currentcolor"),
+        PropertyKey::BumagaTextStrokeWidth => zero(),
+        PropertyKey::BumagaMaterial => keyword("none"),
         _ => {
             error!("there is no initial value for {key:?}");
             keyword("initial")
@@ -338,7 +343,7 @@ fn color(r: u8, g: u8, b: u8, a: u8) -> ComputedValue {
 
 #[inline(always)]
 fn keyword(name: &str) -> ComputedValue {
-    ComputedValue::Keyword(name.to_string())
+    ComputedValue::Keyword(name.into())
 }
 
 #[inline(always)]
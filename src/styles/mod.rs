@@ -11,19 +11,47 @@ mod stats;
 pub use default::*;
 pub use inherit::inherit;
 pub use scrolling::*;
+pub use stats::CascadeStats;
 
 use log::error;
 use std::collections::HashMap;
 use taffy::{NodeId, TaffyTree};
 
 use crate::css::{
-    match_style, ComputedStyle, ComputedValue, Css, Declaration, Definition, PseudoClassMatcher,
-    Shorthand, Variable,
+    match_style, ComputedStyle, ComputedValue, Css, Declaration, Definition, PropertyDescriptor,
+    PropertyKey, PseudoClassMatcher, Shorthand, Variable,
 };
 
-use crate::styles::stats::CascadeStats;
 use crate::{Element, ElementStyle, Input};
 
+/// Returns whether each style of element matches it, dynamic styles are matched
+/// by current attributes and state of element in tree.
+pub fn match_styles(
+    node: NodeId,
+    tree: &TaffyTree<Element>,
+    matcher: &impl PseudoClassMatcher,
+    stats: &mut CascadeStats,
+) -> Vec<bool> {
+    let element = match tree.get_node_context(node) {
+        Some(element) => element,
+        None => return vec![],
+    };
+    element
+        .styles
+        .iter()
+        .map(|style| match style {
+            ElementStyle::Static(_) => {
+                stats.matches_static += 1;
+                true
+            }
+            ElementStyle::Dynamic(style) => {
+                stats.matches_dynamic += 1;
+                match_style(style, node, tree, matcher)
+            }
+        })
+        .collect()
+}
+
 /// The cascade is an algorithm that defines how to combine CSS (Cascading Style Sheets)
 /// property values originating from different sources.
 pub struct Cascade<'c> {
@@ -62,31 +90,27 @@ impl<'c> Cascade<'c> {
     pub fn apply_styles(
         &mut self,
         input: &Input,
-        node: NodeId,
-        tree: &TaffyTree<Element>,
         parent: &Element,
         layout: &mut taffy::Style,
         element: &mut Element,
-        matcher: &impl PseudoClassMatcher,
+        matches: &[bool],
     ) {
         // -1: initial
         reset_element_style(element);
         // 0: inheritance
         inherit::inherit(parent, element);
+        if let Some(lang) = element.attrs.get("lang") {
+            element.font.lang = lang.clone();
+        }
+        apply_tag_style(element);
         // 1: css rules
         let mut computed_style = HashMap::new();
-        for style in element.styles.iter() {
-            match style {
-                ElementStyle::Static(style) => {
-                    self.stats.matches_static += 1;
-                    self.compute_declaration_block(&style.declaration, &mut computed_style);
-                }
-                ElementStyle::Dynamic(style) => {
-                    self.stats.matches_dynamic += 1;
-                    if match_style(&style, node, tree, matcher) {
-                        self.compute_declaration_block(&style.declaration, &mut computed_style);
-                    }
-                }
+        for (style, matched) in element.styles.iter().zip(matches) {
+            let style = match style {
+                ElementStyle::Static(style) | ElementStyle::Dynamic(style) => style,
+            };
+            if *matched {
+                self.compute_declaration_block(&style.declaration, &mut computed_style);
             }
         }
         // 2: inline css
@@ -113,6 +137,12 @@ impl<'c> Cascade<'c> {
         for transition in element.transitions.iter_mut() {
             transition.play(time, &mut computed_style);
         }
+        if computed_style
+            .keys()
+            .any(|property| property.key == PropertyKey::TextShadow)
+        {
+            element.text_shadows.clear();
+        }
         for (property, value) in &computed_style {
             if let Err(error) = self.apply(property.key, property.index, &value, layout, element) {
                 error!("unable to apply {property:?}:{value:?} because of {error:?}");
@@ -124,6 +154,21 @@ impl<'c> Cascade<'c> {
         for transition in element.transitions.iter_mut() {
             transition.init_after_style_applied(&mut computed_style);
         }
+        if element.material.is_none() {
+            element.material = element.attrs.get("data-material").cloned();
+        }
+        for background in element.backgrounds.iter_mut() {
+            background.frame = if background.is_animated() {
+                let frames = background.frames as f32;
+                (time / background.duration * frames) as u32 % background.frames
+            } else {
+                0
+            };
+        }
+        element
+            .will_change
+            .retain(|property| !property.is_empty() && property != "auto");
+        element.layer = !element.will_change.is_empty() || element.is_animated();
     }
 
     pub fn take_variables(self) -> HashMap<String, Shorthand> {
@@ -135,6 +180,14 @@ impl<'c> Cascade<'c> {
             match declaration {
                 Declaration::Variable(variable) => self.set_variable(variable),
                 Declaration::Property(property) => {
+                    // values of list property replace all values of previous declaration
+                    let mut index = property.values.len();
+                    while style
+                        .remove(&PropertyDescriptor::new(property.key, index))
+                        .is_some()
+                    {
+                        index += 1;
+                    }
                     for index in 0..property.values.len() {
                         self.compute_style(property.key, index, &property.values[index], style);
                     }
@@ -6,6 +6,8 @@ pub struct Scrolling {
     pub y: f32,
     pub scroll_x: f32,
     pub scroll_y: f32,
+    /// The kinetic scrolling velocity in pixels per second.
+    pub velocity: [f32; 2],
 }
 
 impl Scrolling {
@@ -16,6 +18,10 @@ impl Scrolling {
             .as_ref()
             .map(|current| [current.x, current.y])
             .unwrap_or_default();
+        let velocity = current
+            .as_ref()
+            .map(|current| current.velocity)
+            .unwrap_or_default();
         if content.width > size.width || content.height > size.height {
             let scroll_x = content.width - size.width;
             let scroll_y = content.height - size.height;
@@ -24,6 +30,7 @@ impl Scrolling {
                 y: y.min(scroll_y),
                 scroll_x,
                 scroll_y,
+                velocity,
             };
             Some(scrolling)
         } else {
@@ -31,15 +38,29 @@ impl Scrolling {
         }
     }
 
+    /// Scrolls by one wheel notch of 50 pixels in direction of wheel delta,
+    /// positive y scrolls up, see [`wheel_distance`] for precise scrolling.
     pub fn offset(&mut self, wheel: [f32; 2]) {
-        let [x, y] = wheel;
-        if x != 0.0 {
-            self.x += x.signum() * 50.0;
-            self.x = self.x.min(self.scroll_x).max(0.0);
-        }
-        if y != 0.0 {
-            self.y -= y.signum() * 50.0;
-            self.y = self.y.min(self.scroll_y).max(0.0);
-        }
+        let notches = wheel.map(|delta| if delta == 0.0 { 0.0 } else { delta.signum() });
+        self.scroll_by(wheel_distance(notches, 50.0));
+    }
+
+    /// Checks element can be scrolled in direction of distance.
+    pub fn can_scroll(&self, distance: [f32; 2]) -> bool {
+        (distance[0] != 0.0 && self.scroll_x > 0.0) || (distance[1] != 0.0 && self.scroll_y > 0.0)
+    }
+
+    /// Scrolls by distance in pixels within limits, returns true if position changed.
+    pub fn scroll_by(&mut self, distance: [f32; 2]) -> bool {
+        let [x, y] = [self.x, self.y];
+        self.x = (self.x + distance[0]).min(self.scroll_x).max(0.0);
+        self.y = (self.y + distance[1]).min(self.scroll_y).max(0.0);
+        self.x != x || self.y != y
     }
 }
+
+/// Returns scroll distance in pixels of wheel delta.
+pub fn wheel_distance(wheel: [f32; 2], sensitivity: f32) -> [f32; 2] {
+    let [x, y] = wheel;
+    [x * sensitivity, -y * sensitivity]
+}
@@ -0,0 +1,264 @@
+use crate::fonts::{StyledText, TextFragment};
+use crate::{FontFace, Hyphens, TabSize, TextAlign, TextDirection, TextLine, VerticalAlign};
+use smol_str::SmolStr;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+/// The lines of plain texts or fragments of styled paragraphs laid out by fonts, so
+/// static texts are not measured again on every layout and final positioning, least
+/// recently used layouts are evicted.
+pub(crate) struct TextCache<V = Vec<TextLine>> {
+    capacity: usize,
+    tick: u64,
+    /// The layouts of texts and tick of their last use.
+    entries: HashMap<Rc<TextKey>, (V, u64)>,
+    /// The keys in order of use, key is outdated if entry used again since.
+    usage: VecDeque<(Rc<TextKey>, u64)>,
+}
+
+/// The text or styled texts of paragraph and properties of font faces affecting
+/// their layout.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct TextKey {
+    text: String,
+    face: FaceKey,
+    spans: Vec<(String, FaceKey, VerticalAlign)>,
+    max_width: Option<u32>,
+}
+
+/// The properties of font face affecting text layout.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct FaceKey {
+    family: SmolStr,
+    style: SmolStr,
+    weight: u16,
+    size: u32,
+    line_height: u32,
+    tab_size: (bool, u32),
+    hyphens: Hyphens,
+    align: TextAlign,
+    direction: TextDirection,
+}
+
+impl TextKey {
+    fn new(text: &str, face: &FontFace, max_width: Option<f32>) -> Self {
+        Self {
+            text: text.to_string(),
+            face: FaceKey::new(face),
+            spans: vec![],
+            max_width: max_width.map(f32::to_bits),
+        }
+    }
+
+    fn styled(spans: &[StyledText], face: &FontFace, max_width: Option<f32>) -> Self {
+        Self {
+            text: String::new(),
+            face: FaceKey::new(face),
+            spans: spans
+                .iter()
+                .map(|span| (span.text.clone(), FaceKey::new(&span.font), span.align))
+                .collect(),
+            max_width: max_width.map(f32::to_bits),
+        }
+    }
+}
+
+impl FaceKey {
+    fn new(face: &FontFace) -> Self {
+        Self {
+            family: face.family.clone(),
+            style: face.style.clone(),
+            weight: face.weight,
+            size: face.size.to_bits(),
+            line_height: face.line_height.to_bits(),
+            tab_size: match face.tab_size {
+                TabSize::Spaces(spaces) => (false, spaces.to_bits()),
+                TabSize::Length(length) => (true, length.to_bits()),
+            },
+            hyphens: face.hyphens,
+            align: face.align.clone(),
+            direction: face.direction,
+        }
+    }
+}
+
+impl<V: Clone> TextCache<V> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            usage: VecDeque::new(),
+        }
+    }
+
+    /// Returns cached layout of key or lays text out and caches layout.
+    fn get_or_insert(&mut self, key: TextKey, layout: impl FnOnce() -> V) -> V {
+        self.tick += 1;
+        if let Some((key, (value, _))) = self.entries.get_key_value(&key) {
+            let (key, value) = (key.clone(), value.clone());
+            if let Some((_, used)) = self.entries.get_mut(&key) {
+                *used = self.tick;
+            }
+            self.usage.push_back((key, self.tick));
+            self.compact();
+            return value;
+        }
+        let value = layout();
+        self.insert(key, value.clone());
+        value
+    }
+
+    fn insert(&mut self, key: TextKey, value: V) {
+        while self.entries.len() >= self.capacity {
+            let Some((oldest, used)) = self.usage.pop_front() else {
+                break;
+            };
+            if self.entries.get(&oldest).map(|(_, last)| *last) == Some(used) {
+                self.entries.remove(&oldest);
+            }
+        }
+        let key = Rc::new(key);
+        self.usage.push_back((key.clone(), self.tick));
+        self.entries.insert(key, (value, self.tick));
+        self.compact();
+    }
+
+    /// Removes outdated keys from usage order, so it holds at most twice as many
+    /// keys as entries.
+    fn compact(&mut self) {
+        if self.usage.len() > 2 * self.capacity.max(self.entries.len()) {
+            let entries = &self.entries;
+            self.usage
+                .retain(|(key, used)| entries.get(key).map(|(_, last)| last) == Some(used));
+        }
+    }
+
+    /// Removes all layouts, e.g. if fonts changed.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.usage.clear();
+    }
+}
+
+impl TextCache<Vec<TextLine>> {
+    /// Returns cached lines of text or lays text out and caches lines.
+    pub(crate) fn get_or_layout(
+        &mut self,
+        text: &str,
+        face: &FontFace,
+        max_width: Option<f32>,
+        layout: impl FnOnce() -> Vec<TextLine>,
+    ) -> Vec<TextLine> {
+        self.get_or_insert(TextKey::new(text, face, max_width), layout)
+    }
+
+    /// Lays out texts missing in cache at their max widths by many threads and
+    /// caches lines, the same texts are laid out once.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn layout_parallel<F>(
+        &mut self,
+        texts: Vec<(String, FontFace, Option<f32>)>,
+        layout: F,
+    ) where
+        F: Fn(&str, &FontFace, Option<f32>) -> Vec<TextLine> + Sync,
+    {
+        use rayon::prelude::*;
+        let mut missing = HashMap::new();
+        for (text, face, max_width) in texts {
+            let key = TextKey::new(&text, &face, max_width);
+            if !self.entries.contains_key(&key) {
+                missing.insert(key, (face, max_width));
+            }
+        }
+        let laid_out: Vec<(TextKey, Vec<TextLine>)> = missing
+            .into_par_iter()
+            .map(|(key, (face, max_width))| {
+                let lines = layout(&key.text, &face, max_width);
+                (key, lines)
+            })
+            .collect();
+        for (key, lines) in laid_out {
+            self.tick += 1;
+            self.insert(key, lines);
+        }
+    }
+}
+
+impl TextCache<Vec<TextFragment>> {
+    /// Returns cached fragments of styled texts or breaks them into lines and caches
+    /// fragments.
+    pub(crate) fn get_or_break(
+        &mut self,
+        spans: &[StyledText],
+        face: &FontFace,
+        max_width: Option<f32>,
+        layout: impl FnOnce() -> Vec<TextFragment>,
+    ) -> Vec<TextFragment> {
+        self.get_or_insert(TextKey::styled(spans, face, max_width), layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TextAlign, TextStroke};
+
+    fn face(size: f32) -> FontFace {
+        FontFace {
+            family: "system-ui".into(),
+            size,
+            style: "normal".into(),
+            weight: 400,
+            line_height: 1.0,
+            align: TextAlign::Start,
+            direction: Default::default(),
+            stroke: TextStroke::default(),
+            hyphens: Hyphens::Manual,
+            lang: String::new(),
+            tab_size: TabSize::Spaces(8.0),
+        }
+    }
+
+    fn measured(cache: &mut TextCache, text: &str, face: &FontFace) -> bool {
+        let mut measured = false;
+        cache.get_or_layout(text, face, None, || {
+            measured = true;
+            vec![]
+        });
+        measured
+    }
+
+    #[test]
+    pub fn test_text_measured_once() {
+        let mut cache = TextCache::new(8);
+        assert!(measured(&mut cache, "Hello", &face(16.0)));
+        assert!(!measured(&mut cache, "Hello", &face(16.0)));
+        assert!(measured(&mut cache, "Hello", &face(20.0)));
+    }
+
+    #[test]
+    pub fn test_least_recently_used_evicted() {
+        let mut cache = TextCache::new(2);
+        measured(&mut cache, "a", &face(16.0));
+        measured(&mut cache, "b", &face(16.0));
+        measured(&mut cache, "a", &face(16.0));
+        measured(&mut cache, "c", &face(16.0));
+        assert!(!measured(&mut cache, "a", &face(16.0)));
+        assert!(measured(&mut cache, "b", &face(16.0)));
+    }
+
+    #[test]
+    pub fn test_usage_order_compacted() {
+        let mut cache = TextCache::new(2);
+        for _ in 0..100 {
+            measured(&mut cache, "a", &face(16.0));
+            measured(&mut cache, "b", &face(16.0));
+        }
+        assert!(cache.usage.len() <= 4);
+        measured(&mut cache, "a", &face(16.0));
+        measured(&mut cache, "c", &face(16.0));
+        assert!(!measured(&mut cache, "a", &face(16.0)));
+        assert!(measured(&mut cache, "b", &face(16.0)));
+    }
+}
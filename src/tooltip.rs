@@ -0,0 +1,78 @@
+use crate::tree::ViewTreeExtensions;
+use crate::{Element, Input, ViewError, ViewModel};
+use taffy::{NodeId, TaffyTree};
+
+/// Advisory text of hovered element specified by title or data-tooltip attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tooltip {
+    pub text: String,
+    /// The position of anchor element.
+    pub position: [f32; 2],
+    /// The size of anchor element.
+    pub size: [f32; 2],
+}
+
+#[derive(Debug)]
+pub(crate) struct TooltipTimer {
+    /// The time in seconds cursor must rest on element before tooltip appears.
+    pub(crate) delay: f32,
+    anchor: Option<NodeId>,
+    elapsed: f32,
+}
+
+impl Default for TooltipTimer {
+    fn default() -> Self {
+        Self {
+            delay: 0.5,
+            anchor: None,
+            elapsed: 0.0,
+        }
+    }
+}
+
+impl ViewModel {
+    pub(crate) fn update_tooltip(
+        &mut self,
+        input: &Input,
+        tree: &TaffyTree<Element>,
+    ) -> Result<Option<Tooltip>, ViewError> {
+        let mut anchor = None;
+        for node in self.elements_under_mouse.iter().rev() {
+            let element = tree.get_element(*node)?;
+            if let Some(text) = tooltip_text(element) {
+                anchor = Some((*node, text, element));
+                break;
+            }
+        }
+        let (node, text, element) = match anchor {
+            Some(anchor) if self.elements_in_action.is_empty() => anchor,
+            _ => {
+                self.tooltip.anchor = None;
+                self.tooltip.elapsed = 0.0;
+                return Ok(None);
+            }
+        };
+        if self.tooltip.anchor != Some(node) {
+            self.tooltip.anchor = Some(node);
+            self.tooltip.elapsed = 0.0;
+        } else {
+            self.tooltip.elapsed += input.time.as_secs_f32();
+        }
+        if self.tooltip.elapsed < self.tooltip.delay {
+            return Ok(None);
+        }
+        Ok(Some(Tooltip {
+            text: text.clone(),
+            position: element.position,
+            size: element.size,
+        }))
+    }
+}
+
+fn tooltip_text(element: &Element) -> Option<&String> {
+    element
+        .attrs
+        .get("title")
+        .or_else(|| element.attrs.get("data-tooltip"))
+        .filter(|text| !text.is_empty())
+}
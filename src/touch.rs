@@ -0,0 +1,213 @@
+use crate::tree::ViewTreeExtensions;
+use crate::view_model::find_elements_at;
+use crate::{
+    Element, Fonts, InputEvent, MouseButtons, PanEvent, PinchEvent, TouchEvent, ViewError,
+    ViewModel,
+};
+use taffy::{NodeId, TaffyTree};
+
+/// The active touch points, primary touch is mapped to mouse, so templates
+/// made for mouse work on touchscreens.
+#[derive(Debug, Default)]
+pub(crate) struct Touches {
+    pub(crate) points: Vec<Touch>,
+    primary: Option<u64>,
+    /// The scrollable element moved by touches, flings once touches released.
+    scrolling: Option<NodeId>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Touch {
+    pub(crate) id: u64,
+    pub(crate) position: [f32; 2],
+    /// The elements touch started on, they receive all touch events of this touch.
+    targets: Vec<NodeId>,
+}
+
+/// The centroid of touch points and their mean distance to it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Gesture {
+    center: [f32; 2],
+    spread: f32,
+}
+
+impl Touches {
+    fn gesture(&self) -> Option<Gesture> {
+        if self.points.is_empty() {
+            return None;
+        }
+        let count = self.points.len() as f32;
+        let mut center = [0.0; 2];
+        for touch in &self.points {
+            center[0] += touch.position[0] / count;
+            center[1] += touch.position[1] / count;
+        }
+        let mut spread = 0.0;
+        for touch in &self.points {
+            let [x, y] = touch.position;
+            spread += (x - center[0]).hypot(y - center[1]) / count;
+        }
+        Some(Gesture { center, spread })
+    }
+
+    /// Checks touch with id started over some elements.
+    pub(crate) fn is_targeted(&self, id: u64) -> bool {
+        self.points
+            .iter()
+            .any(|touch| touch.id == id && !touch.targets.is_empty())
+    }
+}
+
+impl ViewModel {
+    pub(crate) fn handle_touch_input(
+        &mut self,
+        event: InputEvent,
+        body: NodeId,
+        tree: &mut TaffyTree<Element>,
+        fonts: &dyn Fonts,
+    ) -> Result<(), ViewError> {
+        let (id, position) = match event {
+            InputEvent::TouchStart(id, position)
+            | InputEvent::TouchMove(id, position)
+            | InputEvent::TouchEnd(id, position) => (id, position),
+            _ => return Ok(()),
+        };
+        let mouse = match event {
+            InputEvent::TouchStart(..) => {
+                let mut targets = vec![];
+                find_elements_at(tree, body, position, &mut targets)?;
+                self.touches.points.push(Touch {
+                    id,
+                    position,
+                    targets,
+                });
+                self.emit_touch("ontouchstart", id, tree)?;
+                if self.touches.primary.is_none() {
+                    self.touches.primary = Some(id);
+                    vec![
+                        InputEvent::MouseMove(position),
+                        InputEvent::MouseButtonDown(MouseButtons::Left),
+                    ]
+                } else {
+                    vec![]
+                }
+            }
+            InputEvent::TouchMove(..) => {
+                let previous = self.touches.gesture();
+                let touch = self.touches.points.iter_mut().find(|touch| touch.id == id);
+                match touch {
+                    Some(touch) => touch.position = position,
+                    None => return Ok(()),
+                }
+                self.emit_touch("ontouchmove", id, tree)?;
+                self.recognize_gestures(previous, tree)?;
+                if self.touches.primary == Some(id) {
+                    vec![InputEvent::MouseMove(position)]
+                } else {
+                    vec![]
+                }
+            }
+            _ => {
+                if let Some(touch) = self.touches.points.iter_mut().find(|touch| touch.id == id) {
+                    touch.position = position;
+                }
+                self.emit_touch("ontouchend", id, tree)?;
+                self.touches.points.retain(|touch| touch.id != id);
+                if self.touches.points.is_empty() {
+                    if let Some(node) = self.touches.scrolling.take() {
+                        self.fling(node, tree)?;
+                    }
+                }
+                if self.touches.primary == Some(id) {
+                    self.touches.primary = None;
+                    vec![
+                        InputEvent::MouseMove(position),
+                        InputEvent::MouseButtonUp(MouseButtons::Left),
+                    ]
+                } else {
+                    vec![]
+                }
+            }
+        };
+        if !mouse.is_empty() {
+            self.mouse = position;
+            self.handle_elements_input(mouse, body, tree, fonts)?;
+        }
+        Ok(())
+    }
+
+    /// Emits pan event as touches centroid moves and pinch event as multiple
+    /// touches spread or squeeze, to elements where first touch started.
+    fn recognize_gestures(
+        &mut self,
+        previous: Option<Gesture>,
+        tree: &mut TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        let (previous, current) = match (previous, self.touches.gesture()) {
+            (Some(previous), Some(current)) => (previous, current),
+            _ => return Ok(()),
+        };
+        let targets = match self.touches.points.first() {
+            Some(touch) => touch.targets.clone(),
+            None => return Ok(()),
+        };
+        let center = current.center;
+        let delta = [
+            center[0] - previous.center[0],
+            center[1] - previous.center[1],
+        ];
+        let pinch = self.touches.points.len() > 1 && previous.spread > 0.0;
+        // inner scrollable element follows touches unless pan handled by template
+        let mut consumed = false;
+        for node in targets.into_iter().rev() {
+            let element = tree.get_element_mut(node)?;
+            if delta != [0.0, 0.0] {
+                self.emit(element, "onpan", PanEvent::new(center, delta, element));
+                consumed = consumed || element.listeners.contains_key("onpan");
+            }
+            if let Some(scrolling) = element.scrolling.as_mut().filter(|_| !consumed) {
+                let distance = [-delta[0], -delta[1]];
+                scrolling.scroll_by(distance);
+                let time = self.kinetic.frame.max(f32::EPSILON);
+                scrolling.velocity = [distance[0] / time, distance[1] / time];
+                self.touches.scrolling = Some(node);
+                consumed = true;
+            }
+            if pinch && current.spread != previous.spread {
+                let scale = current.spread / previous.spread;
+                self.emit(element, "onpinch", PinchEvent::new(center, scale, element));
+            }
+        }
+        Ok(())
+    }
+
+    fn fling(&mut self, node: NodeId, tree: &mut TaffyTree<Element>) -> Result<(), ViewError> {
+        if let Some(scrolling) = tree.get_element_mut(node)?.scrolling.as_mut() {
+            match self.kinetic.friction {
+                Some(_) => self.kinetic.start(node),
+                None => scrolling.velocity = [0.0; 2],
+            }
+        }
+        Ok(())
+    }
+
+    fn emit_touch(
+        &mut self,
+        name: &str,
+        id: u64,
+        tree: &TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        let touches = self.touches.points.len();
+        let touch = match self.touches.points.iter().find(|touch| touch.id == id) {
+            Some(touch) => touch,
+            None => return Ok(()),
+        };
+        let position = touch.position;
+        for node in touch.targets.clone().into_iter().rev() {
+            let element = tree.get_element(node)?;
+            let event = TouchEvent::new(id, position, touches, element);
+            self.emit(element, name, event);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+use crate::{Transformer, ValueExtensions};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Standard transformers available in every view, e.g. `{ size | bytes }`.
+pub(crate) fn default_transformers() -> HashMap<String, Transformer> {
+    let mut transformers = HashMap::new();
+    let mut register = |name: &str, transformer: fn(Value, &[Value]) -> Value| {
+        transformers.insert(name.to_string(), Box::new(transformer) as Transformer);
+    };
+    register("duration", duration);
+    register("uppercase", uppercase);
+    register("lowercase", lowercase);
+    register("trim", trim);
+    register("number", number);
+    register("percent", percent);
+    register("bytes", bytes);
+    register("ordinal", ordinal);
+    register("json", json);
+    register("sort", sort);
+    register("reverse", reverse);
+    register("filter", filter);
+    register("filter_nonempty", filter_nonempty);
+    register("limit", limit);
+    transformers
+}
+
+fn duration(value: Value, _arguments: &[Value]) -> Value {
+    match value.as_f64() {
+        None => value,
+        Some(value) => {
+            let value = Duration::from_secs_f64(value);
+            let value = format!("{value:?}");
+            Value::String(value)
+        }
+    }
+}
+
+fn uppercase(value: Value, _arguments: &[Value]) -> Value {
+    Value::String(value.eval_string().to_uppercase())
+}
+
+fn lowercase(value: Value, _arguments: &[Value]) -> Value {
+    Value::String(value.eval_string().to_lowercase())
+}
+
+fn trim(value: Value, _arguments: &[Value]) -> Value {
+    Value::String(value.eval_string().trim().to_string())
+}
+
+/// Formats number with fixed precision, `{ price | number:2 }`.
+fn number(value: Value, arguments: &[Value]) -> Value {
+    match as_number(&value) {
+        None => value,
+        Some(number) => {
+            let precision = precision(arguments, 0);
+            Value::String(format!("{number:.precision$}"))
+        }
+    }
+}
+
+/// Formats fraction as percentage, `{ progress | percent }`.
+fn percent(value: Value, arguments: &[Value]) -> Value {
+    match as_number(&value) {
+        None => value,
+        Some(number) => {
+            let precision = precision(arguments, 0);
+            let number = number * 100.0;
+            Value::String(format!("{number:.precision$}%"))
+        }
+    }
+}
+
+/// Formats size in human-readable binary units, `{ size | bytes }`.
+fn bytes(value: Value, arguments: &[Value]) -> Value {
+    const UNITS: [&str; 6] = ["B", "KB", "MB", "GB", "TB", "PB"];
+    match as_number(&value) {
+        None => value,
+        Some(mut number) => {
+            let mut unit = 0;
+            while number.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+                number /= 1024.0;
+                unit += 1;
+            }
+            let unit = UNITS[unit];
+            if unit == "B" {
+                Value::String(format!("{number} {unit}"))
+            } else {
+                let precision = precision(arguments, 1);
+                Value::String(format!("{number:.precision$} {unit}"))
+            }
+        }
+    }
+}
+
+/// Formats integer with English ordinal suffix, `{ place | ordinal }`.
+fn ordinal(value: Value, _arguments: &[Value]) -> Value {
+    match as_number(&value) {
+        None => value,
+        Some(number) => {
+            let number = number as i64;
+            let suffix = match (number.abs() % 10, number.abs() % 100) {
+                (_, 11..=13) => "th",
+                (1, _) => "st",
+                (2, _) => "nd",
+                (3, _) => "rd",
+                _ => "th",
+            };
+            Value::String(format!("{number}{suffix}"))
+        }
+    }
+}
+
+fn json(value: Value, _arguments: &[Value]) -> Value {
+    Value::String(value.to_string())
+}
+
+/// Sorts array by item values or by item field, `{ items | sort:"name" }`.
+fn sort(value: Value, arguments: &[Value]) -> Value {
+    match value {
+        Value::Array(mut array) => {
+            let field = arguments.first().map(|field| field.eval_string());
+            let key = |item: &Value| match field.as_ref() {
+                Some(field) => item.get(field).cloned().unwrap_or_default(),
+                None => item.clone(),
+            };
+            array.sort_by(|a, b| compare(&key(a), &key(b)));
+            Value::Array(array)
+        }
+        value => value,
+    }
+}
+
+fn reverse(value: Value, _arguments: &[Value]) -> Value {
+    match value {
+        Value::Array(mut array) => {
+            array.reverse();
+            Value::Array(array)
+        }
+        value => value,
+    }
+}
+
+/// Keeps array items which field is truthy, `{ items | filter:"visible" }`.
+fn filter(value: Value, arguments: &[Value]) -> Value {
+    match (value, arguments.first()) {
+        (Value::Array(array), Some(field)) => {
+            let field = field.eval_string();
+            let array = array
+                .into_iter()
+                .filter(|item| {
+                    item.get(&field)
+                        .map(|value| value.eval_boolean())
+                        .unwrap_or(false)
+                })
+                .collect();
+            Value::Array(array)
+        }
+        (value, _) => value,
+    }
+}
+
+/// Removes null, false, empty strings and empty collections from array.
+fn filter_nonempty(value: Value, _arguments: &[Value]) -> Value {
+    match value {
+        Value::Array(array) => {
+            let array = array
+                .into_iter()
+                .filter(|item| match item {
+                    Value::Null => false,
+                    Value::Bool(value) => *value,
+                    Value::String(string) => !string.is_empty(),
+                    Value::Array(array) => !array.is_empty(),
+                    Value::Object(object) => !object.is_empty(),
+                    Value::Number(_) => true,
+                })
+                .collect();
+            Value::Array(array)
+        }
+        value => value,
+    }
+}
+
+/// Takes first items of array, `{ items | limit:10 }`.
+fn limit(value: Value, arguments: &[Value]) -> Value {
+    match (value, arguments.first()) {
+        (Value::Array(mut array), Some(count)) => {
+            array.truncate(count.eval_usize());
+            Value::Array(array)
+        }
+        (value, _) => value,
+    }
+}
+
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => {
+            let a = a.as_f64().unwrap_or_default();
+            let b = b.as_f64().unwrap_or_default();
+            a.total_cmp(&b)
+        }
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Less,
+        (_, Value::Null) => Ordering::Greater,
+        (a, b) => a.eval_string().cmp(&b.eval_string()),
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => string.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn precision(arguments: &[Value], default: usize) -> usize {
+    arguments
+        .first()
+        .map(|precision| precision.eval_usize())
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    pub fn test_default_transformers() {
+        let mut transformers = default_transformers();
+        let mut transform = |name: &str, value: Value, arguments: &[Value]| {
+            let transform = transformers.get_mut(name).expect("transformer registered");
+            transform(value, arguments).eval_string()
+        };
+        assert_eq!(transform("uppercase", json!("Hello"), &[]), "HELLO");
+        assert_eq!(transform("lowercase", json!("Hello"), &[]), "hello");
+        assert_eq!(transform("trim", json!("  Hello "), &[]), "Hello");
+        assert_eq!(transform("number", json!(1.23456), &[json!(2)]), "1.23");
+        assert_eq!(transform("number", json!("2.7"), &[]), "3");
+        assert_eq!(transform("percent", json!(0.425), &[json!(1)]), "42.5%");
+        assert_eq!(transform("bytes", json!(512), &[]), "512 B");
+        assert_eq!(transform("bytes", json!(1536), &[]), "1.5 KB");
+        assert_eq!(transform("bytes", json!(5_242_880), &[json!(0)]), "5 MB");
+        assert_eq!(transform("ordinal", json!(1), &[]), "1st");
+        assert_eq!(transform("ordinal", json!(12), &[]), "12th");
+        assert_eq!(transform("ordinal", json!(23), &[]), "23rd");
+        assert_eq!(transform("ordinal", json!(111), &[]), "111th");
+        assert_eq!(transform("json", json!({"a": [1]}), &[]), r#"{"a":[1]}"#);
+        assert_eq!(transform("json", json!("text"), &[]), r#""text""#);
+    }
+
+    #[test]
+    pub fn test_array_transformers() {
+        let mut transformers = default_transformers();
+        let mut transform = |name: &str, value: Value, arguments: &[Value]| {
+            let transform = transformers.get_mut(name).expect("transformer registered");
+            transform(value, arguments)
+        };
+        let items =
+            json!([{"name": "b", "done": true}, {"name": "a"}, {"name": "c", "done": true}]);
+        assert_eq!(
+            transform("sort", items.clone(), &[json!("name")]),
+            json!([{"name": "a"}, {"name": "b", "done": true}, {"name": "c", "done": true}])
+        );
+        assert_eq!(
+            transform("filter", items, &[json!("done")]),
+            json!([{"name": "b", "done": true}, {"name": "c", "done": true}])
+        );
+        assert_eq!(
+            transform("sort", json!([10, 2, 33]), &[]),
+            json!([2, 10, 33])
+        );
+        assert_eq!(
+            transform("reverse", json!([1, 2, 3]), &[]),
+            json!([3, 2, 1])
+        );
+        assert_eq!(
+            transform("limit", json!([1, 2, 3]), &[json!(2)]),
+            json!([1, 2])
+        );
+        assert_eq!(
+            transform("filter_nonempty", json!(["a", "", null, [], 0]), &[]),
+            json!(["a", 0])
+        );
+    }
+}
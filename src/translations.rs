@@ -0,0 +1,144 @@
+use crate::{Reaction, ValueExtensions, ViewModel};
+use log::warn;
+use std::collections::HashMap;
+use taffy::NodeId;
+
+/// Catalogs of localized strings and text spans referencing them.
+#[derive(Debug, Default)]
+pub(crate) struct Translations {
+    pub(crate) locale: String,
+    pub(crate) catalogs: HashMap<String, HashMap<String, String>>,
+    pub(crate) texts: HashMap<(NodeId, usize), TranslatedText>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TranslatedText {
+    pub(crate) key: String,
+    /// The placeholder names and JSON pointers of substituted values.
+    pub(crate) placeholders: Vec<(String, String)>,
+}
+
+impl ViewModel {
+    /// Localizes text span using current locale catalog,
+    /// placeholders like `{name}` are substituted with model values.
+    pub(crate) fn translate(&self, node: NodeId, span: usize) -> Option<Reaction> {
+        let translations = &self.translations;
+        let text = translations.texts.get(&(node, span))?;
+        let catalog = translations.catalogs.get(&translations.locale);
+        let count = text
+            .placeholders
+            .iter()
+            .find(|(name, _)| name == "count")
+            .and_then(|(_, path)| self.model.pointer(path))
+            .and_then(|count| count.as_f64());
+        let plural = |catalog: &HashMap<String, String>| {
+            let count = count?;
+            let category = plural_category(&translations.locale, count);
+            catalog
+                .get(&format!("{}.{category}", text.key))
+                .or_else(|| catalog.get(&format!("{}.other", text.key)))
+                .cloned()
+        };
+        let found =
+            catalog.and_then(|catalog| plural(catalog).or_else(|| catalog.get(&text.key).cloned()));
+        let mut translation = match found {
+            Some(translation) => translation,
+            None => {
+                if catalog.is_some() {
+                    warn!(
+                        "unable to translate {}, not found in locale {}",
+                        text.key, translations.locale
+                    );
+                }
+                text.key.clone()
+            }
+        };
+        for (name, path) in &text.placeholders {
+            let value = self
+                .model
+                .pointer(path)
+                .map(|value| value.eval_string())
+                .unwrap_or_default();
+            translation = translation.replace(&format!("{{{name}}}"), &value);
+        }
+        Some(Reaction::Type {
+            node,
+            span,
+            text: translation,
+        })
+    }
+
+    pub(crate) fn translate_all(&self) -> Vec<Reaction> {
+        self.translations
+            .texts
+            .keys()
+            .filter_map(|(node, span)| self.translate(*node, *span))
+            .collect()
+    }
+}
+
+/// Selects CLDR plural category of count for locale, e.g. `items.one` and `items.other`
+/// catalog keys are chosen by `{t "items" count=cart.size}`.
+fn plural_category(locale: &str, count: f64) -> &'static str {
+    let language = locale.split(['-', '_']).next().unwrap_or_default();
+    let integer = count.fract() == 0.0;
+    let n = count.abs() as u64;
+    match language {
+        "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" | "tr" => "other",
+        "fr" | "pt" if n < 2 => "one",
+        "ru" | "uk" | "be" | "sr" | "hr" | "bs" if integer => match (n % 10, n % 100) {
+            (1, hundred) if hundred != 11 => "one",
+            (2..=4, hundred) if !(12..=14).contains(&hundred) => "few",
+            _ => "many",
+        },
+        "pl" if integer => match (n % 10, n % 100) {
+            _ if n == 1 => "one",
+            (2..=4, hundred) if !(12..=14).contains(&hundred) => "few",
+            _ => "many",
+        },
+        "cs" | "sk" if integer => match n {
+            1 => "one",
+            2..=4 => "few",
+            _ => "other",
+        },
+        "ar" if integer => match (n, n % 100) {
+            (0, _) => "zero",
+            (1, _) => "one",
+            (2, _) => "two",
+            (_, 3..=10) => "few",
+            (_, 11..=99) => "many",
+            _ => "other",
+        },
+        _ if integer && n == 1 => "one",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_plural_categories() {
+        let categories = |locale: &str, counts: &[f64]| -> Vec<&str> {
+            counts
+                .iter()
+                .map(|count| plural_category(locale, *count))
+                .collect()
+        };
+        assert_eq!(
+            categories("en", &[0.0, 1.0, 2.0, 1.5]),
+            ["other", "one", "other", "other"]
+        );
+        assert_eq!(categories("fr", &[0.0, 1.0, 2.0]), ["one", "one", "other"]);
+        assert_eq!(
+            categories("ru-RU", &[1.0, 3.0, 5.0, 11.0, 21.0, 22.0]),
+            ["one", "few", "many", "many", "one", "few"]
+        );
+        assert_eq!(
+            categories("pl", &[1.0, 2.0, 5.0, 21.0]),
+            ["one", "few", "many", "many"]
+        );
+        assert_eq!(categories("ja", &[1.0]), ["other"]);
+    }
+}
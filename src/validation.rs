@@ -0,0 +1,113 @@
+use crate::tree::ViewTreeExtensions;
+use crate::view_model::DerivedValue;
+use crate::{Element, HandlerArgument, ValueExtensions, ViewError, ViewModel};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use taffy::{NodeId, TaffyTree};
+
+/// The problems of template bindings which otherwise appear as silent empty text.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BindingReport {
+    /// JSON pointers of values used in template but not specified in model.
+    pub missing_values: Vec<String>,
+    /// Names of transformers used in template but not registered in view.
+    pub unknown_pipes: Vec<String>,
+}
+
+impl BindingReport {
+    pub fn is_valid(&self) -> bool {
+        self.missing_values.is_empty() && self.unknown_pipes.is_empty()
+    }
+}
+
+impl ViewModel {
+    pub(crate) fn validate(
+        &self,
+        value: &Value,
+        body: NodeId,
+        tree: &TaffyTree<Element>,
+    ) -> Result<BindingReport, ViewError> {
+        let mut missing_values = vec![];
+        if let Value::Object(schema) = &self.model {
+            for (key, schema) in schema {
+                if key.starts_with(DerivedValue::PREFIX) || self.context.contains_key(key) {
+                    continue;
+                }
+                let path = format!("/{key}");
+                match value.get(key) {
+                    Some(value) => validate_value(schema, value, &path, &mut missing_values),
+                    None => missing_values.push(path),
+                }
+            }
+        }
+        for derived in &self.derived {
+            // piped arrays keep item shape, so source items validated against derived schema
+            let schema = self.model.pointer(&derived.target);
+            let source = value.pointer(&derived.source);
+            if let (Some(schema), Some(source)) = (schema, source) {
+                validate_value(schema, source, &derived.source, &mut missing_values);
+            }
+        }
+        missing_values.sort();
+        let mut pipes = BTreeSet::new();
+        for binding in self.bindings.values().flatten() {
+            pipes.extend(binding.pipe.iter().map(|pipe| pipe.name.clone()));
+        }
+        for derived in &self.derived {
+            pipes.extend(derived.pipe.iter().map(|pipe| pipe.name.clone()));
+        }
+        collect_handler_pipes(body, tree, &mut pipes)?;
+        let unknown_pipes = pipes
+            .into_iter()
+            .filter(|name| !self.transformers.contains_key(name))
+            .collect();
+        Ok(BindingReport {
+            missing_values,
+            unknown_pipes,
+        })
+    }
+}
+
+fn validate_value(schema: &Value, value: &Value, path: &str, missing: &mut Vec<String>) {
+    match (schema, value) {
+        (_, value) if value.is_pending() => {}
+        (Value::Object(schema), Value::Object(object)) => {
+            for (key, schema) in schema {
+                let path = format!("{path}/{key}");
+                match object.get(key) {
+                    Some(value) => validate_value(schema, value, &path, missing),
+                    None => missing.push(path),
+                }
+            }
+        }
+        (Value::Array(schema), Value::Array(array)) => {
+            if let Some(schema) = schema.first() {
+                for (index, value) in array.iter().enumerate() {
+                    let path = format!("{path}/{index}");
+                    validate_value(schema, value, &path, missing);
+                }
+            }
+        }
+        // nullable values and leaves are valid in any shape
+        _ => {}
+    }
+}
+
+fn collect_handler_pipes(
+    node: NodeId,
+    tree: &TaffyTree<Element>,
+    pipes: &mut BTreeSet<String>,
+) -> Result<(), ViewError> {
+    let element = tree.get_element(node)?;
+    for handler in element.listeners.values().flatten() {
+        for argument in &handler.arguments {
+            if let HandlerArgument::Binder { pipe, .. } = argument {
+                pipes.extend(pipe.iter().map(|pipe| pipe.name.clone()));
+            }
+        }
+    }
+    for child in &element.children {
+        collect_handler_pipes(*child, tree, pipes)?;
+    }
+    Ok(())
+}
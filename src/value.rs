@@ -1,7 +1,16 @@
 use log::error;
 use serde::de::DeserializeOwned;
 
-use serde_json::Value;
+use serde_json::{json, Value};
+
+/// The marker key of value still loaded by application, see [`pending`].
+pub const PENDING: &str = "$pending";
+
+/// Creates value marking data still loaded by application, bound elements show nothing
+/// and match `:loading` pseudo-class until actual value is bound.
+pub fn pending() -> Value {
+    json!({ PENDING: true })
+}
 
 pub trait ValueExtensions {
     fn eval_array(&self) -> Vec<String>;
@@ -10,6 +19,7 @@ pub trait ValueExtensions {
     fn eval_string(&self) -> String;
     fn eval_boolean(&self) -> bool;
     fn eval<T: Default + DeserializeOwned>(&self) -> T;
+    fn is_pending(&self) -> bool;
 }
 
 impl ValueExtensions for Value {
@@ -58,6 +68,7 @@ impl ValueExtensions for Value {
             Value::Number(number) => number.to_string(),
             Value::String(string) => string.clone(),
             Value::Array(_) => "[array]".to_string(),
+            Value::Object(_) if self.is_pending() => "".to_string(),
             Value::Object(_) => "{object}".to_string(),
         }
     }
@@ -69,7 +80,14 @@ impl ValueExtensions for Value {
             Value::Number(number) => number.as_f64().map(|value| value != 0.0).unwrap_or(false),
             Value::String(string) => string.len() > 0,
             Value::Array(array) => array.len() > 0,
-            Value::Object(_) => true,
+            Value::Object(_) => !self.is_pending(),
+        }
+    }
+
+    fn is_pending(&self) -> bool {
+        match self {
+            Value::Object(object) => object.get(PENDING) == Some(&Value::Bool(true)),
+            _ => false,
         }
     }
 }
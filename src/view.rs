@@ -1,23 +1,46 @@
-use crate::css::{match_style, read_css, read_inline_css, Css, PseudoClassMatcher};
-use crate::fonts::DummyFonts;
+use crate::changes::{collect_patches, find_changes};
+use crate::css::{
+    match_style, read_css, read_inline_css, AncestorFilter, Css, PseudoClassMatcher, Style,
+    StyleIndex,
+};
+use crate::display_list::{collect_display_list, collect_paint_records, find_damage, PaintRecord};
+use crate::fonts::{
+    break_styled_lines, default_fonts, resolve_face, DummyFonts, MonospaceFonts, StyledText,
+    TextFragment,
+};
 use crate::html::{read_html, ElementBinding, Html};
+use crate::hyphenation::hyphenate_text;
+use crate::inline::flow_inline_text;
+use crate::inspector::collect_debug_node;
+use crate::manifest::{collect_resources, find_resource_hints};
 use crate::metrics::ViewMetrics;
+use crate::paint_order::collect_paint_order;
+use crate::pending_fonts::{collect_pending_fonts, remeasure_loaded_fonts};
 use crate::rendering::Renderer;
-use crate::styles::{inherit, Cascade, Scrolling, Sizes, Variables};
+use crate::restyle::{restyle, Restyled, StateMatcher, StyleScope};
+use crate::styles::{Scrolling, Sizes, Variables};
+use crate::text_cache::TextCache;
 use crate::tree::ViewTreeExtensions;
-use crate::view_model::{Reaction, ViewModel};
-use crate::{BindingParams, Element, ElementStyle, Fonts, Input, Output, Transformer, ViewError};
+use crate::view_model::{find_elements_at, Reaction, Schema, ViewModel};
+use crate::{
+    BindingParams, BindingReport, Clipping, DebugNode, DisplayCommand, Element, ElementPatch,
+    ElementStyle, FaceId, FontFace, Fonts, Hyphenator, Input, InputEvent, Keys, MouseButtons,
+    Output, Rect, TextLine, ViewError, ViewSnapshot, ViewState,
+};
 use log::error;
 use mesura::GaugeValue;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
+use std::mem::{replace, take};
 use std::ops::{Add, Deref};
 use std::path::PathBuf;
-use std::time::SystemTime;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use taffy::prelude::length;
 use taffy::style_helpers::TaffyMaxContent;
-use taffy::{AvailableSpace, Layout, NodeId, Point, PrintTree, Size, TaffyTree};
+use taffy::{AvailableSpace, NodeId, Overflow, Point, PrintTree, Size, TaffyTree};
 
 pub struct View {
     model: ViewModel,
@@ -25,14 +48,36 @@ pub struct View {
     root: NodeId,
     body: NodeId,
     css: Css,
+    /// The styles of css bucketed by key selector, built once per stylesheet.
+    style_index: StyleIndex,
     html_source: Source,
     css_source: Source,
     resources: String,
     pub fonts: Box<dyn Fonts>,
     metrics: ViewMetrics,
+    /// The paint records of previous update, so damaged areas can be found.
+    painted: BTreeMap<u64, PaintRecord>,
+    manifest: BTreeSet<String>,
+    /// The element patches of previous update if changes tracking enabled.
+    patches: Option<HashMap<NodeId, ElementPatch>>,
     identified: HashMap<String, NodeId>,
+    state: Option<Box<dyn Any>>,
+    hyphenator: Option<Box<dyn Hyphenator>>,
+    text_cache: TextCache,
+    styled_cache: TextCache<Vec<TextFragment>>,
+    /// The logical viewport size of last update, styles of all elements are
+    /// recalculated if it changes.
+    viewport: [f32; 2],
+    /// The device pixel scale of last update, input events are in device pixels.
+    scale: f32,
+    /// The text elements content or font of which changed since last layout.
+    #[cfg(feature = "parallel")]
+    changed_texts: Vec<NodeId>,
 }
 
+/// The number of text sizes cached between layouts.
+const TEXT_CACHE_CAPACITY: usize = 1024;
+
 impl View {
     pub fn from_html(path: &str, fonts: impl Fonts + 'static) -> Result<Self, ViewError> {
         let mut html_source = Source::file(path);
@@ -43,7 +88,7 @@ impl View {
         let css_base_directory = html_source.folder();
         let mut body = Html::empty();
         let mut templates = HashMap::new();
-        for child in html.children {
+        for child in html.children.iter().cloned() {
             if child.tag == "link" {
                 let mut attrs = HashMap::new();
                 for binding in &child.bindings {
@@ -84,8 +129,10 @@ impl View {
             }
         }
         let mut css_source = Source::files(css_files);
-        let css = css_source.get_content()?;
+        let mut css = css_source.get_content()?;
+        html.collect_styles(&mut css);
         let css = read_css(&css)?;
+        let style_index = StyleIndex::new(&css.styles);
         //
         let mut renderer = Renderer::new(templates);
         let [root, body] = renderer.render(body)?;
@@ -93,7 +140,16 @@ impl View {
         let schema = renderer.schema;
         let tree = renderer.tree;
         let identified = renderer.static_id;
-        let model = ViewModel::create(bindings, schema.value);
+        let mut model = ViewModel::create(bindings, schema.value);
+        model.resources.images = renderer
+            .images
+            .into_iter()
+            .map(|img| (img, String::new()))
+            .collect();
+        model.translations.texts = renderer.translations;
+        model.derived = renderer.derived;
+        model.keyed.keys = renderer.keys;
+        model.local.defaults = renderer.local_state;
         let resources = css_base_directory.display().to_string();
         let mut view = Self {
             model,
@@ -101,20 +157,54 @@ impl View {
             root,
             body,
             css,
+            style_index,
             html_source,
             css_source,
             resources,
             fonts: Box::new(fonts),
             metrics: ViewMetrics::new(),
+            painted: BTreeMap::new(),
+            manifest: BTreeSet::new(),
+            patches: None,
             identified,
+            state: None,
+            hyphenator: None,
+            text_cache: TextCache::new(TEXT_CACHE_CAPACITY),
+            styled_cache: TextCache::new(TEXT_CACHE_CAPACITY),
+            viewport: [0.0; 2],
+            scale: 1.0,
+            #[cfg(feature = "parallel")]
+            changed_texts: vec![],
         };
         view.calculate_elements_stylesheet(body)?;
         view.apply_default_bindings_state()?;
+        for reaction in view.model.init_local_state()? {
+            view.update_tree(reaction)?;
+        }
         Ok(view)
     }
 
     pub fn fonts(mut self, fonts: impl Fonts + 'static) -> Self {
         self.fonts = Box::new(fonts);
+        self.text_cache.clear();
+        self.styled_cache.clear();
+        self
+    }
+
+    /// Registers monospace font family with advance of every char in font sizes, e.g.
+    /// 0.6 for typical console font, text of family is measured arithmetically without
+    /// fonts, so large log views stay cheap.
+    pub fn monospace(mut self, family: &str, advance: f32) -> Self {
+        self.model.monospace.register(family, advance);
+        self
+    }
+
+    /// Sets hyphenator breaking long words of texts with `hyphens: auto` in lines,
+    /// e.g. by dictionaries of supported languages.
+    pub fn hyphenator(mut self, hyphenator: impl Hyphenator + 'static) -> Self {
+        self.hyphenator = Some(Box::new(hyphenator));
+        self.text_cache.clear();
+        self.styled_cache.clear();
         self
     }
 
@@ -131,14 +221,27 @@ impl View {
     }
 
     pub fn create(
+        html_source: Source,
+        css_source: Source,
+        resources: &str,
+    ) -> Result<Self, ViewError> {
+        let fonts = default_fonts(resources);
+        Self::create_with(html_source, css_source, resources, fonts)
+    }
+
+    /// Creates view measuring texts by fonts, so reloaded view reuses loaded fonts.
+    fn create_with(
         mut html_source: Source,
         mut css_source: Source,
         resources: &str,
+        fonts: Box<dyn Fonts>,
     ) -> Result<Self, ViewError> {
         let html = html_source.get_content()?;
-        let css = css_source.get_content()?;
+        let mut css = css_source.get_content()?;
         let html = read_html(&html)?;
+        html.collect_styles(&mut css);
         let css = read_css(&css)?;
+        let style_index = StyleIndex::new(&css.styles);
         // TODO: remove cloned, take ownership
         let mut templates = HashMap::new();
         let _body = Html::empty();
@@ -183,7 +286,16 @@ impl View {
         let schema = renderer.schema;
         let tree = renderer.tree;
         let identified = renderer.static_id;
-        let model = ViewModel::create(bindings, schema.value);
+        let mut model = ViewModel::create(bindings, schema.value);
+        model.resources.images = renderer
+            .images
+            .into_iter()
+            .map(|img| (img, String::new()))
+            .collect();
+        model.translations.texts = renderer.translations;
+        model.derived = renderer.derived;
+        model.keyed.keys = renderer.keys;
+        model.local.defaults = renderer.local_state;
         let resources = resources.to_string();
         let mut view = Self {
             model,
@@ -191,40 +303,219 @@ impl View {
             root,
             body,
             css,
+            style_index,
             html_source,
             css_source,
             resources,
-            fonts: Box::new(DummyFonts),
+            fonts,
             metrics: ViewMetrics::new(),
+            painted: BTreeMap::new(),
+            manifest: BTreeSet::new(),
+            patches: None,
             identified,
+            state: None,
+            hyphenator: None,
+            text_cache: TextCache::new(TEXT_CACHE_CAPACITY),
+            styled_cache: TextCache::new(TEXT_CACHE_CAPACITY),
+            viewport: [0.0; 2],
+            scale: 1.0,
+            #[cfg(feature = "parallel")]
+            changed_texts: vec![],
         };
         view.calculate_elements_stylesheet(body)?;
         view.apply_default_bindings_state()?;
+        for reaction in view.model.init_local_state()? {
+            view.update_tree(reaction)?;
+        }
         Ok(view)
     }
 
-    pub fn pipe(mut self, name: &str, transformer: Transformer) -> Self {
+    pub fn pipe(self, name: &str, mut transformer: impl FnMut(Value) -> Value + 'static) -> Self {
+        self.pipe_with_arguments(name, move |value, _| transformer(value))
+    }
+
+    /// Registers transformer receiving pipe arguments, e.g. `2` in `{ price | round:2 }`.
+    pub fn pipe_with_arguments(
+        mut self,
+        name: &str,
+        transformer: impl FnMut(Value, &[Value]) -> Value + 'static,
+    ) -> Self {
         self.model
             .transformers
-            .insert(name.to_string(), transformer);
+            .insert(name.to_string(), Box::new(transformer));
+        self
+    }
+
+    /// Adds localized strings of locale, strings may contain placeholders like `{name}`
+    /// substituted by `{t "greeting" name=user.name}` template values.
+    pub fn translations<K, V>(
+        mut self,
+        locale: &str,
+        catalog: impl IntoIterator<Item = (K, V)>,
+    ) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.model
+            .translations
+            .catalogs
+            .entry(locale.to_string())
+            .or_default()
+            .extend(
+                catalog
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value.into())),
+            );
+        self.retranslate();
+        self
+    }
+
+    pub fn locale(mut self, locale: &str) -> Self {
+        self.set_locale(locale);
+        self
+    }
+
+    /// Switches current locale, all localized texts are updated immediately.
+    pub fn set_locale(&mut self, locale: &str) {
+        self.model.translations.locale = locale.to_string();
+        self.retranslate();
+    }
+
+    /// Shares value with all templates, it can be referenced by name in any binder
+    /// like `{theme.color}` without threading it through template link aliases.
+    pub fn context(mut self, name: &str, value: Value) -> Self {
+        self.set_context(name, value);
+        self
+    }
+
+    /// Updates shared context value, bound elements are updated immediately.
+    pub fn set_context(&mut self, name: &str, value: Value) {
+        let reactions = match self.model.set_context(name, value) {
+            Ok(reactions) => reactions,
+            Err(error) => {
+                error!("unable to set context {name}, {error:?}");
+                return;
+            }
+        };
+        for reaction in reactions {
+            if let Err(error) = self.update_tree(reaction) {
+                error!("unable to update context {name}, {error:?}");
+            }
+        }
+    }
+
+    fn retranslate(&mut self) {
+        for reaction in self.model.translate_all() {
+            if let Err(error) = self.update_tree(reaction) {
+                error!("unable to translate text, {error:?}");
+            }
+        }
+    }
+
+    /// Sets how long the cursor must rest on element with title before tooltip appears.
+    pub fn tooltip_delay(mut self, delay: Duration) -> Self {
+        self.model.tooltip.delay = delay.as_secs_f32();
+        self
+    }
+
+    /// Sets max time between clicks on element to count them as double click.
+    pub fn double_click_interval(mut self, interval: Duration) -> Self {
+        self.model.clicks.interval = interval.as_secs_f32();
+        self
+    }
+
+    /// Sets how long button must be held on element before hold event.
+    pub fn hold_duration(mut self, duration: Duration) -> Self {
+        self.model.hold.duration = duration.as_secs_f32();
+        self
+    }
+
+    /// Sets min distance in pixels and min speed in pixels per second of pointer
+    /// drag to recognize it as swipe.
+    pub fn swipe_threshold(mut self, distance: f32, velocity: f32) -> Self {
+        self.model.swipe.distance = distance;
+        self.model.swipe.velocity = velocity;
+        self
+    }
+
+    /// Sets how long editing or navigation key must be held before it repeats
+    /// and the time between repeats.
+    pub fn key_repeat(mut self, delay: Duration, interval: Duration) -> Self {
+        self.model.repeat.delay = delay.as_secs_f32();
+        self.model.repeat.interval = interval.as_secs_f32();
+        self
+    }
+
+    /// Enables smooth scrolling, wheel and touch fling give scrollable elements
+    /// velocity which decays by friction rate per second, e.g. 8.0.
+    pub fn kinetic_scrolling(mut self, friction: f32) -> Self {
+        self.model.kinetic.friction = Some(friction);
+        self
+    }
+
+    /// Enables accessibility snapshot in output, so application can expose view
+    /// to screen readers.
+    pub fn accessibility(mut self, enabled: bool) -> Self {
+        self.model.accessibility = enabled;
+        self
+    }
+
+    /// Enables list of changed elements in output, so application with retained
+    /// scene graph can patch it instead of rebuilding.
+    pub fn track_changes(mut self, enabled: bool) -> Self {
+        self.patches = enabled.then(HashMap::new);
+        self
+    }
+
+    /// Sets keys pressed with Ctrl to undo and redo text input edits, `z` and `y` by default.
+    pub fn edit_history_keys(mut self, undo: char, redo: char) -> Self {
+        self.model.history.keys = [undo, redo];
         self
     }
 
     fn watch_changes(&mut self) {
         if self.html_source.detect_changes() || self.css_source.detect_changes() {
-            let view = View::create(
+            // view keeps its loaded fonts, so they are not read again
+            let view = View::create_with(
                 self.html_source.clone(),
                 self.css_source.clone(),
                 &self.resources,
+                Box::new(DummyFonts),
             );
             match view {
                 Ok(mut view) => {
-                    view.model.transformers = self.model.transformers.clone();
+                    let snapshot = self.save_state();
+                    view.model.transformers = take(&mut self.model.transformers);
+                    view.model.tooltip.delay = self.model.tooltip.delay;
+                    view.model.clicks.interval = self.model.clicks.interval;
+                    view.model.hold.duration = self.model.hold.duration;
+                    view.model.swipe.distance = self.model.swipe.distance;
+                    view.model.swipe.velocity = self.model.swipe.velocity;
+                    view.model.kinetic.friction = self.model.kinetic.friction;
+                    view.model.repeat.delay = self.model.repeat.delay;
+                    view.model.repeat.interval = self.model.repeat.interval;
+                    view.model.history.keys = self.model.history.keys;
+                    view.model.accessibility = self.model.accessibility;
+                    view.model.monospace = take(&mut self.model.monospace);
+                    view.model.translations.locale = take(&mut self.model.translations.locale);
+                    view.model.translations.catalogs = take(&mut self.model.translations.catalogs);
+                    view.retranslate();
+                    for (name, value) in take(&mut self.model.context) {
+                        view.set_context(&name, value);
+                    }
                     self.model = view.model;
                     self.tree = view.tree;
                     self.root = view.root;
                     self.body = view.body;
                     self.css = view.css;
+                    self.style_index = view.style_index;
+                    let restored = snapshot.and_then(|snapshot| self.restore_state(&snapshot));
+                    if let Err(error) = restored {
+                        error!("unable to restore view state, {error:?}");
+                    }
+                    // new model must be bound from scratch
+                    self.state = None;
                 }
                 Err(error) => {
                     error!("unable to handle view changes, {error:?}")
@@ -240,8 +531,49 @@ impl View {
         for reaction in reactions {
             self.update_tree(reaction)?;
         }
+        self.handle_input(input)
+    }
+
+    /// Updates view using typed application state, only fields changed since previous
+    /// update are bound, see [`ViewState`].
+    pub fn update_state<T: ViewState>(
+        &mut self,
+        input: Input,
+        state: &T,
+    ) -> Result<Output, ViewError> {
+        self.metrics.updates.inc();
+        self.watch_changes();
+        let previous = self
+            .state
+            .as_ref()
+            .and_then(|previous| previous.downcast_ref::<T>());
+        let mut changes = vec![];
+        state.collect_changes(previous, "", &mut changes);
+        for (pointer, value) in changes {
+            // state may contain fields not used in template
+            if self.model.value_at(&pointer).is_some() {
+                self.patch(&pointer, value)?;
+            }
+        }
+        self.state = Some(Box::new(state.clone()));
+        self.handle_input(input)
+    }
+
+    /// Changes part of view model at specified JSON pointer without whole value rebinding.
+    /// Use [`View::handle_input`] afterward to process input and recompute layout.
+    pub fn patch(&mut self, pointer: &str, value: Value) -> Result<(), ViewError> {
+        let reactions = self.model.set(pointer, &value)?;
+        for reaction in reactions {
+            self.update_tree(reaction)?;
+        }
+        Ok(())
+    }
+
+    /// Updates view using current view model state, see [`View::patch`].
+    pub fn handle_input(&mut self, input: Input) -> Result<Output, ViewError> {
+        self.model.update_images_density(&input, &mut self.tree)?;
         // detect viewport changes
-        let [viewport_width, viewport_height] = input.viewport;
+        let [viewport_width, viewport_height] = input.logical_viewport();
         let mut root_layout = self.tree.style(self.root).unwrap().clone();
         if root_layout.size.width != length(viewport_width)
             && root_layout.size.height != length(viewport_height)
@@ -259,15 +591,90 @@ impl View {
             viewport_width,
             viewport_height,
         };
-        self.apply_styles(self.body, &input, sizes, Variables::default())?;
+        // device pixel alignment of fractional scale replaces layout rounding
+        if input.scale == 1.0 {
+            self.tree.enable_rounding();
+        } else {
+            self.tree.disable_rounding();
+        }
+        let restyle = self.viewport != [viewport_width, viewport_height];
+        self.viewport = [viewport_width, viewport_height];
+        self.apply_styles(&input, sizes, restyle)?;
+        flow_inline_text(&mut self.tree, self.body)?;
+        if !input.fonts_loaded.is_empty() {
+            self.text_cache.clear();
+            self.styled_cache.clear();
+            remeasure_loaded_fonts(&mut self.tree, self.body, &input.fonts_loaded)?;
+        }
+        #[cfg(feature = "parallel")]
+        {
+            let changed = take(&mut self.changed_texts);
+            if let Some(fonts) = self.fonts.as_sync() {
+                let context = TextContext {
+                    fonts,
+                    hyphenator: self.hyphenator.as_deref(),
+                    locale: &self.model.translations.locale,
+                    monospace: &self.model.monospace,
+                };
+                premeasure_texts(&context, fonts, &mut self.text_cache, &self.tree, &changed);
+            }
+        }
         self.tree.compute_layout_with_measure(
             self.body,
             Size::MAX_CONTENT,
-            |size, space, _, view, _| measure_text(self.fonts.as_ref(), size, space, view),
+            |size, space, _, view, _| {
+                let context = TextContext {
+                    fonts: self.fonts.as_ref(),
+                    hyphenator: self.hyphenator.as_deref(),
+                    locale: &self.model.translations.locale,
+                    monospace: &self.model.monospace,
+                };
+                let cache = &mut self.text_cache;
+                let styled_cache = &mut self.styled_cache;
+                measure_text(&context, cache, styled_cache, size, space, view)
+            },
         )?;
         // TODO: clipping of viewport
-        self.compute_final_positions_and_clipping(self.body, Point::ZERO, 1.0, None)?;
-        self.model.handle_output(&input, self.body, &mut self.tree)
+        let scale = input.scale;
+        self.scale = scale;
+        self.compute_final_positions_and_clipping(self.body, Point::ZERO, 1.0, None, scale)?;
+        let mut output =
+            self.model
+                .handle_output(&input, self.body, &mut self.tree, self.fonts.as_ref())?;
+        let mut painted = BTreeMap::new();
+        collect_paint_records(&self.tree, self.body, [0.0; 2], &mut painted)?;
+        output.damage = find_damage(&self.painted, &painted);
+        self.painted = painted;
+        let mut manifest = BTreeSet::new();
+        collect_resources(&self.tree, self.body, &mut manifest)?;
+        output.resource_hints = find_resource_hints(&self.manifest, &manifest);
+        // unloaded resource is requested again once referenced
+        for src in self.manifest.difference(&manifest) {
+            self.model.resources.states.remove(src);
+        }
+        // resources are requested until application reports them
+        output.resources = manifest
+            .iter()
+            .filter(|src| !self.model.resources.states.contains_key(*src))
+            .cloned()
+            .collect();
+        self.manifest = manifest;
+        let mut pending = BTreeSet::new();
+        collect_pending_fonts(&self.tree, self.body, self.fonts.as_ref(), &mut pending)?;
+        output.pending_fonts = pending.into_iter().collect();
+        if let Some(previous) = self.patches.as_mut() {
+            let mut patches = vec![];
+            collect_patches(&self.tree, self.body, None, [0.0; 2], None, &mut patches)?;
+            output.changes = find_changes(previous, &patches);
+            *previous = patches
+                .into_iter()
+                .map(|patch| (patch.node, patch))
+                .collect();
+        }
+        for reaction in take(&mut self.model.local.reactions) {
+            self.update_tree(reaction)?;
+        }
+        Ok(output)
     }
 
     fn compute_final_positions_and_clipping(
@@ -275,27 +682,80 @@ impl View {
         node: NodeId,
         location: Point<f32>,
         mut opacity: f32,
-        mut clipping: Option<Layout>,
+        mut clipping: Option<Clipping>,
+        scale: f32,
     ) -> Result<(), ViewError> {
         self.metrics.elements_shown.inc();
         let mut layout = self.tree.get_final_layout(node).clone();
         layout.location = layout.location.add(location);
         let element = self.tree.get_element_mut(node)?;
         element.opacity = opacity * element.self_opacity;
-        element.position = [layout.location.x, layout.location.y];
-        element.size = [layout.size.width, layout.size.height];
+        let [x, y] = [layout.location.x, layout.location.y];
+        let [width, height] = [layout.size.width, layout.size.height];
+        // aligns element edges to device pixels
+        let align = |value: f32| (value * scale).round() / scale;
+        element.position = [align(x), align(y)];
+        let size = [align(x + width) - align(x), align(y + height) - align(y)];
+        if element.size != size {
+            let previous = replace(&mut element.size, size);
+            self.model.observe_resize(element, previous);
+        }
         element.content_size = [layout.content_size.width, layout.content_size.height];
+        element.radius = element.borders.resolve_radius(element.size);
+        let context = TextContext {
+            fonts: self.fonts.as_ref(),
+            hyphenator: self.hyphenator.as_deref(),
+            locale: &self.model.translations.locale,
+            monospace: &self.model.monospace,
+        };
+        if !element.styled.is_empty() {
+            let [x, y] = element.position;
+            let cache = &mut self.styled_cache;
+            let mut fragments =
+                context.break_styled(cache, &element.styled, &element.font, Some(width));
+            for fragment in fragments.iter_mut() {
+                fragment.origin = [x + fragment.origin[0], y + fragment.origin[1]];
+            }
+            element.fragments = fragments;
+            element.lines = vec![];
+        } else if let Some(text) = element.text.as_ref() {
+            let text = text.to_string();
+            let cache = &mut self.text_cache;
+            let mut lines = context.layout(cache, &text, &element.font, Some(width));
+            let [x, y] = element.position;
+            for line in lines.iter_mut() {
+                line.origin = [x + line.origin[0], y + line.origin[1]];
+            }
+            element.lines = lines;
+            element.fragments = vec![];
+        }
         element.scrolling = Scrolling::ensure(&layout, &element.scrolling);
-        element.clipping = clipping;
+        element.clipping = clipping.clone();
+        let visible = match element.clipping.as_ref() {
+            Some(clipping) => overlaps(element, clipping),
+            None => true,
+        };
+        if element.listeners.contains_key("ontick") {
+            self.model.limiter.tickers.push(node);
+        }
+        if element.state.visible != visible {
+            element.state.visible = visible;
+            self.model.observe_visibility(element);
+        }
         let mut location = layout.location;
         if let Some(scrolling) = element.scrolling.as_ref() {
-            clipping = Some(layout.clone());
             location.x -= scrolling.x;
             location.y -= scrolling.y;
         }
+        let overflow = self.tree.style(node)?.overflow;
+        let element = self.tree.get_element(node)?;
+        if clips_content(element, overflow) {
+            clipping = Some(padding_box_clipping(element, clipping.map(Arc::new)));
+        }
         opacity = element.opacity;
         for child in self.tree.children(node)? {
-            self.compute_final_positions_and_clipping(child, location, opacity, clipping)?;
+            let clipping = clipping.clone();
+            self.compute_final_positions_and_clipping(child, location, opacity, clipping, scale)?;
         }
         Ok(())
     }
@@ -310,6 +770,13 @@ impl View {
                     .ok_or(ViewError::ElementTextContentNotFound)?;
                 element_text.set(span, text);
                 self.tree.mark_dirty(node)?;
+                #[cfg(feature = "parallel")]
+                self.changed_texts.push(node);
+            }
+            Reaction::Translate { node, span } => {
+                if let Some(reaction) = self.model.translate(node, span) {
+                    self.update_tree(reaction)?;
+                }
             }
             Reaction::Reattach {
                 parent,
@@ -377,13 +844,58 @@ impl View {
                     }
                 }
             }
+            Reaction::Reorder {
+                parent,
+                nodes,
+                count,
+            } => {
+                let element = self.tree.get_element_mut(parent)?;
+                let start = element
+                    .children
+                    .iter()
+                    .position(|child| nodes.contains(child))
+                    .ok_or(ViewError::ChildNotFound(nodes[0]))?;
+                element
+                    .children
+                    .splice(start..start + nodes.len(), nodes.iter().copied());
+                let definition = element.children.clone();
+                let visible = self.tree.children(parent)?;
+                let (shown, hidden) = nodes.split_at(count);
+                let children: Vec<NodeId> = definition
+                    .into_iter()
+                    .filter(|node| {
+                        shown.contains(node) || !hidden.contains(node) && visible.contains(node)
+                    })
+                    .collect();
+                self.tree.set_children(parent, &children)?;
+            }
+            Reaction::Pending { node, pending } => {
+                let mut element = self.tree.get_element_mut(node)?;
+                if element.text.is_some() {
+                    // text is styled by containing element
+                    let parent = self
+                        .tree
+                        .parent(node)
+                        .ok_or(ViewError::ParentNotFound(node))?;
+                    element = self.tree.get_element_mut(parent)?;
+                }
+                if pending {
+                    element.state.pending += 1;
+                } else {
+                    element.state.pending = element.state.pending.saturating_sub(1);
+                }
+            }
             Reaction::Tag { node, key, tag } => {
                 let element = self.tree.get_element_mut(node)?;
                 if tag {
-                    element.attrs.insert(key.clone(), key.clone());
+                    element.attrs.insert(key.clone(), key.to_string());
                 } else {
                     element.attrs.remove(&key);
                 };
+                if key == "checked" {
+                    element.state.checked = tag;
+                }
+                element.update_validity();
             }
             Reaction::Bind {
                 node,
@@ -395,7 +907,7 @@ impl View {
                 let attribute = element
                     .attrs_bindings
                     .get_mut(&key)
-                    .ok_or(ViewError::AttributeBindingNotFound(key.clone()))?;
+                    .ok_or(ViewError::AttributeBindingNotFound(key.to_string()))?;
                 attribute.set(span, text);
                 let value = attribute.to_string();
                 element.attrs.insert(key.clone(), value.clone());
@@ -410,9 +922,9 @@ impl View {
                 if key == "id" {
                     self.identified.insert(value.clone(), node);
                 }
-                match (element.tag.as_str(), key.as_str()) {
-                    ("img", "src") => self.model.update_img_src(node, value, &mut self.tree)?,
-                    _ => {}
+                element.update_validity();
+                if element.tag == "img" && (key == "src" || key == "srcset") {
+                    self.model.update_img_src(node, &mut self.tree)?;
                 }
             }
         }
@@ -425,6 +937,7 @@ impl View {
     // But this can degrade performance of View creation.
     fn apply_default_bindings_state(&mut self) -> Result<(), ViewError> {
         let mut reactions = vec![];
+        self.detach_hidden_elements(self.body, &mut reactions)?;
         for bindings in self.model.bindings.values() {
             for binding in bindings {
                 match binding.params {
@@ -445,15 +958,59 @@ impl View {
         Ok(())
     }
 
+    fn detach_hidden_elements(
+        &self,
+        parent: NodeId,
+        reactions: &mut Vec<Reaction>,
+    ) -> Result<(), ViewError> {
+        for node in self.tree.children(parent)? {
+            if self.tree.get_element(node)?.hidden() {
+                reactions.push(Reaction::Reattach {
+                    parent,
+                    node,
+                    visible: false,
+                });
+            }
+            self.detach_hidden_elements(node, reactions)?;
+        }
+        Ok(())
+    }
+
     fn calculate_elements_stylesheet(&mut self, node: NodeId) -> Result<(), ViewError> {
+        let mut ancestors = AncestorFilter::default();
+        let mut parent = self.tree.parent(node);
+        while let Some(ancestor) = parent {
+            ancestors.insert(self.tree.get_element(ancestor)?);
+            parent = self.tree.parent(ancestor);
+        }
+        // index is detached while elements of tree are matched
+        let index = take(&mut self.style_index);
+        let result = self.match_elements_styles(node, &index, ancestors);
+        self.style_index = index;
+        result
+    }
+
+    /// Assigns styles element may match to element and its descendants, only styles
+    /// of index candidates not rejected by ancestors are matched.
+    fn match_elements_styles(
+        &mut self,
+        node: NodeId,
+        index: &StyleIndex,
+        mut ancestors: AncestorFilter,
+    ) -> Result<(), ViewError> {
         struct Matcher;
         impl PseudoClassMatcher for Matcher {
             fn has_pseudo_class(&self, _element: &Element, _class: &str) -> bool {
                 true
             }
         }
-        for style in self.css.styles.iter() {
-            let matches_ignoring_pseudo = match_style(style, node, &self.tree, &Matcher);
+        let candidates = index.candidates(self.tree.get_element(node)?);
+        for style in candidates
+            .into_iter()
+            .map(|position| &self.css.styles[position])
+        {
+            let matches_ignoring_pseudo =
+                ancestors.may_match(style) && match_style(style, node, &self.tree, &Matcher);
             let element = self.tree.get_element_mut(node)?;
             let hints = &element.style_hints;
             let has_pseudo = style.has_pseudo_class_selector();
@@ -475,95 +1032,255 @@ impl View {
                 }
             }
         }
+        ancestors.insert(self.tree.get_element(node)?);
         let children = self.tree.children(node)?;
         for child in children {
-            self.calculate_elements_stylesheet(child)?;
+            self.match_elements_styles(child, index, ancestors)?;
         }
         Ok(())
     }
 
-    fn apply_styles(
-        &mut self,
-        node: NodeId,
-        input: &Input,
-        mut sizes: Sizes,
-        variables: Variables,
-    ) -> Result<(), ViewError> {
-        let parent = unsafe {
-            let ptr = self
-                .tree
-                .parent(node)
-                .and_then(|parent| self.tree.get_node_context(parent))
-                .ok_or(ViewError::ParentNotFound(node))? as *const Element;
-            // TODO:
-            &*ptr
-        };
-        let mut layout = self.tree.style(node)?.clone();
-        let element = unsafe {
-            let ptr = self.tree.get_element_mut(node)? as *mut Element;
-            &mut *ptr
-        };
-
-        if element.text.is_some() {
-            inherit(parent, element);
-            return Ok(());
-        }
-
-        self.metrics.cascades.inc();
-        let mut cascade = Cascade::new(&self.css, sizes, variables);
-        cascade.apply_styles(input, node, &self.tree, parent, &mut layout, element, self);
-        let stats = cascade.stats;
+    fn apply_styles(&mut self, input: &Input, sizes: Sizes, dirty: bool) -> Result<(), ViewError> {
+        let scope = StyleScope {
+            css: &self.css,
+            input,
+            siblings: self.css.styles.iter().any(Style::has_sibling_combinator),
+        };
+        let variables = Variables::default();
+        let mut restyled = Restyled::default();
+        restyle(
+            &mut self.tree,
+            &scope,
+            self.body,
+            sizes,
+            &variables,
+            dirty,
+            &mut restyled,
+        )?;
+        self.metrics.cascades.add(restyled.cascades);
         self.metrics.styles.set(self.css.styles.len());
+        let stats = restyled.stats;
         let cascade_metrics = &mut self.metrics.cascade;
         cascade_metrics.matches_static.add(stats.matches_static);
         cascade_metrics.matches_dynamic.add(stats.matches_dynamic);
         cascade_metrics.apply_ok.add(stats.apply_ok);
         cascade_metrics.apply_error.add(stats.apply_error);
-        let variables = cascade.take_variables();
-
-        // we must update styles only if changes detected to support Taffy cache system
-        if self.tree.style(node)? != &layout {
-            self.metrics.layouts.inc();
-            self.tree.set_style(node, layout)?;
-        }
-
-        // self.tree.set_node_context(node, Some(element));
-
-        match element.tag.as_str() {
-            "img" => {
-                // self.render_img(current_id, &element, tree);
-            }
-            "input" => {
-                // let text = element.html.attrs.get("value").cloned().unwrap_or_default();
-                // self.render_input(text, current_id, &element, tree);
-            }
-            "area" => {}
-            "base" => {}
-            "br" => {}
-            "col" => {}
-            "command" => {}
-            "embed" => {}
-            "hr" => {}
-            "keygen" => {}
-            "link" => {}
-            "meta" => {}
-            "param" => {}
-            "source" => {}
-            "track" => {}
-            "wbr" => {}
-            _ => {
-                let children = self.tree.children(node)?;
-                for child in children {
-                    sizes.parent_font_size = element.font.size;
-                    sizes.parent_color = element.color;
-                    self.apply_styles(child, input, sizes, variables.clone())?;
-                }
-            }
+        self.metrics.layouts.add(restyled.layouts);
+        #[cfg(feature = "parallel")]
+        self.changed_texts.extend(restyled.texts);
+        Ok(())
+    }
+
+    /// Returns model values view currently bound, only values used in template are present.
+    pub fn model(&self) -> &Value {
+        &self.model.model
+    }
+
+    /// Returns currently bound model value at JSON pointer, e.g. `/user/name`.
+    pub fn value_at(&self, pointer: &str) -> Option<&Value> {
+        self.model.value_at(pointer)
+    }
+
+    /// Returns shape of model value expected by template, arrays are described
+    /// by single item and leaf values are null, e.g. `{"items": [{"name": null}]}`.
+    pub fn schema(&self) -> Value {
+        self.model.schema()
+    }
+
+    /// Returns expected model value description in JSON Schema format.
+    pub fn json_schema(&self) -> Value {
+        Schema::to_json_schema(&self.model.schema())
+    }
+
+    /// Checks that model value specifies everything template binds
+    /// and all template pipes are registered, useful on application startup.
+    pub fn validate(&self, value: &Value) -> Result<BindingReport, ViewError> {
+        self.model.validate(value, self.body, &self.tree)
+    }
+
+    /// Returns true if mouse pointer is over element with id.
+    pub fn is_hovered(&self, id: &str) -> bool {
+        self.get_element_by_id(id)
+            .map(|element| element.state.hover)
+            .unwrap_or(false)
+    }
+
+    /// Returns true if element with id is pressed by mouse.
+    pub fn is_active(&self, id: &str) -> bool {
+        self.get_element_by_id(id)
+            .map(|element| element.state.active)
+            .unwrap_or(false)
+    }
+
+    /// Returns true if element with id receives keyboard input.
+    pub fn is_focused(&self, id: &str) -> bool {
+        self.get_element_by_id(id)
+            .map(|element| element.state.focus)
+            .unwrap_or(false)
+    }
+
+    /// Returns true if focused element receives text input, so application can
+    /// suppress own keyboard handling, e.g. camera controls.
+    pub fn is_text_input_focused(&self) -> bool {
+        self.model
+            .focus
+            .and_then(|node| self.tree.get_element(node).ok())
+            .map(|element| {
+                element.tag == "input"
+                    || element.tag == "textarea"
+                    || element.listeners.contains_key("oninput")
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns scroll position of element with id, None if element content is not scrollable.
+    pub fn scroll_offset(&self, id: &str) -> Option<[f32; 2]> {
+        let element = self.get_element_by_id(id)?;
+        let scrolling = element.scrolling.as_ref()?;
+        Some([scrolling.x, scrolling.y])
+    }
+
+    /// Focuses element by id, onblur and onfocus messages appear in next output.
+    pub fn focus(&mut self, id: &str) -> Result<(), ViewError> {
+        let node = self.find_element_by_id(id)?;
+        self.model.set_focus(Some(node), &mut self.tree)
+    }
+
+    /// Removes focus from focused element, onblur message appears in next output.
+    pub fn blur(&mut self) -> Result<(), ViewError> {
+        self.model.set_focus(None, &mut self.tree)
+    }
+
+    /// Captures mouse by element with id until button released, element receives mouse
+    /// move and up events even if cursor leaves it, e.g. custom scrollbar dragged.
+    pub fn capture_pointer(&mut self, id: &str) -> Result<(), ViewError> {
+        let node = self.find_element_by_id(id)?;
+        self.model.pointer_capture = Some(node);
+        Ok(())
+    }
+
+    /// Releases mouse captured by [`View::capture_pointer`] before button released.
+    pub fn release_pointer(&mut self) {
+        self.model.pointer_capture = None;
+    }
+
+    /// Returns input events of left button click at center of element with id,
+    /// coordinates are taken from layout and device pixel scale of last update,
+    /// e.g. for headless tests.
+    pub fn simulate_click(&self, id: &str) -> Result<Vec<InputEvent>, ViewError> {
+        let node = self.find_element_by_id(id)?;
+        let element = self.tree.get_element(node)?;
+        let [x, y] = element.position;
+        let [width, height] = element.size;
+        let center = [x + width / 2.0, y + height / 2.0];
+        Ok(vec![
+            InputEvent::MouseMove(center.map(|value| value * self.scale)),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ])
+    }
+
+    /// Returns input events of click on text input with id, moving caret to the end
+    /// of value, followed by typed text chars.
+    pub fn simulate_type(&self, id: &str, text: &str) -> Result<Vec<InputEvent>, ViewError> {
+        let mut events = self.simulate_click(id)?;
+        events.extend(self.simulate_key(Keys::End));
+        events.extend(text.chars().map(InputEvent::Char));
+        Ok(events)
+    }
+
+    /// Returns input events of key press and release.
+    pub fn simulate_key(&self, key: Keys) -> Vec<InputEvent> {
+        vec![InputEvent::KeyDown(key), InputEvent::KeyUp(key)]
+    }
+
+    /// Captures scroll offsets, focus, input values and animation times of elements,
+    /// so view can be restored after scene transition without visible reset.
+    pub fn save_state(&self) -> Result<ViewSnapshot, ViewError> {
+        ViewSnapshot::capture(&self.tree, self.body, self.model.focus)
+    }
+
+    /// Restores elements state captured by [`View::save_state`], focus is restored
+    /// without onfocus messages.
+    pub fn restore_state(&mut self, snapshot: &ViewSnapshot) -> Result<(), ViewError> {
+        let focus = snapshot.restore(&mut self.tree, self.body)?;
+        if let Some(node) = self.model.focus.take() {
+            self.tree.get_element_mut(node)?.state.focus = false;
+        }
+        if let Some(node) = focus {
+            self.tree.get_element_mut(node)?.state.focus = true;
+            self.model.focus = Some(node);
+        }
+        Ok(())
+    }
+
+    /// Overrides inline style declarations of element with id, e.g. `"opacity: 0.5;"`,
+    /// overrides stay until bound style attribute of element changes.
+    pub fn set_inline_style(&mut self, id: &str, css: &str) -> Result<(), ViewError> {
+        let node = self.find_element_by_id(id)?;
+        let declarations = read_inline_css(css)?;
+        let element = self.tree.get_element_mut(node)?;
+        for declaration in declarations {
+            element
+                .style
+                .retain(|current| !current.has_same_key(&declaration));
+            element.style.push(declaration);
+        }
+        element.style_key = None;
+        Ok(())
+    }
+
+    /// Returns all elements of view matching CSS selector, e.g. `"div.slot[data-target]"`,
+    /// in document order.
+    pub fn query_selector_all(&self, selector: &str) -> Result<Vec<Fragment<'_>>, ViewError> {
+        let css = read_css(&format!("{selector} {{}}"))?;
+        let mut nodes = vec![];
+        if let Some(style) = css.styles.first() {
+            self.collect_matching_nodes(style, self.body, &mut nodes)?;
         }
+        let fragments = nodes
+            .into_iter()
+            .map(|node| self.tree.get_element(node))
+            .map(|element| {
+                element.map(|element| Fragment {
+                    element,
+                    tree: &self.tree,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(fragments)
+    }
+
+    /// Returns first element of view matching CSS selector, see [`View::query_selector_all`].
+    pub fn query_selector(&self, selector: &str) -> Result<Option<Fragment<'_>>, ViewError> {
+        Ok(self.query_selector_all(selector)?.into_iter().next())
+    }
 
+    fn collect_matching_nodes(
+        &self,
+        style: &Style,
+        node: NodeId,
+        nodes: &mut Vec<NodeId>,
+    ) -> Result<(), ViewError> {
+        if self.tree.get_element(node)?.text.is_some() {
+            return Ok(());
+        }
+        if match_style(style, node, &self.tree, self) {
+            nodes.push(node);
+        }
+        for child in self.tree.children(node)? {
+            self.collect_matching_nodes(style, child, nodes)?;
+        }
         Ok(())
     }
 
+    fn find_element_by_id(&self, id: &str) -> Result<NodeId, ViewError> {
+        self.identified
+            .get(id)
+            .copied()
+            .ok_or_else(|| ViewError::ElementIdNotFound(id.to_string()))
+    }
+
     #[inline(always)]
     pub fn get_element_by_id(&self, id: &str) -> Option<&Element> {
         self.identified
@@ -571,6 +1288,61 @@ impl View {
             .and_then(|node| self.tree.get_element(*node).ok())
     }
 
+    /// Returns urls of all resources referenced by view as of last update, in url order.
+    pub fn resource_manifest(&self) -> impl Iterator<Item = &str> {
+        self.manifest.iter().map(String::as_str)
+    }
+
+    /// Returns drawing commands of view in paint order, so renderer draws view
+    /// by executing commands instead of interpreting elements.
+    pub fn display_list(&self) -> Result<Vec<DisplayCommand>, ViewError> {
+        let mut commands = vec![];
+        collect_display_list(&self.tree, self.body, [0.0; 2], None, &mut commands)?;
+        Ok(commands)
+    }
+
+    /// Returns snapshot of live tree with computed layout, matched rules and animations
+    /// state, printable as indented text, to troubleshoot layout without debugger.
+    pub fn debug_tree(&self) -> Result<DebugNode, ViewError> {
+        collect_debug_node(&self.tree, self.body, self)
+    }
+
+    /// Returns top-most element at point honoring pointer-events, clipping and transforms,
+    /// the same way mouse hovers elements, so application can implement custom picking.
+    pub fn element_at(&self, point: [f32; 2]) -> Option<Fragment<'_>> {
+        let mut hits = vec![];
+        let mut order = vec![];
+        let result = find_elements_at(&self.tree, self.body, point, &mut hits)
+            .and_then(|_| collect_paint_order(&self.tree, self.body, &mut order));
+        if let Err(error) = result {
+            error!("unable to find element at {point:?}, {error:?}");
+            return None;
+        }
+        let hits: HashSet<NodeId> = hits.into_iter().collect();
+        let node = order.into_iter().rev().find(|node| hits.contains(node))?;
+        let element = self.tree.get_node_context(node)?;
+        Some(Fragment {
+            element,
+            tree: &self.tree,
+        })
+    }
+
+    /// Returns elements in back-to-front paint order respecting z-index and absolute
+    /// positioning, so overlays are painted over elements following them in tree.
+    pub fn render_order(&self) -> impl Iterator<Item = Fragment<'_>> + '_ {
+        let mut order = vec![];
+        if let Err(error) = collect_paint_order(&self.tree, self.body, &mut order) {
+            error!("unable to collect paint order, {error:?}");
+        }
+        order.into_iter().filter_map(|node| {
+            let element = self.tree.get_node_context(node)?;
+            Some(Fragment {
+                element,
+                tree: &self.tree,
+            })
+        })
+    }
+
     pub fn body(&self) -> Fragment {
         let element = self
             .tree
@@ -608,6 +1380,34 @@ impl Fragment<'_> {
             }
         }
     }
+
+    /// Returns children which may be visible in viewport, children placed outside
+    /// of viewport or their clip area are skipped, so renderer can skip whole subtrees.
+    pub fn children_visible(&self, viewport: &Rect) -> Vec<Fragment<'_>> {
+        let mut children = self.children();
+        children.retain(|child| child.intersects(viewport));
+        children
+    }
+
+    fn intersects(&self, viewport: &Rect) -> bool {
+        let element = self.element;
+        let [x, y] = element.position;
+        // descendants overflowing element box are still visible
+        let width = element.size[0].max(element.content_size[0]);
+        let height = element.size[1].max(element.content_size[1]);
+        let [left, top] = viewport.position;
+        let mut bounds = [left, top, left + viewport.size[0], top + viewport.size[1]];
+        if let Some(clipping) = element.clipping.as_ref() {
+            let [left, top, right, bottom] = clipping.bounds();
+            bounds = [
+                bounds[0].max(left),
+                bounds[1].max(top),
+                bounds[2].min(right),
+                bounds[3].min(bottom),
+            ];
+        }
+        x < bounds[2] && x + width > bounds[0] && y < bounds[3] && y + height > bounds[1]
+    }
 }
 
 impl Deref for Fragment<'_> {
@@ -618,8 +1418,109 @@ impl Deref for Fragment<'_> {
     }
 }
 
-fn measure_text<F: Fonts + ?Sized>(
-    fonts: &F,
+/// The fonts and view settings plain texts and styled paragraphs are laid out with.
+struct TextContext<'t> {
+    fonts: &'t dyn Fonts,
+    hyphenator: Option<&'t dyn Hyphenator>,
+    locale: &'t str,
+    monospace: &'t MonospaceFonts,
+}
+
+impl TextContext<'_> {
+    /// Returns lines of text hyphenated if needed, monospace text is laid out
+    /// arithmetically, text of other fonts is cached.
+    fn layout(
+        &self,
+        cache: &mut TextCache,
+        text: &str,
+        face: &FontFace,
+        max_width: Option<f32>,
+    ) -> Vec<TextLine> {
+        let text = hyphenate_text(self.hyphenator, self.locale, text, face);
+        if self.monospace.contains(face) {
+            return self
+                .monospace
+                .layout(FaceId::default(), &text, face, max_width);
+        }
+        let id = resolve_face(self.fonts, face);
+        cache.get_or_layout(&text, face, max_width, || {
+            self.fonts.layout(id, &text, face, max_width)
+        })
+    }
+
+    /// Returns fragments of styled texts hyphenated if needed, fragments are cached.
+    fn break_styled(
+        &self,
+        cache: &mut TextCache<Vec<TextFragment>>,
+        spans: &[StyledText],
+        face: &FontFace,
+        max_width: Option<f32>,
+    ) -> Vec<TextFragment> {
+        let spans: Vec<StyledText> = spans
+            .iter()
+            .map(|span| {
+                let text = hyphenate_text(self.hyphenator, self.locale, &span.text, &span.font);
+                StyledText {
+                    text: text.into_owned(),
+                    ..span.clone()
+                }
+            })
+            .collect();
+        cache.get_or_break(&spans, face, max_width, || {
+            break_styled_lines(self.fonts, self.monospace, &spans, face, max_width)
+        })
+    }
+}
+
+/// Lays out changed plain texts to be measured by next layout by many threads, so
+/// layout measures them by text cache. Texts are laid out at max-content width and
+/// at content width of parent in last layout, the width layout most likely gives.
+#[cfg(feature = "parallel")]
+fn premeasure_texts(
+    context: &TextContext,
+    fonts: &(dyn Fonts + Sync),
+    cache: &mut TextCache,
+    tree: &TaffyTree<Element>,
+    changed: &[NodeId],
+) {
+    let mut texts = vec![];
+    for node in changed {
+        // changed text may be removed from tree since
+        let element = match tree.get_node_context(*node) {
+            Some(element) => element,
+            None => continue,
+        };
+        if let Some(text) = element.text.as_ref() {
+            let face = &element.font;
+            if element.styled.is_empty() && !context.monospace.contains(face) {
+                let text = text.to_string();
+                let text = hyphenate_text(context.hyphenator, context.locale, &text, face);
+                let text = text.into_owned();
+                if let Some(parent) = tree.parent(*node) {
+                    let layout = tree.get_final_layout(parent);
+                    let width = layout.size.width
+                        - layout.padding.left
+                        - layout.padding.right
+                        - layout.border.left
+                        - layout.border.right;
+                    if width > 0.0 {
+                        texts.push((text.clone(), face.clone(), Some(width)));
+                    }
+                }
+                texts.push((text, face.clone(), None));
+            }
+        }
+    }
+    cache.layout_parallel(texts, |text, face, max_width| {
+        let id = resolve_face(fonts, face);
+        fonts.layout(id, text, face, max_width)
+    });
+}
+
+fn measure_text(
+    context: &TextContext,
+    cache: &mut TextCache,
+    styled_cache: &mut TextCache<Vec<TextFragment>>,
     size: Size<Option<f32>>,
     space: Size<AvailableSpace>,
     element: Option<&mut Element>,
@@ -641,7 +1542,32 @@ fn measure_text<F: Fonts + ?Sized>(
             AvailableSpace::MaxContent => None,
             AvailableSpace::Definite(width) => Some(width),
         });
-        let [width, height] = fonts.measure(&text, &element.font, max_width);
+        // lines are broken by view, so backends measuring only single line text
+        // still get multi-line layout
+        if !element.styled.is_empty() {
+            let fragments =
+                context.break_styled(styled_cache, &element.styled, &element.font, max_width);
+            let [width, height] =
+                fragments
+                    .iter()
+                    .fold([0.0f32; 2], |[width, height], fragment| {
+                        let [x, y] = fragment.origin;
+                        [
+                            width.max(x + fragment.width),
+                            height.max(y + fragment.height),
+                        ]
+                    });
+            return Size { width, height };
+        }
+        let lines = context.layout(cache, &text, &element.font, max_width);
+        if lines.is_empty() {
+            let fonts = context.monospace.select(context.fonts, &element.font);
+            let id = resolve_face(fonts, &element.font);
+            let [width, height] = fonts.measure(id, &text, &element.font, None);
+            return Size { width, height };
+        }
+        let width = lines.iter().map(|line| line.width).fold(0.0, f32::max);
+        let height = lines.iter().map(|line| line.height).sum();
         return Size { width, height };
     }
     Size::ZERO
@@ -649,23 +1575,7 @@ fn measure_text<F: Fonts + ?Sized>(
 
 impl PseudoClassMatcher for View {
     fn has_pseudo_class(&self, element: &Element, class: &str) -> bool {
-        match class {
-            "hover" => element.state.hover,
-            "active" => element.state.active,
-            // The :checked CSS pseudo-class represents any radio, checkbox, or option element
-            // that is checked or toggled to an "on" state.
-            "checked" => element.state.checked,
-            // The :focus CSS pseudo-class represents an element (such as a form input) that
-            // has received focus. It is generally triggered when the user clicks or taps
-            // on an element or selects it with the keyboard's Tab key.
-            "focus" => element.state.focus,
-            // The :blank CSS pseudo-class selects empty user input elements.
-            "blank" => false,
-            _ => {
-                error!("unable to match unknown pseudo class {class}");
-                false
-            }
-        }
+        StateMatcher.has_pseudo_class(element, class)
     }
 }
 
@@ -764,12 +1674,56 @@ impl Source {
     }
 }
 
+fn overlaps(element: &Element, clipping: &Clipping) -> bool {
+    let [x, y] = element.position;
+    let [width, height] = element.size;
+    let [left, top, right, bottom] = clipping.bounds();
+    x < right && x + width > left && y < bottom && y + height > top
+}
+
+/// Checks element clips its descendants, scroll containers and overflow other than visible.
+pub(crate) fn clips_content(element: &Element, overflow: Point<Overflow>) -> bool {
+    element.scrolling.is_some()
+        || overflow.x != Overflow::Visible
+        || overflow.y != Overflow::Visible
+}
+
+/// Returns clip area of element padding box, corner radii are reduced by adjacent border widths.
+fn padding_box_clipping(element: &Element, parent: Option<Arc<Clipping>>) -> Clipping {
+    let [x, y] = element.position;
+    let [width, height] = element.size;
+    let borders = &element.borders;
+    let [top, right, bottom, left] = [
+        borders.top.width,
+        borders.right.width,
+        borders.bottom.width,
+        borders.left.width,
+    ];
+    let [top_left, top_right, bottom_right, bottom_left] = element.radius;
+    Clipping {
+        position: [x + left, y + top],
+        size: [
+            (width - left - right).max(0.0),
+            (height - top - bottom).max(0.0),
+        ],
+        radius: [
+            (top_left - top.max(left)).max(0.0),
+            (top_right - top.max(right)).max(0.0),
+            (bottom_right - bottom.max(right)).max(0.0),
+            (bottom_left - bottom.max(left)).max(0.0),
+        ],
+        parent,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::testing::setup_tests_logging;
     use crate::*;
     use serde_json::json;
+    use std::cell::Cell;
+    use std::rc::Rc;
     use std::time::Duration;
 
     fn view(html: &str, css: &str) -> View {
@@ -1392,9 +2346,3902 @@ mod tests {
         assert_eq!(output.messages, vec![msg("leave", "A")]);
     }
 
-    fn msg(key: &str, value: &str) -> Value {
-        json!({
-            key: value
-        })
+    #[test]
+    pub fn test_two_way_value_binding() {
+        let css = r#"
+            input {
+                width: 32px;
+                height: 32px;
+            }
+        "#;
+        let html = r#"<html>
+        <body>
+            <input &value="{todo}" />
+        </body>
+        </html>"#;
+        let value = json!({ "todo": "Todo" });
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let focus = Input::new()
+            .event(InputEvent::MouseMove([10.0, 10.0]))
+            .event(InputEvent::MouseButtonDown(MouseButtons::Left))
+            .event(InputEvent::MouseButtonUp(MouseButtons::Left))
+            .event(InputEvent::KeyDown(Keys::End));
+        view.update(focus, value.clone()).expect("valid update");
+        let typing = Input::new()
+            .event(InputEvent::Char('s'))
+            .event(InputEvent::Char('!'))
+            .event(InputEvent::KeyDown(Keys::Backspace));
+        let output = view.update(typing, value).expect("valid update");
+
+        let body = view.body();
+        let input = body.children()[0];
+        assert_eq!(input.attrs.get("value"), Some(&"Todos".to_string()));
+        assert_eq!(
+            output.messages.last(),
+            Some(&json!({"change": {"path": "/todo", "value": "Todos"}}))
+        );
+    }
+
+    #[test]
+    pub fn test_key_filtered_keyboard_callbacks() {
+        let css = r#"
+            input {
+                width: 32px;
+                height: 32px;
+            }
+        "#;
+        let html = r#"<html>
+        <body>
+            <input &value="{todo}" ^onkeydown.enter="submit {todo}" ^onkeyup.escape="close" />
+        </body>
+        </html>"#;
+        let value = json!({ "todo": "Todo" });
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let focus = Input::new()
+            .event(InputEvent::MouseMove([10.0, 10.0]))
+            .event(InputEvent::MouseButtonDown(MouseButtons::Left));
+        view.update(focus, value.clone()).expect("valid update");
+        let keys = Input::new()
+            .event(InputEvent::KeyDown(Keys::ArrowLeft))
+            .event(InputEvent::KeyDown(Keys::Enter))
+            .event(InputEvent::KeyUp(Keys::Enter))
+            .event(InputEvent::KeyUp(Keys::Escape));
+        let output = view.update(keys, value).expect("valid update");
+
+        assert_eq!(
+            output.messages,
+            vec![msg("submit", "Todo"), Value::String("close".to_string())]
+        );
+    }
+
+    #[test]
+    pub fn test_boolean_attributes_semantics() {
+        let css = r#"
+            div {
+                width: 32px;
+                height: 32px;
+            }
+            div:checked {
+                width: 64px;
+            }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div #hidden="{hidden}" id="hidden"></div>
+            <div #checked="{checked}" id="checked"></div>
+            <div #disabled="{disabled}" ^onclick="click" id="disabled"></div>
+            <div hidden></div>
+        </body>
+        </html>"#;
+        let value = json!({ "hidden": true, "checked": true, "disabled": true });
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let click = Input::new()
+            .event(InputEvent::MouseMove([10.0, 40.0]))
+            .event(InputEvent::MouseButtonDown(MouseButtons::Left))
+            .event(InputEvent::MouseButtonUp(MouseButtons::Left));
+        let output = view.update(click, value).expect("valid update");
+
+        let body = view.body();
+        let children = body.children();
+        assert_eq!(children.len(), 2, "hidden elements detached");
+        assert_eq!(children[0].attrs.get("id"), Some(&"checked".to_string()));
+        assert!(children[0].state.checked, "checked state");
+        assert_eq!(children[0].size, [64.0, 32.0]);
+        assert_eq!(children[1].attrs.get("id"), Some(&"disabled".to_string()));
+        assert_eq!(output.messages, Vec::<Value>::new(), "disabled element clicked");
+    }
+
+    #[test]
+    pub fn test_tab_focus_traversal() {
+        let html = r#"<html>
+        <body>
+            <div tabindex="0" ^onfocus="focus a"></div>
+            <div tabindex="-1" ^onfocus="focus b"></div>
+            <div tabindex="0" ^onfocus="focus c" ^onblur="blur c"></div>
+            <div tabindex="1" ^onfocus="focus d" ^onblur="blur d"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "").expect("view valid");
+        let mut messages = vec![];
+        let user_input = vec![
+            vec![InputEvent::KeyDown(Keys::Tab)],
+            vec![InputEvent::KeyDown(Keys::Tab)],
+            vec![InputEvent::KeyDown(Keys::Tab)],
+            vec![InputEvent::KeyDown(Keys::Shift), InputEvent::KeyDown(Keys::Tab)],
+        ];
+        for events in user_input {
+            let output = view
+                .update(Input::new().events(events), json!({}))
+                .expect("valid update");
+            messages.extend(output.messages);
+        }
+        assert_eq!(
+            messages,
+            [
+                msg("focus", "d"),
+                msg("blur", "d"),
+                msg("focus", "a"),
+                msg("focus", "c"),
+                msg("blur", "c"),
+                msg("focus", "a")
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_label_activates_associated_input() {
+        let css = r#"
+            label {
+                display: block;
+                width: 32px;
+                height: 32px;
+            }
+        "#;
+        let html = r#"<html>
+        <body>
+            <label for="agree">Agree</label>
+            <input id="agree" &checked="{agree}" />
+            <label>Name <input &value="{name}" ^onfocus="focus" /></label>
+        </body>
+        </html>"#;
+        let value = json!({ "agree": false, "name": "" });
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut messages = vec![];
+        for y in [10.0, 40.0] {
+            let click = Input::new()
+                .event(InputEvent::MouseMove([10.0, y]))
+                .event(InputEvent::MouseButtonDown(MouseButtons::Left))
+                .event(InputEvent::MouseButtonUp(MouseButtons::Left));
+            let output = view.update(click, value.clone()).expect("valid update");
+            messages.extend(output.messages);
+        }
+
+        assert_eq!(
+            messages,
+            [
+                json!({"change": {"path": "/agree", "value": true}}),
+                Value::String("focus".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_title_tooltip_after_delay() {
+        let css = r#"
+            div {
+                width: 32px;
+                height: 32px;
+            }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div></div>
+            <div @title="{hint}"></div>
+        </body>
+        </html>"#;
+        let value = json!({ "hint": "Save game" });
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .tooltip_delay(Duration::from_secs_f32(0.5));
+        let mut tooltips = vec![];
+        for (time, y) in [(0.1, 40.0), (0.3, 40.0), (0.3, 40.0), (0.1, 10.0)] {
+            let input = input(time).event(InputEvent::MouseMove([10.0, y]));
+            let output = view.update(input, value.clone()).expect("valid update");
+            tooltips.push(output.tooltip.map(|tooltip| tooltip.text));
+        }
+
+        assert_eq!(tooltips, [None, None, Some("Save game".to_string()), None]);
+    }
+
+    #[test]
+    pub fn test_anchor_navigation_message() {
+        let css = r#"
+            a {
+                display: block;
+                width: 32px;
+                height: 32px;
+            }
+        "#;
+        let html = r#"<html>
+        <body>
+            <a href="settings.html"><span>Settings</span></a>
+            <a @href="{url}" target="_blank">Homepage</a>
+        </body>
+        </html>"#;
+        let value = json!({ "url": "https://example.com" });
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let click = Input::new()
+            .event(InputEvent::MouseMove([10.0, 10.0]))
+            .event(InputEvent::MouseButtonDown(MouseButtons::Left))
+            .event(InputEvent::MouseButtonUp(MouseButtons::Left));
+        let output = view.update(click, value.clone()).expect("valid update");
+        let mut messages = output.messages;
+        let keyboard = Input::new()
+            .event(InputEvent::KeyDown(Keys::Tab))
+            .event(InputEvent::KeyDown(Keys::Enter));
+        let output = view.update(keyboard, value).expect("valid update");
+        messages.extend(output.messages);
+
+        assert_eq!(
+            messages,
+            [
+                json!({"navigate": {"href": "settings.html", "target": null}}),
+                json!({"navigate": {"href": "https://example.com", "target": "_blank"}})
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_img_srcset_and_load_lifecycle() {
+        let html = r#"<html>
+        <body>
+            <img src="icon.png" srcset="icon@2x.png 2x, icon@3x.png 3x" ^onload="loaded" />
+            <img @src="{avatar}" ^onerror="failed {name}" />
+        </body>
+        </html>"#;
+        let value = json!({ "avatar": "alice.png", "name": "Alice" });
+        let mut view = View::compile(html, "", "").expect("view valid");
+        let input = Input::new().pixel_ratio(2.0);
+        let output = view.update(input, value.clone()).expect("valid update");
+        assert_eq!(output.resources, ["alice.png", "icon@2x.png"]);
+        let body = view.body();
+        let img = body.children()[0];
+        let background = &img.children()[0].backgrounds[0];
+        assert_eq!(background.image, Some("icon@2x.png".to_string()));
+
+        let input = Input::new()
+            .pixel_ratio(2.0)
+            .resource("icon@2x.png", ResourceState::Loaded)
+            .resource("alice.png", ResourceState::Failed);
+        let output = view.update(input, value).expect("valid update");
+        assert_eq!(output.resources, Vec::<String>::new());
+        assert_eq!(
+            output.messages,
+            [Value::String("loaded".to_string()), msg("failed", "Alice")]
+        );
+    }
+
+    #[test]
+    pub fn test_embedded_style_sheet() {
+        let css = r#"
+            div {
+                width: 32px;
+                height: 32px;
+            }
+        "#;
+        let html = r#"<html>
+        <style>
+            .wide { width: 64px; }
+        </style>
+        <body>
+            <style>
+                .tall { height: 64px; }
+            </style>
+            <div class="wide tall"></div>
+            <div></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let body = view.body();
+        let children = body.children();
+        assert_eq!(children.len(), 2, "style is not rendered");
+        assert_eq!(children[0].size, [64.0, 64.0]);
+        assert_eq!(children[1].size, [32.0, 32.0]);
+    }
+
+    #[test]
+    pub fn test_partial_model_patch() {
+        let html = r#"<html>
+        <body>
+            <div @id="{user.name}"></div>
+            <div *item="3 {items}" @id="{item}"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "").expect("view valid");
+        let value = json!({ "user": { "name": "alice" }, "items": ["a", "b"] });
+        view.update(Input::new(), value).expect("valid update");
+
+        view.patch("/user/name", json!("bob")).expect("valid patch");
+        view.patch("/items", json!(["a", "b", "c"]))
+            .expect("valid patch");
+        view.patch("/items/0", json!("x")).expect("valid patch");
+        view.handle_input(Input::new()).expect("valid update");
+        let body = view.body();
+        let ids: Vec<_> = body
+            .children()
+            .iter()
+            .map(|child| child.attrs.get("id").cloned().unwrap_or_default())
+            .collect();
+        assert_eq!(ids, ["bob", "x", "b", "c"]);
+        assert!(view.patch("/user/age", json!(42)).is_err(), "unknown value");
+    }
+
+    #[test]
+    pub fn test_typed_messages() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        enum Message {
+            Close,
+            Select(String),
+            Move(i32, i32),
+        }
+        let html = r#"<html>
+        <body>
+            <div ^onclick="Close"></div>
+            <div ^onclick="Select {name}"></div>
+            <div ^onclick="Move {x} {y}"></div>
+            <div ^onclick="Unknown"></div>
+        </body>
+        </html>"#;
+        let css = "div { width: 10px; height: 10px; }";
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let value = json!({ "name": "Alice", "x": 1, "y": 2 });
+        let mut messages = vec![];
+        for y in [5.0, 15.0, 25.0, 35.0] {
+            let user_input = [
+                InputEvent::MouseMove([5.0, y]),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+                InputEvent::MouseButtonUp(MouseButtons::Left),
+            ];
+            for event in user_input {
+                let input = Input::new().event(event);
+                let output = view.update(input, value.clone()).expect("valid update");
+                messages.extend(output.messages_as::<Message>());
+            }
+        }
+        assert_eq!(
+            messages,
+            [
+                Message::Close,
+                Message::Select("Alice".to_string()),
+                Message::Move(1, 2)
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_stateful_pipe() {
+        let html = r#"<html>
+        <body>
+            <div @id="{status | translate}"></div>
+            <div @id="{counter | calls}"></div>
+        </body>
+        </html>"#;
+        let table = HashMap::from([("done".to_string(), "fertig".to_string())]);
+        let mut calls = 0;
+        let mut view = View::compile(html, "", "")
+            .expect("view valid")
+            .pipe("translate", move |value| {
+                let key = value.eval_string();
+                table.get(&key).cloned().unwrap_or(key).into()
+            })
+            .pipe("calls", move |_| {
+                calls += 1;
+                calls.into()
+            });
+        for counter in [1, 2, 3] {
+            let value = json!({ "status": "done", "counter": counter });
+            view.update(Input::new(), value).expect("valid update");
+        }
+        let body = view.body();
+        let children = body.children();
+        assert_eq!(children[0].attrs.get("id"), Some(&"fertig".to_string()));
+        assert_eq!(children[1].attrs.get("id"), Some(&"3".to_string()));
+    }
+
+    #[test]
+    pub fn test_pipe_arguments() {
+        let html = r#"<html>
+        <body>
+            <div>{price | round:2 | prefix:"$"}</div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "")
+            .expect("view valid")
+            .pipe_with_arguments("round", |value, arguments| {
+                let digits = arguments[0].as_u64().unwrap_or(0) as usize;
+                let value = value.as_f64().unwrap_or_default();
+                format!("{value:.digits$}").into()
+            })
+            .pipe_with_arguments("prefix", |value, arguments| {
+                format!("{}{}", arguments[0].eval_string(), value.eval_string()).into()
+            });
+        let value = json!({ "price": 1.23456 });
+        view.update(Input::new(), value).expect("valid update");
+        let body = view.body();
+        let text = body.children()[0].children()[0]
+            .text
+            .as_ref()
+            .map(|text| text.to_string());
+        assert_eq!(text, Some("$1.23".to_string()));
+    }
+
+    #[test]
+    pub fn test_localized_text() {
+        let html = r#"<html>
+        <body>
+            <div>{t "menu.start"}</div>
+            <div>{t "greeting" name=user.name}</div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "")
+            .expect("view valid")
+            .translations(
+                "en",
+                [("menu.start", "Start"), ("greeting", "Hello, {name}!")],
+            )
+            .translations(
+                "de",
+                [("menu.start", "Starten"), ("greeting", "Hallo, {name}!")],
+            )
+            .locale("en");
+        let texts = |view: &View| -> Vec<String> {
+            let body = view.body();
+            body.children()
+                .iter()
+                .map(|div| div.children()[0].text.as_ref().unwrap().to_string())
+                .collect()
+        };
+        let value = json!({ "user": { "name": "Alice" } });
+        view.update(Input::new(), value).expect("valid update");
+        assert_eq!(texts(&view), ["Start", "Hello, Alice!"]);
+
+        view.set_locale("de");
+        let value = json!({ "user": { "name": "Bob" } });
+        view.update(Input::new(), value).expect("valid update");
+        assert_eq!(texts(&view), ["Starten", "Hallo, Bob!"]);
+    }
+
+    #[test]
+    pub fn test_localized_plural_text() {
+        let html = r#"<html>
+        <body>
+            <div>{t "items" count=cart.size}</div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "")
+            .expect("view valid")
+            .translations(
+                "en",
+                [
+                    ("items.one", "{count} item"),
+                    ("items.other", "{count} items"),
+                ],
+            )
+            .translations(
+                "ru",
+                [
+                    ("items.one", "{count} товар"),
+                    ("items.few", "{count} товара"),
+                    ("items.many", "{count} товаров"),
+                ],
+            )
+            .locale("en");
+        let mut text = |locale: &str, size: u32| -> String {
+            view.set_locale(locale);
+            let value = json!({ "cart": { "size": size } });
+            view.update(Input::new(), value).expect("valid update");
+            let body = view.body();
+            let div = body.children()[0];
+            let text = div.children()[0].text.as_ref().unwrap().to_string();
+            text
+        };
+        assert_eq!(text("en", 1), "1 item");
+        assert_eq!(text("en", 5), "5 items");
+        assert_eq!(text("ru", 1), "1 товар");
+        assert_eq!(text("ru", 3), "3 товара");
+        assert_eq!(text("ru", 5), "5 товаров");
+    }
+
+    #[test]
+    pub fn test_repeat_with_array_pipes() {
+        let html = r#"<html>
+        <body>
+            <div *item="5 {items | filter:"visible" | sort:"name"}" @id="{item.name}"
+                ^onclick="select {item.name}"></div>
+        </body>
+        </html>"#;
+        let css = "div { width: 10px; height: 10px; }";
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let ids = |view: &View| -> Vec<String> {
+            let body = view.body();
+            body.children()
+                .iter()
+                .map(|div| div.attrs.get("id").cloned().unwrap_or_default())
+                .collect()
+        };
+        let value = json!({ "items": [
+            { "name": "c", "visible": true },
+            { "name": "b", "visible": false },
+            { "name": "a", "visible": true },
+        ]});
+        view.update(Input::new(), value).expect("valid update");
+        assert_eq!(ids(&view), ["a", "c"]);
+
+        let value = json!({ "items": [
+            { "name": "c", "visible": true },
+            { "name": "b", "visible": true },
+            { "name": "a", "visible": true },
+        ]});
+        view.update(Input::new(), value.clone())
+            .expect("valid update");
+        assert_eq!(ids(&view), ["a", "b", "c"]);
+
+        let mut messages = vec![];
+        for event in [
+            InputEvent::MouseMove([5.0, 15.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ] {
+            let output = view
+                .update(Input::new().event(event), value.clone())
+                .expect("valid update");
+            messages.extend(output.messages);
+        }
+        assert_eq!(messages, [msg("select", "b")]);
+    }
+
+    #[test]
+    pub fn test_shared_pipe_transformed_once() {
+        let html = r#"<html>
+        <body>
+            <div @id="{counter | calls}"></div>
+            <div @title="{counter | calls}">{counter | calls}</div>
+        </body>
+        </html>"#;
+        let mut calls = 0;
+        let mut view = View::compile(html, "", "")
+            .expect("view valid")
+            .pipe("calls", move |_| {
+                calls += 1;
+                calls.into()
+            });
+        for _ in 0..3 {
+            let value = json!({ "counter": 1 });
+            view.update(Input::new(), value).expect("valid update");
+        }
+        let body = view.body();
+        let children = body.children();
+        assert_eq!(children[0].attrs.get("id"), Some(&"1".to_string()));
+        assert_eq!(children[1].attrs.get("title"), Some(&"1".to_string()));
+    }
+
+    #[test]
+    pub fn test_derived_value_recomputed_on_source_change() {
+        let html = r#"<html>
+        <body>
+            <h1>{title}</h1>
+            <div *item="5 {items | counted}" @id="{item.name}"></div>
+        </body>
+        </html>"#;
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let mut view =
+            View::compile(html, "", "")
+                .expect("view valid")
+                .pipe("counted", move |value| {
+                    counter.set(counter.get() + 1);
+                    value
+                });
+        let value = json!({ "title": "A", "items": [{ "name": "a" }, { "name": "b" }] });
+        view.update(Input::new(), value).expect("valid update");
+        assert_eq!(calls.get(), 1);
+        view.patch("/title", json!("B")).expect("valid patch");
+        assert_eq!(calls.get(), 1, "unrelated source");
+        view.patch("/items/1/name", json!("c"))
+            .expect("valid patch");
+        assert_eq!(calls.get(), 2);
+        let body = view.body();
+        let children = body.children();
+        assert_eq!(children[2].attrs.get("id"), Some(&"c".to_string()));
+    }
+
+    #[test]
+    pub fn test_model_read_access() {
+        let html = r#"<html>
+        <body>
+            <div @id="{user.name}">{items.0}</div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "").expect("view valid");
+        let value = json!({ "user": { "name": "Alice", "age": 42 }, "items": ["a", "b"] });
+        view.update(Input::new(), value).expect("valid update");
+        assert_eq!(view.value_at("/user/name"), Some(&json!("Alice")));
+        assert_eq!(view.value_at("/user/age"), None, "unused value");
+        assert_eq!(
+            view.model(),
+            &json!({ "user": { "name": "Alice" }, "items": ["a", "b"] })
+        );
+    }
+
+    #[test]
+    pub fn test_binding_validation_report() {
+        let html = r#"<html>
+        <body>
+            <div @id="{user.name | upper}">{user.title}</div>
+            <div *item="3 {items | sort}" ^onclick="select {item.id | missing}">{item.label}</div>
+        </body>
+        </html>"#;
+        let view = View::compile(html, "", "").expect("view valid");
+        let value = json!({
+            "user": { "name": "Alice" },
+            "items": [{ "id": 1, "label": "a" }, { "id": 2 }]
+        });
+        let report = view.validate(&value).expect("valid report");
+        assert_eq!(report.missing_values, ["/items/1/label", "/user/title"]);
+        assert_eq!(report.unknown_pipes, ["missing", "upper"]);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    pub fn test_model_schema_export() {
+        let html = r#"<html>
+        <body>
+            <div @id="{user.name}"></div>
+            <div *item="3 {items | sort}">{item.label}</div>
+            <div *tag="2 {tags}">{tag}</div>
+        </body>
+        </html>"#;
+        let view = View::compile(html, "", "").expect("view valid");
+        assert_eq!(
+            view.schema(),
+            json!({
+                "user": { "name": null },
+                "items": [{ "label": null }],
+                "tags": [null]
+            })
+        );
+        assert_eq!(
+            view.json_schema(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "items": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": { "label": {} },
+                            "required": ["label"]
+                        }
+                    },
+                    "tags": { "type": "array", "items": {} },
+                    "user": {
+                        "type": "object",
+                        "properties": { "name": {} },
+                        "required": ["name"]
+                    }
+                },
+                "required": ["items", "tags", "user"]
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_keyed_repeat_preserves_row_elements() {
+        let html = r#"<html>
+        <body>
+            <div id="start"></div>
+            <div *row="4 {rows} by id" @id="{row.name}"></div>
+            <div id="end"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "").expect("view valid");
+        let rows = |view: &View| -> Vec<(String, NodeId)> {
+            let body = view.body();
+            body.children()
+                .iter()
+                .map(|row| {
+                    let id = row.attrs.get("id").cloned().unwrap_or_default();
+                    (id, row.node)
+                })
+                .collect()
+        };
+        let value = json!({ "rows": [{ "id": 1, "name": "a" }, { "id": 2, "name": "b" }] });
+        view.update(Input::new(), value).expect("valid update");
+        let before = rows(&view);
+        let ids: Vec<&str> = before.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["start", "a", "b", "end"]);
+
+        let value = json!({ "rows": [
+            { "id": 3, "name": "c" },
+            { "id": 1, "name": "a" },
+            { "id": 2, "name": "b" }
+        ]});
+        let reactions = view.model.bind(&value);
+        let retyped = reactions
+            .iter()
+            .filter(|reaction| matches!(reaction, Reaction::Bind { .. }))
+            .count();
+        assert_eq!(retyped, 1, "only new row bound");
+        for reaction in reactions {
+            view.update_tree(reaction).expect("valid reaction");
+        }
+        view.handle_input(Input::new()).expect("valid update");
+        let after = rows(&view);
+        let ids: Vec<&str> = after.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["start", "c", "a", "b", "end"]);
+        assert_eq!(after[2].1, before[1].1, "row a element preserved");
+        assert_eq!(after[3].1, before[2].1, "row b element preserved");
+
+        let value = json!({ "rows": [{ "id": 2, "name": "b" }] });
+        view.update(Input::new(), value).expect("valid update");
+        let after = rows(&view);
+        let ids: Vec<&str> = after.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["start", "b", "end"]);
+        assert_eq!(after[1].1, before[2].1, "row b element preserved");
+        let items = view.model.model.pointer("/rows").and_then(Value::as_array);
+        assert_eq!(
+            items.map(Vec::len),
+            Some(2),
+            "truncated after last used slot"
+        );
+
+        let value = json!({ "rows": [
+            { "id": 2, "name": "b" },
+            { "id": 4, "name": "d" },
+            { "id": 5, "name": "e" }
+        ]});
+        view.update(Input::new(), value).expect("valid update");
+        let after = rows(&view);
+        let ids: Vec<&str> = after.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["start", "b", "d", "e", "end"]);
+    }
+
+    #[test]
+    pub fn test_local_state() {
+        let html = r#"<html>
+        <body>
+            <div *panel="2 {panels}" $expanded="false" @id="{panel}-{expanded}">
+                <span ^onclick="toggle {expanded}"></span>
+            </div>
+        </body>
+        </html>"#;
+        let css = "div, span { display: block; width: 10px; height: 10px; }";
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let ids = |view: &View| -> Vec<String> {
+            let body = view.body();
+            body.children()
+                .iter()
+                .map(|panel| panel.attrs.get("id").cloned().unwrap_or_default())
+                .collect()
+        };
+        let value = json!({ "panels": ["a", "b"] });
+        view.update(Input::new(), value.clone())
+            .expect("valid update");
+        assert_eq!(ids(&view), ["a-false", "b-false"]);
+        for y in [15.0, 5.0, 15.0] {
+            let user_input = [
+                InputEvent::MouseMove([5.0, y]),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+                InputEvent::MouseButtonUp(MouseButtons::Left),
+            ];
+            for event in user_input {
+                let input = Input::new().event(event);
+                let output = view.update(input, value.clone()).expect("valid update");
+                assert!(output.messages.is_empty(), "local state not in messages");
+            }
+        }
+        assert_eq!(ids(&view), ["a-true", "b-false"]);
+        assert_eq!(view.schema(), json!({ "panels": [null] }));
+    }
+
+    #[test]
+    pub fn test_context_values() {
+        let html = r##"<html>
+            <template id="item">
+                <div @id="{item}-{theme.color}"></div>
+            </template>
+            <body>
+                <link href="#item" *item="2 {items}" />
+                <div @id="{player}"></div>
+            </body>
+        </html>"##;
+        let mut view = View::compile(html, "", "")
+            .expect("view valid")
+            .context("theme", json!({ "color": "red" }));
+        let ids = |view: &View| -> Vec<String> {
+            let body = view.body();
+            body.children()
+                .iter()
+                .map(|child| child.attrs.get("id").cloned().unwrap_or_default())
+                .collect()
+        };
+        let value = json!({ "items": ["a", "b"], "player": "Alice" });
+        view.update(Input::new(), value.clone())
+            .expect("valid update");
+        assert_eq!(ids(&view), ["a-red", "b-red", "Alice"]);
+        view.set_context("theme", json!({ "color": "blue" }));
+        view.update(Input::new(), value).expect("valid update");
+        assert_eq!(ids(&view), ["a-blue", "b-blue", "Alice"]);
+        assert_eq!(view.schema(), json!({ "items": [null], "player": null }));
+    }
+
+    #[test]
+    pub fn test_pending_values() {
+        let html = r#"<html>
+        <body>
+            <p>{user.name}</p>
+            <p @id="{score}"></p>
+            <p>static</p>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "").expect("view valid");
+        let loading = |view: &View| -> Vec<(bool, String)> {
+            let body = view.body();
+            body.children()
+                .iter()
+                .map(|child| {
+                    let element = view.tree.get_element(child.node).expect("element");
+                    let text = child.children().first().map(|text| text.text.clone());
+                    let text = text.flatten().map(|text| text.to_string());
+                    let loading = view.has_pseudo_class(element, "loading");
+                    (loading, text.unwrap_or_default())
+                })
+                .collect()
+        };
+        let value = json!({ "user": pending(), "score": pending() });
+        view.update(Input::new(), value).expect("valid update");
+        assert_eq!(
+            loading(&view),
+            [
+                (true, "".to_string()),
+                (true, "".to_string()),
+                (false, "static".to_string())
+            ]
+        );
+        let value = json!({ "user": { "name": "Alice" }, "score": pending() });
+        view.update(Input::new(), value).expect("valid update");
+        assert_eq!(
+            loading(&view),
+            [
+                (false, "Alice".to_string()),
+                (true, "".to_string()),
+                (false, "static".to_string())
+            ]
+        );
+        let value = json!({ "user": { "name": "Alice" }, "score": 42 });
+        let report = view.validate(&value).expect("valid report");
+        assert!(report.is_valid());
+        view.update(Input::new(), value).expect("valid update");
+        let body = view.body();
+        assert_eq!(body.children()[1].attrs.get("id"), Some(&"42".to_string()));
+        assert!(loading(&view).iter().all(|(loading, _)| !loading));
+    }
+
+    #[test]
+    pub fn test_event_field_arguments() {
+        let html = r#"<html>
+        <body>
+            <div ^onclick="Click $event.position.x $event.target.size.width $event.missing"></div>
+        </body>
+        </html>"#;
+        let css = "div { width: 10px; height: 20px; }";
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut messages = vec![];
+        let user_input = [
+            InputEvent::MouseMove([4.0, 5.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ];
+        for event in user_input {
+            let input = Input::new().event(event);
+            let output = view.update(input, json!({})).expect("valid update");
+            messages.extend(output.messages);
+        }
+        assert_eq!(
+            messages,
+            [json!({ "Click": [4.0, 10.0, null] })],
+            "missing field keeps argument position"
+        );
+    }
+
+    #[test]
+    pub fn test_multiple_event_handlers() {
+        let html = r##"<html>
+            <template id="button">
+                <div ^onclick="Sound click"></div>
+            </template>
+            <body>
+                <link href="#button" ^onclick="Buy {item}" />
+            </body>
+        </html>"##;
+        let css = "div { width: 10px; height: 10px; }";
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut messages = vec![];
+        let user_input = [
+            InputEvent::MouseMove([5.0, 5.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ];
+        for event in user_input {
+            let input = Input::new().event(event);
+            let output = view
+                .update(input, json!({ "item": 7 }))
+                .expect("valid update");
+            messages.extend(output.messages);
+        }
+        assert_eq!(messages, [json!({ "Buy": 7 }), json!({ "Sound": "click" })]);
+    }
+
+    #[test]
+    pub fn test_handler_rate_limits() {
+        let html = r#"<html>
+        <body>
+            <div ^onmousemove.throttle:100ms="Move $event.position.x"></div>
+            <div ^onmousemove.debounce:100ms="Rest $event.position.x"></div>
+        </body>
+        </html>"#;
+        let css = "div { width: 100px; height: 10px; }";
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut messages = vec![];
+        for (time, x, y) in [
+            (0.0, 1.0, 5.0),
+            (0.05, 2.0, 5.0),
+            (0.05, 3.0, 5.0),
+            (0.05, 4.0, 15.0),
+            (0.05, 5.0, 15.0),
+            (0.05, 5.0, 25.0),
+            (0.05, 5.0, 25.0),
+        ] {
+            let input = input(time).event(InputEvent::MouseMove([x, y]));
+            let output = view.update(input, json!({})).expect("valid update");
+            messages.extend(output.messages);
+        }
+        assert_eq!(
+            messages,
+            [
+                json!({ "Move": 1.0 }),
+                json!({ "Move": 3.0 }),
+                json!({ "Rest": 5.0 })
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_output_calls() {
+        let html = r#"<html>
+        <body>
+            <div ^onclick="Close"></div>
+            <div ^onclick="Move {x} {y}"></div>
+            <div ^onclick="Select {name}"></div>
+        </body>
+        </html>"#;
+        let css = "div { width: 10px; height: 10px; }";
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let value = json!({ "name": "Alice", "x": 1, "y": 2 });
+        let mut calls = vec![];
+        for y in [5.0, 15.0, 25.0] {
+            let user_input = [
+                InputEvent::MouseMove([5.0, y]),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+                InputEvent::MouseButtonUp(MouseButtons::Left),
+            ];
+            for event in user_input {
+                let input = Input::new().event(event);
+                let output = view.update(input, value.clone()).expect("valid update");
+                for call in output {
+                    let description = match call.signature() {
+                        ("Close", []) => "close".to_string(),
+                        ("Move", [x, y]) => format!("move {x} {y}"),
+                        _ => call.describe(),
+                    };
+                    calls.push(description);
+                }
+            }
+        }
+        assert_eq!(calls, ["close", "move 1 2", "Select(\"Alice\")"]);
+    }
+
+    #[test]
+    pub fn test_programmatic_focus() {
+        let html = r#"<html>
+        <body>
+            <input id="search" ^onfocus="focus search" ^onblur="blur search" />
+            <input id="name" ^onfocus="focus name" ^onblur="blur name" />
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "").expect("view valid");
+        let mut messages = vec![];
+        view.focus("search").expect("focus valid");
+        let output = view.update(Input::new(), json!({})).expect("valid update");
+        messages.extend(output.messages);
+        assert!(view.get_element_by_id("search").unwrap().state.focus);
+        view.focus("name").expect("focus valid");
+        view.blur().expect("blur valid");
+        let output = view.update(Input::new(), json!({})).expect("valid update");
+        messages.extend(output.messages);
+        assert!(view.focus("unknown").is_err());
+        assert_eq!(
+            messages,
+            [
+                msg("focus", "search"),
+                msg("blur", "search"),
+                msg("focus", "name"),
+                msg("blur", "name")
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_element_state_queries() {
+        let html = r#"<html>
+        <body>
+            <div id="button"></div>
+            <input id="search" />
+            <div id="list"><div id="content"></div></div>
+        </body>
+        </html>"#;
+        let css = r#"
+            div { width: 10px; height: 10px; }
+            #list { overflow: scroll; }
+            #content { height: 100px; }
+        "#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let input = Input::new().event(InputEvent::MouseMove([5.0, 5.0]));
+        view.update(input, json!({})).expect("valid update");
+        assert!(view.is_hovered("button"));
+        assert!(!view.is_active("button"));
+        assert!(!view.is_focused("search"));
+        assert!(!view.is_text_input_focused());
+        assert_eq!(view.scroll_offset("list"), Some([0.0, 0.0]));
+        assert_eq!(view.scroll_offset("button"), None);
+        view.focus("search").expect("focus valid");
+        assert!(view.is_focused("search"));
+        assert!(view.is_text_input_focused());
+        assert!(!view.is_hovered("unknown"));
+    }
+
+    #[test]
+    pub fn test_runtime_inline_style() {
+        let html = r#"<html>
+        <body>
+            <div id="panel" style="width: 10px; height: 20px;"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let size = |view: &View| view.get_element_by_id("panel").unwrap().size;
+        assert_eq!(size(&view), [10.0, 20.0]);
+        view.set_inline_style("panel", "width: 30px; opacity: 0.5;")
+            .expect("style valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        assert_eq!(size(&view), [30.0, 20.0]);
+        assert_eq!(view.get_element_by_id("panel").unwrap().opacity, 0.5);
+        assert!(view.set_inline_style("unknown", "width: 1px;").is_err());
+    }
+
+    #[test]
+    pub fn test_query_selector() {
+        let html = r#"<html>
+        <body>
+            <div class="slot" data-target="a"></div>
+            <div class="slot"></div>
+            <section>
+                <div class="slot" data-target="b">text</div>
+            </section>
+        </body>
+        </html>"#;
+        let view = View::compile(html, "", "").expect("view valid");
+        let targets = |selector: &str| -> Vec<String> {
+            let elements = view.query_selector_all(selector).expect("selector valid");
+            elements
+                .iter()
+                .map(|element| {
+                    element
+                        .attrs
+                        .get("data-target")
+                        .cloned()
+                        .unwrap_or_default()
+                })
+                .collect()
+        };
+        assert_eq!(targets("div.slot[data-target]"), ["a", "b"]);
+        assert_eq!(targets(".slot"), ["a", "", "b"]);
+        assert_eq!(targets("section > .slot"), ["b"]);
+        assert_eq!(targets("span"), Vec::<String>::new());
+        let first = view
+            .query_selector("[data-target]")
+            .expect("selector valid");
+        assert_eq!(first.map(|element| element.tag.clone()), Some("div".into()));
+    }
+
+    #[test]
+    pub fn test_save_and_restore_view_state() {
+        let html = r#"<html>
+        <body>
+            <div id="list"><div class="content"></div></div>
+            <input ^oninput="Type $event.char" />
+            <input type="checkbox" id="agree" &checked="{agree}" />
+        </body>
+        </html>"#;
+        let css = r#"
+            div { width: 10px; height: 10px; }
+            #list { overflow: scroll; }
+            .content { height: 100px; }
+            input { width: 10px; height: 10px; }
+        "#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let value = json!({ "agree": false });
+        let events = [
+            InputEvent::MouseMove([5.0, 15.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+            InputEvent::Char('a'),
+        ];
+        for event in events {
+            let input = Input::new().event(event);
+            view.update(input, value.clone()).expect("valid update");
+        }
+        let list = view.identified["list"];
+        let list = view.tree.get_element_mut(list).expect("list element");
+        list.scrolling
+            .as_mut()
+            .expect("scrolling")
+            .offset([0.0, -1.0]);
+        let snapshot = view.save_state().expect("state valid");
+        assert_eq!(snapshot.focus, Some("/1".to_string()));
+        assert_eq!(snapshot.elements["#list"].scroll, Some([0.0, 50.0]));
+
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.restore_state(&snapshot).expect("state valid");
+        view.update(Input::new(), value).expect("valid update");
+        assert_eq!(view.scroll_offset("list"), Some([0.0, 50.0]));
+        assert_eq!(view.save_state().expect("state valid"), snapshot);
+        let json = serde_json::to_value(&snapshot).expect("serializable");
+        let restored: ViewSnapshot = serde_json::from_value(json).expect("deserializable");
+        assert_eq!(restored, snapshot);
+    }
+
+    #[test]
+    pub fn test_text_input_undo_redo() {
+        let css = "input { width: 32px; height: 32px; }";
+        let html = r#"<html>
+        <body>
+            <input &value="{name}" />
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut value = json!({ "name": "" });
+        let mut update = |view: &mut View, events: Vec<InputEvent>| -> String {
+            let input = Input::new().events(events);
+            let output = view.update(input, value.clone()).expect("valid update");
+            for message in output.messages {
+                value["name"] = message["change"]["value"].clone();
+            }
+            value["name"].as_str().unwrap_or_default().to_string()
+        };
+        let focus = vec![
+            InputEvent::MouseMove([10.0, 10.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ];
+        update(&mut view, focus);
+        let typing = "hi there".chars().map(InputEvent::Char).collect();
+        assert_eq!(update(&mut view, typing), "hi there");
+        let erase = vec![InputEvent::KeyDown(Keys::Backspace); 2];
+        assert_eq!(update(&mut view, erase), "hi the");
+        let undo = vec![
+            InputEvent::KeyDown(Keys::Ctrl),
+            InputEvent::Char('\u{1a}'),
+            InputEvent::KeyUp(Keys::Ctrl),
+        ];
+        assert_eq!(update(&mut view, undo.clone()), "hi there");
+        assert_eq!(update(&mut view, undo.clone()), "hi ");
+        assert_eq!(update(&mut view, undo.clone()), "");
+        assert_eq!(update(&mut view, undo), "");
+        let redo = vec![
+            InputEvent::KeyDown(Keys::Ctrl),
+            InputEvent::Char('y'),
+            InputEvent::KeyUp(Keys::Ctrl),
+        ];
+        assert_eq!(update(&mut view, redo.clone()), "hi ");
+        assert_eq!(update(&mut view, redo), "hi there");
+    }
+
+    #[test]
+    pub fn test_text_input_clipboard() {
+        let css = "input { width: 32px; height: 32px; }";
+        let html = r#"<html>
+        <body>
+            <input &value="{name}" ^oncopy="Copy $event.text" ^onpaste="Paste $event.text" />
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut value = json!({ "name": "" });
+        let update = |view: &mut View, value: &mut Value, events: Vec<InputEvent>| -> Output {
+            let input = Input::new().events(events);
+            let output = view.update(input, value.clone()).expect("valid update");
+            for message in &output.messages {
+                if let Some(change) = message.get("change") {
+                    value["name"] = change["value"].clone();
+                }
+            }
+            output
+        };
+        let focus = vec![
+            InputEvent::MouseMove([10.0, 10.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ];
+        update(&mut view, &mut value, focus);
+        let typing = "hello".chars().map(InputEvent::Char).collect();
+        update(&mut view, &mut value, typing);
+        let shortcut = |char: char| {
+            vec![
+                InputEvent::KeyDown(Keys::Ctrl),
+                InputEvent::Char(char),
+                InputEvent::KeyUp(Keys::Ctrl),
+            ]
+        };
+        let output = update(&mut view, &mut value, shortcut('c'));
+        assert_eq!(output.clipboard.as_deref(), Some("hello"));
+        assert_eq!(output.messages, vec![json!({"Copy": "hello"})]);
+        let output = update(&mut view, &mut value, shortcut('\u{18}'));
+        assert_eq!(output.clipboard.as_deref(), Some("hello"));
+        assert_eq!(value["name"], json!(""));
+        let output = update(&mut view, &mut value, shortcut('v'));
+        assert!(output.paste_requested);
+        assert_eq!(output.clipboard, None);
+        let output = update(
+            &mut view,
+            &mut value,
+            vec![InputEvent::Paste("world".to_string())],
+        );
+        assert_eq!(output.messages[0], json!({"Paste": "world"}));
+        assert_eq!(value["name"], json!("world"));
+        let output = update(&mut view, &mut value, vec![InputEvent::Copy]);
+        assert_eq!(output.clipboard.as_deref(), Some("world"));
+        assert!(!output.paste_requested);
+    }
+
+    #[test]
+    pub fn test_drag_and_drop_payload() {
+        let css = "body { display: flex; } div { width: 20px; height: 20px; }";
+        let html = r#"<html>
+        <body>
+            <div *item="3 {items}" draggable="true" @data-drag="{item.id}"></div>
+            <div ^ondrop="Drop $event.data"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let value = json!({ "items": [{ "id": 1 }, { "id": 2 }, { "id": 3 }] });
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            view.update(input, value.clone()).expect("valid update")
+        };
+        let output = update(vec![
+            InputEvent::MouseMove([25.0, 5.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+        ]);
+        let drag = output.drag.expect("active drag");
+        assert_eq!(drag.data, json!(2));
+        assert_eq!(drag.offset, [5.0, 5.0]);
+        assert_eq!(drag.size, [20.0, 20.0]);
+        let output = update(vec![InputEvent::MouseMove([65.0, 10.0])]);
+        assert_eq!(output.drag.map(|drag| drag.position), Some([65.0, 10.0]));
+        let output = update(vec![InputEvent::MouseButtonUp(MouseButtons::Left)]);
+        assert_eq!(output.messages, vec![json!({"Drop": 2})]);
+        assert_eq!(output.drag, None);
+        let output = update(vec![
+            InputEvent::MouseMove([5.0, 5.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseMove([5.0, 50.0]),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ]);
+        assert!(output.messages.is_empty());
+        assert_eq!(output.drag, None, "drag ends outside of drop target");
+    }
+
+    #[test]
+    pub fn test_double_click_and_click_count() {
+        let css = "div { width: 20px; height: 20px; }";
+        let html = r#"<html>
+        <body>
+            <div ^onclick="Select $event.detail" ^ondblclick="Use"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .double_click_interval(Duration::from_secs_f32(0.3));
+        let mut click = |time: f32| {
+            let events = vec![
+                InputEvent::MouseMove([10.0, 10.0]),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+                InputEvent::MouseButtonUp(MouseButtons::Left),
+            ];
+            let input = Input::new()
+                .time(Duration::from_secs_f32(time))
+                .events(events);
+            view.update(input, json!({}))
+                .expect("valid update")
+                .messages
+        };
+        assert_eq!(click(0.0), vec![json!({"Select": 1})]);
+        assert_eq!(click(0.1), vec![json!({"Select": 2}), json!("Use")]);
+        assert_eq!(click(0.1), vec![json!({"Select": 3})]);
+        assert_eq!(click(0.5), vec![json!({"Select": 1})]);
+    }
+
+    #[test]
+    pub fn test_wheel_handlers() {
+        let html = r#"<html>
+        <body>
+            <div id="list" ^onwheel="Wheel $event.delta.y">
+                <div class="map" ^onwheel.prevent="Zoom $event.delta.y"></div>
+                <div class="content"></div>
+            </div>
+        </body>
+        </html>"#;
+        let css = r#"
+            #list { width: 50px; height: 50px; overflow: scroll; }
+            .map { width: 50px; height: 20px; }
+            .content { height: 200px; }
+        "#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let wheel = |view: &mut View, position: [f32; 2]| {
+            let events = vec![
+                InputEvent::MouseMove(position),
+                InputEvent::MouseWheel([0.0, -1.0]),
+            ];
+            let input = Input::new().events(events);
+            view.update(input, json!({}))
+                .expect("valid update")
+                .messages
+        };
+        assert_eq!(
+            wheel(&mut view, [10.0, 10.0]),
+            vec![json!({"Zoom": -1.0}), json!({"Wheel": -1.0})]
+        );
+        assert_eq!(
+            view.scroll_offset("list"),
+            Some([0.0, 0.0]),
+            "scrolling prevented"
+        );
+        assert_eq!(wheel(&mut view, [10.0, 40.0]), vec![json!({"Wheel": -1.0})]);
+        assert_eq!(view.scroll_offset("list"), Some([0.0, 50.0]));
+    }
+
+    #[test]
+    pub fn test_hold_gesture() {
+        let css = "div { width: 20px; height: 20px; }";
+        let html = r#"<html>
+        <body>
+            <div ^onclick="Select" ^onhold="Inspect"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .hold_duration(Duration::from_secs_f32(0.5));
+        let mut update = |time: f32, events: Vec<InputEvent>| {
+            let input = Input::new()
+                .time(Duration::from_secs_f32(time))
+                .events(events);
+            view.update(input, json!({}))
+                .expect("valid update")
+                .messages
+        };
+        let press = || {
+            vec![
+                InputEvent::MouseMove([10.0, 10.0]),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+            ]
+        };
+        let release = || vec![InputEvent::MouseButtonUp(MouseButtons::Left)];
+        assert!(update(0.0, press()).is_empty());
+        assert!(update(0.3, vec![]).is_empty());
+        assert_eq!(update(0.3, vec![]), vec![json!("Inspect")]);
+        assert!(update(0.3, vec![]).is_empty(), "hold emitted once");
+        assert!(update(0.0, release()).is_empty(), "hold suppresses click");
+        assert!(update(0.0, press()).is_empty());
+        assert_eq!(update(0.2, release()), vec![json!("Select")]);
+    }
+
+    #[test]
+    pub fn test_resize_observers() {
+        let css = "#canvas { width: 50vw; height: 20px; }";
+        let html = r#"<html>
+        <body>
+            <div id="canvas" ^onresize="Resize $event.target.size.width $event.previous.width">
+            </div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut update = |viewport: [f32; 2]| {
+            let input = Input::new().viewport(viewport);
+            view.update(input, json!({})).expect("valid update")
+        };
+        let output = update([100.0, 100.0]);
+        assert_eq!(output.messages, vec![json!({"Resize": [50.0, 0.0]})]);
+        let resized = vec![Resize {
+            id: "canvas".to_string(),
+            size: [50.0, 20.0],
+        }];
+        assert_eq!(output.resized, resized);
+        let output = update([100.0, 100.0]);
+        assert!(output.messages.is_empty());
+        assert!(output.resized.is_empty());
+        let output = update([200.0, 200.0]);
+        assert_eq!(output.messages, vec![json!({"Resize": [100.0, 50.0]})]);
+        assert_eq!(output.resized.len(), 1);
+    }
+
+    #[test]
+    pub fn test_visibility_observers() {
+        let html = r#"<html>
+        <body>
+            <div id="list">
+                <div class="item" *item="4 {items}" ^onappear="Load {item}" ^ondisappear="Unload {item}"></div>
+            </div>
+        </body>
+        </html>"#;
+        let css = r#"
+            #list { width: 20px; height: 50px; overflow: scroll; }
+            .item { height: 20px; flex-shrink: 0; }
+        "#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let value = json!({ "items": [1, 2, 3, 4] });
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            view.update(input, value.clone())
+                .expect("valid update")
+                .messages
+        };
+        let loaded = vec![json!({"Load": 1}), json!({"Load": 2}), json!({"Load": 3})];
+        assert_eq!(update(vec![]), loaded);
+        assert!(update(vec![]).is_empty());
+        let scroll = vec![
+            InputEvent::MouseMove([10.0, 10.0]),
+            InputEvent::MouseWheel([0.0, -1.0]),
+        ];
+        update(scroll);
+        let messages = update(vec![]);
+        assert_eq!(messages, vec![json!({"Unload": 1}), json!({"Load": 4})]);
+    }
+
+    #[test]
+    pub fn test_declarative_timers() {
+        let html = r#"<html>
+        <body>
+            <div ^ontick="Poll 0.5s"></div>
+            <div ?="{visible}" ^ontick="Blink {count} 200ms"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "").expect("view valid");
+        let mut update = |time: u64, visible: bool| {
+            let input = Input::new().time(Duration::from_millis(time));
+            let value = json!({ "visible": visible, "count": 3 });
+            view.update(input, value).expect("valid update").messages
+        };
+        assert!(update(0, false).is_empty());
+        assert!(update(300, false).is_empty());
+        assert_eq!(update(300, false), vec![json!("Poll")]);
+        assert!(update(100, true).is_empty());
+        assert_eq!(update(200, true), vec![json!({"Blink": 3})]);
+        assert_eq!(update(300, true), vec![json!("Poll"), json!({"Blink": 3})]);
+    }
+
+    #[test]
+    pub fn test_form_submission() {
+        let css = r#"
+            form { display: flex; flex-direction: column; }
+            input, button { width: 20px; height: 20px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <form ^onsubmit="Save $event.values">
+                <input name="nickname" &value="{nickname}" />
+                <input name="music" type="checkbox" checked />
+                <input name="volume" value="50" disabled />
+                <input name="difficulty" type="radio" value="easy" />
+                <input name="difficulty" type="radio" value="hard" checked />
+                <button>Save</button>
+            </form>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            view.update(input, json!({"nickname": "Alice"}))
+                .expect("valid update")
+                .messages
+        };
+        let values = json!({"Save": {"nickname": "Alice", "music": true, "difficulty": "hard"}});
+        let click = vec![
+            InputEvent::MouseMove([10.0, 110.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ];
+        assert_eq!(update(click), vec![values.clone()]);
+        let enter = vec![
+            InputEvent::MouseMove([10.0, 10.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+            InputEvent::KeyDown(Keys::Enter),
+        ];
+        assert_eq!(update(enter), vec![values]);
+    }
+
+    #[test]
+    pub fn test_input_validation() {
+        let css = r#"
+            form { display: flex; flex-direction: column; }
+            input, button { width: 20px; height: 20px; }
+            input:invalid { height: 30px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <form ^onsubmit="Save $event.valid $event.invalid">
+                <input id="nickname" name="nickname" &value="{nickname}" required minlength="3" />
+                <input id="code" name="code" &value="{code}" pattern="[A-Z]{2}[0-9]+" />
+                <button>Save</button>
+            </form>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let update = |view: &mut View, value: Value| {
+            let click = vec![
+                InputEvent::MouseMove([10.0, 5.0]),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+                InputEvent::MouseButtonUp(MouseButtons::Left),
+            ];
+            let input = Input::new().events(click);
+            view.update(input, value.clone()).expect("valid update");
+            let button = view.query_selector("button").expect("valid selector");
+            let [x, y] = button.expect("button").element.position;
+            let submit = vec![
+                InputEvent::MouseMove([x + 5.0, y + 5.0]),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+                InputEvent::MouseButtonUp(MouseButtons::Left),
+            ];
+            let input = Input::new().events(submit);
+            let output = view.update(input, value).expect("valid update");
+            output.messages
+        };
+        let value = json!({ "nickname": "Al", "code": "AB12" });
+        let messages = update(&mut view, value.clone());
+        assert_eq!(messages, vec![json!({"Save": [false, ["nickname"]]})]);
+        let invalid = view.get_element_by_id("nickname").expect("nickname");
+        assert!(view.has_pseudo_class(invalid, "invalid"));
+        assert_eq!(invalid.size[1], 30.0);
+        let value = json!({ "nickname": "Alice", "code": "ab" });
+        let messages = update(&mut view, value.clone());
+        assert_eq!(messages, vec![json!({"Save": [false, ["code"]]})]);
+        let value = json!({ "nickname": "Alice", "code": "XY7" });
+        let messages = update(&mut view, value);
+        assert_eq!(messages, vec![json!({"Save": [true, []]})]);
+        let valid = view.get_element_by_id("code").expect("code");
+        assert!(view.has_pseudo_class(valid, "valid"));
+    }
+
+    #[test]
+    pub fn test_touch_input() {
+        let css = "body { display: flex; } div { width: 20px; height: 20px; }";
+        let html = r#"<html>
+        <body>
+            <div ^onclick="Click" ^ontouchstart="Start $event.id" ^ontouchend="End $event.id"></div>
+            <div ^onclick="Other" ^ontouchmove="Move $event.position.x $event.touches"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            view.update(input, json!({}))
+                .expect("valid update")
+                .messages
+        };
+        assert_eq!(
+            update(vec![InputEvent::TouchStart(7, [10.0, 10.0])]),
+            vec![json!({"Start": 7})]
+        );
+        assert_eq!(
+            update(vec![
+                InputEvent::TouchStart(8, [30.0, 10.0]),
+                InputEvent::TouchMove(8, [35.0, 10.0])
+            ]),
+            vec![json!({"Move": [35.0, 2]})]
+        );
+        assert!(update(vec![InputEvent::TouchEnd(8, [35.0, 10.0])]).is_empty());
+        assert_eq!(
+            update(vec![InputEvent::TouchEnd(7, [10.0, 10.0])]),
+            vec![json!({"End": 7}), json!("Click")]
+        );
+    }
+
+    #[test]
+    pub fn test_pinch_and_pan_gestures() {
+        let css = "#map { width: 100px; height: 100px; }";
+        let html = r#"<html>
+        <body>
+            <div id="map" ^onpinch="Zoom $event.scale" ^onpan="Pan $event.delta"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            view.update(input, json!({}))
+                .expect("valid update")
+                .messages
+        };
+        update(vec![InputEvent::TouchStart(1, [40.0, 50.0])]);
+        assert_eq!(
+            update(vec![InputEvent::TouchMove(1, [45.0, 50.0])]),
+            vec![json!({"Pan": [5.0, 0.0]})]
+        );
+        update(vec![InputEvent::TouchStart(2, [55.0, 50.0])]);
+        assert_eq!(
+            update(vec![InputEvent::TouchMove(2, [65.0, 50.0])]),
+            vec![json!({"Pan": [5.0, 0.0]}), json!({"Zoom": 2.0})]
+        );
+        let messages = update(vec![
+            InputEvent::TouchEnd(1, [45.0, 50.0]),
+            InputEvent::TouchEnd(2, [65.0, 50.0]),
+        ]);
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    pub fn test_ime_composition() {
+        let css = "input { width: 32px; height: 32px; }";
+        let html = r#"<html>
+        <body>
+            <input &value="{name}" />
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut value = json!({ "name": "" });
+        let update = |view: &mut View, value: &mut Value, events: Vec<InputEvent>| -> Output {
+            let input = Input::new().events(events);
+            let output = view.update(input, value.clone()).expect("valid update");
+            for message in &output.messages {
+                value["name"] = message["change"]["value"].clone();
+            }
+            output
+        };
+        let focus = vec![
+            InputEvent::MouseMove([10.0, 10.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ];
+        update(&mut view, &mut value, focus);
+        let compose = vec![InputEvent::ImeCompose("にほん".to_string(), Some([0, 3]))];
+        let output = update(&mut view, &mut value, compose);
+        let composition = output.composition.expect("composition");
+        assert_eq!(composition.text, "にほん");
+        assert_eq!(composition.cursor, Some([0, 3]));
+        assert_eq!(composition.size, [32.0, 32.0]);
+        assert_eq!(value["name"], json!(""), "composition not in value");
+        let output = update(&mut view, &mut value, vec![]);
+        assert!(output.composition.is_some(), "composition persists");
+        let commit = vec![InputEvent::ImeCommit("日本".to_string())];
+        let output = update(&mut view, &mut value, commit);
+        assert_eq!(output.composition, None);
+        assert_eq!(value["name"], json!("日本"));
+    }
+
+    #[test]
+    pub fn test_modifiers_on_events() {
+        let css = "div { width: 20px; height: 20px; }";
+        let html = r#"<html>
+        <body>
+            <div tabindex="0" ^onclick="Select $event.modifiers" ^onkeydown="Key $event.modifiers"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            view.update(input, json!({}))
+                .expect("valid update")
+                .messages
+        };
+        let click = || {
+            vec![
+                InputEvent::MouseMove([10.0, 10.0]),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+                InputEvent::MouseButtonUp(MouseButtons::Left),
+            ]
+        };
+        assert_eq!(update(click()), vec![json!({"Select": 0})]);
+        let shift = Modifiers::SHIFT.0;
+        let ctrl = Modifiers::CTRL.0;
+        assert_eq!(
+            update(vec![InputEvent::KeyDown(Keys::Shift)]),
+            vec![json!({"Key": shift})]
+        );
+        assert_eq!(update(click()), vec![json!({"Select": shift})]);
+        assert_eq!(
+            update(vec![InputEvent::KeyDown(Keys::Ctrl)]),
+            vec![json!({"Key": shift | ctrl})]
+        );
+        update(vec![
+            InputEvent::KeyUp(Keys::Shift),
+            InputEvent::KeyUp(Keys::Ctrl),
+        ]);
+        assert_eq!(update(click()), vec![json!({"Select": 0})]);
+    }
+
+    #[test]
+    pub fn test_key_auto_repeat() {
+        let css = "input { width: 32px; height: 32px; }";
+        let html = r#"<html>
+        <body>
+            <input &value="{name}" />
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .key_repeat(Duration::from_millis(400), Duration::from_millis(100));
+        let mut value = json!({ "name": "abcdefgh" });
+        let mut update = |time: u64, events: Vec<InputEvent>| -> String {
+            let input = Input::new()
+                .time(Duration::from_millis(time))
+                .events(events);
+            let output = view.update(input, value.clone()).expect("valid update");
+            for message in output.messages {
+                value["name"] = message["change"]["value"].clone();
+            }
+            value["name"].as_str().unwrap_or_default().to_string()
+        };
+        let focus = vec![
+            InputEvent::MouseMove([10.0, 10.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+            InputEvent::KeyDown(Keys::End),
+            InputEvent::KeyUp(Keys::End),
+        ];
+        update(0, focus);
+        assert_eq!(
+            update(0, vec![InputEvent::KeyDown(Keys::Backspace)]),
+            "abcdefg"
+        );
+        assert_eq!(update(300, vec![]), "abcdefg");
+        assert_eq!(update(100, vec![]), "abcdef", "first repeat after delay");
+        assert_eq!(update(250, vec![]), "abcd");
+        assert_eq!(update(0, vec![InputEvent::KeyDown(Keys::Backspace)]), "abc");
+        assert_eq!(update(50, vec![InputEvent::KeyUp(Keys::Backspace)]), "abc");
+        assert_eq!(update(1000, vec![]), "abc");
+    }
+
+    #[test]
+    pub fn test_kinetic_scrolling() {
+        let html = r#"<html>
+        <body>
+            <div id="list"><div class="content"></div></div>
+        </body>
+        </html>"#;
+        let css = r#"
+            #list { width: 50px; height: 50px; overflow: scroll; }
+            .content { width: 50px; height: 500px; flex-shrink: 0; }
+        "#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .kinetic_scrolling(10.0);
+        let update = |view: &mut View, events: Vec<InputEvent>| -> f32 {
+            let input = Input::new().time(Duration::from_millis(100)).events(events);
+            view.update(input, json!({})).expect("valid update");
+            view.scroll_offset("list").expect("scrolling")[1]
+        };
+        let wheel = vec![
+            InputEvent::MouseMove([10.0, 10.0]),
+            InputEvent::MouseWheel([0.0, -1.0]),
+        ];
+        assert_eq!(update(&mut view, wheel), 0.0);
+        let mut offsets = vec![];
+        for _ in 0..10 {
+            offsets.push(update(&mut view, vec![]));
+        }
+        assert!(
+            offsets.windows(2).all(|pair| pair[0] <= pair[1]),
+            "{offsets:?}"
+        );
+        assert!(offsets[0] > 25.0 && offsets[0] < 50.0, "smooth start");
+        assert!((offsets[9] - 50.0).abs() < 0.5, "notch distance");
+        let fling = vec![
+            InputEvent::TouchStart(1, [10.0, 40.0]),
+            InputEvent::TouchMove(1, [10.0, 20.0]),
+        ];
+        let offset = update(&mut view, fling);
+        assert!((offset - 70.0).abs() < 0.5, "content follows touch");
+        update(&mut view, vec![InputEvent::TouchEnd(1, [10.0, 20.0])]);
+        assert!(update(&mut view, vec![]) > 70.0, "fling continues");
+    }
+
+    #[test]
+    pub fn test_kinetic_wheel_keeps_other_axis() {
+        let html = r#"<html>
+        <body>
+            <div id="list"><div class="content"></div></div>
+        </body>
+        </html>"#;
+        let css = r#"
+            #list { width: 50px; height: 50px; overflow: scroll; }
+            .content { width: 200px; height: 200px; flex-shrink: 0; }
+        "#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .kinetic_scrolling(10.0);
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().time(Duration::from_millis(100)).events(events);
+            view.update(input, json!({})).expect("valid update");
+            view.scroll_offset("list").expect("scrolling")
+        };
+        update(vec![
+            InputEvent::MouseMove([10.0, 10.0]),
+            InputEvent::MouseWheel([0.0, -1.0]),
+        ]);
+        for _ in 0..10 {
+            update(vec![]);
+        }
+        let [x, y] = update(vec![]);
+        assert_eq!(x, 0.0, "vertical wheel scrolled horizontally");
+        assert!(y > 0.0);
+    }
+
+    #[test]
+    pub fn test_precise_and_horizontal_wheel() {
+        let html = r#"<html>
+        <body>
+            <div id="list"><div class="content"></div></div>
+        </body>
+        </html>"#;
+        let css = r#"
+            #list { width: 50px; height: 50px; overflow: scroll; }
+            .content { width: 200px; height: 200px; flex-shrink: 0; }
+        "#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let update = |view: &mut View, sensitivity: f32, events: Vec<InputEvent>| {
+            let mut events = events;
+            events.insert(0, InputEvent::MouseMove([10.0, 10.0]));
+            let input = Input::new().scroll_sensitivity(sensitivity).events(events);
+            view.update(input, json!({})).expect("valid update");
+            view.scroll_offset("list").expect("scrolling")
+        };
+        update(&mut view, 1.0, vec![]);
+        let trackpad = vec![InputEvent::MouseWheel([0.0, -3.5])];
+        assert_eq!(update(&mut view, 1.0, trackpad), [0.0, 3.5]);
+        let horizontal = vec![InputEvent::MouseWheel([2.0, 0.0])];
+        assert_eq!(update(&mut view, 10.0, horizontal), [20.0, 3.5]);
+        let shift = vec![
+            InputEvent::KeyDown(Keys::Shift),
+            InputEvent::MouseWheel([0.0, -1.0]),
+            InputEvent::KeyUp(Keys::Shift),
+        ];
+        assert_eq!(update(&mut view, 10.0, shift), [30.0, 3.5]);
+    }
+
+    #[test]
+    pub fn test_device_pixel_scale() {
+        let css = r#"
+            body { display: flex; }
+            #a { width: 101px; height: 10px; }
+            #b { width: 10px; height: 10px; }
+            #half { width: 50vw; height: 10px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="a"></div>
+            <div id="b" ^onclick="Click $event.position.x"></div>
+            <div id="half"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let input = Input::new()
+            .scale(1.5)
+            .viewport([300.0, 300.0])
+            .event(InputEvent::MouseMove([159.0, 6.0]))
+            .event(InputEvent::MouseButtonDown(MouseButtons::Left))
+            .event(InputEvent::MouseButtonUp(MouseButtons::Left));
+        let output = view.update(input, json!({})).expect("valid update");
+        let rect = |view: &View, selector: &str| {
+            let element = view.query_selector(selector).expect("valid selector");
+            let element = element.expect("element");
+            let [x, _] = element.position;
+            let [width, _] = element.size;
+            let device = [x * 1.5, width * 1.5];
+            let aligned = device.map(f32::round);
+            assert!(device
+                .iter()
+                .zip(aligned)
+                .all(|(a, b)| (a - b).abs() < 0.001));
+            aligned
+        };
+        assert_eq!(rect(&view, "#a"), [0.0, 152.0]);
+        assert_eq!(rect(&view, "#b"), [152.0, 15.0]);
+        assert_eq!(rect(&view, "#half"), [167.0, 150.0]);
+        assert_eq!(output.messages, vec![json!({"Click": 106.0})]);
+    }
+
+    #[test]
+    pub fn test_pixel_ratio_independent_of_scale() {
+        let html = r#"<html>
+        <body>
+            <img src="icon.png" srcset="icon@2x.png 2x" />
+        </body>
+        </html>"#;
+        let src = |input: Input| {
+            let mut view = View::compile(html, "", "").expect("view valid");
+            view.update(input, json!({})).expect("valid update");
+            let body = view.body();
+            let img = body.children()[0];
+            img.children()[0].backgrounds[0].image.clone()
+        };
+        let icon = |src: &str| Some(src.to_string());
+        assert_eq!(src(Input::new().scale(2.0)), icon("icon@2x.png"));
+        let input = Input::new().pixel_ratio(2.0).scale(1.0);
+        assert_eq!(src(input), icon("icon@2x.png"));
+        let input = Input::new().scale(2.0).pixel_ratio(1.0);
+        assert_eq!(src(input), icon("icon.png"));
+    }
+
+    #[test]
+    pub fn test_uncaptured_input_events() {
+        let html = r#"<html>
+        <body>
+            <div id="list"><div class="content"></div></div>
+            <div class="button" ^onclick="Click"></div>
+        </body>
+        </html>"#;
+        let css = r#"
+            body { width: 100px; height: 100px; pointer-events: none; }
+            #list { width: 50px; height: 20px; overflow: scroll; pointer-events: auto; }
+            .content { height: 100px; flex-shrink: 0; }
+            .button { width: 20px; height: 20px; pointer-events: auto; }
+        "#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            view.update(input, json!({})).expect("valid update")
+        };
+        update(vec![]);
+        let click = |position: [f32; 2]| {
+            vec![
+                InputEvent::MouseMove(position),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+                InputEvent::MouseButtonUp(MouseButtons::Left),
+            ]
+        };
+        let output = update(click([10.0, 30.0]));
+        assert_eq!(output.messages, vec![json!("Click")]);
+        assert!(output.uncaptured.is_empty(), "click on UI");
+        let output = update(click([80.0, 80.0]));
+        assert_eq!(output.uncaptured, click([80.0, 80.0]), "click on world");
+        let wheel = InputEvent::MouseWheel([0.0, -1.0]);
+        let events = vec![InputEvent::MouseMove([10.0, 30.0]), wheel.clone()];
+        let output = update(events);
+        assert_eq!(output.uncaptured, vec![wheel.clone()], "nothing to scroll");
+        let events = vec![InputEvent::MouseMove([10.0, 10.0]), wheel.clone()];
+        let output = update(events);
+        assert!(output.uncaptured.is_empty(), "list scrolled");
+        let output = update(vec![InputEvent::KeyDown(Keys::Enter)]);
+        assert_eq!(output.uncaptured, vec![InputEvent::KeyDown(Keys::Enter)]);
+    }
+
+    #[test]
+    pub fn test_text_caret_and_selection() {
+        let css = "input { width: 100px; height: 20px; font-size: 16px; }";
+        let html = r#"<html>
+        <body>
+            <input &value="{name}" />
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut value = json!({ "name": "hello" });
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            let output = view.update(input, value.clone()).expect("valid update");
+            for message in output.messages.iter() {
+                value["name"] = message["change"]["value"].clone();
+            }
+            (value["name"].clone(), output)
+        };
+        let click = vec![
+            InputEvent::MouseMove([26.0, 10.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ];
+        let (_, output) = update(click);
+        let caret = output.caret.expect("caret");
+        assert_eq!(caret.index, 2);
+        assert_eq!(caret.position, [24.0, 0.0]);
+        assert_eq!(caret.size, [1.0, 16.0]);
+        let (name, _) = update(vec![InputEvent::Char('X')]);
+        assert_eq!(name, json!("heXllo"));
+        let (_, output) = update(vec![
+            InputEvent::KeyDown(Keys::Shift),
+            InputEvent::KeyDown(Keys::ArrowRight),
+            InputEvent::KeyDown(Keys::ArrowRight),
+            InputEvent::KeyUp(Keys::ArrowRight),
+            InputEvent::KeyUp(Keys::Shift),
+            InputEvent::KeyDown(Keys::Ctrl),
+            InputEvent::Char('c'),
+            InputEvent::KeyUp(Keys::Ctrl),
+        ]);
+        let caret = output.caret.expect("caret");
+        assert_eq!(caret.range, [3, 5]);
+        let highlight = Highlight {
+            position: [36.0, 0.0],
+            size: [24.0, 16.0],
+        };
+        assert_eq!(caret.selection, vec![highlight]);
+        assert_eq!(output.clipboard, Some("ll".to_string()));
+        let (name, _) = update(vec![InputEvent::KeyDown(Keys::Backspace)]);
+        assert_eq!(name, json!("heXo"));
+        let (_, output) = update(vec![
+            InputEvent::MouseMove([0.0, 10.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseMove([34.0, 10.0]),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ]);
+        assert_eq!(output.caret.map(|caret| caret.range), Some([0, 3]));
+        let (name, output) = update(vec![InputEvent::Char('Y')]);
+        assert_eq!(name, json!("Yo"));
+        assert_eq!(output.caret.map(|caret| caret.index), Some(1));
+    }
+
+    #[test]
+    pub fn test_mouse_capture_during_drag() {
+        let css = r#"
+            body { width: 200px; height: 50px; }
+            .thumb { width: 20px; height: 20px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div class="thumb" ^onmousemove="Move $event.position.x" ^onmouseup="Up"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            view.update(input, json!({}))
+                .expect("valid update")
+                .messages
+        };
+        update(vec![]);
+        let messages = update(vec![
+            InputEvent::MouseMove([10.0, 10.0]),
+            InputEvent::MouseButtonDown(MouseButtons::Left),
+            InputEvent::MouseMove([100.0, 40.0]),
+        ]);
+        assert_eq!(
+            messages,
+            vec![json!({"Move": 10.0}), json!({"Move": 100.0})]
+        );
+        let messages = update(vec![
+            InputEvent::MouseMove([150.0, 40.0]),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ]);
+        assert_eq!(messages, vec![json!({"Move": 150.0}), json!("Up")]);
+        let messages = update(vec![InputEvent::MouseMove([120.0, 40.0])]);
+        assert!(messages.is_empty(), "capture released");
+    }
+
+    #[test]
+    pub fn test_explicit_pointer_capture() {
+        let css = r#"
+            body { width: 200px; height: 50px; }
+            div { width: 20px; height: 20px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="track" ^onmousemove="Move $event.position.x" ^onmouseup="Up"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let update = |view: &mut View, events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            view.update(input, json!({}))
+                .expect("valid update")
+                .messages
+        };
+        update(&mut view, vec![]);
+        view.capture_pointer("track").expect("element exists");
+        let messages = update(&mut view, vec![InputEvent::MouseMove([100.0, 40.0])]);
+        assert_eq!(messages, vec![json!({"Move": 100.0})]);
+        view.release_pointer();
+        let messages = update(&mut view, vec![InputEvent::MouseMove([110.0, 40.0])]);
+        assert!(messages.is_empty(), "capture released");
+        view.capture_pointer("track").expect("element exists");
+        let messages = update(
+            &mut view,
+            vec![
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+                InputEvent::MouseButtonUp(MouseButtons::Left),
+                InputEvent::MouseMove([120.0, 40.0]),
+            ],
+        );
+        assert_eq!(messages, vec![json!("Up")], "capture released by button");
+    }
+
+    #[test]
+    pub fn test_accessibility_snapshot() {
+        let css = "button { width: 40px; height: 20px; }";
+        let html = r#"<html>
+        <body>
+            <h1>Settings</h1>
+            <button aria-label="Close" ^onclick="Close">X</button>
+            <label>Sound <input type="checkbox" checked /></label>
+            <img alt="Logo" src="logo.png" />
+            <div aria-hidden="true">Decoration</div>
+            <div role="button">Apply</div>
+        </body>
+        </html>"#;
+        let view = View::compile(html, css, "").expect("view valid");
+        let mut view = view.accessibility(true);
+        let output = view.update(Input::new(), json!({})).expect("valid update");
+        let nodes = output.accessibility;
+        let describe = |node: &AccessNode| (node.role, node.name.clone());
+        let named: Vec<_> = nodes
+            .iter()
+            .filter(|node| node.role != Role::Text)
+            .map(describe)
+            .collect();
+        assert_eq!(
+            named,
+            vec![
+                (Role::Document, None),
+                (Role::Heading, Some("Settings".to_string())),
+                (Role::Button, Some("Close".to_string())),
+                (Role::Label, Some("Sound".to_string())),
+                (Role::CheckBox, None),
+                (Role::Image, Some("Logo".to_string())),
+                (Role::Button, Some("Apply".to_string())),
+            ]
+        );
+        let body = &nodes[0];
+        assert_eq!(body.children.len(), 5, "hidden element excluded");
+        let checkbox = nodes.iter().find(|node| node.role == Role::CheckBox);
+        assert_eq!(checkbox.and_then(|node| node.states.checked), Some(true));
+        let close = nodes
+            .iter()
+            .find(|node| node.name.as_deref() == Some("Close"));
+        assert_eq!(close.map(|node| node.size), Some([40.0, 20.0]));
+    }
+
+    #[test]
+    pub fn test_extended_keys() {
+        let css = "input { width: 32px; height: 32px; }";
+        let html = r#"<html>
+        <body>
+            <input &value="{name}"
+                ^onkeydown.f5="Reload"
+                ^onkeydown.q="Quit"
+                ^onkeydown.scancode-100="Custom"
+                ^onkeyup="Bind $event.key" />
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let value = json!({ "name": "" });
+        let focus = Input::new()
+            .event(InputEvent::MouseMove([10.0, 10.0]))
+            .event(InputEvent::MouseButtonDown(MouseButtons::Left))
+            .event(InputEvent::MouseButtonUp(MouseButtons::Left));
+        view.update(focus, value.clone()).expect("valid update");
+        let keys = [Keys::F5, Keys::Character('Q'), Keys::Scancode(100)];
+        let mut input = Input::new();
+        for key in keys {
+            input = input.event(InputEvent::KeyDown(key));
+        }
+        let output = view.update(input, value.clone()).expect("valid update");
+        assert_eq!(
+            output.messages,
+            vec![json!("Reload"), json!("Quit"), json!("Custom")]
+        );
+        let input = Input::new().event(InputEvent::KeyUp(Keys::NumpadAdd));
+        let output = view.update(input, value).expect("valid update");
+        assert_eq!(output.messages, vec![json!({"Bind": "NumpadAdd"})]);
+        assert_eq!(Keys::Character('Q').name(), "q");
+    }
+
+    #[test]
+    pub fn test_input_simulation() {
+        let css = r#"
+            body { display: flex; flex-direction: column; }
+            input { width: 100px; height: 20px; }
+            button { width: 50px; height: 20px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <input id="name" &value="{name}" ^onkeydown.enter="Enter" />
+            <button id="save" ^onclick="Save">Save</button>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut value = json!({ "name": "Bob" });
+        let mut update = |view: &mut View, events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            let output = view.update(input, value.clone()).expect("valid update");
+            for message in output.messages.iter() {
+                if let Some(name) = message["change"]["value"].as_str() {
+                    value["name"] = json!(name);
+                }
+            }
+            (value["name"].clone(), output.messages)
+        };
+        update(&mut view, vec![]);
+        let events = view.simulate_type("name", "by").expect("input exists");
+        let (name, _) = update(&mut view, events);
+        assert_eq!(name, json!("Bobby"));
+        let events = view.simulate_key(Keys::Enter);
+        let (_, messages) = update(&mut view, events);
+        assert_eq!(messages, vec![json!("Enter")]);
+        let events = view.simulate_click("save").expect("button exists");
+        let (_, messages) = update(&mut view, events);
+        assert_eq!(messages, vec![json!("Save")]);
+        assert!(view.simulate_click("unknown").is_err());
+    }
+
+    #[test]
+    pub fn test_simulate_click_on_scaled_view() {
+        let css = r#"
+            div { width: 20px; height: 20px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="a" ^onclick="A"></div>
+            <div id="b" ^onclick="B"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let update = |view: &mut View, events: Vec<InputEvent>| {
+            let input = Input::new().scale(2.0).events(events);
+            view.update(input, json!({}))
+                .expect("valid update")
+                .messages
+        };
+        update(&mut view, vec![]);
+        let events = view.simulate_click("b").expect("element exists");
+        assert_eq!(events[0], InputEvent::MouseMove([20.0, 60.0]));
+        assert_eq!(update(&mut view, events), vec![json!("B")]);
+    }
+
+    #[test]
+    pub fn test_swipe_gestures() {
+        let css = "div { width: 200px; height: 50px; }";
+        let html = r#"<html>
+        <body>
+            <div ^onclick="Select" ^onswipeleft="Next $event.delta" ^onswiperight="Previous"
+                ^onswipeup="Close"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .swipe_threshold(50.0, 300.0);
+        let mut update = |time: f32, events: Vec<InputEvent>| {
+            let input = Input::new()
+                .time(Duration::from_secs_f32(time))
+                .events(events);
+            view.update(input, json!({}))
+                .expect("valid update")
+                .messages
+        };
+        let press = |x: f32| {
+            vec![
+                InputEvent::MouseMove([x, 25.0]),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+            ]
+        };
+        let release = |x: f32, y: f32| {
+            vec![
+                InputEvent::MouseMove([x, y]),
+                InputEvent::MouseButtonUp(MouseButtons::Left),
+            ]
+        };
+        update(0.0, press(150.0));
+        assert_eq!(
+            update(0.1, release(50.0, 30.0)),
+            vec![json!({"Next": [-100.0, 5.0]})],
+            "swipe suppresses click"
+        );
+        update(0.0, press(50.0));
+        assert_eq!(
+            update(0.4, release(150.0, 25.0)),
+            vec![json!("Select")],
+            "too slow"
+        );
+        update(0.0, press(100.0));
+        assert_eq!(
+            update(0.05, release(120.0, 25.0)),
+            vec![json!("Select")],
+            "too short"
+        );
+        update(0.0, press(100.0));
+        assert_eq!(update(0.1, release(100.0, -40.0)), vec![json!("Close")]);
+        update(0.0, vec![InputEvent::TouchStart(1, [50.0, 25.0])]);
+        let end = vec![InputEvent::TouchEnd(1, [150.0, 25.0])];
+        assert_eq!(
+            update(0.1, end),
+            vec![json!("Previous")],
+            "primary touch swipes"
+        );
+    }
+
+    #[test]
+    pub fn test_unhandled_swipe_keeps_click() {
+        let css = "div { width: 200px; height: 50px; }";
+        let html = r#"<html>
+        <body>
+            <div ^onclick="Select" ^onswipeup="Close"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .swipe_threshold(50.0, 300.0);
+        let mut update = |time: f32, events: Vec<InputEvent>| {
+            let input = Input::new()
+                .time(Duration::from_secs_f32(time))
+                .events(events);
+            view.update(input, json!({}))
+                .expect("valid update")
+                .messages
+        };
+        update(
+            0.0,
+            vec![
+                InputEvent::MouseMove([150.0, 25.0]),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+            ],
+        );
+        let release = vec![
+            InputEvent::MouseMove([50.0, 25.0]),
+            InputEvent::MouseButtonUp(MouseButtons::Left),
+        ];
+        assert_eq!(update(0.1, release), vec![json!("Select")]);
+    }
+
+    #[test]
+    pub fn test_focus_visible_by_keyboard() {
+        let css = r#"
+            body { display: flex; }
+            div, input { width: 20px; height: 20px; }
+            :focus-visible { width: 40px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div tabindex="0"></div>
+            <input &value="{name}" />
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            view.update(input, json!({"name": ""}))
+                .expect("valid update");
+            // styles of state changed by input applied next frame
+            view.update(Input::new(), json!({"name": ""}))
+                .expect("valid update");
+            let body = view.body();
+            let children = body.children();
+            [children[0].size[0], children[1].size[0]]
+        };
+        let click = |x: f32| {
+            vec![
+                InputEvent::MouseMove([x, 10.0]),
+                InputEvent::MouseButtonDown(MouseButtons::Left),
+                InputEvent::MouseButtonUp(MouseButtons::Left),
+            ]
+        };
+        assert_eq!(update(click(10.0)), [20.0, 20.0], "pointer focus hidden");
+        assert_eq!(
+            update(click(30.0)),
+            [20.0, 40.0],
+            "text input focus visible"
+        );
+        assert_eq!(update(click(10.0)), [20.0, 20.0]);
+        let tab = || vec![InputEvent::KeyDown(Keys::Tab), InputEvent::KeyUp(Keys::Tab)];
+        assert_eq!(update(tab()), [20.0, 40.0]);
+        assert_eq!(update(tab()), [40.0, 20.0], "keyboard focus visible");
+    }
+
+    #[test]
+    pub fn test_accesskey_shortcuts() {
+        let html = r#"<html>
+        <body>
+            <button accesskey="i" ^onclick="Inventory">Inventory</button>
+            <button data-hotkey="Ctrl+S" ^onclick="Save">Save</button>
+            <button accesskey="m" disabled ^onclick="Map">Map</button>
+            <input id="name" &value="{name}" />
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, "", "").expect("view valid");
+        view.update(Input::new(), json!({"name": ""}))
+            .expect("valid update");
+        view.focus("name").expect("input focused");
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            let output = view
+                .update(input, json!({"name": ""}))
+                .expect("valid update");
+            output.messages
+        };
+        let key = |key: Keys| vec![InputEvent::KeyDown(key), InputEvent::KeyUp(key)];
+        let chord = |modifier: Keys, key: Keys| {
+            vec![
+                InputEvent::KeyDown(modifier),
+                InputEvent::KeyDown(key),
+                InputEvent::KeyUp(key),
+                InputEvent::KeyUp(modifier),
+            ]
+        };
+        assert!(
+            update(key(Keys::Character('i'))).is_empty(),
+            "typing ignored"
+        );
+        // blurs text input
+        update(vec![InputEvent::MouseButtonDown(MouseButtons::Left)]);
+        assert_eq!(update(key(Keys::Character('i'))), vec![json!("Inventory")]);
+        assert!(
+            update(key(Keys::Character('s'))).is_empty(),
+            "modifier required"
+        );
+        assert_eq!(
+            update(chord(Keys::Ctrl, Keys::Character('s'))),
+            vec![json!("Save")]
+        );
+        assert!(update(key(Keys::Character('m'))).is_empty(), "disabled");
+    }
+
+    #[test]
+    pub fn test_pen_pressure_and_tilt() {
+        let css = r#"
+            body { pointer-events: none; }
+            div { width: 20px; height: 20px; pointer-events: auto; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div ^onmousedown="Press" ^onpendown="Ink $event.pressure"
+                ^onpenmove="Stroke $event.position $event.tilt" ^onpenup="Lift" ^onclick="Click">
+            </div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut update = |phase: PenPhase, pen: Pen| {
+            let input = Input::new().event(InputEvent::Pen(phase, pen));
+            view.update(input, json!({})).expect("valid update")
+        };
+        let output = update(PenPhase::Down, Pen::new([10.0, 10.0], 0.25));
+        assert_eq!(
+            output.messages,
+            vec![json!("Press"), json!({"Ink": 0.25})],
+            "pen acts as mouse"
+        );
+        let pen = Pen::new([30.0, 10.0], 0.5).tilt([45.0, -30.0]);
+        let output = update(PenPhase::Move, pen);
+        assert_eq!(
+            output.messages,
+            vec![json!({"Stroke": [[30.0, 10.0], [45.0, -30.0]]})],
+            "pressed element captures pen"
+        );
+        let output = update(PenPhase::Up, Pen::new([30.0, 10.0], 0.0));
+        assert_eq!(output.messages, vec![json!("Lift")]);
+        assert!(output.uncaptured.is_empty());
+        let output = update(PenPhase::Move, Pen::new([50.0, 10.0], 0.0));
+        assert!(output.messages.is_empty());
+        assert_eq!(output.uncaptured.len(), 1, "pen outside of elements");
+    }
+
+    #[test]
+    pub fn test_display_list_commands() {
+        let css = r#"
+            body { width: 100px; height: 100px; }
+            .card {
+                width: 40px;
+                height: 20px;
+                background-color: #ff0000;
+                border: 2px solid #0000ff;
+                border-radius: 4px;
+                opacity: 0.5;
+            }
+            .list { height: 10px; overflow-y: scroll; }
+            span { color: #00ff00; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div class="card"></div>
+            <div class="list"><span>Item</span></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let commands = view.display_list().expect("display list valid");
+        let blue = [0, 0, 255, 128];
+        let expected = vec![
+            DisplayCommand::Rect {
+                position: [0.0, 0.0],
+                size: [40.0, 20.0],
+                color: [255, 0, 0, 128],
+                radius: [4.0; 4],
+            },
+            DisplayCommand::Border {
+                position: [0.0, 0.0],
+                size: [40.0, 20.0],
+                widths: [2.0; 4],
+                colors: [blue; 4],
+                styles: [BorderStyle::Solid; 4],
+                radius: [4.0; 4],
+            },
+            DisplayCommand::PushClip {
+                position: [0.0, 20.0],
+                size: [100.0, 10.0],
+                radius: [0.0; 4],
+            },
+            DisplayCommand::TextRun {
+                text: "Item".to_string(),
+                position: [0.0, 20.0],
+                size: [100.0, 16.0],
+                color: [0, 255, 0, 255],
+                font: view.body().children()[1].children()[0].font.clone(),
+                baseline: 12.8,
+            },
+            DisplayCommand::PopClip,
+        ];
+        assert_eq!(commands, expected);
+    }
+
+    #[test]
+    pub fn test_damage_tracking() {
+        let css = r#"
+            body { display: flex; pointer-events: none; }
+            div { width: 20px; height: 10px; background: #ff0000; pointer-events: auto; }
+            div:hover { width: 30px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div></div>
+            <div></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut update = |events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            let output = view.update(input, json!({})).expect("valid update");
+            output.damage
+        };
+        let rect = |x: f32, width: f32| Rect {
+            position: [x, 0.0],
+            size: [width, 10.0],
+        };
+        let away = vec![InputEvent::MouseMove([50.0, 5.0])];
+        assert_eq!(
+            update(away),
+            vec![rect(0.0, 20.0), rect(20.0, 20.0)],
+            "first paint"
+        );
+        assert!(update(vec![]).is_empty(), "nothing changed");
+        update(vec![InputEvent::MouseMove([10.0, 5.0])]);
+        assert_eq!(
+            update(vec![]),
+            vec![
+                rect(0.0, 20.0),
+                rect(0.0, 30.0),
+                rect(20.0, 20.0),
+                rect(30.0, 20.0)
+            ],
+            "old and new areas of resized and moved elements"
+        );
+    }
+
+    #[test]
+    pub fn test_render_order_by_z_index() {
+        let css = r#"
+            #a { z-index: 2; }
+            #b { position: absolute; }
+            #d { z-index: -1; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="a"><div id="a1"></div></div>
+            <div id="b"></div>
+            <div id="c"><div id="d"></div></div>
+            <div id="e"><div id="f"></div></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let order: Vec<String> = view
+            .render_order()
+            .map(|element| match element.attrs.get("id") {
+                Some(id) => id.clone(),
+                None => element.tag.to_string(),
+            })
+            .collect();
+        assert_eq!(order, ["body", "d", "c", "e", "f", "b", "a", "a1"]);
+    }
+
+    #[test]
+    pub fn test_stacking_context_only_if_needed() {
+        let css = r#"
+            #a { position: absolute; }
+            #a1 { z-index: 3; }
+            #b { z-index: 2; }
+            #c { opacity: 0.5; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="a"><div id="a1"></div><div id="a2"></div></div>
+            <div id="b"></div>
+            <div id="c"><div id="c1"></div></div>
+            <div id="d"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let order: Vec<String> = view
+            .render_order()
+            .map(|element| match element.attrs.get("id") {
+                Some(id) => id.clone(),
+                None => element.tag.to_string(),
+            })
+            .collect();
+        assert_eq!(order, ["body", "d", "a", "a2", "c", "c1", "b", "a1"]);
+    }
+
+    #[test]
+    pub fn test_rounded_clipping_chain() {
+        let css = r#"
+            body { width: 100px; height: 100px; }
+            #card {
+                width: 50px;
+                height: 50px;
+                border: 2px solid #000000;
+                border-radius: 10px;
+                overflow: hidden;
+            }
+            #list { width: 40px; height: 20px; overflow: scroll; }
+            .item { height: 30px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="card"><div id="list"><div class="item"></div></div></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let body = view.body();
+        let card = body.children()[0];
+        let list = card.children()[0];
+        let item = list.children()[0];
+        let expected = Clipping {
+            position: [2.0, 2.0],
+            size: [46.0, 46.0],
+            radius: [8.0; 4],
+            parent: None,
+        };
+        assert_eq!(list.clipping.as_ref(), Some(&expected));
+        let clipping = item.clipping.as_ref().expect("item clipped");
+        assert_eq!(clipping.radius, [0.0; 4]);
+        assert_eq!(clipping.parent.as_deref(), Some(&expected));
+        assert_eq!(clipping.chain().count(), 2);
+        assert_eq!(clipping.bounds(), [2.0, 2.0, 42.0, 22.0]);
+        let commands = view.display_list().expect("display list valid");
+        let clips: Vec<_> = commands
+            .iter()
+            .filter(|command| matches!(command, DisplayCommand::PushClip { .. }))
+            .collect();
+        assert_eq!(
+            clips[0],
+            &DisplayCommand::PushClip {
+                position: [2.0, 2.0],
+                size: [46.0, 46.0],
+                radius: [8.0; 4],
+            }
+        );
+        assert_eq!(clips.len(), 2);
+    }
+
+    #[test]
+    pub fn test_resolved_border_radius() {
+        let css = r#"
+            body { width: 100px; height: 100px; }
+            div { width: 40px; height: 20px; }
+            #fits { border-radius: 5px 10px 0 2px; }
+            #overlaps { border-radius: 15px; }
+            #percent { border-radius: 50%; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="fits"></div>
+            <div id="overlaps"></div>
+            <div id="percent"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let radii: Vec<[f32; 4]> = view
+            .body()
+            .children()
+            .iter()
+            .map(|element| element.radius)
+            .collect();
+        assert_eq!(
+            radii,
+            vec![[5.0, 10.0, 0.0, 2.0], [10.0; 4], [10.0; 4]],
+            "adjacent radii must be scaled to fit box sides"
+        );
+    }
+
+    #[test]
+    pub fn test_border_styles() {
+        let css = r#"
+            #dashed { border: 2px dashed #ff0000; border-bottom: 1px dotted #00ff00; }
+            #double { border-width: 3px; border-style: double none; }
+            #none { border: 2px solid #000000; border: none; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="dashed"></div>
+            <div id="double"></div>
+            <div id="none"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let styles: Vec<[BorderStyle; 4]> = view
+            .body()
+            .children()
+            .iter()
+            .map(|element| {
+                let borders = &element.borders;
+                [borders.top, borders.right, borders.bottom, borders.left].map(|side| side.style)
+            })
+            .collect();
+        let [dashed, dotted, double, none] = [
+            BorderStyle::Dashed,
+            BorderStyle::Dotted,
+            BorderStyle::Double,
+            BorderStyle::None,
+        ];
+        assert_eq!(
+            styles,
+            vec![
+                [dashed, dashed, dotted, dashed],
+                [double, none, double, none],
+                [none; 4]
+            ]
+        );
+        let borders: Vec<_> = view
+            .display_list()
+            .expect("display list valid")
+            .into_iter()
+            .filter(|command| matches!(command, DisplayCommand::Border { .. }))
+            .collect();
+        assert_eq!(
+            borders.len(),
+            2,
+            "borders without visible style are not drawn"
+        );
+    }
+
+    #[test]
+    pub fn test_resource_manifest_hints() {
+        let css = r#"
+            .card { background-image: url("card.png"); }
+            .fancy { font-family: "Fancy"; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div class="card"></div>
+            <img @src="{avatar}" />
+            <div ?="{banner}"><img src="banner.png" /></div>
+            <div class="fancy">Hello</div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(LazyFonts(Rc::new(Cell::new(false))));
+        let value = json!({ "avatar": "alice.png", "banner": true });
+        let output = view.update(Input::new(), value).expect("valid update");
+        let load = |src: &str| ResourceHint::Load(src.to_string());
+        let unload = |src: &str| ResourceHint::Unload(src.to_string());
+        assert_eq!(
+            output.resource_hints,
+            [load("alice.png"), load("banner.png"), load("card.png")]
+        );
+        assert_eq!(output.resources, ["alice.png", "banner.png", "card.png"]);
+        assert_eq!(output.pending_fonts, ["Fancy"]);
+        let value = json!({ "avatar": "alice.png", "banner": true });
+        let input = Input::new().resource("card.png", ResourceState::Loaded);
+        let output = view.update(input, value).expect("valid update");
+        assert_eq!(output.resource_hints, []);
+        assert_eq!(output.resources, ["alice.png", "banner.png"]);
+        let value = json!({ "avatar": "bob.png", "banner": false });
+        let output = view.update(Input::new(), value).expect("valid update");
+        assert_eq!(
+            output.resource_hints,
+            [unload("alice.png"), unload("banner.png"), load("bob.png")]
+        );
+        assert_eq!(output.resources, ["bob.png"]);
+        let manifest: Vec<&str> = view.resource_manifest().collect();
+        assert_eq!(manifest, ["bob.png", "card.png"]);
+        let value = json!({ "avatar": "bob.png", "banner": true });
+        let input = Input::new().resource("banner.png", ResourceState::Loaded);
+        let output = view.update(input, value).expect("valid update");
+        assert_eq!(output.resources, ["bob.png"]);
+        let value = json!({ "avatar": "bob.png", "banner": false });
+        view.update(Input::new(), value).expect("valid update");
+        let value = json!({ "avatar": "bob.png", "banner": true });
+        let output = view.update(Input::new(), value).expect("valid update");
+        assert_eq!(output.resources, ["banner.png", "bob.png"], "unloaded");
+    }
+
+    #[test]
+    pub fn test_retained_change_list() {
+        let html = r#"<html>
+        <body>
+            <div id="title">{title}</div>
+            <div id="banner" ?="{banner}"></div>
+        </body>
+        </html>"#;
+        let view = View::compile(html, "", "").expect("view valid");
+        let mut view = view.track_changes(true);
+        let value = json!({ "title": "Hello", "banner": true });
+        let output = view.update(Input::new(), value).expect("valid update");
+        let created = output
+            .changes
+            .iter()
+            .filter(|change| matches!(change, ElementChange::Created(_)))
+            .count();
+        assert_eq!(created, output.changes.len());
+        let body = view.body().element.node;
+        let title = view.get_element_by_id("title").expect("title").node;
+        let banner = view.get_element_by_id("banner").expect("banner").node;
+        assert!(matches!(&output.changes[0], ElementChange::Created(patch) if patch.node == body));
+        let value = json!({ "title": "Hello", "banner": true });
+        let output = view.update(Input::new(), value).expect("valid update");
+        assert_eq!(output.changes, []);
+        let value = json!({ "title": "World", "banner": false });
+        let output = view.update(Input::new(), value).expect("valid update");
+        let changed: Vec<_> = output
+            .changes
+            .iter()
+            .map(|change| match change {
+                ElementChange::Created(patch) => ("created", patch.node),
+                ElementChange::Updated(patch) => ("updated", patch.node),
+                ElementChange::Removed(node) => ("removed", *node),
+            })
+            .collect();
+        assert!(changed.contains(&("removed", banner)));
+        let text = view.tree.children(title).expect("children")[0];
+        assert!(changed.contains(&("updated", text)));
+    }
+
+    #[test]
+    pub fn test_layer_hints() {
+        let css = r#"
+            #hint { will-change: transform, opacity; }
+            .a { will-change: transform, opacity; }
+            .b { will-change: left; }
+            #animated { animation: 1s linear Pulse; }
+            @keyframes Pulse {
+                0% { opacity: 1.0; }
+                100% { opacity: 0.5; }
+            }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="hint"></div>
+            <div id="animated"></div>
+            <div id="plain"></div>
+            <div id="overridden" class="a b"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut layers = |time: f32| {
+            let input = Input::new().time(Duration::from_secs_f32(time));
+            view.update(input, json!({})).expect("valid update");
+            ["hint", "animated", "plain"].map(|id| view.get_element_by_id(id).unwrap().layer)
+        };
+        assert_eq!(layers(0.1), [true, true, false]);
+        assert_eq!(layers(1.0), [true, false, false], "finished animation");
+        let hint = view.get_element_by_id("hint").unwrap();
+        assert_eq!(hint.will_change, ["transform", "opacity"]);
+        let overridden = view.get_element_by_id("overridden").unwrap();
+        assert_eq!(overridden.will_change, ["left"]);
+    }
+
+    #[test]
+    pub fn test_line_breaking_measures_words_once() {
+        let measured = |words: usize| {
+            let html = format!(
+                "<html><body><div>{}</div></body></html>",
+                "word ".repeat(words)
+            );
+            let count = Rc::new(Cell::new(0));
+            let mut view = View::compile(&html, "div { width: 4000px; }", "")
+                .expect("view valid")
+                .fonts(MeasuredChars(count.clone()));
+            view.update(Input::new(), json!({})).expect("valid update");
+            count.get()
+        };
+        assert!(measured(200) <= 2 * measured(100) + 10);
+    }
+
+    #[test]
+    pub fn test_text_line_boxes() {
+        let css = r#"
+            body { font-size: 10px; }
+            div { width: 40px; text-align: center; }
+        "#;
+        let html = r#"<html>
+        <body><div>aa bb cc</div></body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let body = view.body();
+        let div = body.children()[0];
+        let text = div.children()[0];
+        let lines: Vec<_> = text
+            .lines
+            .iter()
+            .map(|line| (line.text.as_str(), line.range, line.origin, line.width))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                ("aa bb", [0, 5], [1.25, 0.0], 37.5),
+                ("cc", [6, 8], [12.5, 10.0], 15.0)
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_children_visible_in_viewport() {
+        let css = r#"
+            body { width: 100px; height: 100px; }
+            #list { width: 100px; height: 50px; overflow: scroll; }
+            .row { height: 10px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="list"><div *row="100 {rows}" class="row"></div></div>
+        </body>
+        </html>"#;
+        let rows: Vec<i32> = (0..100).collect();
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({ "rows": rows }))
+            .expect("valid update");
+        let body = view.body();
+        let list = body.children()[0];
+        let viewport = Rect {
+            position: [0.0, 0.0],
+            size: [100.0, 100.0],
+        };
+        assert_eq!(list.children_visible(&viewport).len(), 5, "clipped by list");
+        let viewport = Rect {
+            position: [0.0, 25.0],
+            size: [100.0, 100.0],
+        };
+        let visible = list.children_visible(&viewport);
+        assert_eq!(visible.len(), 3, "partially scrolled out of viewport");
+        assert_eq!(visible[0].position, [0.0, 20.0]);
+    }
+
+    #[test]
+    pub fn test_element_at_point() {
+        let css = r#"
+            body { width: 100px; height: 100px; }
+            div { width: 40px; height: 40px; }
+            #overlay { position: absolute; left: 0; top: 0; z-index: 1; }
+            #ghost { position: absolute; left: 0; top: 0; z-index: 2; pointer-events: none; }
+            #round { border-radius: 20px; overflow: hidden; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="base"></div>
+            <div id="round"><div id="inner"></div></div>
+            <div id="overlay"></div>
+            <div id="ghost"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let id_at = |point: [f32; 2]| {
+            view.element_at(point)
+                .and_then(|element| element.attrs.get("id").cloned())
+                .unwrap_or_default()
+        };
+        assert_eq!(
+            id_at([10.0, 10.0]),
+            "overlay",
+            "z-index over base, ghost ignored"
+        );
+        assert_eq!(id_at([20.0, 60.0]), "inner");
+        assert_eq!(
+            id_at([2.0, 42.0]),
+            "round",
+            "inner clipped by rounded corner"
+        );
+        assert_eq!(id_at([60.0, 60.0]), "");
+    }
+
+    #[test]
+    pub fn test_debug_tree_dump() {
+        let css = r#"
+            body { width: 100px; height: 100px; }
+            .card { width: 20px; }
+            #empty:hover, .card > div { height: 10px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="empty" class="card main"><div>Text</div></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let input = Input::new().event(InputEvent::MouseMove([90.0, 90.0]));
+        view.update(input, json!({})).expect("valid update");
+        let tree = view.debug_tree().expect("debug tree valid");
+        let card = &tree.children[0];
+        assert_eq!(card.id.as_deref(), Some("empty"));
+        assert_eq!(card.classes, ["card", "main"]);
+        assert_eq!(card.size, [20.0, 10.0]);
+        assert_eq!(card.rules, [".card"], "hover rule not matched");
+        assert_eq!(card.children[0].rules, ["#empty:hover, .card > div"]);
+        assert_eq!(tree.overlay().len(), 4);
+        let dump = tree.to_string();
+        assert!(dump.contains("  div#empty.card.main 20x10 at 0,0 (Block Relative) rules: .card\n"));
+        assert!(dump.contains("      \"Text\" 20x16 at 0,0 (Block Relative)\n"));
+    }
+
+    #[test]
+    pub fn test_display_list_shadows_and_gradient() {
+        let css = r#"
+            body { width: 100px; height: 100px; }
+            #card {
+                width: 40px;
+                height: 20px;
+                background-image: linear-gradient(45deg, #ff0000, #00ff00, #0000ff 80%, #000000);
+                box-shadow: 0 2px 4px #000000, inset 1px 1px #ffffff, 2px 2px 0 1px #ff0000;
+            }
+            #title { text-shadow: 1px 1px 2px #0000ff; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="card"></div>
+            <div id="title">Title</div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let commands = view.display_list().expect("display list valid");
+        let shadow = |offset, blur, spread, color, inset| DisplayCommand::BoxShadow {
+            position: [0.0, 0.0],
+            size: [40.0, 20.0],
+            radius: [0.0; 4],
+            shadow: Shadow {
+                offset,
+                blur,
+                spread,
+                color,
+                inset,
+            },
+        };
+        let stop = |color, offset| ColorStop { color, offset };
+        let expected = vec![
+            shadow([2.0, 2.0], 0.0, 1.0, [255, 0, 0, 255], false),
+            shadow([0.0, 2.0], 4.0, 0.0, [0, 0, 0, 255], false),
+            DisplayCommand::Gradient {
+                position: [0.0, 0.0],
+                size: [40.0, 20.0],
+                radius: [0.0; 4],
+                gradient: LinearGradient {
+                    angle: 45.0,
+                    stops: vec![
+                        stop([255, 0, 0, 255], 0.0),
+                        stop([0, 255, 0, 255], 0.4),
+                        stop([0, 0, 255, 255], 0.8),
+                        stop([0, 0, 0, 255], 1.0),
+                    ],
+                },
+            },
+            shadow([1.0, 1.0], 0.0, 0.0, [255, 255, 255, 255], true),
+        ];
+        assert_eq!(commands[..4], expected);
+        let text = match &commands[4..] {
+            [DisplayCommand::TextShadow { text, shadow, .. }, DisplayCommand::TextRun { .. }] => {
+                assert_eq!(shadow.offset, [1.0, 1.0]);
+                assert_eq!(shadow.blur, 2.0);
+                assert_eq!(shadow.color, [0, 0, 255, 255], "inherited by text");
+                text
+            }
+            commands => panic!("unexpected text commands {commands:?}"),
+        };
+        assert_eq!(text, "Title");
+    }
+
+    #[test]
+    pub fn test_sprite_sheet_background() {
+        let css = r#"
+            div { width: 10px; height: 10px; }
+            #coin {
+                background-image: url("coin.png"), url("glow.png");
+                -bumaga-background-frames: 8, 4;
+                -bumaga-background-duration: 0.8s, 0s;
+            }
+        "#;
+        let html = r#"<html>
+        <body><div id="coin"></div></body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut frames = |time: f32| {
+            let input = Input::new().time(Duration::from_secs_f32(time));
+            view.update(input, json!({})).expect("valid update");
+            let coin = view.get_element_by_id("coin").unwrap();
+            let frames: Vec<_> = coin
+                .backgrounds
+                .iter()
+                .map(|background| [background.frame, background.frames])
+                .collect();
+            (frames, coin.layer)
+        };
+        assert_eq!(frames(0.05), (vec![[0, 8], [0, 4]], true));
+        assert_eq!(frames(0.35), (vec![[3, 8], [0, 4]], true));
+        assert_eq!(frames(1.25), (vec![[4, 8], [0, 4]], true), "looped");
+        let images: Vec<_> = view
+            .display_list()
+            .unwrap()
+            .into_iter()
+            .filter_map(|command| match command {
+                DisplayCommand::Image { src, frame, .. } => Some((src, frame)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            images,
+            [
+                ("glow.png".to_string(), [0, 4]),
+                ("coin.png".to_string(), [4, 8])
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_text_stroke() {
+        let css = r#"
+            body { color: #ffffff; }
+            #hud { -bumaga-text-stroke: 2px #000000; }
+            #plain { -bumaga-text-stroke: none; }
+            #current { -bumaga-text-stroke-width: 1px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="hud"><span id="score">100</span></div>
+            <div id="plain">0</div>
+            <div id="current">0</div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        view.update(Input::new(), json!({})).expect("valid update");
+        let stroke = |id: &str| view.get_element_by_id(id).unwrap().font.stroke;
+        let outline = TextStroke {
+            width: 2.0,
+            color: Some([0, 0, 0, 255]),
+        };
+        assert_eq!(stroke("hud"), outline);
+        assert_eq!(stroke("score"), outline, "inherited");
+        assert_eq!(stroke("plain").width, 0.0);
+        assert_eq!(stroke("current").color, None, "text color");
+        let runs: Vec<_> = view
+            .display_list()
+            .unwrap()
+            .into_iter()
+            .filter_map(|command| match command {
+                DisplayCommand::TextRun { text, font, .. } => Some((text, font.stroke.width)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(runs[0], ("100".to_string(), 2.0));
+    }
+
+    #[test]
+    pub fn test_material_pass_through() {
+        let css = r#"
+            div { width: 10px; height: 10px; background-color: #ff0000; }
+            #cooldown { -bumaga-material: sweep; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div id="cooldown"><div id="icon"></div></div>
+            <div id="holo" data-material="holo"></div>
+            <div id="plain"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .track_changes(true);
+        let output = view.update(Input::new(), json!({})).expect("valid update");
+        let material = |id: &str| view.get_element_by_id(id).unwrap().material.clone();
+        assert_eq!(material("cooldown"), Some("sweep".to_string()));
+        assert_eq!(material("holo"), Some("holo".to_string()), "attribute");
+        assert_eq!(material("icon"), None);
+        let materials: Vec<_> = view
+            .display_list()
+            .unwrap()
+            .into_iter()
+            .filter_map(|command| match command {
+                DisplayCommand::Material { name } => Some(name.unwrap_or("default".into())),
+                DisplayCommand::Rect { .. } => Some("rect".to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            materials,
+            ["sweep", "rect", "rect", "default", "holo", "rect", "default", "rect"]
+        );
+        let sweep = Some("sweep".to_string());
+        let icon = view.get_element_by_id("icon").unwrap().node;
+        let patch = output.changes.iter().find_map(|change| match change {
+            ElementChange::Created(patch) if patch.node == icon => Some(patch),
+            _ => None,
+        });
+        assert_eq!(patch.unwrap().material, sweep, "inherited by descendants");
+    }
+
+    #[test]
+    pub fn test_fonts_resolve_face_by_weight_and_style() {
+        let css = r#"
+            .bold { font-weight: bold; }
+            .italic { font-style: italic; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div>Text</div>
+            <div class="bold">Text</div>
+            <div class="italic">Text</div>
+            <div class="bold italic">Text</div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(StyledFonts);
+        view.update(Input::new(), json!({})).expect("valid update");
+        let body = view.body();
+        let widths: Vec<f32> = body
+            .children()
+            .iter()
+            .map(|div| div.children()[0].lines[0].width)
+            .collect();
+        assert_eq!(widths, [40.0, 48.0, 44.0, 52.0]);
+    }
+
+    #[test]
+    pub fn test_text_wrapped_by_view() {
+        let css = r#"
+            div { width: 75px; font-size: 16px; }
+        "#;
+        let html = r#"<html><body><div>aaa bbb ccc</div></body></html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(StyledFonts);
+        view.update(Input::new(), json!({})).expect("valid update");
+        let body = view.body();
+        let div = body.children()[0];
+        let text = div.children()[0];
+        let lines: Vec<_> = text.lines.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(lines, ["aaa bbb", "ccc"]);
+        assert_eq!(div.size, [75.0, 32.0]);
+    }
+
+    #[test]
+    pub fn test_text_layout_memoized_between_layouts() {
+        let css = r#"
+            div { width: 50vw; }
+        "#;
+        let html = r#"<html><body><div>aaa bbb ccc ddd eee fff ggg</div></body></html>"#;
+        let count = Rc::new(Cell::new(0));
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(CountingFonts(count.clone()));
+        let mut update = |viewport: [f32; 2]| {
+            count.set(0);
+            let input = Input::new().viewport(viewport);
+            view.update(input, json!({})).expect("valid update");
+            count.get()
+        };
+        assert_ne!(update([200.0, 100.0]), 0);
+        assert_ne!(update([300.0, 150.0]), 0, "text not laid out by new width");
+        assert_eq!(
+            update([200.0, 100.0]),
+            0,
+            "text laid out again by the same width"
+        );
+    }
+
+    #[test]
+    pub fn test_rich_inline_text() {
+        let css = r#"
+            div { width: 100px; font-size: 10px; }
+            b { font-size: 20px; color: #ff0000; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div>Hello <b>big</b> <i>world</i><span style="color: #00ff00;">!</span></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(StyledFonts);
+        view.update(Input::new(), json!({})).expect("valid update");
+        let body = view.body();
+        let div = body.children()[0];
+        assert_eq!(div.size, [100.0, 30.0]);
+        let runs: Vec<_> = view
+            .display_list()
+            .unwrap()
+            .into_iter()
+            .filter_map(|command| match command {
+                DisplayCommand::TextRun {
+                    text,
+                    position,
+                    color,
+                    font,
+                    ..
+                } => Some((text, position, color, font.weight, font.style)),
+                _ => None,
+            })
+            .collect();
+        let normal = SmolStr::new_static("normal");
+        let italic = SmolStr::new_static("italic");
+        assert_eq!(
+            runs,
+            [
+                (
+                    "Hello".to_string(),
+                    [0.0, 8.0],
+                    [0, 0, 0, 255],
+                    400,
+                    normal.clone()
+                ),
+                (
+                    " big".to_string(),
+                    [50.0, 0.0],
+                    [255, 0, 0, 255],
+                    700,
+                    normal.clone()
+                ),
+                (
+                    "world".to_string(),
+                    [0.0, 20.0],
+                    [0, 0, 0, 255],
+                    400,
+                    italic
+                ),
+                ("!".to_string(), [55.0, 20.0], [0, 255, 0, 255], 400, normal),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_inline_element_restores_cascaded_display() {
+        let css = r#"
+            span { display: flex; }
+            div { width: 10px; height: 10px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <p>Hello <span>world</span> <b><div ?="{boxed}"></div></b></p>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let span = |view: &View| {
+            let body = view.body();
+            let node = body.children()[0].children()[1].element.node;
+            let display = view.tree.style(node).expect("span style").display;
+            (view.tree.get_element(node).expect("span").inline, display)
+        };
+        let input = Input::new().event(InputEvent::MouseMove([500.0, 500.0]));
+        view.update(input, json!({ "boxed": true }))
+            .expect("valid update");
+        assert_eq!(span(&view), (false, taffy::Display::Flex));
+        view.update(Input::new(), json!({ "boxed": false }))
+            .expect("valid update");
+        assert_eq!(
+            span(&view),
+            (true, taffy::Display::None),
+            "paragraph of texts only"
+        );
+        view.update(Input::new(), json!({ "boxed": true }))
+            .expect("valid update");
+        assert_eq!(span(&view), (false, taffy::Display::Flex));
+    }
+
+    #[test]
+    pub fn test_bidirectional_text() {
+        let css = r#"
+            div { width: 200px; font-size: 10px; }
+            .rtl { direction: rtl; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div>Hi שלום</div>
+            <div class="rtl">Hi שלום</div>
+            <div class="rtl">שלום <b>world</b></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(StyledFonts);
+        view.update(Input::new(), json!({})).expect("valid update");
+        let runs: Vec<_> = view
+            .display_list()
+            .unwrap()
+            .into_iter()
+            .filter_map(|command| match command {
+                DisplayCommand::TextRun { text, position, .. } => Some((text, position[0])),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            runs,
+            [
+                ("Hi םולש".to_string(), 0.0),
+                ("םולש Hi".to_string(), 0.0),
+                ("םולש".to_string(), 160.0),
+                ("world ".to_string(), 88.0),
+            ]
+        );
+        let body = view.body();
+        let rtl = body.children()[1];
+        assert_eq!(rtl.children()[0].lines[0].origin, [130.0, 10.0]);
+    }
+
+    #[test]
+    pub fn test_emoji_measured_by_emoji_face() {
+        let css = r#"
+            div { width: 200px; font-size: 10px; }
+        "#;
+        let html = r#"<html><body><div>gg 😀</div></body></html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(StyledFonts);
+        view.update(Input::new(), json!({})).expect("valid update");
+        let body = view.body();
+        let div = body.children()[0];
+        let text = div.children()[0];
+        assert_eq!(text.lines[0].width, 50.0);
+    }
+
+    #[test]
+    pub fn test_inline_text_aligned_by_baseline() {
+        let css = r#"
+            div { width: 200px; font-size: 10px; }
+            b { font-size: 20px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div>aa <b>bb</b> <span style="vertical-align: top;">cc</span></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(StyledFonts);
+        view.update(Input::new(), json!({})).expect("valid update");
+        let runs: Vec<_> = view
+            .display_list()
+            .unwrap()
+            .into_iter()
+            .filter_map(|command| match command {
+                DisplayCommand::TextRun {
+                    text,
+                    position,
+                    baseline,
+                    ..
+                } => Some((text, position[1], baseline)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            runs,
+            [
+                ("aa".to_string(), 8.0, 8.0),
+                (" bb".to_string(), 0.0, 16.0),
+                (" cc".to_string(), 0.0, 8.0)
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_long_words_hyphenated() {
+        let css = r#"
+            div { width: 60px; font-size: 10px; }
+            .auto { hyphens: auto; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div class="auto" lang="de">Donaudampfschiff</div>
+            <div class="auto" lang="en">Donaudampfschiff</div>
+            <div>{text}</div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(StyledFonts)
+            .hyphenator(|lang: &str, word: &str| match (lang, word) {
+                ("de", "Donaudampfschiff") => vec![5, 10],
+                _ => vec![],
+            });
+        let value = json!({"text": "ab cd\u{AD}ef"});
+        view.update(Input::new(), value).expect("valid update");
+        let body = view.body();
+        let lines: Vec<Vec<_>> = body
+            .children()
+            .iter()
+            .map(|div| {
+                let text = div.children()[0];
+                text.lines.iter().map(|line| line.text.clone()).collect()
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                vec!["Donau-", "dampf-", "schiff"],
+                vec!["Donaudampfschiff"],
+                vec!["ab cd-", "ef"],
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_tabs_expanded_to_tab_stops() {
+        let css = r#"
+            div { width: 500px; font-size: 10px; }
+            .spaces { tab-size: 4; }
+            .length { tab-size: 25px; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div class="spaces">{log}</div>
+            <div class="length">{log}</div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(StyledFonts);
+        let value = json!({"log": "a\tbc\td"});
+        view.update(Input::new(), value).expect("valid update");
+        let body = view.body();
+        let lines: Vec<_> = body
+            .children()
+            .iter()
+            .map(|div| {
+                let line = &div.children()[0].lines[0];
+                (line.text.clone(), line.width)
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                ("a   bc  d".to_string(), 90.0),
+                ("a  bc   d".to_string(), 90.0)
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_text_remeasured_when_font_loaded() {
+        let css = r#"
+            div { width: 200px; font-size: 10px; }
+            .fancy { font-family: "Fancy"; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div class="fancy">Hello</div>
+            <div>World</div>
+        </body>
+        </html>"#;
+        let loaded = Rc::new(Cell::new(false));
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(LazyFonts(loaded.clone()));
+        let output = view.update(Input::new(), json!({})).expect("valid update");
+        assert_eq!(output.pending_fonts, ["Fancy"]);
+        assert_eq!(view.body().children()[0].size[1], 10.0);
+        loaded.set(true);
+        let input = Input::new().font_loaded("Fancy");
+        let output = view.update(input, json!({})).expect("valid update");
+        assert_eq!(output.pending_fonts, Vec::<String>::new());
+        let body = view.body();
+        let sizes: Vec<_> = body.children().iter().map(|div| div.size[1]).collect();
+        assert_eq!(sizes, [20.0, 10.0]);
+    }
+
+    #[test]
+    pub fn test_same_labels_measured_once() {
+        let measures = |labels: usize| {
+            let html = format!(
+                "<html><body>{}</body></html>",
+                "<div>Start game</div>".repeat(labels)
+            );
+            let count = Rc::new(Cell::new(0));
+            let mut view = View::compile(&html, "", "")
+                .expect("view valid")
+                .fonts(CountingFonts(count.clone()));
+            view.update(Input::new(), json!({})).expect("valid update");
+            count.get()
+        };
+        assert_eq!(measures(1), measures(5));
+    }
+
+    #[test]
+    pub fn test_same_paragraphs_measured_once() {
+        let measures = |paragraphs: usize| {
+            let html = format!(
+                "<html><body>{}</body></html>",
+                "<div>Start <b>new</b> game</div>".repeat(paragraphs)
+            );
+            let count = Rc::new(Cell::new(0));
+            let mut view = View::compile(&html, "", "")
+                .expect("view valid")
+                .fonts(CountingFonts(count.clone()));
+            view.update(Input::new(), json!({})).expect("valid update");
+            count.get()
+        };
+        assert_eq!(measures(1), measures(5));
+    }
+
+    #[test]
+    pub fn test_paragraph_hyphenated_and_monospace() {
+        let css = r#"
+            div { width: 60px; font-size: 10px; hyphens: auto; }
+            .log { font-family: "Console"; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div lang="de">Donaudampfschiff <b>ok</b></div>
+            <div class="log">[info] <b>started</b></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "")
+            .expect("view valid")
+            .fonts(StyledFonts)
+            .monospace("console", 0.5)
+            .hyphenator(|lang: &str, word: &str| match (lang, word) {
+                ("de", "Donaudampfschiff") => vec![5, 10],
+                _ => vec![],
+            });
+        view.update(Input::new(), json!({})).expect("valid update");
+        let body = view.body();
+        let fragments: Vec<Vec<_>> = body
+            .children()
+            .iter()
+            .map(|div| {
+                let text = div.children()[0];
+                text.fragments
+                    .iter()
+                    .map(|fragment| (fragment.text.clone(), fragment.width))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            fragments,
+            [
+                vec![
+                    ("Donau-".to_string(), 60.0),
+                    ("dampf-".to_string(), 60.0),
+                    ("schiff".to_string(), 60.0),
+                    ("ok".to_string(), 24.0),
+                ],
+                vec![("[info]".to_string(), 30.0), ("started".to_string(), 35.0)],
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_monospace_text_measured_without_fonts() {
+        let css = r#"
+            div { width: 80px; font-size: 10px; }
+            .log { font-family: "Console"; }
+        "#;
+        let compile = |html: &str, count: Rc<Cell<usize>>| {
+            let mut view = View::compile(html, css, "")
+                .expect("view valid")
+                .fonts(CountingFonts(count))
+                .monospace("console", 0.5);
+            view.update(Input::new(), json!({})).expect("valid update");
+            view
+        };
+        let count = Rc::new(Cell::new(0));
+        compile(
+            "<html><body><div>started</div></body></html>",
+            count.clone(),
+        );
+        let measures = count.get();
+        count.set(0);
+        let html = r#"<html>
+        <body>
+            <div>started</div>
+            <div class="log">[info] started game</div>
+        </body>
+        </html>"#;
+        let view = compile(html, count.clone());
+        assert_eq!(count.get(), measures);
+        let body = view.body();
+        let log = body.children()[1];
+        let text = log.children()[0];
+        let lines: Vec<_> = text
+            .lines
+            .iter()
+            .map(|line| (line.text.as_str(), line.width))
+            .collect();
+        assert_eq!(lines, [("[info] started", 70.0), ("game", 20.0)]);
+    }
+
+    #[test]
+    pub fn test_monospace_kept_on_reload() {
+        let folder = std::env::temp_dir().join(format!("bumaga-reload-{}", std::process::id()));
+        fs::create_dir_all(&folder).expect("folder created");
+        let html = folder.join("index.html");
+        let css = folder.join("style.css");
+        let template =
+            |text: &str| format!(r#"<html><body><div class="log">{text}</div></body></html>"#);
+        fs::write(&html, template("[info] started game")).expect("html written");
+        let style = r#"div { width: 80px; font-size: 10px; font-family: "Console"; }"#;
+        fs::write(&css, style).expect("css written");
+        let mut view = View::watch(html.to_str().unwrap(), css.to_str().unwrap(), "")
+            .expect("view valid")
+            .monospace("console", 0.5);
+        view.update(Input::new(), json!({})).expect("valid update");
+        fs::write(&html, template("[info] reloaded game")).expect("html written");
+        let modified = SystemTime::now() + Duration::from_secs(60);
+        let file = fs::File::options()
+            .write(true)
+            .open(&html)
+            .expect("html opened");
+        file.set_modified(modified).expect("html touched");
+        view.update(Input::new(), json!({})).expect("valid update");
+        fs::remove_dir_all(&folder).expect("folder removed");
+        let body = view.body();
+        let log = body.children()[0];
+        let text = log.children()[0];
+        let lines: Vec<_> = text
+            .lines
+            .iter()
+            .map(|line| (line.text.as_str(), line.width))
+            .collect();
+        assert_eq!(lines, [("[info] reloaded", 75.0), ("game", 20.0)]);
+    }
+
+    #[test]
+    pub fn test_clean_elements_not_restyled() {
+        let css = r#"
+            div { width: 20px; height: 10px; color: #ff0000; }
+            div.on { color: #00ff00; }
+            div:hover { color: #0000ff; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <div @class="{class}"></div>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let update = |view: &mut View, class: &str, events: Vec<InputEvent>| {
+            let input = Input::new().events(events);
+            view.update(input, json!({ "class": class }))
+                .expect("valid update");
+            let body = view.body();
+            let div = &body.children()[0];
+            (div.node, div.color)
+        };
+        let away = || vec![InputEvent::MouseMove([5.0, 50.0])];
+        let (div, color) = update(&mut view, "", away());
+        assert_eq!(color, [255, 0, 0, 255]);
+        view.tree.get_element_mut(div).unwrap().color = [1, 2, 3, 4];
+        let (_, color) = update(&mut view, "", away());
+        assert_eq!(color, [1, 2, 3, 4], "idle update skips cascade");
+        let (_, color) = update(&mut view, "on", away());
+        assert_eq!(color, [0, 255, 0, 255], "class change restyles");
+        update(&mut view, "on", vec![InputEvent::MouseMove([5.0, 5.0])]);
+        let (_, color) = update(&mut view, "on", vec![]);
+        assert_eq!(color, [0, 0, 255, 255], "hover restyles");
+    }
+
+    #[test]
+    pub fn test_sibling_subtrees_restyled() {
+        let css = r#"
+            section { --accent: #ff0000; font-size: 10px; }
+            div { height: 10px; color: var(--accent); }
+            div.big { font-size: 20px; color: #00ff00; }
+        "#;
+        let html = r#"<html>
+        <body>
+            <section>
+                <div *item="8 {items}" @class="{item}"><span>{item}</span></div>
+            </section>
+        </body>
+        </html>"#;
+        let mut view = View::compile(html, css, "").expect("view valid");
+        let mut update = |items: Value| {
+            let input = Input::new().event(InputEvent::MouseMove([500.0, 500.0]));
+            view.update(input, json!({ "items": items }))
+                .expect("valid update");
+            let body = view.body();
+            let section = body.children()[0];
+            section
+                .children()
+                .iter()
+                .map(|div| {
+                    let span = div.children()[0];
+                    (div.color, span.element.font.size)
+                })
+                .collect::<Vec<_>>()
+        };
+        let red = [255, 0, 0, 255];
+        let green = [0, 255, 0, 255];
+        let divs = update(json!(["a", "b", "c", "d"]));
+        assert_eq!(divs, vec![(red, 10.0); 4]);
+        let divs = update(json!(["a", "big", "c", "d"]));
+        assert_eq!(divs, [(red, 10.0), (green, 20.0), (red, 10.0), (red, 10.0)]);
+    }
+
+    fn msg(key: &str, value: &str) -> Value {
+        json!({
+            key: value
+        })
+    }
+
+    /// The fonts counting measured chars.
+    struct MeasuredChars(Rc<Cell<usize>>);
+
+    impl Fonts for MeasuredChars {
+        fn measure(&self, _id: FaceId, text: &str, face: &FontFace, _: Option<f32>) -> [f32; 2] {
+            self.0.set(self.0.get() + text.len());
+            [text.len() as f32 * 10.0, face.size]
+        }
+    }
+
+    struct StyledFonts;
+
+    impl Fonts for StyledFonts {
+        fn resolve(&self, _family: &str, weight: u16, style: &str) -> FaceId {
+            FaceId(usize::from(weight >= 700) + 2 * usize::from(style == "italic"))
+        }
+
+        fn resolve_emoji(&self, _id: FaceId) -> FaceId {
+            FaceId(4)
+        }
+
+        fn measure(&self, id: FaceId, text: &str, face: &FontFace, _: Option<f32>) -> [f32; 2] {
+            let advance = [10.0, 12.0, 11.0, 13.0, 20.0][id.0];
+            [text.chars().count() as f32 * advance, face.size]
+        }
+    }
+
+    struct LazyFonts(Rc<Cell<bool>>);
+
+    impl Fonts for LazyFonts {
+        fn is_loaded(&self, family: &str) -> bool {
+            family != "Fancy" || self.0.get()
+        }
+
+        fn measure(&self, _id: FaceId, text: &str, face: &FontFace, _: Option<f32>) -> [f32; 2] {
+            let scale = if self.0.get() { 2.0 } else { 1.0 };
+            [text.len() as f32 * 10.0 * scale, face.size * scale]
+        }
+    }
+
+    struct CountingFonts(Rc<Cell<usize>>);
+
+    impl Fonts for CountingFonts {
+        fn measure(&self, _id: FaceId, text: &str, face: &FontFace, _: Option<f32>) -> [f32; 2] {
+            self.0.set(self.0.get() + 1);
+            [text.len() as f32 * 10.0, face.size]
+        }
     }
 }
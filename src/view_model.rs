@@ -1,24 +1,48 @@
 use crate::{
-    Element, ElementState, HandlerArgument, Input, InputEvent, Keys, MouseButtons, Output,
-    PointerEvents, ValueExtensions, ViewError,
+    pending, Composition, Element, ElementState, Fonts, Handler, HandlerArgument, Input,
+    InputEvent, Keys, Modifiers, MouseButtons, Output, Pen, PenPhase, PointerEvents, Resize,
+    ValueExtensions, ViewError, PENDING,
 };
 use log::error;
 
+use crate::accessibility::collect_access_nodes;
+use crate::clicks::{ClickCounter, HoldTimer, SwipeTracker};
+use crate::controls::{
+    is_ctrl_char, is_submit_button, is_text_input, EditHistory, EditKind, Resources, TextCursor,
+};
+use crate::display_list::element_translate;
+use crate::fonts::MonospaceFonts;
+use crate::kinetic::KineticScrolling;
+use crate::limiter::{HandlerId, RateLimiter};
+use crate::local_state::LocalState;
+use crate::repeat::KeyRepeat;
+use crate::styles::wheel_distance;
+use crate::tooltip::TooltipTimer;
+use crate::touch::Touches;
+use crate::transformers::default_transformers;
+use crate::translations::Translations;
 use crate::tree::ViewTreeExtensions;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
+use smol_str::SmolStr;
 use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fmt::{Display, Formatter};
 use std::mem::take;
-use std::time::Duration;
 use taffy::{NodeId, TaffyTree};
 
 pub type Bindings = BTreeMap<String, Vec<Binding>>;
 
-pub type Transformer = fn(Value) -> Value;
+/// Transforms bound value before it is shown using pipe arguments,
+/// can capture application state.
+pub type Transformer = Box<dyn FnMut(Value, &[Value]) -> Value>;
 
 pub struct ViewModel {
     pub(crate) bindings: Bindings,
-    model: Value,
+    pub(crate) derived: Vec<DerivedValue>,
+    pub(crate) keyed: KeyedArrays,
+    pub(crate) model: Value,
+    schema: Value,
     model_array_default: HashMap<String, Value>,
     pub(crate) transformers: HashMap<String, Transformer>,
     // state
@@ -26,9 +50,39 @@ pub struct ViewModel {
     pub(crate) mouse: [f32; 2],
     pub(crate) elements_under_mouse: Vec<NodeId>,
     pub(crate) elements_in_action: Vec<NodeId>,
-    output: Output,
+    /// The element capturing mouse explicitly until button released, see
+    /// [`View::capture_pointer`](crate::View::capture_pointer).
+    pub(crate) pointer_capture: Option<NodeId>,
+    pub(crate) output: Output,
     pub(crate) drag: Option<DragContext>,
     pub(crate) focus: Option<NodeId>,
+    /// Whether last interaction was keyboard, so focus is indicated as visible.
+    pub(crate) keyboard_modality: bool,
+    pub(crate) modifiers: Modifiers,
+    pub(crate) tooltip: TooltipTimer,
+    pub(crate) clicks: ClickCounter,
+    pub(crate) hold: HoldTimer,
+    pub(crate) swipe: SwipeTracker,
+    pub(crate) repeat: KeyRepeat,
+    pub(crate) kinetic: KineticScrolling,
+    pub(crate) touches: Touches,
+    /// The IME composition in progress and text input it belongs to.
+    pub(crate) composition: Option<(NodeId, Composition)>,
+    /// The caret and selection of focused text input.
+    pub(crate) cursor: Option<TextCursor>,
+    /// Whether accessibility snapshot collected to output.
+    pub(crate) accessibility: bool,
+    /// The identified elements resized by layout since last output.
+    pub(crate) resized: Vec<Resize>,
+    pub(crate) resources: Resources,
+    pub(crate) history: EditHistory,
+    pub(crate) translations: Translations,
+    pub(crate) local: LocalState,
+    /// The values shared with all templates, not bound from application model.
+    pub(crate) context: Map<String, Value>,
+    pub(crate) limiter: RateLimiter,
+    /// The monospace families measured without fonts, see [`crate::View::monospace`].
+    pub(crate) monospace: MonospaceFonts,
 }
 
 impl ViewModel {
@@ -37,15 +91,39 @@ impl ViewModel {
         Self::memorize_array_default("", &model, &mut model_array_default);
         Self {
             bindings,
+            derived: vec![],
+            keyed: KeyedArrays::default(),
+            schema: Schema::shape(&model),
             model,
             model_array_default,
             transformers: default_transformers(),
             mouse: [0.0, 0.0],
             elements_under_mouse: Vec::new(),
             elements_in_action: vec![],
+            pointer_capture: None,
             output: Output::new(),
             drag: None,
             focus: None,
+            keyboard_modality: false,
+            modifiers: Modifiers::default(),
+            tooltip: TooltipTimer::default(),
+            clicks: ClickCounter::default(),
+            hold: HoldTimer::default(),
+            swipe: SwipeTracker::default(),
+            repeat: KeyRepeat::default(),
+            kinetic: KineticScrolling::default(),
+            touches: Touches::default(),
+            cursor: None,
+            accessibility: false,
+            composition: None,
+            resized: vec![],
+            resources: Resources::default(),
+            history: EditHistory::default(),
+            translations: Translations::default(),
+            local: LocalState::default(),
+            context: Map::new(),
+            limiter: RateLimiter::default(),
+            monospace: MonospaceFonts::default(),
         }
     }
 
@@ -70,37 +148,171 @@ impl ViewModel {
 
     pub fn bind(&mut self, value: &Value) -> Vec<Reaction> {
         let mut reactions = vec![];
-        Self::bind_value(
-            &mut self.model,
-            value,
-            "",
-            "",
-            &self.bindings,
-            &mut reactions,
-            &self.transformers,
-            &self.model_array_default,
-        );
+        // context values detached, so application model can't override them
+        let mut context = vec![];
+        if let Value::Object(model) = &mut self.model {
+            for key in self.context.keys() {
+                if let Some(value) = model.remove(key) {
+                    context.push((key.clone(), value));
+                }
+            }
+        }
+        let mut bind_context = BindContext {
+            bindings: &self.bindings,
+            reactions: &mut reactions,
+            transformers: &mut self.transformers,
+            default: &self.model_array_default,
+            keyed: &mut self.keyed,
+        };
+        let changed = Self::bind_value(&mut self.model, value, "", "", &mut bind_context);
+        if let Value::Object(model) = &mut self.model {
+            model.extend(context);
+        }
+        if changed {
+            reactions.extend(self.derive(String::new()));
+        }
         reactions
     }
 
+    /// Sets value available in any template by its name, e.g. `{theme.color}`.
+    pub fn set_context(&mut self, name: &str, value: Value) -> Result<Vec<Reaction>, ViewError> {
+        self.context.insert(name.to_string(), value.clone());
+        let pointer = format!("/{name}");
+        if self.model.pointer(&pointer).is_none() {
+            // context value not used in templates
+            return Ok(vec![]);
+        }
+        self.set(&pointer, &value)
+    }
+
+    /// Returns shape of model value expected by template, arrays are described by single item
+    /// and leaf values are null.
+    pub fn schema(&self) -> Value {
+        let mut schema = self.schema.clone();
+        for derived in &self.derived {
+            let shape = schema.pointer(&derived.target).cloned();
+            if let (Some(shape), Some(source)) = (shape, schema.pointer_mut(&derived.source)) {
+                *source = shape;
+            }
+        }
+        if let Value::Object(object) = &mut schema {
+            object.retain(|key, _| {
+                !key.starts_with(DerivedValue::PREFIX) && !self.context.contains_key(key)
+            });
+        }
+        schema
+    }
+
+    pub fn value_at(&self, pointer: &str) -> Option<&Value> {
+        self.model.pointer(pointer)
+    }
+
+    /// Recomputes piped values of sources changed at pointer, e.g. sorted items of
+    /// `*item="{items | sort}"`, values of other sources and unchanged ones are kept.
+    fn derive(&mut self, pointer: String) -> Vec<Reaction> {
+        let mut reactions = vec![];
+        // derived values may be sources of next ones, e.g. nested repeats
+        let mut changed = vec![pointer];
+        for index in 0..self.derived.len() {
+            let derived = &self.derived[index];
+            if !changed
+                .iter()
+                .any(|pointer| is_path_related(&derived.source, pointer))
+            {
+                continue;
+            }
+            let source = self.model.pointer(&derived.source).unwrap_or(&Value::Null);
+            if *source == derived.source_value {
+                continue;
+            }
+            let source = source.clone();
+            let target = derived.target.clone();
+            let value = transform(source.clone(), &derived.pipe, &mut self.transformers);
+            self.derived[index].source_value = source;
+            match self.set_value(&target, &value) {
+                Ok(changes) => reactions.extend(changes),
+                Err(error) => error!("unable to derive value {target}, {error:?}"),
+            }
+            changed.push(target);
+        }
+        reactions
+    }
+
+    /// Binds value to model at specified JSON pointer,
+    /// only bindings of this value and its ancestors react.
+    pub fn set(&mut self, pointer: &str, value: &Value) -> Result<Vec<Reaction>, ViewError> {
+        let mut reactions = self.set_value(pointer, value)?;
+        reactions.extend(self.derive(pointer.to_string()));
+        Ok(reactions)
+    }
+
+    fn set_value(&mut self, pointer: &str, value: &Value) -> Result<Vec<Reaction>, ViewError> {
+        let mut reactions = vec![];
+        let arrays_path = arrays_path(pointer);
+        let dst = self
+            .model
+            .pointer_mut(pointer)
+            .ok_or_else(|| ViewError::ModelValueNotFound(pointer.to_string()))?;
+        let mut bind_context = BindContext {
+            bindings: &self.bindings,
+            reactions: &mut reactions,
+            transformers: &mut self.transformers,
+            default: &self.model_array_default,
+            keyed: &mut self.keyed,
+        };
+        let changed = Self::bind_value(dst, value, pointer, &arrays_path, &mut bind_context);
+        if changed {
+            let mut path = pointer;
+            while let Some(end) = path.rfind('/') {
+                path = &path[..end];
+                if let Some(Value::Object(_)) = self.model.pointer(path) {
+                    Self::react(
+                        path,
+                        &json!({}),
+                        &self.bindings,
+                        &mut reactions,
+                        &mut self.transformers,
+                    );
+                }
+            }
+        }
+        Ok(reactions)
+    }
+
     pub fn bind_value(
         mut dst: &mut Value,
         src: &Value,
         path: &str,
         arrays_path: &str,
-        bindings: &Bindings,
-        reactions: &mut Vec<Reaction>,
-        transformers: &HashMap<String, Transformer>,
-        default: &HashMap<String, Value>,
+        context: &mut BindContext,
     ) -> bool {
+        if dst == src {
+            // fast path, unchanged subtree does not need paths evaluation
+            return false;
+        }
+        if src.is_pending() && dst.is_array() {
+            // items of pending array are not known yet
+            return Self::bind_value(dst, &Value::Null, path, arrays_path, context);
+        }
         match (&mut dst, src) {
+            (dst, src) if dst.is_pending() || (src.is_pending() && !dst.is_object()) => {
+                **dst = src.clone();
+                context.react(path, src);
+                Self::react_pending(path, src.is_pending(), context.bindings, context.reactions);
+                true
+            }
+            (Value::Array(current), Value::Array(next))
+                if context.keyed.keys.contains_key(arrays_path) =>
+            {
+                Self::bind_keyed_array(current, next, src, path, arrays_path, context)
+            }
             (Value::Array(current), Value::Array(next)) => {
                 let mut array_changed = false;
                 if current.len() != next.len() {
-                    if let Some(default) = default.get(arrays_path).cloned() {
+                    if let Some(default) = context.default.get(arrays_path).cloned() {
                         array_changed = true;
                         current.resize(next.len(), default);
-                        Self::react(path, src, bindings, reactions, transformers);
+                        context.react(path, src);
                     } else {
                         error!("unable to resize array {path} default not found");
                     }
@@ -108,16 +320,7 @@ impl ViewModel {
                 for (index, dst) in current.iter_mut().enumerate() {
                     let src = &next[index];
                     let path = format!("{path}/{index}");
-                    let changed = Self::bind_value(
-                        dst,
-                        src,
-                        &path,
-                        arrays_path,
-                        bindings,
-                        reactions,
-                        transformers,
-                        default,
-                    );
+                    let changed = Self::bind_value(dst, src, &path, arrays_path, context);
                     array_changed = array_changed || changed;
                 }
                 array_changed
@@ -125,13 +328,7 @@ impl ViewModel {
             (Value::Array(current), Value::Null) => {
                 if current.len() != 0 {
                     current.clear();
-                    Self::react(
-                        path,
-                        &Value::Array(vec![]),
-                        bindings,
-                        reactions,
-                        transformers,
-                    );
+                    context.react(path, &Value::Array(vec![]));
                     true
                 } else {
                     false
@@ -143,10 +340,17 @@ impl ViewModel {
             }
             (Value::Object(object), Value::Object(src)) => {
                 let mut object_changed = false;
+                // all fields of pending object are pending too
+                let undefined = match src.get(PENDING) {
+                    Some(Value::Bool(true)) => pending(),
+                    _ => Value::Null,
+                };
                 for (key, dst) in object.iter_mut() {
+                    if path.is_empty() && key.starts_with(DerivedValue::PREFIX) {
+                        continue;
+                    }
                     let path = format!("{path}/{key}");
                     let arrays_path = format!("{arrays_path}/{key}");
-                    let undefined = Value::Null;
                     let src = match src.get(key) {
                         Some(src) => src,
                         None => {
@@ -154,49 +358,34 @@ impl ViewModel {
                             &undefined
                         }
                     };
-                    let changed = Self::bind_value(
-                        dst,
-                        src,
-                        &path,
-                        &arrays_path,
-                        bindings,
-                        reactions,
-                        transformers,
-                        default,
-                    );
+                    let changed = Self::bind_value(dst, src, &path, &arrays_path, context);
                     object_changed = object_changed || changed;
                 }
                 if object_changed {
-                    Self::react(path, &json!({}), bindings, reactions, transformers);
+                    context.react(path, &json!({}));
                 }
                 object_changed
             }
             (Value::Object(object), Value::Null) => {
                 let mut object_changed = false;
                 for (key, dst) in object.iter_mut() {
+                    if path.is_empty() && key.starts_with(DerivedValue::PREFIX) {
+                        continue;
+                    }
                     let path = format!("{path}/{key}");
                     let arrays_path = format!("{arrays_path}/{key}");
-                    let changed = Self::bind_value(
-                        dst,
-                        &Value::Null,
-                        &path,
-                        &arrays_path,
-                        bindings,
-                        reactions,
-                        transformers,
-                        default,
-                    );
+                    let changed = Self::bind_value(dst, &Value::Null, &path, &arrays_path, context);
                     object_changed = object_changed || changed;
                 }
                 if object_changed {
-                    Self::react(path, &Value::Null, bindings, reactions, transformers);
+                    context.react(path, &Value::Null);
                 }
                 object_changed
             }
             (dst, src) => {
                 if *dst != src {
                     **dst = src.clone();
-                    Self::react(path, src, bindings, reactions, transformers);
+                    context.react(path, src);
                     true
                 } else {
                     false
@@ -205,28 +394,124 @@ impl ViewModel {
         }
     }
 
+    /// Binds array items to element slots by identity key, so moved item keeps its elements
+    /// and only order of elements changes. Slots of removed items stay hidden until reused,
+    /// model array is truncated after last used slot once dropped slots are reset to default.
+    fn bind_keyed_array(
+        current: &mut Vec<Value>,
+        next: &[Value],
+        src: &Value,
+        path: &str,
+        arrays_path: &str,
+        context: &mut BindContext,
+    ) -> bool {
+        let key = context.keyed.keys[arrays_path].clone();
+        let mut array_changed = false;
+        let count = next.len();
+        if current.len() < count {
+            match context.default.get(arrays_path) {
+                Some(default) => current.resize(count, default.clone()),
+                None => {
+                    error!("unable to resize array {path} default not found");
+                    return false;
+                }
+            }
+        }
+        let resized = context.keyed.orders.get(path).map(Vec::len) != Some(count);
+        if resized {
+            array_changed = true;
+            context.react(path, src);
+        }
+        let identities: Vec<Option<&Value>> = current
+            .iter()
+            .map(|item| item.get(&key).filter(|id| !id.is_null()))
+            .collect();
+        let mut order = vec![None; count];
+        let mut used = vec![false; current.len()];
+        for (index, item) in next.iter().enumerate() {
+            if let Some(id) = item.get(&key).filter(|id| !id.is_null()) {
+                let slot = identities.iter().position(|identity| *identity == Some(id));
+                if let Some(slot) = slot.filter(|slot| !used[*slot]) {
+                    order[index] = Some(slot);
+                    used[slot] = true;
+                }
+            }
+        }
+        let mut free = (0..current.len()).filter(|slot| !used[*slot]);
+        let order: Vec<usize> = order
+            .into_iter()
+            .map(|slot| slot.or_else(|| free.next()).unwrap_or_default())
+            .collect();
+        for (index, slot) in order.iter().enumerate() {
+            let changed = Self::bind_value(
+                &mut current[*slot],
+                &next[index],
+                &format!("{path}/{slot}"),
+                arrays_path,
+                context,
+            );
+            array_changed = array_changed || changed;
+        }
+        // dropped slots are reset to default, so elements reused later react to all values
+        let length = order.iter().max().map_or(0, |slot| slot + 1);
+        if current.len() > length {
+            if let Some(default) = context.default.get(arrays_path).cloned() {
+                for (slot, item) in current.iter_mut().enumerate().skip(length) {
+                    let path = format!("{path}/{slot}");
+                    Self::bind_value(item, &default, &path, arrays_path, context);
+                }
+            }
+            current.truncate(length);
+            array_changed = true;
+        }
+        // resize reactions show elements in slots order, so display order must be restored
+        if resized || context.keyed.orders.get(path) != Some(&order) {
+            if let Some(bindings) = context.bindings.get(path) {
+                for binding in bindings {
+                    if let BindingParams::Order(parent, slots) = &binding.params {
+                        let mut nodes: Vec<NodeId> = order
+                            .iter()
+                            .filter_map(|slot| slots.get(*slot))
+                            .copied()
+                            .collect();
+                        let count = nodes.len();
+                        let unused = (0..slots.len()).filter(|slot| !order.contains(slot));
+                        nodes.extend(unused.map(|slot| slots[slot]));
+                        context.reactions.push(Reaction::Reorder {
+                            parent: *parent,
+                            nodes,
+                            count,
+                        });
+                    }
+                }
+            }
+            context.keyed.orders.insert(path.to_string(), order);
+            array_changed = true;
+        }
+        array_changed
+    }
+
     #[inline]
     fn react(
         path: &str,
         value: &Value,
         bindings: &Bindings,
         reactions: &mut Vec<Reaction>,
-        transformers: &HashMap<String, Transformer>,
+        transformers: &mut HashMap<String, Transformer>,
     ) {
         if let Some(bindings) = bindings.get(path) {
+            // bindings of the same value often share pipe, transform it once
+            let mut memo: Vec<(&[Pipe], Value)> = vec![];
             for binding in bindings {
-                if binding.pipe.len() > 0 {
-                    let mut value = value.clone();
-                    for name in &binding.pipe {
-                        match transformers.get(name) {
-                            None => {
-                                error!("unable to bind value, transformer {name} not found")
-                            }
-                            Some(transform) => {
-                                value = transform(value);
-                            }
+                if binding.pipe.len() > 0 && !value.is_pending() {
+                    let value = match memo.iter().find(|(pipe, _)| *pipe == binding.pipe) {
+                        Some((_, value)) => value.clone(),
+                        None => {
+                            let value = transform(value.clone(), &binding.pipe, transformers);
+                            memo.push((&binding.pipe, value.clone()));
+                            value
                         }
-                    }
+                    };
                     reactions.push(binding.react_value_change(&value));
                 } else {
                     reactions.push(binding.react_value_change(value))
@@ -235,14 +520,36 @@ impl ViewModel {
         }
     }
 
+    /// Marks elements bound to value as loading until actual value is bound.
+    fn react_pending(
+        path: &str,
+        pending: bool,
+        bindings: &Bindings,
+        reactions: &mut Vec<Reaction>,
+    ) {
+        if let Some(bindings) = bindings.get(path) {
+            for binding in bindings {
+                let node = match binding.params {
+                    BindingParams::Text(node, _)
+                    | BindingParams::Attribute(node, _, _)
+                    | BindingParams::Tag(node, _)
+                    | BindingParams::Translation(node, _) => node,
+                    _ => continue,
+                };
+                reactions.push(Reaction::Pending { node, pending });
+            }
+        }
+    }
+
     pub fn handle_output(
         &mut self,
         input: &Input,
         body: NodeId,
         tree: &mut TaffyTree<Element>,
+        fonts: &dyn Fonts,
     ) -> Result<Output, ViewError> {
         let mut has_mouse_move = false;
-        let mut events = input.events.clone();
+        let mut events = input.logical_events();
         for event in events.iter() {
             match *event {
                 InputEvent::MouseMove(mouse) => {
@@ -258,35 +565,173 @@ impl ViewModel {
             // TODO: proper solution to fix problem
             events.insert(0, InputEvent::MouseMove(self.mouse))
         }
+        if let Some((key, count)) = self.repeat.advance(input.time) {
+            // key held since previous update repeats before new events
+            let position = if has_mouse_move { 0 } else { 1 };
+            let repeats = std::iter::repeat_n(InputEvent::KeyDown(key), count);
+            events.splice(position..position, repeats);
+        }
+        // messages emitted between updates, e.g. by programmatic focus
+        let messages = take(&mut self.output.messages);
         self.output = Output::new();
-        self.handle_elements_input(events, body, tree)?;
+        self.output.messages = messages;
+        self.limiter.advance(input.time);
+        self.clicks.advance(input.time);
+        self.hold.advance(input.time);
+        self.swipe.advance(input.time);
+        self.kinetic.frame = input.time.as_secs_f32();
+        self.kinetic.sensitivity = input.scroll_sensitivity;
+        self.advance_kinetic_scrolling(tree)?;
+        self.blur_disabled_focus(tree)?;
+        // synthetic events precede input events
+        let synthetic = events.len() - input.events.len();
+        let captures = self.handle_elements_input(events, body, tree, fonts)?;
+        self.output.uncaptured = input
+            .events
+            .iter()
+            .zip(&captures[synthetic..])
+            .filter(|(_, captured)| !**captured)
+            .map(|(event, _)| event.clone())
+            .collect();
+        self.handle_ticks(tree)?;
+        self.update_hold(tree)?;
+        let messages = self.limiter.flush();
+        self.output.messages.extend(messages);
+        if self.composition.as_ref().map(|(node, _)| Some(*node)) != Some(self.focus) {
+            // composition is canceled once input loses focus
+            self.composition = None;
+        }
+        self.output.composition = self.composition.as_ref().map(|(_, value)| value.clone());
+        if self.cursor.map(|cursor| cursor.input) != self.focus {
+            // caret is placed again once input focused
+            self.cursor = None;
+        }
+        self.output.caret = self.caret_output(tree, fonts)?;
+        if self.accessibility {
+            collect_access_nodes(tree, body, &mut self.output.accessibility)?;
+        }
+        self.output.resized = take(&mut self.resized);
+        self.output.tooltip = self.update_tooltip(input, tree)?;
+        self.handle_resources(input, tree)?;
+        self.output.drag = self.drag.as_ref().map(|drag| DragPayload {
+            position: self.mouse,
+            ..drag.payload.clone()
+        });
         self.output.is_input_captured = !self.elements_under_mouse.is_empty()
             || self.drag.is_some()
+            || !self.touches.points.is_empty()
             || self.focus.is_some()
-            || !self.elements_in_action.is_empty();
+            || !self.elements_in_action.is_empty()
+            || self.pointer_capture.is_some();
         Ok(take(&mut self.output))
     }
 
-    fn handle_elements_input(
+    /// Returns capturing and pressed elements outside of mouse, they capture mouse until
+    /// button released, so slider thumbs and other dragged controls keep receiving mouse
+    /// move and up events.
+    pub(crate) fn mouse_capture(&self) -> Vec<NodeId> {
+        let mut capture = vec![];
+        let pressed = self.elements_in_action.iter().rev();
+        for node in self.pointer_capture.iter().chain(pressed) {
+            if !self.elements_under_mouse.contains(node) && !capture.contains(node) {
+                capture.push(*node);
+            }
+        }
+        capture
+    }
+
+    fn blur_disabled_focus(&mut self, tree: &mut TaffyTree<Element>) -> Result<(), ViewError> {
+        if let Some(focus) = self.focus {
+            let element = tree.get_element_mut(focus)?;
+            if element.disabled() {
+                self.focus = None;
+                element.state.focus = false;
+                let event = MouseEvent::new(self.mouse, self.modifiers, element);
+                self.emit(element, "onblur", event);
+            }
+        }
+        Ok(())
+    }
+
+    /// Notifies about element size change, identified elements are reported in output.
+    pub(crate) fn observe_resize(&mut self, element: &Element, previous: [f32; 2]) {
+        let event = ResizeEvent::new(previous, element);
+        self.emit(element, "onresize", event);
+        if let Some(id) = element.attrs.get("id") {
+            self.resized.push(Resize {
+                id: id.clone(),
+                size: element.size,
+            });
+        }
+    }
+
+    /// Emits tick event to displayed elements, handler interval limits messages rate.
+    fn handle_ticks(&mut self, tree: &TaffyTree<Element>) -> Result<(), ViewError> {
+        let time = self.limiter.time().as_secs_f32();
+        for node in take(&mut self.limiter.tickers) {
+            let element = tree.get_element(node)?;
+            let event = TickEvent::new(time, element);
+            self.emit(element, "ontick", event);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn observe_visibility(&mut self, element: &Element) {
+        let visible = element.state.visible;
+        let event = VisibilityEvent::new(visible, element);
+        let name = if visible { "onappear" } else { "ondisappear" };
+        self.emit(element, name, event);
+    }
+
+    fn end_drag(&mut self, tree: &mut TaffyTree<Element>) -> Result<(), ViewError> {
+        if let Some(drag) = self.drag.take() {
+            let element = tree.get_element_mut(drag.source)?;
+            let event = DragEvent::new(self.mouse, &drag.payload.data, element);
+            self.emit(element, "ondragend", event);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn handle_elements_input(
         &mut self,
         events: Vec<InputEvent>,
         body: NodeId,
         tree: &mut TaffyTree<Element>,
-    ) -> Result<(), ViewError> {
+        fonts: &dyn Fonts,
+    ) -> Result<Vec<bool>, ViewError> {
+        let mut captures = vec![];
         for event in events {
-            match event {
+            // view state before event decides whether view is addressee of event
+            let mut captured = match &event {
+                InputEvent::MouseButtonUp(_) => {
+                    !self.elements_in_action.is_empty() || self.drag.is_some()
+                }
+                InputEvent::TouchEnd(id, _) => self.touches.is_targeted(*id),
+                InputEvent::Pen(PenPhase::Up, _) => !self.elements_in_action.is_empty(),
+                InputEvent::KeyDown(_)
+                | InputEvent::KeyUp(_)
+                | InputEvent::Char(_)
+                | InputEvent::Paste(_)
+                | InputEvent::Copy
+                | InputEvent::Cut
+                | InputEvent::ImeCompose(..)
+                | InputEvent::ImeCommit(_) => self.focus.is_some(),
+                _ => false,
+            };
+            match event.clone() {
                 InputEvent::Unknown => {}
                 InputEvent::MouseMove(position) => {
+                    self.mouse = position;
                     let previous_update = take(&mut self.elements_under_mouse);
                     self.calculate_mouse_hovers(tree, body, position)?;
                     for node in previous_update.iter().rev() {
                         if !self.elements_under_mouse.contains(node) {
                             let element = tree.get_element_mut(*node)?;
                             element.state.hover = false;
-                            let event = MouseEvent::new(self.mouse, element);
+                            let event = MouseEvent::new(self.mouse, self.modifiers, element);
                             self.emit(element, "onmouseleave", event);
-                            if self.drag.is_some() {
-                                let event = MouseEvent::new(self.mouse, element);
+                            if let Some(drag) = self.drag.as_ref() {
+                                let event = DragEvent::new(self.mouse, &drag.payload.data, element);
                                 self.emit(element, "ondragleave", event);
                             }
                         }
@@ -296,32 +741,55 @@ impl ViewModel {
                         if !previous_update.contains(node) {
                             let element = tree.get_element_mut(*node)?;
                             element.state.hover = true;
-                            let event = MouseEvent::new(self.mouse, element);
+                            let event = MouseEvent::new(self.mouse, self.modifiers, element);
                             self.emit(element, "onmouseenter", event);
-                            if self.drag.is_some() {
-                                let event = MouseEvent::new(self.mouse, element);
+                            if let Some(drag) = self.drag.as_ref() {
+                                let event = DragEvent::new(self.mouse, &drag.payload.data, element);
                                 self.emit(element, "ondragenter", event);
                             }
                         }
                         let element = tree.get_element_mut(*node)?;
-                        let event = MouseEvent::new(self.mouse, element);
+                        let event = MouseEvent::new(self.mouse, self.modifiers, element);
                         self.emit(element, "onmousemove", event);
-                        if self.drag.is_some() {
-                            let event = MouseEvent::new(self.mouse, element);
+                        if let Some(drag) = self.drag.as_ref() {
+                            let event = DragEvent::new(self.mouse, &drag.payload.data, element);
                             self.emit(element, "ondragover", event);
                         }
                     }
+                    for node in self.mouse_capture() {
+                        let element = tree.get_element(node)?;
+                        let event = MouseEvent::new(self.mouse, self.modifiers, element);
+                        self.emit(element, "onmousemove", event);
+                    }
+                    if let Some(focus) = self.focus {
+                        if self.elements_in_action.contains(&focus) {
+                            // mouse drag selects text
+                            self.point_caret(focus, tree, fonts, true)?;
+                        }
+                    }
+                    captured = !self.elements_under_mouse.is_empty()
+                        || self.drag.is_some()
+                        || self.pointer_capture.is_some();
                 }
                 InputEvent::MouseButtonDown(button) => {
+                    self.keyboard_modality = false;
                     if let Some(focus) = self.focus {
                         if !self.elements_under_mouse.contains(&focus) {
                             self.focus = None;
                             let element = tree.get_element_mut(focus)?;
                             element.state.focus = false;
-                            let event = MouseEvent::new(self.mouse, element);
+                            let event = MouseEvent::new(self.mouse, self.modifiers, element);
                             self.emit(&element, "onblur", event);
                         }
                     }
+                    let detail = if button == MouseButtons::Left {
+                        self.hold.press();
+                        self.swipe.press(self.mouse);
+                        self.clicks.press(self.elements_under_mouse.last().copied())
+                    } else {
+                        self.clicks.reset();
+                        1
+                    };
                     let elements_under_mouse = self.elements_under_mouse.clone();
                     for node in elements_under_mouse.iter().copied().rev() {
                         let mut element = tree.get_element_mut(node)?;
@@ -329,14 +797,15 @@ impl ViewModel {
                         element.state.active = true;
                         self.elements_in_action.push(node);
 
-                        if element.listeners.contains_key("oninput") {
+                        if element.focusable() {
                             // valid focus target
                             if let Some(focus) = self.focus {
                                 if focus != node {
                                     self.focus = None;
                                     element = tree.get_element_mut(focus)?;
                                     element.state.focus = false;
-                                    let event = MouseEvent::new(self.mouse, element);
+                                    let event =
+                                        MouseEvent::new(self.mouse, self.modifiers, element);
                                     self.emit(&element, "onblur", event);
                                     element = tree.get_element_mut(node)?;
                                 }
@@ -344,164 +813,474 @@ impl ViewModel {
                             if Some(node) != self.focus {
                                 self.focus = Some(node);
                                 element.state.focus = true;
-                                let event = MouseEvent::new(self.mouse, element);
+                                element.state.focus_visible = is_text_input(element);
+                                let event = MouseEvent::new(self.mouse, self.modifiers, element);
                                 self.emit(&element, "onfocus", event);
                             }
                         }
 
-                        let event = MouseEvent::new(self.mouse, element);
+                        let event =
+                            MouseEvent::new(self.mouse, self.modifiers, element).detail(detail);
                         self.emit(&element, "onmousedown", event);
                         if button == MouseButtons::Left && element.draggable() {
-                            let event = MouseEvent::new(self.mouse, element);
-                            self.emit(element, "ondragstart", event);
-                            self.drag = DragContext::new(node);
+                            let drag = DragContext::new(node, element, self.mouse);
+                            if let Some(drag) = drag.as_ref() {
+                                let event = DragEvent::new(self.mouse, &drag.payload.data, element);
+                                self.emit(element, "ondragstart", event);
+                            }
+                            self.drag = drag;
                         }
                     }
+                    if let Some(focus) = self.focus {
+                        if button == MouseButtons::Left
+                            && self.elements_under_mouse.contains(&focus)
+                        {
+                            let extend = self.modifiers.contains(Modifiers::SHIFT);
+                            self.point_caret(focus, tree, fonts, extend)?;
+                        }
+                    }
+                    captured = !self.elements_under_mouse.is_empty();
                 }
                 InputEvent::MouseButtonUp(button) => {
+                    let detail = match button {
+                        MouseButtons::Left => self.clicks.count(),
+                        _ => 1,
+                    };
+                    // hold and swipe replace click as alternative actions
+                    let held = button == MouseButtons::Left && self.hold.fired;
+                    self.hold.release();
+                    let swiped = button == MouseButtons::Left && self.recognize_swipe(tree)?;
                     let elements_under_mouse = self.elements_under_mouse.clone();
                     for node in elements_under_mouse.iter().rev() {
                         let element = tree.get_element_mut(*node)?;
-                        let event = MouseEvent::new(self.mouse, element);
+                        let event =
+                            MouseEvent::new(self.mouse, self.modifiers, element).detail(detail);
                         self.emit(&element, "onmouseup", event);
-                        if let Some(drag) = self.drag.as_mut() {
+                        if let Some(drag) = self.drag.as_ref() {
                             if element.listeners.contains_key("ondrop") {
                                 // valid drop target
-                                let source = drag.source;
-                                let event = MouseEvent::new(self.mouse, element);
+                                let data = drag.payload.data.clone();
+                                let event = DragEvent::new(self.mouse, &data, element);
                                 self.emit(element, "ondrop", event);
-                                self.drag = None;
-                                let element = tree.get_element_mut(source)?;
-                                let event = MouseEvent::new(self.mouse, element);
-                                self.emit(element, "ondragend", event);
+                                self.end_drag(tree)?;
                             }
                         } else {
-                            if button == MouseButtons::Left && element.state.active {
-                                let event = MouseEvent::new(self.mouse, element);
-                                self.emit(&element, "onclick", event);
-                            }
                             if button == MouseButtons::Right {
-                                let event = MouseEvent::new(self.mouse, element);
+                                let event = MouseEvent::new(self.mouse, self.modifiers, element)
+                                    .detail(detail);
                                 self.emit(&element, "oncontextmenu", event);
                             }
+                            if button == MouseButtons::Left
+                                && element.state.active
+                                && !held
+                                && !swiped
+                            {
+                                let event = MouseEvent::new(self.mouse, self.modifiers, element)
+                                    .detail(detail);
+                                self.emit(&element, "onclick", event);
+                                if detail == 2 {
+                                    self.emit(element, "ondblclick", event);
+                                }
+                                self.activate(*node, body, tree)?;
+                            }
                         }
                     }
+                    for node in self.mouse_capture() {
+                        let element = tree.get_element(node)?;
+                        let event =
+                            MouseEvent::new(self.mouse, self.modifiers, element).detail(detail);
+                        self.emit(element, "onmouseup", event);
+                    }
+                    self.pointer_capture = None;
                     for node in take(&mut self.elements_in_action) {
                         let element = tree.get_element_mut(node)?;
                         element.state.active = false;
                     }
+                    // dropped outside of any drop target
+                    self.end_drag(tree)?;
+                    captured |= !self.elements_under_mouse.is_empty();
+                }
+                InputEvent::MouseWheel(delta) => {
+                    // inner scrollable element scrolls unless handler prevents it
+                    let mut consumed = false;
+                    let wheel = match delta {
+                        // vertical wheel with Shift scrolls horizontally
+                        [x, y] if x == 0.0 && self.modifiers.contains(Modifiers::SHIFT) => {
+                            [-y, 0.0]
+                        }
+                        _ => delta,
+                    };
+                    let distance = wheel_distance(wheel, self.kinetic.sensitivity);
+                    let elements_under_mouse = self.elements_under_mouse.clone();
+                    for node in elements_under_mouse.iter().rev() {
+                        let element = tree.get_element_mut(*node)?;
+                        let event = WheelEvent::new(self.mouse, delta, element);
+                        self.emit(element, "onwheel", event);
+                        if element.listeners.contains_key("onwheel.prevent") {
+                            self.emit(element, "onwheel.prevent", event);
+                            consumed = true;
+                        }
+                        let scrolling = element
+                            .scrolling
+                            .as_mut()
+                            .filter(|scrolling| !consumed && scrolling.can_scroll(distance));
+                        if let Some(scrolling) = scrolling {
+                            match self.kinetic.friction {
+                                Some(friction) => {
+                                    // velocity to travel wheel distance until stop
+                                    scrolling.velocity[0] += distance[0] * friction;
+                                    scrolling.velocity[1] += distance[1] * friction;
+                                    self.kinetic.start(*node);
+                                }
+                                None => {
+                                    scrolling.scroll_by(distance);
+                                }
+                            }
+                            consumed = true;
+                        }
+                    }
+                    captured = consumed;
                 }
-                InputEvent::MouseWheel(_) => {}
                 InputEvent::KeyDown(key) => {
+                    self.keyboard_modality = true;
+                    if let Some(modifier) = Modifiers::from_key(key) {
+                        self.modifiers.set(modifier, true);
+                    }
+                    self.repeat.press(key);
                     if let Some(node) = self.focus {
                         let element = tree.get_element(node)?;
-                        let event = KeyboardEvent::new(key, element);
-                        self.emit(element, "onkeydown", event)
+                        let event = KeyboardEvent::new(key, self.modifiers, element);
+                        self.emit(element, "onkeydown", event);
+                        let filtered = format!("onkeydown.{}", key.name());
+                        self.emit(element, &filtered, event);
+                        match key {
+                            Keys::Backspace => self.delete_input_text(node, tree, false)?,
+                            Keys::Delete => self.delete_input_text(node, tree, true)?,
+                            _ => self.move_caret(node, tree, key)?,
+                        }
+                    }
+                    captured |= self.trigger_hotkey(key, body, tree)?;
+                    match key {
+                        Keys::Tab => self.traverse_focus(body, tree)?,
+                        Keys::Enter | Keys::NumpadEnter => {
+                            if let Some(node) = self.focus {
+                                self.navigate_anchor(node, tree)?;
+                                let element = tree.get_element(node)?;
+                                if element.tag == "input" || is_submit_button(element) {
+                                    self.submit_form(node, tree)?;
+                                }
+                            }
+                        }
+                        _ => {}
                     }
                 }
                 InputEvent::KeyUp(key) => {
+                    if let Some(modifier) = Modifiers::from_key(key) {
+                        self.modifiers.set(modifier, false);
+                    }
+                    self.repeat.release(key);
                     if let Some(node) = self.focus {
                         let element = tree.get_element(node)?;
-                        let event = KeyboardEvent::new(key, element);
-                        self.emit(element, "onkeyup", event)
+                        let event = KeyboardEvent::new(key, self.modifiers, element);
+                        self.emit(element, "onkeyup", event);
+                        let filtered = format!("onkeyup.{}", key.name());
+                        self.emit(element, &filtered, event);
                     }
                 }
                 InputEvent::Char(char) => {
                     if let Some(node) = self.focus {
                         let element = tree.get_element(node)?;
                         let event = TextEvent::new(char, element);
-                        self.emit(element, "oninput", event)
+                        self.emit(element, "oninput", event);
+                        if self.modifiers.contains(Modifiers::CTRL) {
+                            if let Some(undo) = self.history.shortcut(char) {
+                                self.undo_input_value(node, tree, !undo)?;
+                            } else if is_ctrl_char(char, 'c') {
+                                self.copy_input_value(node, tree, false)?;
+                            } else if is_ctrl_char(char, 'x') {
+                                self.copy_input_value(node, tree, true)?;
+                            } else if is_ctrl_char(char, 'v') {
+                                self.output.paste_requested = true;
+                            } else if is_ctrl_char(char, 'a') {
+                                self.select_all(node, tree)?;
+                            }
+                        } else if !char.is_control() {
+                            let text = char.to_string();
+                            self.insert_input_text(node, tree, EditKind::Insert, &text)?;
+                        }
+                    }
+                }
+                InputEvent::TouchStart(..)
+                | InputEvent::TouchMove(..)
+                | InputEvent::TouchEnd(..) => {
+                    self.handle_touch_input(event.clone(), body, tree, fonts)?;
+                    if let InputEvent::TouchStart(id, _) | InputEvent::TouchMove(id, _) = event {
+                        captured = self.touches.is_targeted(id);
+                    }
+                }
+                InputEvent::Pen(phase, pen) => {
+                    self.handle_pen_input(phase, pen, body, tree, fonts)?;
+                    captured |= !self.elements_under_mouse.is_empty() || self.drag.is_some();
+                }
+                InputEvent::Paste(text) => {
+                    if let Some(node) = self.focus {
+                        self.paste_input_value(node, tree, &text)?;
+                    }
+                }
+                InputEvent::ImeCompose(text, cursor) => {
+                    if let Some(node) = self.focus {
+                        self.compose_input_value(node, tree, text, cursor)?;
+                    }
+                }
+                InputEvent::ImeCommit(text) => {
+                    if let Some(node) = self.focus {
+                        self.commit_input_value(node, tree, &text)?;
+                    }
+                }
+                InputEvent::Copy | InputEvent::Cut => {
+                    if let Some(node) = self.focus {
+                        let cut = event == InputEvent::Cut;
+                        self.copy_input_value(node, tree, cut)?;
                     }
                 }
             }
+            captures.push(captured);
         }
-        Ok(())
+        Ok(captures)
     }
 
-    fn calculate_mouse_hovers(
+    /// Moves focus to the next (or previous if Shift pressed) element in sequential
+    /// keyboard navigation order: positive tabindex first, then document order.
+    fn traverse_focus(
         &mut self,
+        body: NodeId,
+        tree: &mut TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        let mut candidates = vec![];
+        Self::collect_focus_candidates(tree, body, &mut candidates)?;
+        // stable sort keeps document order of elements with same tabindex
+        candidates.sort_by_key(|(index, _)| if *index > 0 { *index } else { i32::MAX });
+        let candidates: Vec<NodeId> = candidates.into_iter().map(|(_, node)| node).collect();
+        if candidates.is_empty() {
+            return Ok(());
+        }
+        let current = self
+            .focus
+            .and_then(|focus| candidates.iter().position(|node| *node == focus));
+        let next = match (current, self.modifiers.contains(Modifiers::SHIFT)) {
+            (None, false) => 0,
+            (None, true) => candidates.len() - 1,
+            (Some(current), false) => (current + 1) % candidates.len(),
+            (Some(current), true) => (current + candidates.len() - 1) % candidates.len(),
+        };
+        self.set_focus(Some(candidates[next]), tree)
+    }
+
+    fn collect_focus_candidates(
         tree: &TaffyTree<Element>,
         node: NodeId,
-        position: [f32; 2],
+        candidates: &mut Vec<(i32, NodeId)>,
     ) -> Result<(), ViewError> {
         let element = tree.get_element(node)?;
-        if element.pointer_events == PointerEvents::Auto && hovers(position, &element) {
-            self.elements_under_mouse.push(node);
+        if let Some(index) = element.tab_index() {
+            if index >= 0 {
+                candidates.push((index, node));
+            }
         }
         for child in tree.children(node)? {
-            self.calculate_mouse_hovers(tree, child, position)?;
+            Self::collect_focus_candidates(tree, child, candidates)?;
         }
         Ok(())
     }
 
-    pub(crate) fn emit<T: Serialize>(&mut self, element: &Element, handler: &str, event: T) {
-        if let Some(handler) = element.listeners.get(handler) {
-            let mut key = "Undefined".to_string();
-            let mut arguments = vec![];
-            for (index, argument) in handler.arguments.iter().enumerate() {
-                let argument = match argument {
-                    HandlerArgument::Keyword(keyword) => Value::String(keyword.clone()),
-                    HandlerArgument::Event => match serde_json::to_value(&event) {
-                        Ok(event) => event,
-                        Err(error) => {
-                            error!("unable to serialize event, {error:?}");
-                            continue;
+    /// Performs default action of element clicked or triggered by hotkey.
+    pub(crate) fn activate(
+        &mut self,
+        node: NodeId,
+        body: NodeId,
+        tree: &mut TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        let element = tree.get_element(node)?;
+        let is_label = element.tag == "label";
+        let is_submit = is_submit_button(element);
+        self.toggle_input_checked(node, tree)?;
+        self.navigate_anchor(node, tree)?;
+        if is_submit {
+            self.submit_form(node, tree)?;
+        }
+        if is_label {
+            self.activate_label(node, body, tree)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_focus(
+        &mut self,
+        focus: Option<NodeId>,
+        tree: &mut TaffyTree<Element>,
+    ) -> Result<(), ViewError> {
+        if self.focus == focus {
+            return Ok(());
+        }
+        if let Some(node) = self.focus.take() {
+            let element = tree.get_element_mut(node)?;
+            element.state.focus = false;
+            let event = MouseEvent::new(self.mouse, self.modifiers, element);
+            self.emit(element, "onblur", event);
+        }
+        if let Some(node) = focus {
+            let element = tree.get_element_mut(node)?;
+            element.state.focus = true;
+            // text inputs indicate focus anyway, they accept keyboard input
+            element.state.focus_visible = self.keyboard_modality || is_text_input(element);
+            self.focus = Some(node);
+            let event = MouseEvent::new(self.mouse, self.modifiers, element);
+            self.emit(element, "onfocus", event);
+        }
+        Ok(())
+    }
+
+    fn calculate_mouse_hovers(
+        &mut self,
+        tree: &TaffyTree<Element>,
+        node: NodeId,
+        position: [f32; 2],
+    ) -> Result<(), ViewError> {
+        find_elements_at(tree, node, position, &mut self.elements_under_mouse)
+    }
+
+    /// Writes user edits back into the model and notifies application about them,
+    /// so the next bound value will not revert the edit.
+    pub(crate) fn change(&mut self, path: &str, value: Value) {
+        if LocalState::contains(path) {
+            self.set_local_state(path, value);
+            return;
+        }
+        match self.model.pointer_mut(path) {
+            Some(target) => *target = value.clone(),
+            None => error!("unable to change value at {path:?}, not found"),
+        }
+        let message = json!({
+            "change": {
+                "path": path,
+                "value": value
+            }
+        });
+        self.output.messages.push(message);
+    }
+
+    pub(crate) fn emit<T: Serialize>(&mut self, element: &Element, name: &str, event: T) {
+        if let Some(handlers) = element.listeners.get(name) {
+            for (index, handler) in handlers.iter().enumerate() {
+                let id = handler
+                    .rate
+                    .map(|_| (element.node, name.to_string(), index));
+                self.handle_event(handler, id, &event);
+            }
+        }
+    }
+
+    fn handle_event<T: Serialize>(&mut self, handler: &Handler, id: Option<HandlerId>, event: &T) {
+        let mut key = "Undefined".to_string();
+        let mut arguments = vec![];
+        for (index, argument) in handler.arguments.iter().enumerate() {
+            let argument = match argument {
+                HandlerArgument::Keyword(keyword) => Value::String(keyword.clone()),
+                HandlerArgument::Event(path) => match serde_json::to_value(event) {
+                    Ok(event) => match select_event_field(event, path) {
+                        Some(value) => value,
+                        None => {
+                            error!("unable to get event field {path:?}, not found");
+                            Value::Null
                         }
                     },
-                    HandlerArgument::Binder { path, pipe } => {
-                        let mut value = match self.model.pointer(&path).cloned() {
-                            Some(value) => value,
+                    Err(error) => {
+                        error!("unable to serialize event, {error:?}");
+                        Value::Null
+                    }
+                },
+                HandlerArgument::Binder { path, pipe } => {
+                    let mut value = match self.model.pointer(&path).cloned() {
+                        Some(value) => value,
+                        None => {
+                            error!("unable to get value at {path:?}, not found");
+                            continue;
+                        }
+                    };
+                    for pipe in pipe {
+                        match self.transformers.get_mut(&pipe.name) {
+                            Some(transform) => value = transform(value, &pipe.arguments),
                             None => {
-                                error!("unable to get value at {path:?}, not found");
+                                let name = &pipe.name;
+                                error!(
+                                    "unable to get value {path:?}, transformer {name} not found"
+                                );
                                 continue;
                             }
-                        };
-                        for name in pipe {
-                            match self.transformers.get(name) {
-                                Some(transform) => value = transform(value),
-                                None => {
-                                    error!("unable to get value {path:?}, transformer {name} not found");
-                                    continue;
-                                }
-                            }
                         }
-                        value
                     }
-                };
-                if index == 0 {
-                    key = argument.eval_string();
-                } else {
-                    arguments.push(argument);
-                }
-            }
-            let message = match arguments.len() {
-                0 => Value::String(key),
-                1 => {
-                    let mut object = Map::new();
-                    object.insert(key, arguments.into_iter().next().expect("one argument"));
-                    Value::Object(object)
-                }
-                _ => {
-                    let mut object = Map::new();
-                    object.insert(key, Value::Array(arguments));
-                    Value::Object(object)
+                    value
                 }
             };
+            if index == 0 {
+                key = argument.eval_string();
+            } else {
+                arguments.push(argument);
+            }
+        }
+        if self.handle_local_action(handler, &key, &arguments) {
+            return;
+        }
+        let message = match arguments.len() {
+            0 => Value::String(key),
+            1 => {
+                let mut object = Map::new();
+                object.insert(key, arguments.into_iter().next().expect("one argument"));
+                Value::Object(object)
+            }
+            _ => {
+                let mut object = Map::new();
+                object.insert(key, Value::Array(arguments));
+                Value::Object(object)
+            }
+        };
+        let message = match (handler.rate, id) {
+            (Some(rate), Some(id)) => self.limiter.limit(id, rate, message),
+            _ => Some(message),
+        };
+        if let Some(message) = message {
             self.output.messages.push(message);
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardEvent {
+    pub text: String,
+    pub target: EventTarget,
+}
+
+impl ClipboardEvent {
+    pub fn new(text: &str, element: &Element) -> Self {
+        Self {
+            text: text.to_string(),
+            target: EventTarget::create(element),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct KeyboardEvent {
     pub key: Keys,
+    pub modifiers: Modifiers,
     pub target: EventTarget,
 }
 
 impl KeyboardEvent {
-    pub fn new(key: Keys, element: &Element) -> Self {
+    pub fn new(key: Keys, modifiers: Modifiers, element: &Element) -> Self {
         Self {
             key,
+            modifiers,
             target: EventTarget::create(element),
         }
     }
@@ -510,13 +1289,232 @@ impl KeyboardEvent {
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct MouseEvent {
     pub position: [f32; 2],
+    /// The click count of button events, 2 for second click of double click,
+    /// 0 for other events.
+    pub detail: u32,
+    pub modifiers: Modifiers,
     pub target: EventTarget,
 }
 
 impl MouseEvent {
-    pub fn new(position: [f32; 2], element: &Element) -> Self {
+    pub fn new(position: [f32; 2], modifiers: Modifiers, element: &Element) -> Self {
+        Self {
+            position,
+            detail: 0,
+            modifiers,
+            target: EventTarget::create(element),
+        }
+    }
+
+    pub fn detail(mut self, detail: u32) -> Self {
+        self.detail = detail;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DragEvent {
+    pub position: [f32; 2],
+    /// The data of dragged element, see [`Element::drag_data`].
+    pub data: Value,
+    pub target: EventTarget,
+}
+
+impl DragEvent {
+    pub fn new(position: [f32; 2], data: &Value, element: &Element) -> Self {
+        Self {
+            position,
+            data: data.clone(),
+            target: EventTarget::create(element),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitEvent {
+    /// The values of named form fields by name.
+    pub values: Map<String, Value>,
+    /// Whether all form fields satisfy their constraints.
+    pub valid: bool,
+    /// The names of fields with invalid values.
+    pub invalid: Vec<String>,
+    pub target: EventTarget,
+}
+
+impl SubmitEvent {
+    pub fn new(values: Map<String, Value>, invalid: Vec<String>, element: &Element) -> Self {
+        Self {
+            values,
+            valid: invalid.is_empty(),
+            invalid,
+            target: EventTarget::create(element),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TouchEvent {
+    pub id: u64,
+    pub position: [f32; 2],
+    /// The number of active touch points.
+    pub touches: usize,
+    pub target: EventTarget,
+}
+
+impl TouchEvent {
+    pub fn new(id: u64, position: [f32; 2], touches: usize, element: &Element) -> Self {
+        Self {
+            id,
+            position,
+            touches,
+            target: EventTarget::create(element),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PanEvent {
+    /// The centroid of touch points.
+    pub center: [f32; 2],
+    /// The movement of centroid since previous event.
+    pub delta: [f32; 2],
+    pub target: EventTarget,
+}
+
+impl PanEvent {
+    pub fn new(center: [f32; 2], delta: [f32; 2], element: &Element) -> Self {
+        Self {
+            center,
+            delta,
+            target: EventTarget::create(element),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PenEvent {
+    pub position: [f32; 2],
+    /// The normalized pressure of pen tip, from 0.0 to 1.0.
+    pub pressure: f32,
+    /// The angles in degrees between pen and surface normal along X and Y axes.
+    pub tilt: [f32; 2],
+    pub modifiers: Modifiers,
+    pub target: EventTarget,
+}
+
+impl PenEvent {
+    pub fn new(pen: Pen, modifiers: Modifiers, element: &Element) -> Self {
+        Self {
+            position: pen.position,
+            pressure: pen.pressure,
+            tilt: pen.tilt,
+            modifiers,
+            target: EventTarget::create(element),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SwipeEvent {
+    /// The pointer position where swipe ended.
+    pub position: [f32; 2],
+    /// The pointer displacement from press to release.
+    pub delta: [f32; 2],
+    /// The mean pointer velocity of swipe in pixels per second.
+    pub velocity: [f32; 2],
+    pub target: EventTarget,
+}
+
+impl SwipeEvent {
+    pub fn new(position: [f32; 2], delta: [f32; 2], velocity: [f32; 2], element: &Element) -> Self {
         Self {
             position,
+            delta,
+            velocity,
+            target: EventTarget::create(element),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PinchEvent {
+    /// The centroid of touch points.
+    pub center: [f32; 2],
+    /// The scale factor of touch points spread since previous event.
+    pub scale: f32,
+    pub target: EventTarget,
+}
+
+impl PinchEvent {
+    pub fn new(center: [f32; 2], scale: f32, element: &Element) -> Self {
+        Self {
+            center,
+            scale,
+            target: EventTarget::create(element),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TickEvent {
+    /// The time in seconds since view creation.
+    pub time: f32,
+    pub target: EventTarget,
+}
+
+impl TickEvent {
+    pub fn new(time: f32, element: &Element) -> Self {
+        Self {
+            time,
+            target: EventTarget::create(element),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct VisibilityEvent {
+    pub visible: bool,
+    pub target: EventTarget,
+}
+
+impl VisibilityEvent {
+    pub fn new(visible: bool, element: &Element) -> Self {
+        Self {
+            visible,
+            target: EventTarget::create(element),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ResizeEvent {
+    /// The size of element before layout change.
+    pub previous: [f32; 2],
+    pub target: EventTarget,
+}
+
+impl ResizeEvent {
+    pub fn new(previous: [f32; 2], element: &Element) -> Self {
+        Self {
+            previous,
+            target: EventTarget::create(element),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WheelEvent {
+    pub position: [f32; 2],
+    /// The wheel rotation, positive y means scrolling up.
+    pub delta: [f32; 2],
+    pub target: EventTarget,
+}
+
+impl WheelEvent {
+    pub fn new(position: [f32; 2], delta: [f32; 2], element: &Element) -> Self {
+        Self {
+            position,
+            delta,
             target: EventTarget::create(element),
         }
     }
@@ -537,6 +1535,21 @@ impl TextEvent {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceEvent {
+    pub src: String,
+    pub target: EventTarget,
+}
+
+impl ResourceEvent {
+    pub fn new(src: &str, element: &Element) -> Self {
+        Self {
+            src: src.to_string(),
+            target: EventTarget::create(element),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct EventTarget {
     pub size: [f32; 2],
@@ -557,14 +1570,32 @@ impl EventTarget {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Binder {
     pub path: Vec<String>,
-    pub pipe: Vec<String>,
+    pub pipe: Vec<Pipe>,
+}
+
+/// The transformer call of binder, e.g. `round:2` in `{ price | round:2 }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pipe {
+    pub name: String,
+    pub arguments: Vec<Value>,
+}
+
+impl Display for Pipe {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for argument in &self.arguments {
+            write!(f, ":{argument}")?;
+        }
+        Ok(())
+    }
 }
 
 impl Binder {
     pub fn to_string(&self) -> String {
         let path = self.path.join(".");
         if self.pipe.len() > 0 {
-            let pipe = self.pipe.join(" | ");
+            let pipe: Vec<String> = self.pipe.iter().map(Pipe::to_string).collect();
+            let pipe = pipe.join(" | ");
             format!("{{ {path} | {pipe} }}")
         } else {
             format!("{{ {path} }}")
@@ -589,16 +1620,19 @@ impl Binder {
 #[derive(Debug, Clone)]
 pub struct Binding {
     pub params: BindingParams,
-    pub pipe: Vec<String>,
+    pub pipe: Vec<Pipe>,
 }
 
 #[derive(Debug, Clone)]
 pub enum BindingParams {
     Text(NodeId, usize),
     Visibility(NodeId, NodeId, bool),
-    Attribute(NodeId, String, usize),
-    Tag(NodeId, String),
+    Attribute(NodeId, SmolStr, usize),
+    Tag(NodeId, SmolStr),
     Repeat(NodeId, usize, usize),
+    Translation(NodeId, usize),
+    /// The parent and repeated element slots of keyed array.
+    Order(NodeId, Vec<NodeId>),
 }
 
 impl Binding {
@@ -627,6 +1661,12 @@ impl Binding {
                 let text = value.eval_string();
                 Reaction::Type { node, span, text }
             }
+            BindingParams::Translation(node, span) => Reaction::Translate { node, span },
+            BindingParams::Order(parent, slots) => Reaction::Reorder {
+                parent,
+                count: value.as_array().map(Vec::len).unwrap_or(0),
+                nodes: slots,
+            },
             BindingParams::Repeat(parent, start, size) => {
                 if let Some(value) = value.as_array() {
                     let count = value.len();
@@ -676,15 +1716,88 @@ pub enum Reaction {
     },
     Tag {
         node: NodeId,
-        key: String,
+        key: SmolStr,
         tag: bool,
     },
     Bind {
         node: NodeId,
-        key: String,
+        key: SmolStr,
         span: usize,
         text: String,
     },
+    Translate {
+        node: NodeId,
+        span: usize,
+    },
+    Reorder {
+        parent: NodeId,
+        nodes: Vec<NodeId>,
+        count: usize,
+    },
+    Pending {
+        node: NodeId,
+        pending: bool,
+    },
+}
+
+/// Selects event field by path like `position.x`, vector components
+/// can be selected by name or index.
+fn select_event_field(mut value: Value, path: &[String]) -> Option<Value> {
+    for field in path {
+        value = match value {
+            Value::Object(mut object) => object.remove(field)?,
+            Value::Array(mut array) => {
+                let index = match field.as_str() {
+                    "x" | "width" => 0,
+                    "y" | "height" => 1,
+                    index => index.parse().ok()?,
+                };
+                if index >= array.len() {
+                    return None;
+                }
+                array.swap_remove(index)
+            }
+            _ => return None,
+        }
+    }
+    Some(value)
+}
+
+/// Collects interactive elements at position, parents go before children.
+pub(crate) fn find_elements_at(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    position: [f32; 2],
+    elements: &mut Vec<NodeId>,
+) -> Result<(), ViewError> {
+    find_translated_elements_at(tree, node, position, [0.0; 2], elements)
+}
+
+fn find_translated_elements_at(
+    tree: &TaffyTree<Element>,
+    node: NodeId,
+    position: [f32; 2],
+    translate: [f32; 2],
+    elements: &mut Vec<NodeId>,
+) -> Result<(), ViewError> {
+    let element = tree.get_element(node)?;
+    let translate = element_translate(element, translate);
+    let point = [position[0] - translate[0], position[1] - translate[1]];
+    let clipped = match element.clipping.as_ref() {
+        Some(clipping) => !clipping.contains(point),
+        None => false,
+    };
+    if element.pointer_events == PointerEvents::Auto
+        && !element.disabled()
+        && hovers(point, element)
+        && !clipped
+    {
+        elements.push(node);
+    }
+    for child in tree.children(node)? {
+        find_translated_elements_at(tree, child, position, translate, elements)?;
+    }
+    Ok(())
 }
 
 fn hovers(point: [f32; 2], element: &Element) -> bool {
@@ -717,6 +1830,45 @@ impl Schema {
         pointer
     }
 
+    pub fn shape(value: &Value) -> Value {
+        match value {
+            Value::Object(object) => Value::Object(
+                object
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Self::shape(value)))
+                    .collect(),
+            ),
+            Value::Array(array) => match array.first() {
+                Some(item) => Value::Array(vec![Self::shape(item)]),
+                None => json!([]),
+            },
+            _ => Value::Null,
+        }
+    }
+
+    /// Describes shape of model value as JSON Schema.
+    pub fn to_json_schema(shape: &Value) -> Value {
+        match shape {
+            Value::Object(object) => {
+                let properties: Map<String, Value> = object
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Self::to_json_schema(value)))
+                    .collect();
+                let required: Vec<&String> = object.keys().collect();
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required
+                })
+            }
+            Value::Array(array) => match array.first() {
+                Some(item) => json!({ "type": "array", "items": Self::to_json_schema(item) }),
+                None => json!({ "type": "array" }),
+            },
+            _ => json!({}),
+        }
+    }
+
     fn define_value(mut target: &mut Value, pointer: &str) {
         if pointer == Schema::THIS {
             return;
@@ -748,37 +1900,151 @@ impl Schema {
     }
 }
 
-fn default_transformers() -> HashMap<String, Transformer> {
-    fn duration(value: Value) -> Value {
-        match value.as_f64() {
-            None => value,
-            Some(value) => {
-                let value = Duration::from_secs_f64(value);
-                let value = format!("{value:?}");
-                Value::String(value)
-            }
+/// Removes array indices from JSON pointer, so all items of array share the same path.
+pub(crate) fn arrays_path(pointer: &str) -> String {
+    pointer
+        .split('/')
+        .skip(1)
+        .filter(|segment| segment.parse::<usize>().is_err())
+        .map(|segment| format!("/{segment}"))
+        .collect()
+}
+
+/// The bindings and state shared by all values bound by one model update.
+pub struct BindContext<'m> {
+    pub bindings: &'m Bindings,
+    pub reactions: &'m mut Vec<Reaction>,
+    pub transformers: &'m mut HashMap<String, Transformer>,
+    /// The default items of arrays by arrays path, used to grow model arrays.
+    pub default: &'m HashMap<String, Value>,
+    pub keyed: &'m mut KeyedArrays,
+}
+
+impl BindContext<'_> {
+    fn react(&mut self, path: &str, value: &Value) {
+        ViewModel::react(
+            path,
+            value,
+            self.bindings,
+            self.reactions,
+            self.transformers,
+        );
+    }
+}
+
+/// The arrays which items bound to elements by identity key instead of index.
+#[derive(Debug, Default)]
+pub struct KeyedArrays {
+    /// The item identity key fields by arrays path.
+    pub(crate) keys: HashMap<String, String>,
+    /// The current element slots of array items in display order by array path.
+    pub(crate) orders: HashMap<String, Vec<usize>>,
+}
+
+/// Applies pipe transformers to value in order.
+fn transform(
+    mut value: Value,
+    pipe: &[Pipe],
+    transformers: &mut HashMap<String, Transformer>,
+) -> Value {
+    for pipe in pipe {
+        match transformers.get_mut(&pipe.name) {
+            None => error!("unable to bind value, transformer {} not found", pipe.name),
+            Some(transform) => value = transform(value, &pipe.arguments),
+        }
+    }
+    value
+}
+
+/// Checks one JSON pointer is the same as other or its ancestor, so value at one
+/// changes if value at other changed.
+fn is_path_related(path: &str, other: &str) -> bool {
+    let (short, long) = match path.len() <= other.len() {
+        true => (path, other),
+        false => (other, path),
+    };
+    long.starts_with(short) && matches!(long.as_bytes().get(short.len()), None | Some(b'/'))
+}
+
+/// The model value computed from another one by pipe,
+/// view model maintains it at the root key with reserved prefix.
+#[derive(Debug, Clone)]
+pub struct DerivedValue {
+    pub source: String,
+    pub target: String,
+    pub pipe: Vec<Pipe>,
+    source_value: Value,
+}
+
+impl DerivedValue {
+    pub const PREFIX: &'static str = "@";
+
+    pub fn new(source: String, target: String, pipe: Vec<Pipe>) -> Self {
+        Self {
+            source,
+            target,
+            pipe,
+            source_value: Value::Null,
         }
     }
-    let mut transformers = HashMap::new();
-    transformers.insert("duration".to_string(), duration as Transformer);
-    transformers
 }
 
 #[derive(Debug)]
 pub struct DragContext {
     source: NodeId,
+    payload: DragPayload,
 }
 
 impl DragContext {
-    pub fn new(node: NodeId) -> Option<Self> {
-        Some(Self { source: node })
+    pub fn new(node: NodeId, element: &Element, mouse: [f32; 2]) -> Option<Self> {
+        let [x, y] = element.position;
+        let payload = DragPayload {
+            data: element.drag_data(),
+            position: mouse,
+            offset: [mouse[0] - x, mouse[1] - y],
+            size: element.size,
+        };
+        Some(Self {
+            source: node,
+            payload,
+        })
     }
 }
 
+/// The data of active drag, renderer can draw drag ghost of source element size
+/// at cursor position minus offset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DragPayload {
+    pub data: Value,
+    /// The current cursor position.
+    pub position: [f32; 2],
+    /// The cursor position relative to source element at drag start.
+    pub offset: [f32; 2],
+    /// The size of source element.
+    pub size: [f32; 2],
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    pub fn test_rebind_unchanged_value_without_reactions() {
+        let model = json!({
+            "user": { "name": null, "tags": [null] },
+        });
+        let bindings = BTreeMap::from([
+            ("/user/name".to_string(), vec![text(100), text(200)]),
+            ("/user/tags".to_string(), vec![text(300)]),
+        ]);
+        let mut view_model = ViewModel::create(bindings, model);
+        let value = json!({ "user": { "name": "Alice", "tags": ["a"], "age": 42 } });
+        let reactions = view_model.bind(&value);
+        assert_eq!(reactions.len(), 2, "first bind reactions");
+        let reactions = view_model.bind(&value);
+        assert_eq!(reactions, vec![], "unchanged value reactions");
+    }
+
     #[test]
     pub fn test_rebind_root_property_with_undefined() {
         let model = json!({
@@ -1093,7 +2359,7 @@ mod tests {
                 },
                 Reaction::Bind {
                     node: items_0.into(),
-                    key: "id".to_string(),
+                    key: "id".into(),
                     span: 0,
                     text: "1".to_string(),
                 },
@@ -1104,7 +2370,7 @@ mod tests {
                 },
                 Reaction::Bind {
                     node: items_1.into(),
-                    key: "id".to_string(),
+                    key: "id".into(),
                     span: 0,
                     text: "2".to_string(),
                 },
@@ -1140,7 +2406,7 @@ mod tests {
 
     fn attr(node: u64, attr: &str, span: usize) -> Binding {
         Binding {
-            params: BindingParams::Attribute(NodeId::new(node), attr.to_string(), span),
+            params: BindingParams::Attribute(NodeId::new(node), attr.into(), span),
             pipe: vec![],
         }
     }
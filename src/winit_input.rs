@@ -0,0 +1,249 @@
+use crate::{Input, InputEvent, Keys, MouseButtons};
+use std::mem::take;
+use winit::event::{ElementState, Ime, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent};
+use winit::keyboard::{KeyCode, NativeKeyCode, PhysicalKey};
+
+/// Collects winit window events between frames and converts them to view input.
+/// Positions stay in physical pixels, window scale factor is passed to Input.
+#[derive(Debug)]
+pub struct WinitInput {
+    events: Vec<InputEvent>,
+    viewport: [f32; 2],
+    scale: f32,
+    /// The scroll distance in logical pixels of one wheel line.
+    pub line_height: f32,
+}
+
+impl WinitInput {
+    /// Creates adapter of window with inner size in physical pixels and scale factor.
+    pub fn new(viewport: [f32; 2], scale: f32) -> Self {
+        Self {
+            events: vec![],
+            viewport,
+            scale,
+            line_height: 50.0,
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::Resized(size) => {
+                self.viewport = [size.width as f32, size.height as f32];
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.scale = *scale_factor as f32;
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let position = [position.x as f32, position.y as f32];
+                self.events.push(InputEvent::MouseMove(position));
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let button = match button {
+                    MouseButton::Left => MouseButtons::Left,
+                    MouseButton::Right => MouseButtons::Right,
+                    _ => return,
+                };
+                self.events.push(match state {
+                    ElementState::Pressed => InputEvent::MouseButtonDown(button),
+                    ElementState::Released => InputEvent::MouseButtonUp(button),
+                });
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                // winit positive x reveals content on the left
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => {
+                        [-x * self.line_height, y * self.line_height]
+                    }
+                    MouseScrollDelta::PixelDelta(delta) => {
+                        let [x, y] = [delta.x as f32, delta.y as f32];
+                        [-x / self.scale, y / self.scale]
+                    }
+                };
+                self.events.push(InputEvent::MouseWheel(delta));
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                let key = map_physical_key(event.physical_key);
+                match event.state {
+                    // view repeats held editing and navigation keys itself
+                    ElementState::Pressed if !event.repeat => {
+                        self.events.push(InputEvent::KeyDown(key))
+                    }
+                    ElementState::Pressed => {}
+                    ElementState::Released => self.events.push(InputEvent::KeyUp(key)),
+                }
+                if event.state == ElementState::Pressed {
+                    if let Some(text) = event.text.as_ref() {
+                        self.events.extend(text.chars().map(InputEvent::Char));
+                    }
+                }
+            }
+            WindowEvent::Ime(Ime::Preedit(text, cursor)) => {
+                // winit reports cursor in bytes of text
+                let chars = |index: usize| text[..index].chars().count();
+                let cursor = cursor.map(|(start, end)| [chars(start), chars(end)]);
+                self.events
+                    .push(InputEvent::ImeCompose(text.clone(), cursor));
+            }
+            WindowEvent::Ime(Ime::Commit(text)) => {
+                self.events.push(InputEvent::ImeCommit(text.clone()));
+            }
+            WindowEvent::Touch(touch) => {
+                let position = [touch.location.x as f32, touch.location.y as f32];
+                self.events.push(match touch.phase {
+                    TouchPhase::Started => InputEvent::TouchStart(touch.id, position),
+                    TouchPhase::Moved => InputEvent::TouchMove(touch.id, position),
+                    TouchPhase::Ended | TouchPhase::Cancelled => {
+                        InputEvent::TouchEnd(touch.id, position)
+                    }
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns input of events collected since previous frame.
+    pub fn take_input(&mut self) -> Input {
+        Input::new()
+            .viewport(self.viewport)
+            .scale(self.scale)
+            .scroll_sensitivity(1.0)
+            .events(take(&mut self.events))
+    }
+}
+
+/// Returns key of physical key, keys without platform code are Unknown.
+pub fn map_physical_key(key: PhysicalKey) -> Keys {
+    match key {
+        PhysicalKey::Code(code) => map_key_code(code),
+        PhysicalKey::Unidentified(code) => match code {
+            NativeKeyCode::Unidentified => Keys::Unknown,
+            NativeKeyCode::Android(code) | NativeKeyCode::Xkb(code) => Keys::Scancode(code),
+            NativeKeyCode::MacOS(code) | NativeKeyCode::Windows(code) => {
+                Keys::Scancode(code as u32)
+            }
+        },
+    }
+}
+
+pub fn map_key_code(code: KeyCode) -> Keys {
+    match code {
+        // UI keys
+        KeyCode::Escape => Keys::Escape,
+        // Editing keys
+        KeyCode::Backspace => Keys::Backspace,
+        KeyCode::Delete => Keys::Delete,
+        KeyCode::Insert => Keys::Insert,
+        // Whitespace keys
+        KeyCode::Enter => Keys::Enter,
+        KeyCode::Tab => Keys::Tab,
+        KeyCode::Space => Keys::Space,
+        // Navigation keys
+        KeyCode::ArrowUp => Keys::ArrowUp,
+        KeyCode::ArrowDown => Keys::ArrowDown,
+        KeyCode::ArrowLeft => Keys::ArrowLeft,
+        KeyCode::ArrowRight => Keys::ArrowRight,
+        KeyCode::End => Keys::End,
+        KeyCode::Home => Keys::Home,
+        KeyCode::PageDown => Keys::PageDown,
+        KeyCode::PageUp => Keys::PageUp,
+        // Modifier keys
+        KeyCode::AltLeft | KeyCode::AltRight => Keys::Alt,
+        KeyCode::CapsLock => Keys::CapsLock,
+        KeyCode::ControlLeft | KeyCode::ControlRight => Keys::Ctrl,
+        KeyCode::ShiftLeft | KeyCode::ShiftRight => Keys::Shift,
+        KeyCode::SuperLeft | KeyCode::SuperRight => Keys::Meta,
+        KeyCode::NumLock => Keys::NumLock,
+        KeyCode::ScrollLock => Keys::ScrollLock,
+        // Punctuation keys
+        KeyCode::Minus => Keys::Minus,
+        KeyCode::Equal => Keys::Equal,
+        KeyCode::BracketLeft => Keys::BracketLeft,
+        KeyCode::BracketRight => Keys::BracketRight,
+        KeyCode::Backslash => Keys::Backslash,
+        KeyCode::Semicolon => Keys::Semicolon,
+        KeyCode::Quote => Keys::Quote,
+        KeyCode::Backquote => Keys::Backquote,
+        KeyCode::Comma => Keys::Comma,
+        KeyCode::Period => Keys::Period,
+        KeyCode::Slash => Keys::Slash,
+        // Function keys
+        KeyCode::F1 => Keys::F1,
+        KeyCode::F2 => Keys::F2,
+        KeyCode::F3 => Keys::F3,
+        KeyCode::F4 => Keys::F4,
+        KeyCode::F5 => Keys::F5,
+        KeyCode::F6 => Keys::F6,
+        KeyCode::F7 => Keys::F7,
+        KeyCode::F8 => Keys::F8,
+        KeyCode::F9 => Keys::F9,
+        KeyCode::F10 => Keys::F10,
+        KeyCode::F11 => Keys::F11,
+        KeyCode::F12 => Keys::F12,
+        // Numpad keys
+        KeyCode::Numpad0 => Keys::Numpad0,
+        KeyCode::Numpad1 => Keys::Numpad1,
+        KeyCode::Numpad2 => Keys::Numpad2,
+        KeyCode::Numpad3 => Keys::Numpad3,
+        KeyCode::Numpad4 => Keys::Numpad4,
+        KeyCode::Numpad5 => Keys::Numpad5,
+        KeyCode::Numpad6 => Keys::Numpad6,
+        KeyCode::Numpad7 => Keys::Numpad7,
+        KeyCode::Numpad8 => Keys::Numpad8,
+        KeyCode::Numpad9 => Keys::Numpad9,
+        KeyCode::NumpadAdd => Keys::NumpadAdd,
+        KeyCode::NumpadSubtract => Keys::NumpadSubtract,
+        KeyCode::NumpadMultiply => Keys::NumpadMultiply,
+        KeyCode::NumpadDivide => Keys::NumpadDivide,
+        KeyCode::NumpadDecimal => Keys::NumpadDecimal,
+        KeyCode::NumpadEnter => Keys::NumpadEnter,
+        // Device and media keys
+        KeyCode::PrintScreen => Keys::PrintScreen,
+        KeyCode::Pause => Keys::Pause,
+        KeyCode::ContextMenu => Keys::ContextMenu,
+        KeyCode::MediaPlayPause => Keys::MediaPlayPause,
+        KeyCode::MediaStop => Keys::MediaStop,
+        KeyCode::MediaTrackNext => Keys::MediaTrackNext,
+        KeyCode::MediaTrackPrevious => Keys::MediaTrackPrevious,
+        KeyCode::AudioVolumeUp => Keys::AudioVolumeUp,
+        KeyCode::AudioVolumeDown => Keys::AudioVolumeDown,
+        KeyCode::AudioVolumeMute => Keys::AudioVolumeMute,
+        // Letter and digit keys
+        KeyCode::KeyA => Keys::Character('a'),
+        KeyCode::KeyB => Keys::Character('b'),
+        KeyCode::KeyC => Keys::Character('c'),
+        KeyCode::KeyD => Keys::Character('d'),
+        KeyCode::KeyE => Keys::Character('e'),
+        KeyCode::KeyF => Keys::Character('f'),
+        KeyCode::KeyG => Keys::Character('g'),
+        KeyCode::KeyH => Keys::Character('h'),
+        KeyCode::KeyI => Keys::Character('i'),
+        KeyCode::KeyJ => Keys::Character('j'),
+        KeyCode::KeyK => Keys::Character('k'),
+        KeyCode::KeyL => Keys::Character('l'),
+        KeyCode::KeyM => Keys::Character('m'),
+        KeyCode::KeyN => Keys::Character('n'),
+        KeyCode::KeyO => Keys::Character('o'),
+        KeyCode::KeyP => Keys::Character('p'),
+        KeyCode::KeyQ => Keys::Character('q'),
+        KeyCode::KeyR => Keys::Character('r'),
+        KeyCode::KeyS => Keys::Character('s'),
+        KeyCode::KeyT => Keys::Character('t'),
+        KeyCode::KeyU => Keys::Character('u'),
+        KeyCode::KeyV => Keys::Character('v'),
+        KeyCode::KeyW => Keys::Character('w'),
+        KeyCode::KeyX => Keys::Character('x'),
+        KeyCode::KeyY => Keys::Character('y'),
+        KeyCode::KeyZ => Keys::Character('z'),
+        KeyCode::Digit0 => Keys::Character('0'),
+        KeyCode::Digit1 => Keys::Character('1'),
+        KeyCode::Digit2 => Keys::Character('2'),
+        KeyCode::Digit3 => Keys::Character('3'),
+        KeyCode::Digit4 => Keys::Character('4'),
+        KeyCode::Digit5 => Keys::Character('5'),
+        KeyCode::Digit6 => Keys::Character('6'),
+        KeyCode::Digit7 => Keys::Character('7'),
+        KeyCode::Digit8 => Keys::Character('8'),
+        KeyCode::Digit9 => Keys::Character('9'),
+        _ => Keys::Unknown,
+    }
+}